@@ -0,0 +1,586 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! A persistent, content-addressed cache for the remote sources of virtual directories,
+//! i.e. git repositories, remote archives and remote files.
+//!
+//! The cache directory (`~/.weaver/cache` by default) contains:
+//! - `content/<digest>`: the fetched content, named after its digest, i.e.
+//!   `git-<commit id>` for a git checkout and `sha256-<hash>` for a downloaded archive
+//!   or file. Sources resolving to the same content share it.
+//! - `entries/<key>.json`: the entry of a source, keyed by the SHA-256 hash of the
+//!   source (the URL followed by the git refspec, if any), with the digest of its
+//!   content and the time it was fetched.
+//! - `tmp`: the staging area where a source is fetched before being moved atomically
+//!   into `content`.
+//!
+//! An entry is reused until it is older than the time-to-live of the cache, except for
+//! the git repositories pinned to a commit SHA whose content never changes. When the
+//! refresh of an expired entry fails, e.g. without network, the expired entry is reused
+//! with a warning. In offline mode, entries are reused whatever their age and a source
+//! missing from the cache is an error.
+//!
+//! The cache is only used by [`crate::vdir::VirtualDirectory`] once enabled with
+//! [`init_global_cache`], otherwise remote sources are fetched into temporary
+//! directories deleted after use. A virtual directory works on a temporary copy of the
+//! cached content (see [`copy_content`]), so nothing written through it can alter the
+//! content shared by the other sources and runs.
+
+use crate::Error;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Default time-to-live of the cache entries.
+pub const DEFAULT_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Staging directories older than this are leftovers of interrupted fetches.
+const STALE_STAGING_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// The cache used by the virtual directories of the process, if enabled.
+static GLOBAL_CACHE: OnceLock<Cache> = OnceLock::new();
+
+/// Enables the persistent cache for every virtual directory created afterwards.
+/// Only the first call has an effect.
+pub fn init_global_cache(cache: Cache) {
+    _ = GLOBAL_CACHE.set(cache);
+}
+
+/// Returns the cache used by the virtual directories, if enabled.
+#[must_use]
+pub fn global_cache() -> Option<&'static Cache> {
+    GLOBAL_CACHE.get()
+}
+
+/// A persistent cache of fetched remote sources.
+#[derive(Debug, Clone)]
+pub struct Cache {
+    root: PathBuf,
+    ttl: Duration,
+    offline: bool,
+}
+
+/// The entry of a cached source.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CacheEntry {
+    /// The cached source, e.g. `https://github.com/org/repo.git@v1.0.0`.
+    pub source: String,
+    /// The digest of the content, also the name of its directory.
+    pub digest: String,
+    /// True if the content of the source never changes, e.g. a git commit SHA.
+    pub immutable: bool,
+    /// When the source was fetched, in seconds since the Unix epoch.
+    pub fetched_at: u64,
+}
+
+impl CacheEntry {
+    /// Returns the time elapsed since the source was fetched.
+    #[must_use]
+    pub fn age(&self) -> Duration {
+        Duration::from_secs(now().saturating_sub(self.fetched_at))
+    }
+}
+
+/// What [`Cache::prune`] removed.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct PruneReport {
+    /// The number of removed entries.
+    pub entries: usize,
+    /// The number of bytes freed.
+    pub bytes: u64,
+}
+
+impl Cache {
+    /// Creates a cache stored in `root`, online and with the default time-to-live.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            ttl: DEFAULT_TTL,
+            offline: false,
+        }
+    }
+
+    /// Returns the default cache directory, i.e. `~/.weaver/cache`.
+    pub fn default_dir() -> Result<PathBuf, Error> {
+        let home = dirs::home_dir().ok_or(Error::HomeDirNotFound)?;
+        Ok(home.join(".weaver/cache"))
+    }
+
+    /// Sets the time-to-live of the entries.
+    #[must_use]
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Sets the offline mode, where only cached sources are used.
+    #[must_use]
+    pub fn with_offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    /// Returns the cache directory.
+    #[must_use]
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Returns the time-to-live of the entries.
+    #[must_use]
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Returns true if the offline mode is enabled.
+    #[must_use]
+    pub fn is_offline(&self) -> bool {
+        self.offline
+    }
+
+    /// Returns true if the entry is older than the time-to-live and not immutable.
+    #[must_use]
+    pub fn is_expired(&self, entry: &CacheEntry) -> bool {
+        !entry.immutable && entry.age() >= self.ttl
+    }
+
+    /// Returns the path of the content of an entry.
+    #[must_use]
+    pub fn content_path(&self, entry: &CacheEntry) -> PathBuf {
+        self.root.join("content").join(&entry.digest)
+    }
+
+    /// Returns the size in bytes of the content of an entry.
+    #[must_use]
+    pub fn content_size(&self, entry: &CacheEntry) -> u64 {
+        dir_size(&self.content_path(entry))
+    }
+
+    /// Returns the entries of the cache, sorted by source. Unreadable entries and
+    /// entries whose content is missing are ignored.
+    pub fn entries(&self) -> Result<Vec<CacheEntry>, Error> {
+        let mut entries: Vec<CacheEntry> = self
+            .all_entries()?
+            .into_iter()
+            .filter(|entry| self.content_path(entry).exists())
+            .collect();
+        entries.sort_by(|a, b| a.source.cmp(&b.source));
+        Ok(entries)
+    }
+
+    /// Returns the entry of a source, if cached.
+    #[must_use]
+    pub fn entry(&self, source: &str) -> Option<CacheEntry> {
+        read_entry(&self.entry_path(source)).filter(|entry| self.content_path(entry).exists())
+    }
+
+    /// Returns the path of the cached content of a source, fetching it if it is missing
+    /// or expired.
+    ///
+    /// `fetch` populates the empty directory it receives and returns the digest of the
+    /// content. `immutable` marks the sources whose content never changes.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::SourceNotCached`] if the source is missing in offline mode, or
+    /// the error of `fetch` if the source is missing or can't be refreshed.
+    pub fn get_or_fetch(
+        &self,
+        source: &str,
+        immutable: bool,
+        fetch: impl FnOnce(&Path) -> Result<String, Error>,
+    ) -> Result<PathBuf, Error> {
+        let cached = self.entry(source);
+        match &cached {
+            Some(entry) if self.offline || !self.is_expired(entry) => {
+                return Ok(self.content_path(entry));
+            }
+            None if self.offline => {
+                return Err(Error::SourceNotCached {
+                    url: source.to_owned(),
+                });
+            }
+            _ => {}
+        }
+
+        match self.fetch(source, immutable, fetch) {
+            Ok(path) => Ok(path),
+            Err(error) => match cached {
+                Some(entry) => {
+                    warn!(
+                        "Failed to refresh `{source}`, using the copy cached {}s ago: {error}",
+                        entry.age().as_secs()
+                    );
+                    Ok(self.content_path(&entry))
+                }
+                None => Err(error),
+            },
+        }
+    }
+
+    /// Removes the entries matching `filter`, then the content no longer referenced
+    /// and the leftovers of interrupted fetches.
+    pub fn prune(&self, filter: impl Fn(&CacheEntry) -> bool) -> Result<PruneReport, Error> {
+        let mut report = PruneReport::default();
+        let mut kept = Vec::new();
+        for entry in self.all_entries()? {
+            if filter(&entry) {
+                let path = self.entry_path(&entry.source);
+                fs::remove_file(&path).map_err(|e| cache_error(&path, e))?;
+                report.entries += 1;
+            } else {
+                kept.push(entry.digest);
+            }
+        }
+
+        let content_dir = self.root.join("content");
+        for (path, _) in children(&content_dir)? {
+            let digest = path.file_name().map(|name| name.to_string_lossy());
+            if digest.is_some_and(|digest| !kept.iter().any(|kept| *kept == digest)) {
+                report.bytes += dir_size(&path);
+                fs::remove_dir_all(&path).map_err(|e| cache_error(&path, e))?;
+            }
+        }
+
+        for (path, age) in children(&self.root.join("tmp"))? {
+            if age >= STALE_STAGING_AGE {
+                report.bytes += dir_size(&path);
+                fs::remove_dir_all(&path).map_err(|e| cache_error(&path, e))?;
+            }
+        }
+        Ok(report)
+    }
+
+    /// Fetches a source into the staging area, publishes its content and records its entry.
+    fn fetch(
+        &self,
+        source: &str,
+        immutable: bool,
+        fetch: impl FnOnce(&Path) -> Result<String, Error>,
+    ) -> Result<PathBuf, Error> {
+        let staging_root = self.root.join("tmp");
+        let content_root = self.root.join("content");
+        for dir in [&staging_root, &content_root, &self.root.join("entries")] {
+            fs::create_dir_all(dir).map_err(|e| Error::CacheDirNotCreated {
+                message: format!("{}: {e}", dir.display()),
+            })?;
+        }
+        // Deleted on drop, whether the fetch succeeds or not.
+        let staging = tempfile::Builder::new()
+            .prefix("fetch")
+            .tempdir_in(&staging_root)
+            .map_err(|e| cache_error(&staging_root, e))?;
+        let staged_content = staging.path().join("content");
+        fs::create_dir_all(&staged_content).map_err(|e| cache_error(&staged_content, e))?;
+
+        let digest = fetch(&staged_content)?;
+        let content_path = content_root.join(&digest);
+        if !content_path.exists() {
+            // A concurrent fetch of the same content may win the race, which is fine.
+            if let Err(e) = fs::rename(&staged_content, &content_path) {
+                if !content_path.exists() {
+                    return Err(cache_error(&content_path, e));
+                }
+            }
+        }
+
+        let entry = CacheEntry {
+            source: source.to_owned(),
+            digest,
+            immutable,
+            fetched_at: now(),
+        };
+        let staged_entry = staging.path().join("entry.json");
+        let json = serde_json::to_string_pretty(&entry).map_err(|e| Error::CacheError {
+            path: staged_entry.display().to_string(),
+            error: e.to_string(),
+        })?;
+        fs::write(&staged_entry, json).map_err(|e| cache_error(&staged_entry, e))?;
+        let entry_path = self.entry_path(source);
+        fs::rename(&staged_entry, &entry_path).map_err(|e| cache_error(&entry_path, e))?;
+        Ok(content_path)
+    }
+
+    /// Returns every readable entry, including the ones whose content is missing.
+    fn all_entries(&self) -> Result<Vec<CacheEntry>, Error> {
+        Ok(children(&self.root.join("entries"))?
+            .into_iter()
+            .filter_map(|(path, _)| read_entry(&path))
+            .collect())
+    }
+
+    /// Returns the path of the entry of a source.
+    fn entry_path(&self, source: &str) -> PathBuf {
+        let key = hex(&Sha256::digest(source.as_bytes()));
+        self.root.join("entries").join(format!("{key}.json"))
+    }
+}
+
+/// Returns the `sha256-<hash>` digest of `prefix` followed by the content of a file.
+pub(crate) fn file_digest(path: &Path, prefix: &[u8]) -> io::Result<String> {
+    let mut hasher = Sha256::new();
+    hasher.update(prefix);
+    _ = io::copy(&mut File::open(path)?, &mut hasher)?;
+    Ok(format!("sha256-{}", hex(&hasher.finalize())))
+}
+
+/// Lowercase hexadecimal representation of bytes.
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Current time in seconds since the Unix epoch.
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Reads an entry file, `None` if it's missing or invalid.
+fn read_entry(path: &Path) -> Option<CacheEntry> {
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Returns the children of a directory with the time elapsed since their last
+/// modification, none if the directory doesn't exist.
+fn children(dir: &Path) -> Result<Vec<(PathBuf, Duration)>, Error> {
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+    Ok(fs::read_dir(dir)
+        .map_err(|e| cache_error(dir, e))?
+        .filter_map(Result::ok)
+        .map(|child| {
+            let age = child
+                .metadata()
+                .and_then(|metadata| metadata.modified())
+                .ok()
+                .and_then(|modified| modified.elapsed().ok())
+                .unwrap_or_default();
+            (child.path(), age)
+        })
+        .collect())
+}
+
+/// Copies the cached content `from` into the directory `to`, recursively. Symbolic
+/// links are copied as links on Unix.
+pub(crate) fn copy_content(from: &Path, to: &Path) -> Result<(), Error> {
+    for child in fs::read_dir(from).map_err(|e| cache_error(from, e))? {
+        let child = child.map_err(|e| cache_error(from, e))?;
+        let source = child.path();
+        let target = to.join(child.file_name());
+        let file_type = child.file_type().map_err(|e| cache_error(&source, e))?;
+        #[cfg(unix)]
+        if file_type.is_symlink() {
+            let link = fs::read_link(&source).map_err(|e| cache_error(&source, e))?;
+            std::os::unix::fs::symlink(link, &target).map_err(|e| cache_error(&target, e))?;
+            continue;
+        }
+        if file_type.is_dir() {
+            fs::create_dir_all(&target).map_err(|e| cache_error(&target, e))?;
+            copy_content(&source, &target)?;
+        } else {
+            _ = fs::copy(&source, &target).map_err(|e| cache_error(&target, e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Total size in bytes of the files of a directory, symbolic links not followed.
+fn dir_size(path: &Path) -> u64 {
+    match fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.is_dir() => fs::read_dir(path)
+            .map(|children| {
+                children
+                    .filter_map(Result::ok)
+                    .map(|child| dir_size(&child.path()))
+                    .sum()
+            })
+            .unwrap_or_default(),
+        Ok(metadata) => metadata.len(),
+        Err(_) => 0,
+    }
+}
+
+fn cache_error(path: &Path, error: io::Error) -> Error {
+    Error::CacheError {
+        path: path.display().to_string(),
+        error: error.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    /// Writes `content` into the fetched directory and returns its digest.
+    fn fetch_content<'a>(
+        content: &str,
+        calls: &'a Cell<usize>,
+    ) -> impl FnOnce(&Path) -> Result<String, Error> + 'a {
+        let content = content.to_owned();
+        move |target| {
+            calls.set(calls.get() + 1);
+            fs::write(target.join("file.txt"), &content).expect("Failed to write");
+            Ok(file_digest(&target.join("file.txt"), b"").expect("Failed to hash"))
+        }
+    }
+
+    fn failed_fetch(_: &Path) -> Result<String, Error> {
+        Err(Error::RemoteFileDownloadFailed {
+            url: "https://example.com/registry.zip".to_owned(),
+            error: "no network".to_owned(),
+        })
+    }
+
+    #[test]
+    fn test_get_or_fetch() {
+        let dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let cache = Cache::new(dir.path());
+        let calls = Cell::new(0);
+
+        let path = cache
+            .get_or_fetch(
+                "https://example.com/a.zip",
+                false,
+                fetch_content("a", &calls),
+            )
+            .expect("Fetch failed");
+        assert_eq!(fs::read_to_string(path.join("file.txt")).unwrap(), "a");
+        assert!(path.ends_with(cache.entry("https://example.com/a.zip").unwrap().digest));
+
+        // Fresh entries are reused.
+        let cached = cache
+            .get_or_fetch(
+                "https://example.com/a.zip",
+                false,
+                fetch_content("b", &calls),
+            )
+            .expect("Fetch failed");
+        assert_eq!(cached, path);
+        assert_eq!(calls.get(), 1);
+
+        // Sources with the same content share it.
+        let shared = cache
+            .get_or_fetch(
+                "https://mirror.com/a.zip",
+                false,
+                fetch_content("a", &calls),
+            )
+            .expect("Fetch failed");
+        assert_eq!(shared, path);
+        assert_eq!(calls.get(), 2);
+        assert_eq!(cache.entries().unwrap().len(), 2);
+        assert_eq!(fs::read_dir(dir.path().join("content")).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_expiration() {
+        let dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let cache = Cache::new(dir.path()).with_ttl(Duration::ZERO);
+        let calls = Cell::new(0);
+
+        _ = cache
+            .get_or_fetch("repo@main", false, fetch_content("a", &calls))
+            .expect("Fetch failed");
+        let path = cache
+            .get_or_fetch("repo@main", false, fetch_content("b", &calls))
+            .expect("Fetch failed");
+        assert_eq!(calls.get(), 2);
+        assert_eq!(fs::read_to_string(path.join("file.txt")).unwrap(), "b");
+
+        // Expired entries are used when they can't be refreshed.
+        let stale = cache
+            .get_or_fetch("repo@main", false, failed_fetch)
+            .expect("The stale entry should be used");
+        assert_eq!(stale, path);
+
+        // Immutable entries never expire.
+        _ = cache
+            .get_or_fetch("repo@0123456789abcdef", true, fetch_content("c", &calls))
+            .expect("Fetch failed");
+        _ = cache
+            .get_or_fetch("repo@0123456789abcdef", true, fetch_content("d", &calls))
+            .expect("Fetch failed");
+        assert_eq!(calls.get(), 3);
+    }
+
+    #[test]
+    fn test_offline() {
+        let dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let calls = Cell::new(0);
+        let path = Cache::new(dir.path())
+            .get_or_fetch("repo", false, fetch_content("a", &calls))
+            .expect("Fetch failed");
+
+        let offline = Cache::new(dir.path())
+            .with_ttl(Duration::ZERO)
+            .with_offline(true);
+        assert_eq!(
+            offline
+                .get_or_fetch("repo", false, fetch_content("b", &calls))
+                .expect("The cached entry should be used"),
+            path
+        );
+        assert_eq!(calls.get(), 1);
+        assert_eq!(
+            offline.get_or_fetch("other", false, fetch_content("b", &calls)),
+            Err(Error::SourceNotCached {
+                url: "other".to_owned()
+            })
+        );
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn test_prune() {
+        let dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let cache = Cache::new(dir.path());
+        let calls = Cell::new(0);
+        for (source, content) in [("a", "a"), ("b", "b"), ("shared", "b")] {
+            _ = cache
+                .get_or_fetch(source, false, fetch_content(content, &calls))
+                .expect("Fetch failed");
+        }
+        assert!(cache.get_or_fetch("failed", false, failed_fetch).is_err());
+        // A failed fetch leaves nothing behind.
+        assert_eq!(fs::read_dir(dir.path().join("tmp")).unwrap().count(), 0);
+
+        // Content still referenced by an entry is kept.
+        let report = cache.prune(|entry| entry.source == "b").unwrap();
+        assert_eq!(
+            report,
+            PruneReport {
+                entries: 1,
+                bytes: 0
+            }
+        );
+        let report = cache.prune(|entry| entry.source == "a").unwrap();
+        assert_eq!(
+            report,
+            PruneReport {
+                entries: 1,
+                bytes: 1
+            }
+        );
+
+        let report = cache.prune(|_| true).unwrap();
+        assert_eq!(
+            report,
+            PruneReport {
+                entries: 1,
+                bytes: 1
+            }
+        );
+        assert!(cache.entries().unwrap().is_empty());
+        assert_eq!(fs::read_dir(dir.path().join("content")).unwrap().count(), 0);
+    }
+}
@@ -2,6 +2,7 @@
 
 #![doc = include_str!("../README.md")]
 
+pub mod cache;
 pub mod diagnostic;
 pub mod error;
 pub mod http_auth;
@@ -37,6 +38,22 @@ pub enum Error {
         message: String,
     },
 
+    /// A cache operation failed.
+    #[error("Cache error on `{path}`: {error}")]
+    CacheError {
+        /// The path in the cache
+        path: String,
+        /// The error message
+        error: String,
+    },
+
+    /// A remote source is not cached while offline.
+    #[error("`{url}` is not in the cache and the offline mode is enabled")]
+    SourceNotCached {
+        /// The URL of the source, with its refspec if any
+        url: String,
+    },
+
     /// Git repo not created.
     #[error("Git repo `{repo_url}` not created: {message}")]
     GitRepoNotCreated {
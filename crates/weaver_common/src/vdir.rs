@@ -59,6 +59,7 @@
 //!    of a Git repo, so the `.git` suffix is not required.
 //! 3. Otherwise → remote file.
 
+use crate::cache::{copy_content, file_digest, global_cache, Cache};
 use crate::http_auth::HttpAuthResolver;
 use crate::vdir::VirtualDirectoryPath::{
     GitRepo, LocalArchive, LocalFolder, RemoteArchive, RemoteFile,
@@ -77,7 +78,7 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt::Display;
-use std::fs::{create_dir_all, File};
+use std::fs::{create_dir_all, remove_dir_all, File};
 use std::io;
 use std::num::NonZeroU32;
 use std::path::{Path, PathBuf};
@@ -556,6 +557,8 @@ impl Display for VirtualDirectoryPath {
 /// - Simply pointing to an existing local directory.
 /// - Cloning a Git repository into a temporary cache directory.
 /// - Downloading and extracting an archive into a temporary cache directory.
+/// - Copying the content of a remote source from the persistent cache, when enabled,
+///   into a temporary cache directory.
 ///
 /// Temporary directories are managed and automatically cleaned up when this struct goes out of scope.
 #[derive(Default, Debug, Clone)]
@@ -609,30 +612,99 @@ impl VirtualDirectory {
     }
 
     /// Resolve a [`VirtualDirectoryPath`], using `auth` to look up Bearer
-    /// credentials for any remote HTTP fetches.
+    /// credentials for any remote HTTP fetches. Remote sources are reused from the
+    /// persistent cache when it is enabled, see [`crate::cache::init_global_cache`].
     pub fn try_new_with_auth(
         vdir_path: &VirtualDirectoryPath,
         auth: &HttpAuthResolver,
+    ) -> Result<Self, Error> {
+        Self::try_new_with_cache(vdir_path, auth, global_cache())
+    }
+
+    /// Resolve a [`VirtualDirectoryPath`], fetching the remote sources through `cache`
+    /// if set, or into temporary directories otherwise.
+    pub fn try_new_with_cache(
+        vdir_path: &VirtualDirectoryPath,
+        auth: &HttpAuthResolver,
+        cache: Option<&Cache>,
     ) -> Result<Self, Error> {
         let vdir_path_repr = vdir_path.to_string();
-        let vdir = match vdir_path {
-            LocalFolder { path } => Ok(Self {
+        let vdir = match (vdir_path, cache) {
+            (LocalFolder { path }, _) => Ok(Self {
                 vdir_path: vdir_path_repr,
                 path: path.into(),
                 tmp_dir: Arc::new(None),
             }),
-            GitRepo {
-                url,
-                sub_folder,
-                refspec,
-            } => Self::try_from_git_url(url, sub_folder, refspec, vdir_path_repr),
-            LocalArchive { path, sub_folder } => {
+            (
+                GitRepo {
+                    url,
+                    sub_folder,
+                    refspec,
+                },
+                Some(cache),
+            ) => {
+                let source = match refspec {
+                    Some(refspec) => format!("{url}@{refspec}"),
+                    None => url.clone(),
+                };
+                let is_sha = refspec.as_ref().is_some_and(|r| is_commit_sha(r));
+                let root = cache.get_or_fetch(&source, is_sha, |target| {
+                    let commit = Self::clone_git_repo(url, refspec, target)?;
+                    // Only the worktree is cached.
+                    remove_dir_all(target.join(".git")).map_err(|e| GitError {
+                        repo_url: url.to_owned(),
+                        message: e.to_string(),
+                    })?;
+                    Ok(format!("git-{commit}"))
+                })?;
+                let tmp_dir = Self::copy_cached_content(&root)?;
+                Ok(Self {
+                    vdir_path: vdir_path_repr,
+                    path: Self::git_sub_folder(url, tmp_dir.path().to_path_buf(), sub_folder)?,
+                    tmp_dir: Arc::new(Some(tmp_dir)),
+                })
+            }
+            (
+                GitRepo {
+                    url,
+                    sub_folder,
+                    refspec,
+                },
+                None,
+            ) => Self::try_from_git_url(url, sub_folder, refspec, vdir_path_repr),
+            (LocalArchive { path, sub_folder }, _) => {
                 // Create a temporary directory for the virtual directory that will be deleted
                 // when the `VirtualDirectory` goes out of scope.
                 let tmp_dir = Self::create_tmp_repo()?;
                 Self::try_from_local_archive(path, sub_folder.as_ref(), tmp_dir, vdir_path_repr)
             }
-            RemoteArchive { url, sub_folder } => {
+            (RemoteArchive { url, sub_folder }, Some(cache)) => {
+                // The whole archive is cached, the sub-folder is selected afterwards.
+                let root = cache.get_or_fetch(url, false, |target| {
+                    let download_dir = Self::create_tmp_repo()?;
+                    let archive = Self::download_archive(url, download_dir.path(), auth)?;
+                    let digest =
+                        file_digest(&archive, b"").map_err(|e| InvalidRegistryArchive {
+                            archive: url.to_owned(),
+                            error: e.to_string(),
+                        })?;
+                    Self::unpack_archive(archive.to_str().unwrap_or_default(), None, target)?;
+                    Ok(digest)
+                })?;
+                let tmp_dir = Self::copy_cached_content(&root)?;
+                let path = match sub_folder {
+                    Some(sub_folder) if !sub_folder.trim().is_empty() => {
+                        tmp_dir.path().join(sub_folder)
+                    }
+                    _ => tmp_dir.path().to_path_buf(),
+                };
+                Ok(Self {
+                    vdir_path: vdir_path_repr,
+                    path,
+                    tmp_dir: Arc::new(Some(tmp_dir)),
+                })
+            }
+            (RemoteArchive { url, sub_folder }, None) => {
                 // Create a temporary directory for the virtual directory that will be deleted
                 // when the `VirtualDirectory` goes out of scope.
                 let tmp_dir = Self::create_tmp_repo()?;
@@ -644,7 +716,30 @@ impl VirtualDirectory {
                     auth,
                 )
             }
-            RemoteFile { url } => {
+            (RemoteFile { url }, Some(cache)) => {
+                let file_name = Self::remote_file_name(url)?;
+                let root = cache.get_or_fetch(url, false, |target| {
+                    let save_path = target.join(&file_name);
+                    download_to_file(url, &save_path, auth, |error| RemoteFileDownloadFailed {
+                        url: url.to_owned(),
+                        error,
+                    })?;
+                    // The file name is part of the digest as the content is stored under it.
+                    file_digest(&save_path, format!("{file_name}\0").as_bytes()).map_err(|e| {
+                        RemoteFileDownloadFailed {
+                            url: url.to_owned(),
+                            error: e.to_string(),
+                        }
+                    })
+                })?;
+                let tmp_dir = Self::copy_cached_content(&root)?;
+                Ok(Self {
+                    vdir_path: vdir_path_repr,
+                    path: tmp_dir.path().join(file_name),
+                    tmp_dir: Arc::new(Some(tmp_dir)),
+                })
+            }
+            (RemoteFile { url }, None) => {
                 let tmp_dir = Self::create_tmp_repo()?;
                 Self::try_from_remote_file(url, tmp_dir, vdir_path_repr, auth)
             }
@@ -670,15 +765,26 @@ impl VirtualDirectory {
     ) -> Result<Self, Error> {
         let tmp_dir = Self::create_tmp_repo()?;
         let tmp_path = tmp_dir.path().to_path_buf();
+        _ = Self::clone_git_repo(url, refspec, &tmp_path)?;
+
+        Ok(Self {
+            vdir_path,
+            path: Self::git_sub_folder(url, tmp_path, sub_folder)?,
+            tmp_dir: Arc::new(Some(tmp_dir)),
+        })
+    }
 
-        // Clones the repo into the temporary directory.
+    /// Clones a Git repository into the empty directory `target` and checks out the
+    /// refspec, or the default branch. Returns the id of the checked out commit.
+    fn clone_git_repo(url: &str, refspec: &Option<String>, target: &Path) -> Result<String, Error> {
+        // Clones the repo into the target directory.
         // Use shallow clone to save time and space when no specific refspec is given.
         // When a refspec is provided, we skip shallow clone because gix's shallow+single-branch
         // code path assumes the ref is a branch (refs/heads/), which breaks for tags (refs/tags/).
         // See upstream issue: https://github.com/GitoxideLabs/gitoxide/issues/2554
         let prepare = PrepareFetch::new(
             url,
-            tmp_path.clone(),
+            target,
             Kind::WithWorktree,
             create::Options {
                 destination_must_be_empty: Some(true),
@@ -732,24 +838,35 @@ impl VirtualDirectory {
                 repo_url: url.to_owned(),
                 message: format!("failed to checkout commit {sha}: {e}"),
             })?;
+            Ok(sha.to_lowercase())
         } else {
-            // Call purely for its side effect: `main_worktree` checks out the
-            // default branch onto disk at `tmp_path`, which is what the returned
-            // `VirtualDirectory` points at. We don't need the resulting
-            // `Repository` — and dropping it is safe: `main_worktree` mutates `checkout`
-            // disarming its delete-clone-on-drop guard, so the worktree files
-            // persist. Temp-dir will be cleaned up separately.
-            let _ = checkout
+            // `main_worktree` checks out the default branch onto disk at `target`.
+            // Dropping the resulting `Repository` is safe: `main_worktree` mutates
+            // `checkout` disarming its delete-clone-on-drop guard, so the worktree
+            // files persist.
+            let (repo, _outcome) = checkout
                 .main_worktree(progress::Discard, &AtomicBool::new(false))
                 .map_err(|e| GitError {
                     repo_url: url.to_owned(),
                     message: e.to_string(),
                 })?;
+            let head = repo.head_id().map_err(|e| GitError {
+                repo_url: url.to_owned(),
+                message: e.to_string(),
+            })?;
+            Ok(head.to_string())
         }
+    }
 
-        // Determines the final path to the repo taking into account the sub_folder.
-        let path = if let Some(sub_folder) = sub_folder {
-            let path_to_repo = tmp_path.join(sub_folder);
+    /// Returns the path of the sub-folder of a cloned repository, or the path of the
+    /// repository itself.
+    fn git_sub_folder(
+        url: &str,
+        repo_path: PathBuf,
+        sub_folder: &Option<String>,
+    ) -> Result<PathBuf, Error> {
+        if let Some(sub_folder) = sub_folder {
+            let path_to_repo = repo_path.join(sub_folder);
 
             // Checks the existence of the path in the repo.
             // If the path doesn't exist, returns an error.
@@ -760,16 +877,10 @@ impl VirtualDirectory {
                 });
             }
 
-            path_to_repo
+            Ok(path_to_repo)
         } else {
-            tmp_path
-        };
-
-        Ok(Self {
-            vdir_path,
-            path,
-            tmp_dir: Arc::new(Some(tmp_dir)),
-        })
+            Ok(repo_path)
+        }
     }
 
     /// Checkout a specific commit SHA in a cloned repository using gix APIs.
@@ -821,6 +932,23 @@ impl VirtualDirectory {
         target_dir: TempDir,
         vdir_path: String,
     ) -> Result<Self, Error> {
+        let target_path_buf = target_dir.path().to_path_buf();
+        Self::unpack_archive(archive_filename, sub_folder, &target_path_buf)?;
+
+        Ok(Self {
+            vdir_path,
+            path: target_path_buf,
+            tmp_dir: Arc::new(Some(target_dir)),
+        })
+    }
+
+    /// Unpacks a `.tar.gz` or `.zip` archive into `target_path`, keeping only the
+    /// entries of the sub-folder if set.
+    fn unpack_archive(
+        archive_filename: &str,
+        sub_folder: Option<&String>,
+        target_path: &Path,
+    ) -> Result<(), Error> {
         let archive_path = Path::new(archive_filename);
         if !archive_path.exists() {
             return Err(InvalidRegistryArchive {
@@ -832,24 +960,17 @@ impl VirtualDirectory {
             archive: archive_filename.to_owned(),
             error: e.to_string(),
         })?;
-        let target_path_buf = target_dir.path().to_path_buf();
 
         // Process the supported formats (i.e.: `.tar.gz`, and `.zip`)
         if archive_filename.ends_with(TAR_GZ_EXT) {
-            Self::unpack_tar_gz(archive_filename, archive_file, &target_path_buf, sub_folder)?;
+            Self::unpack_tar_gz(archive_filename, archive_file, target_path, sub_folder)
         } else if archive_filename.ends_with(ZIP_EXT) {
-            Self::unpack_zip(archive_filename, archive_file, &target_path_buf, sub_folder)?;
+            Self::unpack_zip(archive_filename, archive_file, target_path, sub_folder)
         } else {
-            return Err(UnsupportedRegistryArchive {
+            Err(UnsupportedRegistryArchive {
                 archive: archive_filename.to_owned(),
-            });
-        };
-
-        Ok(Self {
-            vdir_path,
-            path: target_path_buf,
-            tmp_dir: Arc::new(Some(target_dir)),
-        })
+            })
+        }
     }
 
     /// Extracts the contents of a `.tar.gz` archive into the specified directory.
@@ -1034,7 +1155,18 @@ impl VirtualDirectory {
         vdir_path: String,
         auth: &HttpAuthResolver,
     ) -> Result<Self, Error> {
-        let tmp_path = target_dir.path().to_path_buf();
+        let save_path = Self::download_archive(url, target_dir.path(), auth)?;
+
+        Self::try_from_local_archive(
+            save_path.to_str().unwrap_or_default(),
+            sub_folder,
+            target_dir,
+            vdir_path,
+        )
+    }
+
+    /// Downloads a remote archive into `dir` and returns the path of the archive file.
+    fn download_archive(url: &str, dir: &Path, auth: &HttpAuthResolver) -> Result<PathBuf, Error> {
         let err = |msg: String| InvalidRegistryArchive {
             archive: url.to_owned(),
             error: msg,
@@ -1049,15 +1181,9 @@ impl VirtualDirectory {
             .and_then(|name| if name.is_empty() { None } else { Some(name) })
             .ok_or_else(|| err("Failed to extract file name from URL".to_owned()))?;
 
-        let save_path = tmp_path.join(file_name);
+        let save_path = dir.join(file_name);
         download_to_file(url, &save_path, auth, err)?;
-
-        Self::try_from_local_archive(
-            save_path.to_str().unwrap_or_default(),
-            sub_folder,
-            target_dir,
-            vdir_path,
-        )
+        Ok(save_path)
     }
 
     /// Downloads a single remote file via HTTP(S) into a temporary directory.
@@ -1073,23 +1199,11 @@ impl VirtualDirectory {
         vdir_path: String,
         auth: &HttpAuthResolver,
     ) -> Result<Self, Error> {
-        let tmp_path = target_dir.path().to_path_buf();
-        let err = |msg: String| RemoteFileDownloadFailed {
+        let save_path = target_dir.path().join(Self::remote_file_name(url)?);
+        download_to_file(url, &save_path, auth, |error| RemoteFileDownloadFailed {
             url: url.to_owned(),
-            error: msg,
-        };
-
-        // Use the original URL for the filename (not the resolved API URL, which
-        // has an opaque numeric asset ID).
-        let parsed_url = Url::parse(url).map_err(|e| err(e.to_string()))?;
-        let file_name = parsed_url
-            .path_segments()
-            .and_then(|mut segments| segments.next_back())
-            .and_then(|name| if name.is_empty() { None } else { Some(name) })
-            .unwrap_or("downloaded_file");
-
-        let save_path = tmp_path.join(file_name);
-        download_to_file(url, &save_path, auth, err)?;
+            error,
+        })?;
 
         Ok(Self {
             vdir_path,
@@ -1098,6 +1212,23 @@ impl VirtualDirectory {
         })
     }
 
+    /// Returns the name of the file downloaded from a URL.
+    ///
+    /// Uses the original URL for the filename (not the resolved API URL, which
+    /// has an opaque numeric asset ID).
+    fn remote_file_name(url: &str) -> Result<String, Error> {
+        let parsed_url = Url::parse(url).map_err(|e| RemoteFileDownloadFailed {
+            url: url.to_owned(),
+            error: e.to_string(),
+        })?;
+        Ok(parsed_url
+            .path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .and_then(|name| if name.is_empty() { None } else { Some(name) })
+            .unwrap_or("downloaded_file")
+            .to_owned())
+    }
+
     /// Returns the local filesystem path to the resolved virtual directory content.
     #[must_use]
     pub fn path(&self) -> &Path {
@@ -1118,6 +1249,14 @@ impl VirtualDirectory {
             .expect("VirtualDirectory should not have invalid `vdir_path`.")
     }
 
+    /// Copies the content of a cache entry into a new temporary directory, so that the
+    /// virtual directory can't alter the cached content.
+    fn copy_cached_content(root: &Path) -> Result<TempDir, Error> {
+        let tmp_dir = Self::create_tmp_repo()?;
+        copy_content(root, tmp_dir.path())?;
+        Ok(tmp_dir)
+    }
+
     /// Creates and returns a new temporary directory within `.weaver/vdir_cache`.
     ///
     /// The created directory and its contents are automatically deleted when dropped.
@@ -1553,6 +1692,81 @@ mod tests {
         assert_eq!(content, "file: A");
     }
 
+    #[test]
+    fn test_cached_remote_sources() {
+        use crate::cache::Cache;
+        use crate::http_auth::HttpAuthResolver;
+
+        let cache_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let cache = Cache::new(cache_dir.path());
+        let server = ServeStaticFiles::from("tests/test_data").expect("failed to start server");
+        let archive_path = format!(
+            "{}[model]",
+            server.relative_path_to_url("semconv_registry_v1.26.0.tar.gz")
+        )
+        .parse::<VirtualDirectoryPath>()
+        .expect("failed to parse registry path");
+        let file_path = VirtualDirectoryPath::RemoteFile {
+            url: server.relative_path_to_url("file_a.yaml"),
+        };
+
+        let archive = VirtualDirectory::try_new_with_cache(
+            &archive_path,
+            &HttpAuthResolver::empty(),
+            Some(&cache),
+        )
+        .expect("failed to fetch the remote archive");
+        assert!(archive.path().join("general.yaml").exists());
+        let file = VirtualDirectory::try_new_with_cache(
+            &file_path,
+            &HttpAuthResolver::empty(),
+            Some(&cache),
+        )
+        .expect("failed to fetch the remote file");
+        assert_eq!(cache.entries().expect("failed to list entries").len(), 2);
+
+        // The virtual directories work on a copy of the cached content, which outlives
+        // them and the server.
+        let entry = cache
+            .entry(&server.relative_path_to_url("semconv_registry_v1.26.0.tar.gz"))
+            .expect("the archive should be cached");
+        let cached_general = cache.content_path(&entry).join("model/general.yaml");
+        let general = std::fs::read_to_string(&cached_general).expect("failed to read");
+        std::fs::write(archive.path().join("general.yaml"), "corrupted").expect("failed to write");
+        std::fs::write(file.path(), "corrupted").expect("failed to write");
+        let archive_dir = archive.path().to_path_buf();
+        drop(archive);
+        drop(file);
+        drop(server);
+        assert!(!archive_dir.exists());
+        assert_eq!(
+            std::fs::read_to_string(&cached_general).expect("failed to read"),
+            general
+        );
+
+        let offline = Cache::new(cache_dir.path()).with_offline(true);
+        let archive = VirtualDirectory::try_new_with_cache(
+            &archive_path,
+            &HttpAuthResolver::empty(),
+            Some(&offline),
+        )
+        .expect("the archive should be cached");
+        assert_eq!(
+            std::fs::read_to_string(archive.path().join("general.yaml")).expect("failed to read"),
+            general
+        );
+        let file = VirtualDirectory::try_new_with_cache(
+            &file_path,
+            &HttpAuthResolver::empty(),
+            Some(&offline),
+        )
+        .expect("the file should be cached");
+        assert_eq!(
+            std::fs::read_to_string(file.path()).expect("failed to read cached file"),
+            "file: A"
+        );
+    }
+
     #[test]
     fn test_parse_github_release_url() {
         use super::parse_github_release_url;
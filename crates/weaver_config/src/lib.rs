@@ -26,7 +26,7 @@ pub use effective::{
 };
 pub use live_check::{
     FailOnLevel, FindingFilter, FindingLevelOverride, LiveCheckConfig, LiveCheckEmitConfig,
-    LiveCheckOtlpConfig,
+    LiveCheckOtlpConfig, LiveCheckRecordConfig, LiveCheckSamplingConfig,
 };
 pub use overrides::{CliOverrides, CommandConfig, FieldMapping};
 pub use registry::{DiagnosticsConfig, PolicyConfig, RegistryConfig};
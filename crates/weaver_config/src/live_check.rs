@@ -93,7 +93,8 @@ pub struct LiveCheckConfig {
     /// Where to read the input telemetry from. `{file path}` | `stdin` | `otlp`.
     pub input_source: String,
 
-    /// The format of the input telemetry. `text` | `json`. (Not used for OTLP.)
+    /// The format of the input telemetry. `text` | `json` | `otlp-json` |
+    /// `otlp-proto-{traces,metrics,logs,profiles}`. (Not used for OTLP.)
     pub input_format: String,
 
     /// Format used to render the report.
@@ -110,6 +111,13 @@ pub struct LiveCheckConfig {
     /// Disable statistics accumulation. Useful for long-running live-check sessions.
     pub no_stats: bool,
 
+    /// Report the findings aggregated by `(id, signal_type, signal_name, attribute)`
+    /// instead of attached to every sample. Forces report mode.
+    pub aggregate: bool,
+
+    /// Number of first and last example contexts kept for each aggregated finding.
+    pub aggregate_examples: usize,
+
     /// Severity threshold that causes a non-zero exit code. Findings at this
     /// level or higher fail the run. Use `none` to never fail.
     pub fail_on: FailOnLevel,
@@ -135,6 +143,12 @@ pub struct LiveCheckConfig {
 
     /// OTLP log emission settings.
     pub emit: LiveCheckEmitConfig,
+
+    /// Recording of the received OTLP requests (used when `input_source = "otlp"`).
+    pub record: LiveCheckRecordConfig,
+
+    /// Sampling of the checked samples, for long-running or high-volume sessions.
+    pub sampling: LiveCheckSamplingConfig,
 }
 
 impl Default for LiveCheckConfig {
@@ -148,6 +162,8 @@ impl Default for LiveCheckConfig {
             templates: PathBuf::from("live_check_templates"),
             no_stream: false,
             no_stats: false,
+            aggregate: false,
+            aggregate_examples: 3,
             fail_on: FailOnLevel::default(),
             output: None,
             advice_policies: None,
@@ -155,6 +171,8 @@ impl Default for LiveCheckConfig {
             advice_preprocessor: None,
             otlp: LiveCheckOtlpConfig::default(),
             emit: LiveCheckEmitConfig::default(),
+            record: LiveCheckRecordConfig::default(),
+            sampling: LiveCheckSamplingConfig::default(),
         }
     }
 }
@@ -167,6 +185,15 @@ pub struct LiveCheckOtlpConfig {
     pub grpc_address: String,
     /// Port used by the gRPC OTLP listener.
     pub grpc_port: u16,
+    /// Enable the OTLP/HTTP listener (protobuf and JSON).
+    pub http: bool,
+    /// Address used by the OTLP/HTTP listener. Defaults to the gRPC address.
+    pub http_address: Option<String>,
+    /// Port used by the OTLP/HTTP listener.
+    pub http_port: u16,
+    /// Upstream OTLP gRPC endpoint every received request is forwarded to,
+    /// unchanged (proxy mode). Forwarding is disabled when unset.
+    pub forward_endpoint: Option<String>,
     /// Port used by the HTTP admin port (endpoints: `/stop`, `/health`).
     pub admin_port: u16,
     /// Max inactivity time in seconds before stopping the listener.
@@ -178,6 +205,10 @@ impl Default for LiveCheckOtlpConfig {
         Self {
             grpc_address: "0.0.0.0".to_owned(),
             grpc_port: 4317,
+            http: false,
+            http_address: None,
+            http_port: 4318,
+            forward_endpoint: None,
             admin_port: 4320,
             inactivity_timeout: 10,
         }
@@ -206,6 +237,51 @@ impl Default for LiveCheckEmitConfig {
     }
 }
 
+/// Settings of the on-disk archive of the received OTLP requests.
+#[derive(Debug, Clone, Deserialize, PartialEq, JsonSchema)]
+#[serde(default)]
+pub struct LiveCheckRecordConfig {
+    /// Directory where the received OTLP requests are recorded. Recording is
+    /// disabled when unset.
+    pub dir: Option<PathBuf>,
+    /// Encoding of the recorded files. `json` (OTLP/JSON lines) | `proto`
+    /// (length-prefixed protobuf, one file per signal).
+    pub format: String,
+    /// Size in MiB after which a recorded file is rotated.
+    pub max_file_size: u64,
+    /// Maximum number of recorded files kept per stream (0 = unlimited).
+    pub max_files: usize,
+}
+
+impl Default for LiveCheckRecordConfig {
+    fn default() -> Self {
+        Self {
+            dir: None,
+            format: "json".to_owned(),
+            max_file_size: 100,
+            max_files: 0,
+        }
+    }
+}
+
+/// Sampling strategies applied to the samples before they are checked.
+///
+/// The strategies are combined: a sample is checked only if every configured
+/// strategy keeps it. Skipped samples are counted in the statistics. Resources
+/// and instrumentation scopes are never skipped.
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, JsonSchema)]
+#[serde(default)]
+pub struct LiveCheckSamplingConfig {
+    /// Fraction of the samples to check, chosen at random (`0.0` to `1.0`).
+    pub ratio: Option<f64>,
+    /// Only check the first N samples of each signal name (e.g. span name or
+    /// metric name). Span links are counted per parent span name.
+    pub first_n: Option<usize>,
+    /// Maximum number of samples checked per second for each signal type
+    /// (token bucket, bursts up to one second of samples).
+    pub rate_limit: Option<f64>,
+}
+
 /// A filter that drops findings by ID exclusion or minimum level.
 /// Optional `signal_type` and `sample_names` scope the filter to a specific
 /// signal type and/or set of sample names.
@@ -335,6 +411,8 @@ format = "ansi"
 templates = "live_check_templates"
 no_stream = false
 no_stats = true
+aggregate = true
+aggregate_examples = 5
 fail_on = "improvement"
 output = "reports"
 advice_policies = "policies"
@@ -344,6 +422,10 @@ advice_preprocessor = "pre.jq"
 ["live-check".otlp]
 grpc_address = "127.0.0.1"
 grpc_port = 4317
+http = true
+http_address = "127.0.0.1"
+http_port = 4418
+forward_endpoint = "http://collector:4317"
 admin_port = 4320
 inactivity_timeout = 30
 
@@ -351,6 +433,17 @@ inactivity_timeout = 30
 otlp_logs = true
 otlp_logs_endpoint = "http://localhost:4317"
 otlp_logs_stdout = false
+
+["live-check".record]
+dir = "recorded"
+format = "proto"
+max_file_size = 10
+max_files = 5
+
+["live-check".sampling]
+ratio = 0.25
+first_n = 100
+rate_limit = 50.0
 "#;
         let config: WeaverConfig = toml::from_str(toml).expect("Failed to parse TOML");
         let lc = live_check(&config);
@@ -360,6 +453,8 @@ otlp_logs_stdout = false
         assert_eq!(lc.templates, Path::new("live_check_templates"));
         assert!(!lc.no_stream);
         assert!(lc.no_stats);
+        assert!(lc.aggregate);
+        assert_eq!(lc.aggregate_examples, 5);
         assert_eq!(lc.fail_on, FailOnLevel::Improvement);
         assert_eq!(lc.output.as_deref(), Some(Path::new("reports")));
         assert_eq!(lc.advice_policies.as_deref(), Some(Path::new("policies")));
@@ -368,12 +463,28 @@ otlp_logs_stdout = false
 
         assert_eq!(lc.otlp.grpc_address, "127.0.0.1");
         assert_eq!(lc.otlp.grpc_port, 4317);
+        assert!(lc.otlp.http);
+        assert_eq!(lc.otlp.http_address.as_deref(), Some("127.0.0.1"));
+        assert_eq!(lc.otlp.http_port, 4418);
+        assert_eq!(
+            lc.otlp.forward_endpoint.as_deref(),
+            Some("http://collector:4317")
+        );
         assert_eq!(lc.otlp.admin_port, 4320);
         assert_eq!(lc.otlp.inactivity_timeout, 30);
 
         assert!(lc.emit.otlp_logs);
         assert_eq!(lc.emit.otlp_logs_endpoint, "http://localhost:4317");
         assert!(!lc.emit.otlp_logs_stdout);
+
+        assert_eq!(lc.record.dir.as_deref(), Some(Path::new("recorded")));
+        assert_eq!(lc.record.format, "proto");
+        assert_eq!(lc.record.max_file_size, 10);
+        assert_eq!(lc.record.max_files, 5);
+
+        assert_eq!(lc.sampling.ratio, Some(0.25));
+        assert_eq!(lc.sampling.first_n, Some(100));
+        assert_eq!(lc.sampling.rate_limit, Some(50.0));
     }
 
     #[test]
@@ -386,9 +497,18 @@ grpc_port = 9999
         let lc = live_check(&config);
         assert_eq!(lc.otlp.grpc_port, 9999);
         assert_eq!(lc.otlp.grpc_address, "0.0.0.0");
+        assert_eq!(lc.otlp.http_port, 4318);
+        assert!(!lc.otlp.http);
+        assert_eq!(lc.otlp.http_address, None);
+        assert!(lc.otlp.forward_endpoint.is_none());
         assert_eq!(lc.otlp.admin_port, 4320);
         assert_eq!(lc.format, "ansi");
         assert!(!lc.emit.otlp_logs);
+        assert!(lc.record.dir.is_none());
+        assert_eq!(lc.record.format, "json");
+        assert_eq!(lc.record.max_file_size, 100);
+        assert_eq!(lc.record.max_files, 0);
+        assert_eq!(lc.sampling, LiveCheckSamplingConfig::default());
     }
 
     #[test]
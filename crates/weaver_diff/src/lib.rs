@@ -5,9 +5,9 @@
 use serde_json::Value;
 use similar::TextDiff;
 use std::cmp::Ordering;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
 const GREEN: &str = "\x1b[32m";
@@ -37,40 +37,107 @@ pub fn diff_output(original: &str, updated: &str) -> String {
     result
 }
 
-/// Displays differences between two directories and returns whether they are identical.
-/// The function will print differences to stderr.
-#[allow(clippy::print_stderr)]
-pub fn diff_dir<P: AsRef<Path>>(expected_dir: P, observed_dir: P) -> std::io::Result<bool> {
-    let mut expected_files = HashSet::new();
-    let mut observed_files = HashSet::new();
-
-    // Walk through the first directory and add files to files1 set
-    for entry in WalkDir::new(&expected_dir)
-        .into_iter()
-        .filter_map(|e| e.ok())
-    {
-        let path = entry.path();
-        if path.is_file() {
-            let relative_path = path
-                .strip_prefix(&expected_dir)
-                .map_err(std::io::Error::other)?;
-            _ = expected_files.insert(relative_path.to_path_buf());
+/// Constructs a unified diff (without colors) of the original vs. updated, with
+/// three lines of context around each change.
+#[must_use]
+pub fn unified_diff(
+    original: &str,
+    updated: &str,
+    original_name: &str,
+    updated_name: &str,
+) -> String {
+    TextDiff::from_lines(original, updated)
+        .unified_diff()
+        .context_radius(3)
+        .header(original_name, updated_name)
+        .to_string()
+}
+
+/// A difference between the expected files and the files of a directory.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FileDiff {
+    /// An expected file is missing from the directory, with its unified diff.
+    Added {
+        /// The path of the file, relative to the directory.
+        path: PathBuf,
+        /// The unified diff from an empty file to the expected content.
+        diff: String,
+    },
+    /// A file of the directory differs from the expected one, with their unified diff.
+    Changed {
+        /// The path of the file, relative to the directory.
+        path: PathBuf,
+        /// The unified diff from the content of the file to the expected content.
+        diff: String,
+    },
+    /// A file of the directory is not expected.
+    Removed {
+        /// The path of the file, relative to the directory.
+        path: PathBuf,
+    },
+}
+
+/// Compares the expected content of files, keyed by their path relative to `dir`, with
+/// the files of `dir`, ignoring the differences of line endings. The files of `dir` that
+/// are not expected are only reported when `is_tracked` returns true for their path.
+pub fn diff_dir_with_files<P: AsRef<Path>>(
+    dir: P,
+    expected: &BTreeMap<PathBuf, String>,
+    is_tracked: impl Fn(&Path) -> bool,
+) -> std::io::Result<Vec<FileDiff>> {
+    let dir = dir.as_ref();
+    let existing = relative_files(dir)?;
+    let mut diffs = Vec::new();
+    for (path, content) in expected {
+        let name = path.display().to_string();
+        if !existing.contains(path) {
+            diffs.push(FileDiff::Added {
+                path: path.clone(),
+                diff: unified_diff("", content, "/dev/null", &name),
+            });
+            continue;
+        }
+        let current = fs::read(dir.join(path))?;
+        let current = String::from_utf8_lossy(&current).replace("\r\n", "\n");
+        let content = content.replace("\r\n", "\n");
+        if current != content {
+            diffs.push(FileDiff::Changed {
+                path: path.clone(),
+                diff: unified_diff(&current, &content, &name, &name),
+            });
         }
     }
+    diffs.extend(
+        existing
+            .into_iter()
+            .filter(|path| !expected.contains_key(path) && is_tracked(path))
+            .map(|path| FileDiff::Removed { path }),
+    );
+    Ok(diffs)
+}
 
-    // Walk through the second directory and add files to files2 set
-    for entry in WalkDir::new(&observed_dir)
-        .into_iter()
-        .filter_map(|e| e.ok())
-    {
-        let path = entry.path();
-        if path.is_file() {
-            let relative_path = path
-                .strip_prefix(&observed_dir)
+/// The paths of the files of a directory, relative to it. Empty if the directory
+/// doesn't exist.
+fn relative_files(dir: &Path) -> std::io::Result<BTreeSet<PathBuf>> {
+    let mut files = BTreeSet::new();
+    for entry in WalkDir::new(dir).into_iter().filter_map(|e| e.ok()) {
+        if entry.file_type().is_file() {
+            let relative_path = entry
+                .path()
+                .strip_prefix(dir)
                 .map_err(std::io::Error::other)?;
-            _ = observed_files.insert(relative_path.to_path_buf());
+            _ = files.insert(relative_path.to_path_buf());
         }
     }
+    Ok(files)
+}
+
+/// Displays differences between two directories and returns whether they are identical.
+/// The function will print differences to stderr.
+#[allow(clippy::print_stderr)]
+pub fn diff_dir<P: AsRef<Path>>(expected_dir: P, observed_dir: P) -> std::io::Result<bool> {
+    let expected_files = relative_files(expected_dir.as_ref())?;
+    let observed_files = relative_files(observed_dir.as_ref())?;
 
     // Assume directories are identical until proven otherwise
     let mut are_identical = true;
@@ -307,6 +374,50 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_unified_diff() {
+        let diff = unified_diff("a\nb\nc\n", "a\nB\nc\n", "expected", "generated");
+        assert_eq!(
+            diff,
+            "--- expected\n+++ generated\n@@ -1,3 +1,3 @@\n a\n-b\n+B\n c\n"
+        );
+    }
+
+    #[test]
+    fn test_diff_dir_with_files() {
+        let dir = tempfile::tempdir().expect("Failed to create temp dir");
+        fs::write(dir.path().join("same.txt"), "same\r\n").expect("Failed to write");
+        fs::write(dir.path().join("changed.txt"), "a\n").expect("Failed to write");
+        fs::write(dir.path().join("tracked.txt"), "").expect("Failed to write");
+        fs::write(dir.path().join("hand-written.txt"), "").expect("Failed to write");
+        let expected = BTreeMap::from([
+            (PathBuf::from("same.txt"), "same\n".to_owned()),
+            (PathBuf::from("changed.txt"), "b\n".to_owned()),
+            (PathBuf::from("added.txt"), "c\n".to_owned()),
+        ]);
+
+        let diffs = diff_dir_with_files(dir.path(), &expected, |path| {
+            path == Path::new("tracked.txt")
+        })
+        .expect("Failed to diff");
+        assert_eq!(
+            diffs,
+            vec![
+                FileDiff::Added {
+                    path: PathBuf::from("added.txt"),
+                    diff: "--- /dev/null\n+++ added.txt\n@@ -0,0 +1 @@\n+c\n".to_owned(),
+                },
+                FileDiff::Changed {
+                    path: PathBuf::from("changed.txt"),
+                    diff: "--- changed.txt\n+++ changed.txt\n@@ -1 +1 @@\n-a\n+b\n".to_owned(),
+                },
+                FileDiff::Removed {
+                    path: PathBuf::from("tracked.txt"),
+                },
+            ]
+        );
+    }
+
     #[test]
     fn test_diff_dir() {
         let expected_dir = "./src";
@@ -38,6 +38,7 @@ use crate::file_loader::{FileContent, FileLoader};
 use crate::formats::html::HtmlRenderOptions;
 use crate::formats::markdown::MarkdownRenderOptions;
 use crate::formats::WordWrapConfig;
+use crate::jq::CompiledJq;
 use crate::WEAVER_YAML;
 
 /// Weaver configuration.
@@ -76,6 +77,79 @@ pub struct WeaverConfig {
     /// List of acronyms to be considered as unmodifiable words in the case
     /// conversion.
     pub(crate) acronyms: Option<Vec<String>>,
+
+    /// User-defined Jinja filters, by name. A filter can't have the name of a
+    /// built-in filter.
+    pub(crate) filters: Option<BTreeMap<String, CustomFunction>>,
+
+    /// User-defined Jinja functions, by name. A function can't have the name of a
+    /// built-in function.
+    pub(crate) functions: Option<BTreeMap<String, CustomFunction>>,
+
+    /// Write a `<file>.map.json` source map next to each generated file, mapping
+    /// the line ranges marked by the `source_location` and `mark_source` filters
+    /// to the semantic convention definitions. Default is `false`.
+    pub(crate) source_map: Option<bool>,
+
+    /// Post-processors applied to every file generated by the target, after the
+    /// ones of its template.
+    pub(crate) post_process: Option<Vec<PostProcessor>>,
+
+    /// Whether the `command` post-processors may be run. Never read from a
+    /// `weaver.yaml` file, see [`WeaverConfig::allow_post_process_commands`].
+    #[serde(skip)]
+    pub(crate) allow_post_process_commands: bool,
+}
+
+/// A step transforming the content of a generated file before it is written.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum PostProcessor {
+    /// A built-in processor without options.
+    Builtin(BuiltinPostProcessor),
+    /// Insert a header, typically a license, at the top of the file unless the
+    /// file already starts with it.
+    LicenseHeader {
+        /// The header text.
+        license_header: String,
+    },
+    /// Run an external command.
+    Command {
+        /// The program followed by its arguments. Without a `{file}` argument,
+        /// the content is written to the standard input of the command and
+        /// replaced by its standard output. Otherwise `{file}` is replaced by the
+        /// path of a temporary copy of the file, with the same file name, that
+        /// the command formats in place.
+        command: Vec<String>,
+    },
+}
+
+/// A built-in post-processor without options.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BuiltinPostProcessor {
+    /// Remove the trailing spaces and tabs of each line.
+    TrimTrailingWhitespace,
+}
+
+/// The body of a user-defined Jinja filter or function.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum CustomFunction {
+    /// A jq expression. For a filter, the input of the expression is the filtered
+    /// value; for a function, the input is `null`. The remaining arguments are
+    /// exposed as the `$args` array.
+    Jq {
+        /// The jq expression.
+        jq: String,
+    },
+    /// A template file, relative to the templates of the target, defining a
+    /// macro with the same name as the filter or function. For a filter, the
+    /// filtered value is the first argument of the macro.
+    Macro {
+        /// The template file defining the macro.
+        r#macro: String,
+    },
 }
 
 /// Case convention for naming of functions and structs.
@@ -217,6 +291,9 @@ pub(crate) struct TemplateConfig {
     /// explicit comparison to produce a boolean. When absent, the template is
     /// always applied.
     pub(crate) when: Option<String>,
+    /// Post-processors applied to the files generated by this template, before
+    /// the ones of the target.
+    pub(crate) post_process: Option<Vec<PostProcessor>>,
 }
 
 fn default_filter() -> String {
@@ -491,6 +568,11 @@ impl Default for WeaverConfig {
             params: None,
             templates: None,
             acronyms: None,
+            filters: None,
+            functions: None,
+            source_map: None,
+            post_process: None,
+            allow_post_process_commands: false,
         }
     }
 }
@@ -508,6 +590,35 @@ impl WeaverConfig {
         Self::resolve_from(&configs)
     }
 
+    /// Attempts to load and build a `WeaverConfig` from layered template directories, from the
+    /// highest to the lowest precedence (see `LayeredFileLoader`). The `weaver.yaml` files of the
+    /// other directories are loaded first, from the last one, then the configuration files of the
+    /// first directory are loaded as in `try_from_path`.
+    ///
+    /// The `post_process` steps of the other directories, the template packages, are ignored:
+    /// only the templates directory can post-process the generated files.
+    pub fn try_from_layers<P: AsRef<Path>>(paths: &[P]) -> Result<Self, Error> {
+        let Some((first, others)) = paths.split_first() else {
+            return Ok(WeaverConfig::default());
+        };
+        let mut config = WeaverConfig::default();
+        for path in others.iter().rev() {
+            if let Ok(conf) = FileContent::try_from_path(path.as_ref().join(WEAVER_YAML)) {
+                let mut package_config = Self::parse(&conf)?;
+                package_config.post_process = None;
+                for template in package_config.templates.iter_mut().flatten() {
+                    template.post_process = None;
+                }
+                config.override_with(package_config);
+            }
+        }
+        for conf in Self::collect_from_path(first) {
+            config.override_with(Self::parse(&conf)?);
+        }
+        log::trace!("Using the following Weaver configuration: {config:#?}");
+        Ok(config)
+    }
+
     /// Attempts to load all the configuration files and build a unique `WeaverConfig` from the
     /// specified configuration files. The last files in the list will override the first ones.
     ///
@@ -555,19 +666,38 @@ impl WeaverConfig {
 
         // Each configuration is loaded and merged into the current configuration.
         for conf in configs {
-            let weaver_config: WeaverConfig =
-                serde_yaml::from_str(&conf.content).map_err(|e| InvalidConfigFile {
-                    config_file: conf.path.clone(),
-                    error: e.to_string(),
-                })?;
-            log::debug!("Loaded Weaver configuration from {}", conf.path.display());
-            config.override_with(weaver_config);
+            config.override_with(Self::parse(conf)?);
         }
 
         log::trace!("Using the following Weaver configuration: {config:#?}");
         Ok(config)
     }
 
+    /// Deserializes a single configuration file.
+    fn parse(conf: &FileContent) -> Result<WeaverConfig, Error> {
+        let weaver_config: WeaverConfig =
+            serde_yaml::from_str(&conf.content).map_err(|e| InvalidConfigFile {
+                config_file: conf.path.clone(),
+                error: e.to_string(),
+            })?;
+        // The jq expressions of the user-defined filters and functions are checked on load.
+        for (name, body) in weaver_config
+            .filters
+            .iter()
+            .chain(&weaver_config.functions)
+            .flatten()
+        {
+            if let CustomFunction::Jq { jq } = body {
+                _ = CompiledJq::compile(jq, &["args"]).map_err(|e| InvalidConfigFile {
+                    config_file: conf.path.clone(),
+                    error: format!("The jq expression of `{name}` is invalid: {e}"),
+                })?;
+            }
+        }
+        log::debug!("Loaded Weaver configuration from {}", conf.path.display());
+        Ok(weaver_config)
+    }
+
     fn collect_from_path<P: AsRef<Path>>(path: P) -> Vec<FileContent> {
         let mut file_contents = Vec::new();
 
@@ -678,6 +808,24 @@ impl WeaverConfig {
         if child.acronyms.is_some() {
             self.acronyms = child.acronyms;
         }
+        // Custom filters and functions are merged by name, so that a parent
+        // configuration can share helpers across targets.
+        if let Some(filters) = child.filters {
+            self.filters
+                .get_or_insert_with(BTreeMap::new)
+                .extend(filters);
+        }
+        if let Some(functions) = child.functions {
+            self.functions
+                .get_or_insert_with(BTreeMap::new)
+                .extend(functions);
+        }
+        if child.source_map.is_some() {
+            self.source_map = child.source_map;
+        }
+        if child.post_process.is_some() {
+            self.post_process = child.post_process;
+        }
     }
 
     /// Merge additional acronyms from a higher-precedence source, such as the
@@ -703,6 +851,13 @@ impl WeaverConfig {
         self.acronyms = Some(merged);
     }
 
+    /// Allow the `command` post-processors to run external programs. Without it, a
+    /// generation using one fails. This can only be set by the caller, e.g. from a
+    /// command line flag, so that a templates directory can't run programs on its own.
+    pub fn allow_post_process_commands(&mut self, allow: bool) {
+        self.allow_post_process_commands = allow;
+    }
+
     /// Merge additional `text_maps` from a higher-precedence source, such as the
     /// project-level `.weaver.toml` `[template]` section, into the template
     /// package's own `text_maps` (from `weaver.yaml`).
@@ -721,6 +876,7 @@ impl WeaverConfig {
 #[cfg(test)]
 mod tests {
     use crate::config::{ApplicationMode, WeaverConfig};
+    use crate::error::Error::InvalidConfigFile;
     use crate::file_loader::FileContent;
 
     #[test]
@@ -1145,4 +1301,46 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_try_from_layers_ignores_package_post_process() {
+        let templates = tempfile::tempdir().expect("Failed to create temp dir");
+        let package = tempfile::tempdir().expect("Failed to create temp dir");
+        std::fs::write(
+            package.path().join(crate::WEAVER_YAML),
+            r#"post_process:
+  - command: ["sh", "-c", "echo pwned"]
+templates:
+  - template: "a.md"
+    filter: "."
+    application_mode: single
+    post_process:
+      - command: ["sh", "-c", "echo pwned"]
+"#,
+        )
+        .expect("Failed to write the package configuration");
+
+        let config = WeaverConfig::try_from_layers(&[templates.path(), package.path()])
+            .expect("Failed to load the layers");
+        assert!(config.post_process.is_none());
+        let templates = config
+            .templates
+            .expect("The package templates are inherited");
+        assert!(templates[0].post_process.is_none());
+        assert!(!config.allow_post_process_commands);
+    }
+    #[test]
+    fn test_invalid_custom_jq_on_load() {
+        let dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let path = dir.path().join(crate::WEAVER_YAML);
+        std::fs::write(&path, "filters:\n  broken:\n    jq: '.['\n")
+            .expect("Failed to write the configuration");
+        let Err(InvalidConfigFile { config_file, error }) =
+            WeaverConfig::try_from_config_files(&[&path])
+        else {
+            panic!("An invalid jq expression must fail the configuration");
+        };
+        assert_eq!(config_file, path);
+        assert!(error.contains("`broken`"));
+    }
 }
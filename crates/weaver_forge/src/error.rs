@@ -199,6 +199,96 @@ pub enum Error {
         error: String,
     },
 
+    /// A post-processor of a generated file failed.
+    #[error("Post-processing of the generated file `{file}` with `{command}` failed: {error}")]
+    #[diagnostic(help(
+        "Check the `post_process` section of the weaver.yaml file and that the command is installed."
+    ))]
+    PostProcessingFailed {
+        /// Generated file, relative to the output directory.
+        file: PathBuf,
+        /// The command of the post-processor.
+        command: String,
+        /// Error message.
+        error: String,
+    },
+
+    /// A `command` post-processor is used without being allowed.
+    #[error("The post-processing command `{command}` is not allowed to run.")]
+    #[diagnostic(help(
+        "Post-processing commands run external programs: pass `--allow-post-process-commands`, or set `allow_post_process_commands = true` in the `[generate]` section of `.weaver.toml`, if you trust the templates."
+    ))]
+    PostProcessCommandNotAllowed {
+        /// The command of the post-processor.
+        command: String,
+    },
+
+    /// A `command` post-processor is used along with source maps.
+    #[error("The post-processing command `{command}` can't be used with `source_map: true`.")]
+    #[diagnostic(help(
+        "A command can move the lines of a generated file and invalidate its source map: disable `source_map` or remove the command from the `post_process` section of the weaver.yaml file."
+    ))]
+    SourceMapWithPostProcessCommand {
+        /// The command of the post-processor.
+        command: String,
+    },
+
+    /// A user-defined filter or function has the name of a built-in one.
+    #[error("The user-defined {kind} `{name}` has the name of a built-in {kind}.")]
+    #[diagnostic(
+        help("Rename the {kind} in the `filters` or `functions` section of the weaver.yaml file."),
+        url("https://github.com/open-telemetry/weaver/blob/main/docs/weaver-config.md")
+    )]
+    CustomFunctionNameClash {
+        /// `filter` or `function`.
+        kind: String,
+        /// The name of the filter or function.
+        name: String,
+    },
+
+    /// Reading, writing or pruning with the generation manifest failed.
+    #[error("Generation manifest error on `{path}`: {error}")]
+    GenerationManifestFailed {
+        /// Path of the manifest or of the generated file.
+        path: PathBuf,
+        /// Error message.
+        error: String,
+    },
+
+    /// A template package can't be fetched, is invalid or is not compatible.
+    #[error("Template package `{package}`: {error}")]
+    #[diagnostic(url(
+        "https://github.com/open-telemetry/weaver/blob/main/crates/weaver_forge/README.md#template-packages"
+    ))]
+    InvalidTemplatePackage {
+        /// Name or location of the package.
+        package: String,
+        /// Error message.
+        error: String,
+    },
+
+    /// The resolved template packages differ from the ones recorded in the lock file.
+    #[error("The template packages don't match the lock file `{lock_file}`: {error}")]
+    #[diagnostic(help(
+        "Run the command with `--update-lock` to accept the resolved template packages."
+    ))]
+    TemplatePackageLockMismatch {
+        /// Path of the lock file.
+        lock_file: PathBuf,
+        /// Error message.
+        error: String,
+    },
+
+    /// The templates directory has dependencies but no lock file.
+    #[error("The template packages are not locked, the lock file `{lock_file}` is missing.")]
+    #[diagnostic(help(
+        "Run `weaver registry generate` on the local templates directory, or with `--update-lock`, to write it."
+    ))]
+    TemplatePackageLockMissing {
+        /// Path of the lock file.
+        lock_file: PathBuf,
+    },
+
     /// Attribute reference not found in the catalog.
     #[error("Attribute reference {attr_ref} (group: {group_id}) not found in the catalog")]
     AttributeNotFound {
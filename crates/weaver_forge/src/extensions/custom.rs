@@ -0,0 +1,219 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! User-defined filters and functions declared in the `filters` and `functions`
+//! sections of the `weaver.yaml` file.
+
+use std::collections::BTreeMap;
+
+use minijinja::value::Rest;
+use minijinja::{Environment, ErrorKind, State, Value};
+
+use crate::config::{CustomFunction, WeaverConfig};
+use crate::error::Error;
+use crate::jq::CompiledJq;
+
+/// The body of a user-defined filter or function, with its jq expression compiled.
+enum Body {
+    Jq(CompiledJq),
+    Macro(String),
+}
+
+impl Body {
+    fn try_new(body: &CustomFunction) -> Result<Self, Error> {
+        Ok(match body {
+            CustomFunction::Jq { jq } => Body::Jq(CompiledJq::compile(jq, &["args"])?),
+            CustomFunction::Macro { r#macro: file } => Body::Macro(file.clone()),
+        })
+    }
+}
+
+/// Add the user-defined filters to the environment. Fails if a filter has the name of
+/// a filter already defined, e.g. a built-in one, or if its jq expression is invalid.
+pub(crate) fn add_filters(
+    env: &mut Environment<'_>,
+    target_config: &WeaverConfig,
+) -> Result<(), Error> {
+    for (name, body) in target_config.filters.iter().flatten() {
+        if is_filter_defined(env, name) {
+            return Err(Error::CustomFunctionNameClash {
+                kind: "filter".to_owned(),
+                name: name.clone(),
+            });
+        }
+        let body = Body::try_new(body)?;
+        let name = name.clone();
+        env.add_filter(
+            name.clone(),
+            move |state: &State<'_, '_>, value: Value, args: Rest<Value>| match &body {
+                Body::Jq(jq) => call_jq(jq, &value, &args),
+                Body::Macro(file) => {
+                    let mut macro_args = vec![value];
+                    macro_args.extend(args.iter().cloned());
+                    call_macro(state, file, &name, &macro_args)
+                }
+            },
+        );
+    }
+    Ok(())
+}
+
+/// Add the user-defined functions to the environment. Fails if a function has the name
+/// of a function already defined, e.g. a built-in one, or if its jq expression is invalid.
+pub(crate) fn add_functions(
+    env: &mut Environment<'_>,
+    target_config: &WeaverConfig,
+) -> Result<(), Error> {
+    for (name, body) in target_config.functions.iter().flatten() {
+        // The functions are globals of the environment.
+        if env.globals().any(|(global, _)| global == name) {
+            return Err(Error::CustomFunctionNameClash {
+                kind: "function".to_owned(),
+                name: name.clone(),
+            });
+        }
+        let body = Body::try_new(body)?;
+        let name = name.clone();
+        env.add_function(
+            name.clone(),
+            move |state: &State<'_, '_>, args: Rest<Value>| match &body {
+                Body::Jq(jq) => call_jq(jq, &Value::from(()), &args),
+                Body::Macro(file) => call_macro(state, file, &name, &args),
+            },
+        );
+    }
+    Ok(())
+}
+
+/// Returns true if a filter with this name is defined. The filters of an environment
+/// can't be listed, so the filter is applied to `none`: only an unknown filter fails
+/// with `UnknownFilter`.
+fn is_filter_defined(env: &Environment<'_>, name: &str) -> bool {
+    match env
+        .compile_expression_owned(format!("none | {name}"))
+        .and_then(|expr| expr.eval(()))
+    {
+        Ok(_) => true,
+        Err(e) => e.kind() != ErrorKind::UnknownFilter,
+    }
+}
+
+/// Run a jq filter on the input, with the arguments exposed as `$args`.
+fn call_jq(jq: &CompiledJq, input: &Value, args: &[Value]) -> Result<Value, minijinja::Error> {
+    let to_json = |value: &Value| {
+        serde_json::to_value(value).map_err(|e| {
+            minijinja::Error::new(
+                ErrorKind::InvalidOperation,
+                format!("Failed to convert the value to JSON: {e}"),
+            )
+        })
+    };
+    let input = to_json(input)?;
+    let args = args.iter().map(to_json).collect::<Result<Vec<_>, _>>()?;
+    let params = BTreeMap::from([("args".to_owned(), serde_json::Value::Array(args))]);
+    let result = jq
+        .run(&input, &params)
+        .map_err(|e| minijinja::Error::new(ErrorKind::InvalidOperation, e.to_string()))?;
+    Ok(Value::from_serialize(&result))
+}
+
+/// Call the macro `name` defined in the template `file`. The template is rendered,
+/// and its output discarded, with the variables of the calling template so that
+/// the macro can use them, e.g. `params`.
+fn call_macro(
+    state: &State<'_, '_>,
+    file: &str,
+    name: &str,
+    args: &[Value],
+) -> Result<Value, minijinja::Error> {
+    let template = state.env().get_template(file)?;
+    let context: BTreeMap<String, Value> = state
+        .known_variables()
+        .into_iter()
+        .filter_map(|variable| Some((variable.to_string(), state.lookup(&variable)?)))
+        .collect();
+    let captured = template.render_captured(context)?;
+    let macro_state = captured.state();
+    if macro_state.lookup(name).is_none() {
+        return Err(minijinja::Error::new(
+            ErrorKind::UnknownFunction,
+            format!("The macro `{name}` is not defined in `{file}`"),
+        ));
+    }
+    // A macro returns markup, like when it is called from a template.
+    Ok(Value::from_safe_string(macro_state.call_macro(name, args)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use minijinja::context;
+
+    use super::*;
+
+    #[test]
+    fn test_custom_filters_and_functions() {
+        let config: WeaverConfig = serde_yaml::from_str(
+            r#"
+filters:
+  names:
+    jq: '[.[].name] | join($args[0])'
+  shout:
+    macro: macros.j2
+functions:
+  answer:
+    jq: '$args[0] * 2'
+"#,
+        )
+        .expect("Failed to parse the configuration");
+
+        let mut env = Environment::new();
+        env.add_template(
+            "macros.j2",
+            "{% macro shout(value, suffix) %}{{ value | upper }}{{ suffix }}{{ params.mark }}{% endmacro %}",
+        )
+        .unwrap();
+        add_filters(&mut env, &config).unwrap();
+        add_functions(&mut env, &config).unwrap();
+
+        // The macro sees the variables of the calling template.
+        let render = |source: &str| {
+            env.render_str(
+                source,
+                context! {
+                    attrs => vec![context! { name => "a" }, context! { name => "b" }],
+                    params => context! { mark => "?" },
+                },
+            )
+        };
+        assert_eq!(render("{{ attrs | names(', ') }}").unwrap(), "a, b");
+        assert_eq!(render("{{ 'hi' | shout('!') }}").unwrap(), "HI!?");
+        assert_eq!(render("{{ answer(21) }}").unwrap(), "42");
+    }
+
+    #[test]
+    fn test_invalid_custom_filters_and_functions() {
+        let add = |yaml: &str| {
+            let config: WeaverConfig = serde_yaml::from_str(yaml).unwrap();
+            let mut env = Environment::new();
+            crate::extensions::case::add_filters(&mut env);
+            add_filters(&mut env, &config)?;
+            add_functions(&mut env, &config)
+        };
+        // An invalid jq expression is reported when the filter is added.
+        assert!(matches!(
+            add("functions:\n  broken:\n    jq: '.['\n"),
+            Err(Error::FilterError { .. })
+        ));
+        // Built-in names are rejected.
+        for yaml in [
+            "filters:\n  upper:\n    jq: '.'\n",
+            "filters:\n  snake_case:\n    jq: '.'\n",
+            "functions:\n  range:\n    jq: '.'\n",
+        ] {
+            assert!(matches!(
+                add(yaml),
+                Err(Error::CustomFunctionNameClash { .. })
+            ));
+        }
+        assert!(add("filters:\n  mine:\n    jq: '.'\n").is_ok());
+    }
+}
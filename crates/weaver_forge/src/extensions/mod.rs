@@ -4,6 +4,8 @@
 pub mod ansi;
 pub mod case;
 pub mod code;
+pub(crate) mod custom;
 pub mod otel;
 mod prom;
+pub(crate) mod source;
 pub mod util;
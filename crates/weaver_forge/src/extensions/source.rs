@@ -0,0 +1,112 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Filters exposing where the attributes and signals are defined in the
+//! semantic convention files.
+
+use minijinja::{Environment, Value};
+
+use crate::config::WeaverConfig;
+use crate::source_map::marker;
+
+/// Add the source location filters to the environment.
+pub(crate) fn add_filters(env: &mut Environment<'_>, target_config: &WeaverConfig) {
+    let source_map = target_config.source_map.unwrap_or(false);
+    env.add_filter("source_location", move |value: Value| {
+        source_location(&value, source_map)
+    });
+    env.add_filter("mark_source", move |value: Value| {
+        mark_source(&value, source_map)
+    });
+}
+
+/// Returns the `path:line` where an attribute or a signal is defined, or only
+/// the path if the line is unknown. Returns an empty string if the definition
+/// is not local (e.g. it comes from a dependency).
+///
+/// The input is an attribute or a signal of the v2 registry, its `provenance`,
+/// or a group of the v1 registry (whose line is unknown). When source maps are
+/// enabled, the output of the template from this line is mapped to the definition.
+fn source_location(value: &Value, source_map: bool) -> String {
+    let Some((path, line)) = definition(value) else {
+        return String::new();
+    };
+    let mut location = match line {
+        Some(line) => format!("{path}:{line}"),
+        None => path.clone(),
+    };
+    if source_map {
+        location.push_str(&marker(&path, line));
+    }
+    location
+}
+
+/// Maps the output of the template from this line to the definition of an
+/// attribute or a signal in the source map, without rendering anything. Accepts
+/// the same inputs as `source_location` and renders nothing when source maps
+/// are disabled.
+fn mark_source(value: &Value, source_map: bool) -> String {
+    match definition(value) {
+        Some((path, line)) if source_map => marker(&path, line),
+        _ => String::new(),
+    }
+}
+
+/// The path and the line of the definition of an item.
+fn definition(value: &Value) -> Option<(String, Option<usize>)> {
+    let attr = |value: &Value, name: &str| {
+        value
+            .get_attr(name)
+            .ok()
+            .filter(|v| !v.is_undefined() && !v.is_none())
+    };
+    let provenance = attr(value, "provenance")
+        .or_else(|| attr(value, "lineage").and_then(|lineage| attr(&lineage, "provenance")))
+        .unwrap_or_else(|| value.clone());
+    let path = attr(&provenance, "path")?.as_str()?.to_owned();
+    let line = attr(&provenance, "line").and_then(|line| line.as_usize());
+    Some((path, line))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source_map::extract;
+    use minijinja::context;
+
+    #[test]
+    fn test_source_location() {
+        let v2_attr = Value::from_serialize(
+            serde_json::json!({"key": "a", "provenance": {"path": "model/a.yaml", "line": 3}}),
+        );
+        let v1_group = Value::from_serialize(
+            serde_json::json!({"id": "g", "lineage": {"provenance": {"path": "model/g.yaml"}}}),
+        );
+        let dependency = Value::from_serialize(
+            serde_json::json!({"key": "d", "provenance": {"source": "https://x/1.0.0"}}),
+        );
+
+        assert_eq!(source_location(&v2_attr, false), "model/a.yaml:3");
+        assert_eq!(source_location(&v1_group, false), "model/g.yaml");
+        assert_eq!(source_location(&dependency, false), "");
+        assert_eq!(mark_source(&v2_attr, false), "");
+
+        let mut env = Environment::new();
+        let config = WeaverConfig {
+            source_map: Some(true),
+            ..Default::default()
+        };
+        add_filters(&mut env, &config);
+        let output = env
+            .render_str(
+                "// {{ attr | source_location }}\nA\n{{ group | mark_source }}B\n",
+                context! { attr => v2_attr, group => v1_group },
+            )
+            .unwrap();
+        let (output, mappings) = extract(&output);
+        assert_eq!(output, "// model/a.yaml:3\nA\nB");
+        assert_eq!(mappings.len(), 2);
+        assert_eq!(mappings[0].source_line, Some(3));
+        assert_eq!((mappings[0].start_line, mappings[0].end_line), (1, 2));
+        assert_eq!(mappings[1].source, "model/g.yaml");
+    }
+}
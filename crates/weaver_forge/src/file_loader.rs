@@ -224,6 +224,76 @@ impl FileLoader for FileSystemFileLoader {
     }
 }
 
+/// A loader that loads files from several template directories, e.g. the
+/// templates of a package and of the packages it depends on. A file of a
+/// directory overrides the file with the same name in the next directories.
+pub struct LayeredFileLoader {
+    loaders: Vec<FileSystemFileLoader>,
+}
+
+impl LayeredFileLoader {
+    /// Create a new layered loader from template directories, from the highest
+    /// to the lowest precedence. The directories without the target are skipped,
+    /// fails if none of them has the target.
+    pub fn try_new(dirs: &[PathBuf], target: &str) -> Result<Self, Error> {
+        let mut loaders = Vec::new();
+        let mut first_error = None;
+        for dir in dirs {
+            match FileSystemFileLoader::try_new(dir.clone(), target) {
+                Ok(loader) => loaders.push(loader),
+                Err(e) => {
+                    _ = first_error.get_or_insert(e);
+                }
+            }
+        }
+        match first_error {
+            Some(e) if loaders.is_empty() => Err(e),
+            _ => Ok(Self { loaders }),
+        }
+    }
+
+    /// The root of the target in each template directory that has it, from the
+    /// highest to the lowest precedence.
+    #[must_use]
+    pub fn roots(&self) -> Vec<PathBuf> {
+        self.loaders
+            .iter()
+            .map(|loader| loader.root().to_path_buf())
+            .collect()
+    }
+}
+
+impl FileLoader for LayeredFileLoader {
+    /// Returns the root of the directory with the highest precedence.
+    fn root(&self) -> &Path {
+        self.loaders
+            .first()
+            .map_or(Path::new(""), |loader| loader.root())
+    }
+
+    /// Returns the files of all the directories, each name only once.
+    fn all_files(&self) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = self
+            .loaders
+            .iter()
+            .flat_map(|loader| loader.all_files())
+            .collect();
+        files.sort();
+        files.dedup();
+        files
+    }
+
+    /// Returns the file from the first directory that has it.
+    fn load_file(&self, file: &str) -> Result<Option<FileContent>, Error> {
+        for loader in &self.loaders {
+            if let Some(content) = loader.load_file(file)? {
+                return Ok(Some(content));
+            }
+        }
+        Ok(None)
+    }
+}
+
 // Combine a root path and a template name, ensuring that the combined path is
 // a subdirectory of the base path.
 fn safe_join(root: &Path, template: &str) -> Result<PathBuf, minijinja::Error> {
@@ -366,6 +436,30 @@ mod tests {
         assert!(fs_content.is_none());
     }
 
+    #[test]
+    fn test_layered_loader() {
+        let loader = LayeredFileLoader::try_new(
+            &[
+                PathBuf::from("./does-not-exist"),
+                PathBuf::from("./overloaded-templates"),
+                PathBuf::from("./templates"),
+            ],
+            "test",
+        )
+        .unwrap();
+        assert_eq!(loader.roots().len(), 2);
+        assert!(loader.root().ends_with("overloaded-templates/test"));
+        let group = loader.load_file("group.md").unwrap().unwrap();
+        assert!(group
+            .content
+            .contains("# Overloaded Group `{{ ctx.id }}` ({{ ctx.type }})"));
+        let metric = loader.load_file("metric.md").unwrap().unwrap();
+        assert!(metric.path.starts_with(loader.roots()[1].as_path()));
+        assert_eq!(loader.all_files().len(), 18);
+
+        assert!(LayeredFileLoader::try_new(&[PathBuf::from("./templates")], "missing").is_err());
+    }
+
     #[test]
     fn test_embedded_loader_error() {
         let embedded_loader = EmbeddedFileLoader::try_new(
@@ -30,16 +30,6 @@ fn val_to_serde(v: Val) -> serde_json::Value {
     serde_json::from_str(&v.to_string()).unwrap_or(serde_json::Value::Null)
 }
 
-fn prepare_jq_context(
-    params: &BTreeMap<String, serde_json::Value>,
-) -> Result<(Vec<String>, Vec<Val>), Error> {
-    params
-        .iter()
-        .map(|(k, v)| Ok((format!("${k}"), serde_to_val(v.clone())?)))
-        .collect::<Result<Vec<_>, _>>()
-        .map(|pairs: Vec<_>| pairs.into_iter().unzip())
-}
-
 /// This is our single entry point for calling into the jaq library to run jq filters.
 pub fn execute_jq(
     // The JSON input to JQ.
@@ -54,90 +44,127 @@ pub fn execute_jq(
     } else if log::log_enabled!(log::Level::Debug) {
         log::debug!("Executing JQ filter: {filter_expr} with params {params:#?}");
     }
+    let names: Vec<&str> = params.keys().map(String::as_str).collect();
+    CompiledJq::compile(filter_expr, &names)?.run(input, params)
+}
 
-    let loader = Loader::new(
-        // ToDo: Allow custom preludes?
-        jaq_core::defs()
-            .chain(jaq_std::defs())
-            .chain(jaq_json::defs())
-            .chain(semconv_prelude()),
-    );
-    let arena = Arena::default();
-    let program: File<&str, JqFileType> = File {
-        code: filter_expr,
-        path: (), // ToDo - give this the weaver-config location.
-    };
+/// A jq filter compiled once and run on several inputs.
+pub(crate) struct CompiledJq {
+    filter_expr: String,
+    /// The names of the global variables, with their `$`, in the order of compilation.
+    names: Vec<String>,
+    filter: jaq_core::Filter<data::JustLut<Val>>,
+}
 
-    // parse the filter
-    let modules = loader
-        .load(&arena, program)
-        .map_err(load_errors)
-        .map_err(|details| Error::FilterError {
-            filter: filter_expr.to_owned(),
-            details,
-        })?;
-
-    let (names, values) = prepare_jq_context(params)?;
-    let funs = jaq_core::funs()
-        .chain(jaq_std::funs())
-        .chain(jaq_json::funs());
-    #[allow(clippy::map_identity)]
-    let filter = jaq_core::Compiler::default()
-        .with_global_vars(names.iter().map(|s| s.as_str()))
-        // Re-borrow &'static str with shorter lifetime so 'global_vars lifetime is unified.
-        // This is NOT a simple identity function — it's a lifetime inference workaround.
-        .with_funs(funs.map(|x| x))
-        .compile(modules)
-        .map_err(compile_errors)
-        .map_err(|details| Error::FilterError {
-            filter: filter_expr.to_owned(),
-            details,
-        })?;
-
-    let ctx = Ctx::<data::JustLut<Val>>::new(&filter.lut, Vars::new(values));
-
-    // Bundle Results
-    let mut errs = Vec::new();
-    let mut values = Vec::new();
-    for r in filter
-        .id
-        .run((ctx, serde_to_val(input.clone())?))
-        .map(unwrap_valr)
-    {
-        match r {
-            Ok(v) => values.push(val_to_serde(v)),
-            Err(e) => errs.push(e),
-        }
-    }
+impl CompiledJq {
+    /// Compile a jq filter using the global variables `names`, without their `$`.
+    pub(crate) fn compile(filter_expr: &str, names: &[&str]) -> Result<Self, Error> {
+        let loader = Loader::new(
+            // ToDo: Allow custom preludes?
+            jaq_core::defs()
+                .chain(jaq_std::defs())
+                .chain(jaq_json::defs())
+                .chain(semconv_prelude()),
+        );
+        let arena = Arena::default();
+        let program: File<&str, JqFileType> = File {
+            code: filter_expr,
+            path: (), // ToDo - give this the weaver-config location.
+        };
 
-    if !errs.is_empty() {
-        return Err(Error::FilterError {
-            filter: filter_expr.to_owned(),
-            details: errs
-                .into_iter()
-                .map(|e| FilterErrorDetail {
-                    error: format!("{e}"),
-                    source: None,
-                })
-                .collect(),
-        });
+        // parse the filter
+        let modules = loader
+            .load(&arena, program)
+            .map_err(load_errors)
+            .map_err(|details| Error::FilterError {
+                filter: filter_expr.to_owned(),
+                details,
+            })?;
+
+        let names: Vec<String> = names.iter().map(|name| format!("${name}")).collect();
+        let funs = jaq_core::funs()
+            .chain(jaq_std::funs())
+            .chain(jaq_json::funs());
+        #[allow(clippy::map_identity)]
+        let filter = jaq_core::Compiler::default()
+            .with_global_vars(names.iter().map(|s| s.as_str()))
+            // Re-borrow &'static str with shorter lifetime so 'global_vars lifetime is unified.
+            // This is NOT a simple identity function — it's a lifetime inference workaround.
+            .with_funs(funs.map(|x| x))
+            .compile(modules)
+            .map_err(compile_errors)
+            .map_err(|details| Error::FilterError {
+                filter: filter_expr.to_owned(),
+                details,
+            })?;
+        Ok(Self {
+            filter_expr: filter_expr.to_owned(),
+            names,
+            filter,
+        })
     }
 
-    if log::log_enabled!(log::Level::Trace) {
-        log::trace!(
-            "JQ filter produced {} result(s): {}",
-            values.len(),
-            serde_json::Value::from(values.clone())
-        );
-    } else {
-        log::debug!("JQ filter produced {} result(s)", values.len());
-    }
+    /// Run the filter on the input, with the values of the global variables. A single
+    /// result is returned as is, several results as an array.
+    pub(crate) fn run(
+        &self,
+        input: &serde_json::Value,
+        params: &BTreeMap<String, serde_json::Value>,
+    ) -> Result<serde_json::Value, Error> {
+        let values = self
+            .names
+            .iter()
+            .map(|name| {
+                let value = params.get(&name[1..]).cloned().unwrap_or_default();
+                serde_to_val(value)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let ctx = Ctx::<data::JustLut<Val>>::new(&self.filter.lut, Vars::new(values));
+
+        // Bundle Results
+        let mut errs = Vec::new();
+        let mut values = Vec::new();
+        for r in self
+            .filter
+            .id
+            .run((ctx, serde_to_val(input.clone())?))
+            .map(unwrap_valr)
+        {
+            match r {
+                Ok(v) => values.push(val_to_serde(v)),
+                Err(e) => errs.push(e),
+            }
+        }
 
-    if values.len() == 1 {
-        return Ok(values.pop().expect("values.len() == 1, should not happen"));
-    }
+        if !errs.is_empty() {
+            return Err(Error::FilterError {
+                filter: self.filter_expr.clone(),
+                details: errs
+                    .into_iter()
+                    .map(|e| FilterErrorDetail {
+                        error: format!("{e}"),
+                        source: None,
+                    })
+                    .collect(),
+            });
+        }
+
+        if log::log_enabled!(log::Level::Trace) {
+            log::trace!(
+                "JQ filter produced {} result(s): {}",
+                values.len(),
+                serde_json::Value::from(values.clone())
+            );
+        } else {
+            log::debug!("JQ filter produced {} result(s)", values.len());
+        }
 
-    Ok(serde_json::Value::Array(values))
+        if values.len() == 1 {
+            return Ok(values.pop().expect("values.len() == 1, should not happen"));
+        }
+
+        Ok(serde_json::Value::Array(values))
+    }
 }
 
 // JAQ errors must be parsed and synthesized.  All of this code is adapted from `jaq/src/main.rs`.
@@ -3,9 +3,11 @@
 #![doc = include_str!("../README.md")]
 
 use std::borrow::Cow;
-use std::collections::BTreeMap;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::ffi::OsString;
 use std::fmt::{Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::{fmt, fs};
@@ -25,12 +27,16 @@ use error::Error::{
 use weaver_common::error::handle_errors;
 use weaver_common::log_success;
 
-use crate::config::{ApplicationMode, AutoEscapeMode, Params, TemplateConfig, WeaverConfig};
+use crate::config::{
+    ApplicationMode, AutoEscapeMode, Params, PostProcessor, TemplateConfig, WeaverConfig,
+};
 use crate::debug::error_summary;
 use crate::error::Error::{InvalidConfigFile, InvalidFilePath};
-use crate::extensions::{ansi, case, code, otel, util};
+use crate::extensions::{ansi, case, code, custom, otel, source, util};
 use crate::file_loader::FileLoader;
 use crate::filter::Filter;
+use crate::manifest::GenerationManifest;
+use crate::plan::{GenerationPlan, SkipReason, TemplatePlan};
 use crate::registry::{ResolvedGroup, ResolvedRegistry};
 
 pub mod config;
@@ -41,8 +47,13 @@ pub mod file_loader;
 mod filter;
 mod formats;
 pub mod jq;
+pub mod manifest;
 pub mod output_processor;
+pub mod package;
+pub mod plan;
+mod post_process;
 pub mod registry;
+pub mod source_map;
 pub mod v2;
 
 pub use output_processor::{OutputProcessor, OutputTarget};
@@ -74,7 +85,7 @@ pub const COMMENT_START: &str = "{#";
 pub const COMMEND_END: &str = "#}";
 
 /// Enumeration defining where the output of program execution should be directed.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone)]
 pub(crate) enum OutputDirective {
     /// Write the generated content to the standard output.
     Stdout,
@@ -82,6 +93,14 @@ pub(crate) enum OutputDirective {
     Stderr,
     /// Write the generated content to a file.
     File,
+    /// Keep the generated content in memory, by file path relative to the output
+    /// directory.
+    Memory(Arc<Mutex<BTreeMap<PathBuf, String>>>),
+    /// Write the generated content to a file and record it in a generation manifest.
+    FileWithManifest(Arc<Mutex<GenerationManifest>>),
+    /// Only record the paths of the generated files, relative to the output
+    /// directory, without post-processing them (see `plan`).
+    Plan(Arc<Mutex<BTreeSet<PathBuf>>>),
 }
 
 /// A template object accessible from the template.
@@ -181,6 +200,39 @@ pub(crate) struct TemplateEngine {
     snippet_params: Params,
 }
 
+/// State kept between two incremental generations (see `registry generate --watch`).
+///
+/// Records a hash of the filter output of each template so that templates whose
+/// input did not change are not rendered again.
+#[derive(Debug, Default)]
+pub struct IncrementalState {
+    /// Hash of the filter output per (template file, template glob, filter).
+    filter_hashes: HashMap<(PathBuf, String, String), u64>,
+}
+
+impl IncrementalState {
+    /// Forget every recorded filter output, the next generation renders all
+    /// the templates.
+    pub fn clear(&mut self) {
+        self.filter_hashes.clear();
+    }
+
+    fn key(template_file: &Path, template: &TemplateConfig) -> (PathBuf, String, String) {
+        (
+            template_file.to_path_buf(),
+            template.template.glob().to_owned(),
+            template.filter.clone(),
+        )
+    }
+
+    /// Hash of a filter output, `None` when the `when` clause is not met.
+    fn hash(filtered: Option<&serde_json::Value>) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        filtered.map(|v| v.to_string()).hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
 /// Global context for the template engine.
 #[derive(Serialize, Debug)]
 pub struct Context<'a> {
@@ -400,7 +452,12 @@ impl TemplateEngine {
             }
         }
 
-        Ok(results.join(""))
+        let output = results.join("");
+        if self.target_config.source_map.unwrap_or(false) {
+            // There is no file to map, only the markers are removed.
+            return Ok(source_map::extract(&output).0);
+        }
+        Ok(output)
     }
 
     /// Generate artifacts from a serializable context and a template directory,
@@ -421,6 +478,7 @@ impl TemplateEngine {
         output_dir: &Path,
         output_directive: &OutputDirective,
     ) -> Result<(), Error> {
+        post_process::check_commands(&self.target_config)?;
         let files = self.file_loader.all_files();
         let tmpl_matcher = self.target_config.template_matcher()?;
 
@@ -456,6 +514,184 @@ impl TemplateEngine {
         handle_errors(errs)
     }
 
+    /// Incrementally generate artifacts from a serializable context.
+    ///
+    /// A template file is only rendered when the output of its `when` clause and
+    /// jq filter differs from the one recorded in `state` by a previous call, or
+    /// when the template file is listed in `changed_templates` (paths relative to
+    /// the root of the file loader). A changed file that is not a template, such
+    /// as an included file or a macro, may be used by any template: every template
+    /// is rendered again. An empty `state` renders every template.
+    ///
+    /// Returns the number of template files rendered.
+    pub(crate) fn generate_incremental<T: Serialize>(
+        &self,
+        context: &T,
+        output_dir: &Path,
+        output_directive: &OutputDirective,
+        state: &mut IncrementalState,
+        changed_templates: &[PathBuf],
+    ) -> Result<usize, Error> {
+        post_process::check_commands(&self.target_config)?;
+        let files = self.file_loader.all_files();
+        let tmpl_matcher = self.target_config.template_matcher()?;
+
+        // Serialize the context in JSON
+        let context = serde_json::to_value(context).map_err(|e| ContextSerializationFailed {
+            error: e.to_string(),
+        })?;
+
+        if changed_templates
+            .iter()
+            .any(|file| tmpl_matcher.matches(file).is_empty())
+        {
+            state.clear();
+        }
+        let previous = &state.filter_hashes;
+        let results = files
+            .into_par_iter()
+            .flat_map(|file_to_process| {
+                tmpl_matcher
+                    .matches(file_to_process.clone())
+                    .into_par_iter()
+                    .map(|template| {
+                        let key = IncrementalState::key(&file_to_process, template);
+                        let filtered = match self.filter_template(template, &context) {
+                            Ok(filtered) => filtered,
+                            Err(e) => return (key, None, Some(e)),
+                        };
+                        let hash = IncrementalState::hash(filtered.as_ref().map(|(v, _)| v));
+                        if previous.get(&key) == Some(&hash)
+                            && !changed_templates.contains(&file_to_process)
+                        {
+                            return (key, Some((hash, false)), None);
+                        }
+                        let result = match &filtered {
+                            Some((filtered_result, yaml_params)) => self.render_filtered(
+                                filtered_result,
+                                yaml_params,
+                                &file_to_process,
+                                template,
+                                output_dir,
+                                output_directive,
+                            ),
+                            None => Ok(()),
+                        };
+                        match result {
+                            Ok(()) => (key, Some((hash, true)), None),
+                            Err(e) => (key, None, Some(e)),
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let mut rendered = 0;
+        let mut errs = Vec::new();
+        for (key, outcome, err) in results {
+            match outcome {
+                Some((hash, was_rendered)) => {
+                    rendered += usize::from(was_rendered);
+                    _ = state.filter_hashes.insert(key, hash);
+                }
+                // A failed template is rendered again on the next call.
+                None => _ = state.filter_hashes.remove(&key),
+            }
+            errs.extend(err);
+        }
+        handle_errors(errs)?;
+        Ok(rendered)
+    }
+
+    /// Plan the generation of artifacts from a serializable context, without
+    /// writing anything.
+    ///
+    /// Each template is filtered and rendered in memory, as `generate` would, to
+    /// report the number of items produced by its filter, the resolved file names
+    /// (including the ones set by the template itself) and why a template is
+    /// skipped.
+    pub(crate) fn plan<T: Serialize>(&self, context: &T) -> Result<GenerationPlan, Error> {
+        let files = self.file_loader.all_files();
+        let tmpl_matcher = self.target_config.template_matcher()?;
+
+        // Serialize the context in JSON
+        let context = serde_json::to_value(context).map_err(|e| ContextSerializationFailed {
+            error: e.to_string(),
+        })?;
+
+        let results = files
+            .into_par_iter()
+            .flat_map(|file_to_process| {
+                tmpl_matcher
+                    .matches(file_to_process.clone())
+                    .into_par_iter()
+                    .map(|template| self.plan_template(&file_to_process, template, &context))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let mut templates = Vec::new();
+        let mut errs = Vec::new();
+        for result in results {
+            match result {
+                Ok(template) => templates.push(template),
+                Err(e) => errs.push(e),
+            }
+        }
+        handle_errors(errs)?;
+        templates.sort_by(|a, b| (&a.template, &a.pattern).cmp(&(&b.template, &b.pattern)));
+        Ok(GenerationPlan { templates })
+    }
+
+    /// Plan a single template file with the given template configuration.
+    fn plan_template(
+        &self,
+        template_file: &Path,
+        template: &TemplateConfig,
+        context: &serde_json::Value,
+    ) -> Result<TemplatePlan, Error> {
+        let mut plan = TemplatePlan {
+            template: template_file.to_path_buf(),
+            pattern: template.template.glob().to_owned(),
+            filter: template.filter.clone(),
+            application_mode: template.application_mode.clone(),
+            when: template.when.clone(),
+            items: None,
+            files: Vec::new(),
+            skipped: None,
+        };
+        let Some((filtered_result, yaml_params)) = self.filter_template(template, context)? else {
+            plan.skipped = template.when.clone().map(|when| SkipReason::When { when });
+            return Ok(plan);
+        };
+        plan.items = Some(match &filtered_result {
+            serde_json::Value::Array(values) => values.len(),
+            serde_json::Value::Null => 0,
+            _ => 1,
+        });
+
+        // The files are rendered to resolve their names, they are not post-processed.
+        let files = Arc::new(Mutex::new(BTreeSet::new()));
+        self.render_filtered(
+            &filtered_result,
+            &yaml_params,
+            template_file,
+            template,
+            Path::new(""),
+            &OutputDirective::Plan(Arc::clone(&files)),
+        )?;
+        plan.files = files
+            .lock()
+            .map_err(|e| Error::InternalError(e.to_string()))?
+            .iter()
+            .cloned()
+            .collect();
+        if plan.files.is_empty() {
+            plan.skipped = Some(SkipReason::EmptyFilterResult);
+        }
+        Ok(plan)
+    }
+
     /// Evaluate a template's optional `when` JQ expression.
     ///
     /// The expression is evaluated against the same context as the template's
@@ -507,6 +743,29 @@ impl TemplateEngine {
             "Processing template file: {template_file:#?}, output directory: {output_dir:#?}"
         );
 
+        match self.filter_template(template, context)? {
+            Some((filtered_result, yaml_params)) => self.render_filtered(
+                &filtered_result,
+                &yaml_params,
+                template_file,
+                template,
+                output_dir,
+                output_directive,
+            ),
+            None => Ok(()),
+        }
+    }
+
+    /// Apply the `when` clause and the jq filter of a template to the context.
+    ///
+    /// Returns `None` when the `when` clause is not met, otherwise the filtered
+    /// context along with the template parameters.
+    #[allow(clippy::type_complexity)]
+    fn filter_template(
+        &self,
+        template: &TemplateConfig,
+        context: &serde_json::Value,
+    ) -> Result<Option<(serde_json::Value, BTreeMap<String, serde_yaml::Value>)>, Error> {
         let yaml_params = Self::init_params(template.params.clone())?;
         let params = Self::prepare_jq_context(&yaml_params)?;
 
@@ -514,30 +773,50 @@ impl TemplateEngine {
         // evaluates to `true`. Evaluated before filtering so a skipped template
         // does no work.
         if !self.evaluate_when(template, context, &params)? {
-            return Ok(());
+            return Ok(None);
         }
 
         let filter = Filter::new(template.filter.as_str());
         let filtered_result = filter.apply(context.clone(), &params)?;
+        Ok(Some((filtered_result, yaml_params)))
+    }
 
+    /// Render a template file from the output of its filter.
+    fn render_filtered(
+        &self,
+        filtered_result: &serde_json::Value,
+        yaml_params: &BTreeMap<String, serde_yaml::Value>,
+        template_file: &Path,
+        template: &TemplateConfig,
+        output_dir: &Path,
+        output_directive: &OutputDirective,
+    ) -> Result<(), Error> {
+        let post_process: Vec<&PostProcessor> = template
+            .post_process
+            .iter()
+            .chain(&self.target_config.post_process)
+            .flatten()
+            .collect();
         match template.application_mode {
             ApplicationMode::Single => self.process_single_mode(
-                &filtered_result,
+                filtered_result,
                 template.file_name.as_ref(),
-                &yaml_params,
+                yaml_params,
                 template_file,
                 output_dir,
                 output_directive,
                 &template.auto_escape,
+                &post_process,
             ),
             ApplicationMode::Each => self.process_each_mode(
-                &filtered_result,
+                filtered_result,
                 template.file_name.as_ref(),
-                &yaml_params,
+                yaml_params,
                 template_file,
                 output_dir,
                 output_directive,
                 &template.auto_escape,
+                &post_process,
             ),
         }
     }
@@ -545,6 +824,7 @@ impl TemplateEngine {
     /// Evaluate the template for each object in the context if the context is an array, otherwise
     /// evaluate the template for the context entire object.
     /// The evaluation is done in parallel.
+    #[allow(clippy::too_many_arguments)]
     fn process_each_mode(
         &self,
         ctx: &serde_json::Value,
@@ -554,6 +834,7 @@ impl TemplateEngine {
         output_dir: &Path,
         output_directive: &OutputDirective,
         auto_escape: &AutoEscapeMode,
+        post_process: &[&PostProcessor],
     ) -> Result<(), Error> {
         match ctx {
             serde_json::Value::Array(values) => {
@@ -569,6 +850,7 @@ impl TemplateEngine {
                             output_directive,
                             output_dir,
                             auto_escape,
+                            post_process,
                         )
                         .err()
                     })
@@ -583,11 +865,13 @@ impl TemplateEngine {
                 output_directive,
                 output_dir,
                 auto_escape,
+                post_process,
             ),
         }
     }
 
     /// Evaluate the template for the entire context.
+    #[allow(clippy::too_many_arguments)]
     fn process_single_mode(
         &self,
         ctx: &serde_json::Value,
@@ -597,6 +881,7 @@ impl TemplateEngine {
         output_dir: &Path,
         output_directive: &OutputDirective,
         auto_escape: &AutoEscapeMode,
+        post_process: &[&PostProcessor],
     ) -> Result<(), Error> {
         if ctx.is_null() || (ctx.is_array() && ctx.as_array().expect("is_array").is_empty()) {
             // Skip the template evaluation if the filtered result is null or an empty array
@@ -610,6 +895,7 @@ impl TemplateEngine {
             output_directive,
             output_dir,
             auto_escape,
+            post_process,
         )
     }
 
@@ -743,6 +1029,7 @@ impl TemplateEngine {
 
     #[allow(clippy::print_stdout)] // This is used for the OutputDirective::Stdout variant
     #[allow(clippy::print_stderr)] // This is used for the OutputDirective::Stderr variant
+    #[allow(clippy::too_many_arguments)]
     fn evaluate_template(
         &self,
         ctx: serde_json::Value,
@@ -752,25 +1039,117 @@ impl TemplateEngine {
         output_directive: &OutputDirective,
         output_dir: &Path,
         auto_escape: &AutoEscapeMode,
+        post_process: &[&PostProcessor],
     ) -> Result<(), Error> {
         let (output, template_object) =
             self.render_template(ctx, params, template_path, file_path, auto_escape)?;
+        let relative_path = template_object.file_name();
         match output_directive {
+            // The source map of a standard stream is not printed.
             OutputDirective::Stdout => {
+                let (output, _) =
+                    self.finalize_generated_code(relative_path, output, post_process)?;
                 println!("{output}");
             }
             OutputDirective::Stderr => {
+                let (output, _) =
+                    self.finalize_generated_code(relative_path, output, post_process)?;
                 eprintln!("{output}");
             }
             OutputDirective::File => {
-                let generated_file =
-                    Self::save_generated_code(output_dir, template_object.file_name(), output)?;
-                log_success(format!("Generated file {generated_file:?}"));
+                for (relative_path, _) in
+                    self.save_generated_code(output_dir, relative_path, output, post_process)?
+                {
+                    log_success(format!(
+                        "Generated file {:?}",
+                        output_dir.join(relative_path)
+                    ));
+                }
+            }
+            OutputDirective::FileWithManifest(manifest) => {
+                for (relative_path, output) in
+                    self.save_generated_code(output_dir, relative_path, output, post_process)?
+                {
+                    manifest
+                        .lock()
+                        .map_err(|e| Error::InternalError(e.to_string()))?
+                        .record(&relative_path, &output, template_path);
+                    log_success(format!(
+                        "Generated file {:?}",
+                        output_dir.join(relative_path)
+                    ));
+                }
+            }
+            OutputDirective::Memory(files) => {
+                let (output, source_map) =
+                    self.finalize_generated_code(relative_path.clone(), output, post_process)?;
+                let mut files = files
+                    .lock()
+                    .map_err(|e| Error::InternalError(e.to_string()))?;
+                // The output directory is ignored, the paths stay relative to it.
+                for (relative_path, output) in
+                    [(relative_path, output)].into_iter().chain(source_map)
+                {
+                    _ = files.insert(relative_path.components().collect(), output);
+                }
+            }
+            OutputDirective::Plan(files) => {
+                let mut files = files
+                    .lock()
+                    .map_err(|e| Error::InternalError(e.to_string()))?;
+                let relative_path: PathBuf = relative_path.components().collect();
+                if self.target_config.source_map.unwrap_or(false) {
+                    _ = files.insert(source_map::source_map_path(&relative_path));
+                }
+                _ = files.insert(relative_path);
             }
         }
         Ok(())
     }
 
+    /// Apply the post-processors to a rendered file and remove its source markers.
+    /// Returns the content of the file and, when `source_map` is enabled, the path
+    /// of its source map, relative to the output directory, and its content.
+    fn finalize_generated_code(
+        &self,
+        relative_path: PathBuf,
+        output: String,
+        post_process: &[&PostProcessor],
+    ) -> Result<(String, Option<(PathBuf, String)>), Error> {
+        if !self.target_config.source_map.unwrap_or(false) {
+            return Ok((
+                post_process::apply(post_process, &relative_path, output)?,
+                None,
+            ));
+        }
+        let (output, mut mappings) = source_map::extract(&output);
+        let line_count = output.lines().count();
+        let output = post_process::apply(post_process, &relative_path, output)?;
+        // The commands are rejected along with source maps and the built-in
+        // processors only add lines at the top of the file, the mapped ranges are
+        // shifted by the number of added lines.
+        source_map::shift(
+            &mut mappings,
+            output.lines().count().saturating_sub(line_count),
+        );
+        let source_map = source_map::SourceMap {
+            file: relative_path
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/"),
+            mappings,
+        };
+        let source_map_path = source_map::source_map_path(&relative_path);
+        let mut source_map =
+            serde_json::to_string_pretty(&source_map).map_err(|e| WriteGeneratedCodeFailed {
+                template: source_map_path.clone(),
+                error: e.to_string(),
+            })?;
+        source_map.push('\n');
+        Ok((output, Some((source_map_path, source_map))))
+    }
+
     /// Create a new template engine based on the target configuration.
     fn template_engine(&self, auto_escape: &AutoEscapeMode) -> Result<Environment<'_>, Error> {
         let mut env = Environment::new();
@@ -851,32 +1230,41 @@ impl TemplateEngine {
         Ok(env)
     }
 
-    /// Save the generated code to the output directory.
+    /// Post-process a rendered file, then save it and its source map to the output
+    /// directory. Returns the saved files, relative to the output directory, with
+    /// their content.
     fn save_generated_code(
+        &self,
         output_dir: &Path,
         relative_path: PathBuf,
         generated_code: String,
-    ) -> Result<PathBuf, Error> {
-        // Create all intermediary directories if they don't exist
-        let output_file_path = output_dir.join(relative_path);
-        if let Some(parent_dir) = output_file_path.parent() {
-            if let Err(e) = fs::create_dir_all(parent_dir) {
-                return Err(WriteGeneratedCodeFailed {
-                    template: output_file_path.clone(),
-                    error: format!("{e}"),
-                });
+        post_process: &[&PostProcessor],
+    ) -> Result<Vec<(PathBuf, String)>, Error> {
+        let (generated_code, source_map) =
+            self.finalize_generated_code(relative_path.clone(), generated_code, post_process)?;
+        let files: Vec<_> = [(relative_path, generated_code)]
+            .into_iter()
+            .chain(source_map)
+            .collect();
+        for (relative_path, content) in &files {
+            // Create all intermediary directories if they don't exist
+            let output_file_path = output_dir.join(relative_path);
+            if let Some(parent_dir) = output_file_path.parent() {
+                if let Err(e) = fs::create_dir_all(parent_dir) {
+                    return Err(WriteGeneratedCodeFailed {
+                        template: output_file_path.clone(),
+                        error: format!("{e}"),
+                    });
+                }
             }
-        }
 
-        // Write the generated code to the output directory
-        fs::write(output_file_path.clone(), generated_code).map_err(|e| {
-            WriteGeneratedCodeFailed {
+            // Write the generated code to the output directory
+            fs::write(&output_file_path, content).map_err(|e| WriteGeneratedCodeFailed {
                 template: output_file_path.clone(),
                 error: format!("{e}"),
-            }
-        })?;
-
-        Ok(output_file_path)
+            })?;
+        }
+        Ok(files)
     }
 }
 
@@ -891,9 +1279,14 @@ pub(crate) fn install_weaver_extensions(
     ansi::add_filters(env);
     case::add_filters(env);
     otel::add_filters(env);
+    source::add_filters(env, config);
     util::add_filters(env, config);
     util::add_functions(env);
     otel::add_tests(env);
+    // User-defined filters and functions come last to be checked against the built-in
+    // ones.
+    custom::add_filters(env, config)?;
+    custom::add_functions(env, config)?;
     Ok(())
 }
 
@@ -912,19 +1305,21 @@ mod tests {
     use weaver_semconv::schema_url::SchemaUrl;
 
     use crate::config::{
-        ApplicationMode, AutoEscapeMode, CaseConvention, Params, TemplateConfig, WeaverConfig,
+        ApplicationMode, AutoEscapeMode, BuiltinPostProcessor, CaseConvention, Params,
+        PostProcessor, TemplateConfig, WeaverConfig,
     };
     use crate::debug::print_dedup_errors;
     use crate::error::Error;
     use crate::extensions::case::case_converter;
     use crate::file_loader::FileSystemFileLoader;
+    use crate::plan::SkipReason;
     use crate::registry::ResolvedRegistry;
     use crate::v2::entity::Entity;
     use crate::v2::event::Event;
     use crate::v2::metric::Metric;
     use crate::v2::registry::{ForgeResolvedRegistry, Refinements, Registry as V2Registry};
     use crate::v2::span::Span;
-    use crate::{run_filter_raw, OutputDirective, TemplateEngine};
+    use crate::{run_filter_raw, IncrementalState, OutputDirective, TemplateEngine};
     use weaver_semconv::group::{InstrumentSpec, SpanKindSpec};
     use weaver_semconv::v2::{signal_id::SignalId, span::SpanName, CommonFields};
 
@@ -1266,6 +1661,7 @@ mod tests {
             file_name: None,
             auto_escape: AutoEscapeMode::None,
             when: None,
+            post_process: None,
         });
         engine.target_config.templates = Some(templates);
 
@@ -1323,6 +1719,7 @@ mod tests {
             file_name: None,
             auto_escape: AutoEscapeMode::None,
             when: when.map(str::to_owned),
+            post_process: None,
         };
 
         let no_params = BTreeMap::new();
@@ -1402,6 +1799,7 @@ mod tests {
             file_name: Some("converter.md".to_owned()),
             auto_escape: AutoEscapeMode::None,
             when: Some("false".to_owned()),
+            post_process: None,
         }]);
 
         let output = tempfile::tempdir().expect("Failed to create temp dir");
@@ -1415,6 +1813,228 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_generate_incremental() {
+        let loader = FileSystemFileLoader::try_new("templates".into(), "simple")
+            .expect("Failed to create file system loader");
+        let mut config = WeaverConfig::try_from_loader(&loader)
+            .expect("Failed to load `templates/simple/weaver.yaml`");
+        let template = |filter: &str, file_name: &str| TemplateConfig {
+            template: Glob::new("output.txt.j2").unwrap(),
+            filter: filter.to_owned(),
+            application_mode: ApplicationMode::Single,
+            params: None,
+            file_name: Some(file_name.to_owned()),
+            auto_escape: AutoEscapeMode::None,
+            when: None,
+            post_process: None,
+        };
+        config.templates = Some(vec![
+            template(".first", "first.txt"),
+            template(".second", "second.txt"),
+        ]);
+        let engine = TemplateEngine::try_new(config, loader, Params::default())
+            .expect("Failed to create template engine");
+
+        let output = tempfile::tempdir().expect("Failed to create temp dir");
+        let mut state = IncrementalState::default();
+        let mut generate = |context: &serde_json::Value, changed: &[PathBuf]| {
+            engine
+                .generate_incremental(
+                    context,
+                    output.path(),
+                    &OutputDirective::File,
+                    &mut state,
+                    changed,
+                )
+                .expect("Failed to generate")
+        };
+
+        let context = serde_json::json!({
+            "first": {"name": "a", "value": 1},
+            "second": {"name": "b", "value": 2},
+        });
+        // The first generation renders everything.
+        assert_eq!(generate(&context, &[]), 2);
+        // Nothing changed, nothing is rendered.
+        assert_eq!(generate(&context, &[]), 0);
+
+        // Only the template whose filter output changed is rendered.
+        let context = serde_json::json!({
+            "first": {"name": "a", "value": 1},
+            "second": {"name": "b", "value": 3},
+        });
+        assert_eq!(generate(&context, &[]), 1);
+        assert_eq!(
+            fs::read_to_string(output.path().join("second.txt")).unwrap(),
+            "b: 3"
+        );
+
+        // A changed template file is rendered again regardless of its input.
+        assert_eq!(generate(&context, &[PathBuf::from("output.txt.j2")]), 2);
+        assert_eq!(generate(&context, &[]), 0);
+
+        // A changed file that is not a template may be included by any template.
+        assert_eq!(generate(&context, &[PathBuf::from("macros.j2")]), 2);
+    }
+
+    #[test]
+    fn test_source_map_with_post_process() {
+        let templates = tempfile::tempdir().expect("Failed to create temp dir");
+        let target = templates.path().join("tgt");
+        fs::create_dir_all(&target).expect("Failed to create the target dir");
+        fs::write(
+            target.join("out.txt.j2"),
+            "{% for item in ctx.items %}{{ item | mark_source }}{{ item.name }}\n{% endfor %}",
+        )
+        .expect("Failed to write the template");
+        let loader = FileSystemFileLoader::try_new(templates.path().to_path_buf(), "tgt")
+            .expect("Failed to create file system loader");
+        let mut config = WeaverConfig {
+            source_map: Some(true),
+            post_process: Some(vec![PostProcessor::LicenseHeader {
+                license_header: "// Header\n// Second line\n".to_owned(),
+            }]),
+            templates: Some(vec![TemplateConfig {
+                template: Glob::new("out.txt.j2").unwrap(),
+                filter: ".".to_owned(),
+                application_mode: ApplicationMode::Single,
+                params: None,
+                file_name: Some("out.txt".to_owned()),
+                auto_escape: AutoEscapeMode::None,
+                when: None,
+                post_process: Some(vec![PostProcessor::Builtin(
+                    BuiltinPostProcessor::TrimTrailingWhitespace,
+                )]),
+            }]),
+            ..Default::default()
+        };
+        let context = serde_json::json!({"items": [
+            {"name": "a", "provenance": {"path": "a.yaml", "line": 3}},
+            {"name": "b", "provenance": {"path": "b.yaml", "line": 7}},
+        ]});
+
+        let engine = TemplateEngine::try_new(config.clone(), loader, Params::default())
+            .expect("Failed to create template engine");
+        let output = tempfile::tempdir().expect("Failed to create temp dir");
+        engine
+            .generate(&context, output.path(), &OutputDirective::File)
+            .expect("Failed to generate");
+        assert_eq!(
+            fs::read_to_string(output.path().join("out.txt")).unwrap(),
+            "// Header\n// Second line\na\nb\n"
+        );
+        let source_map: crate::source_map::SourceMap = serde_json::from_str(
+            &fs::read_to_string(output.path().join("out.txt.map.json")).unwrap(),
+        )
+        .expect("Invalid source map");
+        let ranges: Vec<_> = source_map
+            .mappings
+            .iter()
+            .map(|m| (m.start_line, m.end_line, m.source.as_str()))
+            .collect();
+        assert_eq!(ranges, vec![(3, 3, "a.yaml"), (4, 4, "b.yaml")]);
+
+        // A command could move the mapped lines.
+        config.allow_post_process_commands(true);
+        config.post_process = Some(vec![PostProcessor::Command {
+            command: vec!["cat".to_owned()],
+        }]);
+        let loader = FileSystemFileLoader::try_new(templates.path().to_path_buf(), "tgt")
+            .expect("Failed to create file system loader");
+        let engine = TemplateEngine::try_new(config, loader, Params::default())
+            .expect("Failed to create template engine");
+        assert!(matches!(
+            engine.generate(&context, output.path(), &OutputDirective::File),
+            Err(Error::SourceMapWithPostProcessCommand { .. })
+        ));
+    }
+
+    #[test]
+    fn test_plan() {
+        let loader = FileSystemFileLoader::try_new("templates".into(), "simple")
+            .expect("Failed to create file system loader");
+        let mut config = WeaverConfig::try_from_loader(&loader)
+            .expect("Failed to load `templates/simple/weaver.yaml`");
+        let template =
+            |filter: &str, mode: ApplicationMode, file_name: &str, when: Option<&str>| {
+                TemplateConfig {
+                    template: Glob::new("output.txt.j2").unwrap(),
+                    filter: filter.to_owned(),
+                    application_mode: mode,
+                    params: None,
+                    file_name: Some(file_name.to_owned()),
+                    auto_escape: AutoEscapeMode::None,
+                    when: when.map(str::to_owned),
+                    post_process: None,
+                }
+            };
+        config.templates = Some(vec![
+            template(".first", ApplicationMode::Single, "first.txt", None),
+            template(".items", ApplicationMode::Each, "{{ ctx.name }}.txt", None),
+            template(".missing", ApplicationMode::Single, "missing.txt", None),
+            template(".", ApplicationMode::Single, "gated.txt", Some("false")),
+        ]);
+        // The post-processors are not run, the command would fail.
+        config.post_process = Some(vec![PostProcessor::Command {
+            command: vec!["weaver-missing-formatter".to_owned()],
+        }]);
+        let engine = TemplateEngine::try_new(config, loader, Params::default())
+            .expect("Failed to create template engine");
+
+        let context = serde_json::json!({
+            "first": {"name": "a", "value": 1},
+            "items": [{"name": "b", "value": 2}, {"name": "c", "value": 3}],
+        });
+        let plan = engine.plan(&context).expect("Failed to plan");
+        let summary: Vec<_> = plan
+            .templates
+            .iter()
+            .map(|t| {
+                (
+                    t.filter.as_str(),
+                    t.items,
+                    t.files.clone(),
+                    t.skipped.clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (".first", Some(1), vec![PathBuf::from("first.txt")], None),
+                (
+                    ".items",
+                    Some(2),
+                    vec![PathBuf::from("b.txt"), PathBuf::from("c.txt")],
+                    None
+                ),
+                (
+                    ".missing",
+                    Some(0),
+                    vec![],
+                    Some(SkipReason::EmptyFilterResult)
+                ),
+                (
+                    ".",
+                    None,
+                    vec![],
+                    Some(SkipReason::When {
+                        when: "false".to_owned()
+                    })
+                ),
+            ]
+        );
+        assert_eq!(plan.files().len(), 3);
+        let text = plan.to_string();
+        assert!(text.contains("  -> b.txt"), "{text}");
+        assert!(
+            text.contains("skipped: `when` clause `false` is not met"),
+            "{text}"
+        );
+        assert!(text.ends_with("4 template configuration(s), 3 file(s) would be generated"));
+    }
+
     #[test]
     fn test_generate_to_string_skips_template_when_false() {
         let (mut engine, registry) = prepare_test_readonly("test", Params::default(), true);
@@ -1427,6 +2047,7 @@ mod tests {
             file_name: None,
             auto_escape: AutoEscapeMode::None,
             when: Some(when.to_owned()),
+            post_process: None,
         };
 
         // With a `when` that evaluates to `true`, string generation renders the template.
@@ -0,0 +1,219 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Manifest of the files produced by a generation.
+//!
+//! The manifest is saved in the output directory. It records the content hash of
+//! each generated file and the template that produced it, so that a later
+//! generation can prune the files it no longer produces without touching the
+//! files edited by hand since.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::error::Error;
+use crate::error::Error::GenerationManifestFailed;
+
+/// Name of the manifest file in the output directory.
+pub const MANIFEST_FILE_NAME: &str = ".weaver-manifest.json";
+
+/// The files produced by a generation.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct GenerationManifest {
+    /// Generated files, by `/`-separated path relative to the output directory.
+    pub files: BTreeMap<String, GeneratedFile>,
+}
+
+/// A file produced by a generation.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GeneratedFile {
+    /// Hex-encoded SHA-256 of the generated content.
+    pub sha256: String,
+    /// The template that produced the file, relative to the templates of the target.
+    pub template: String,
+}
+
+/// The outcome of a prune.
+#[derive(Debug, Default)]
+pub struct PruneReport {
+    /// Files deleted from the output directory.
+    pub deleted: Vec<PathBuf>,
+    /// Files not generated anymore but kept because they were edited since
+    /// their generation.
+    pub modified: Vec<PathBuf>,
+}
+
+impl GenerationManifest {
+    /// Record a generated file.
+    pub fn record(&mut self, path: &Path, content: &str, template: &Path) {
+        _ = self.files.insert(
+            to_manifest_path(path),
+            GeneratedFile {
+                sha256: sha256(content.as_bytes()),
+                template: to_manifest_path(template),
+            },
+        );
+    }
+
+    /// Load the manifest of the output directory, `None` if there is none.
+    pub fn load(output_dir: &Path) -> Result<Option<Self>, Error> {
+        let path = output_dir.join(MANIFEST_FILE_NAME);
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => {
+                return Err(GenerationManifestFailed {
+                    path,
+                    error: e.to_string(),
+                })
+            }
+        };
+        serde_json::from_str(&content)
+            .map(Some)
+            .map_err(|e| GenerationManifestFailed {
+                path,
+                error: e.to_string(),
+            })
+    }
+
+    /// Save the manifest in the output directory.
+    pub fn save(&self, output_dir: &Path) -> Result<(), Error> {
+        let path = output_dir.join(MANIFEST_FILE_NAME);
+        let mut content =
+            serde_json::to_string_pretty(self).map_err(|e| GenerationManifestFailed {
+                path: path.clone(),
+                error: e.to_string(),
+            })?;
+        content.push('\n');
+        fs::create_dir_all(output_dir)
+            .and_then(|()| fs::write(&path, content))
+            .map_err(|e| GenerationManifestFailed {
+                path,
+                error: e.to_string(),
+            })
+    }
+
+    /// Returns true if the file at `path`, relative to the output directory, is recorded
+    /// in this manifest.
+    #[must_use]
+    pub fn contains(&self, path: &Path) -> bool {
+        self.files.contains_key(&to_manifest_path(path))
+    }
+
+    /// Keep the files of the `previous` manifest that this manifest does not
+    /// contain, so that a later prune can still delete them.
+    pub fn carry_over(&mut self, previous: &Self) {
+        for (file, generated) in &previous.files {
+            _ = self
+                .files
+                .entry(file.clone())
+                .or_insert_with(|| generated.clone());
+        }
+    }
+
+    /// Delete the files of the `previous` manifest that this manifest does not
+    /// contain. A file whose content no longer matches its recorded hash was
+    /// edited by hand and is kept. Directories left empty are removed.
+    pub fn prune(&self, previous: &Self, output_dir: &Path) -> Result<PruneReport, Error> {
+        let mut report = PruneReport::default();
+        for (file, generated) in &previous.files {
+            // Only relative paths below the output directory are pruned.
+            let is_below_output_dir = Path::new(file)
+                .components()
+                .all(|c| matches!(c, Component::Normal(_)));
+            if self.files.contains_key(file) || !is_below_output_dir {
+                continue;
+            }
+            let path = output_dir.join(file);
+            let content = match fs::read(&path) {
+                Ok(content) => content,
+                // Already gone.
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => {
+                    return Err(GenerationManifestFailed {
+                        path,
+                        error: e.to_string(),
+                    })
+                }
+            };
+            if sha256(&content) != generated.sha256 {
+                report.modified.push(path);
+                continue;
+            }
+            fs::remove_file(&path).map_err(|e| GenerationManifestFailed {
+                path: path.clone(),
+                error: e.to_string(),
+            })?;
+            remove_empty_parents(&path, output_dir);
+            report.deleted.push(path);
+        }
+        Ok(report)
+    }
+}
+
+/// Remove the directories left empty between a deleted file and the output directory.
+fn remove_empty_parents(path: &Path, output_dir: &Path) {
+    for dir in path.ancestors().skip(1) {
+        // `remove_dir` fails on a non-empty directory, which ends the walk.
+        if dir == output_dir || !dir.starts_with(output_dir) || fs::remove_dir(dir).is_err() {
+            break;
+        }
+    }
+}
+
+/// A relative path with `/` separators, so that manifests are portable.
+fn to_manifest_path(path: &Path) -> String {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn sha256(content: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(content))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prune() {
+        let output = tempfile::tempdir().expect("Failed to create temp dir");
+        let dir = output.path();
+        let write = |file: &str, content: &str| {
+            let path = dir.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        };
+
+        let mut previous = GenerationManifest::default();
+        for (file, content) in [
+            ("kept.rs", "kept"),
+            ("ns/gone.rs", "gone"),
+            ("edited.rs", "a"),
+        ] {
+            write(file, content);
+            previous.record(Path::new(file), content, Path::new("tmpl.j2"));
+        }
+        write("edited.rs", "edited by hand");
+        previous.save(dir).unwrap();
+        assert_eq!(
+            GenerationManifest::load(dir).unwrap(),
+            Some(previous.clone())
+        );
+
+        let mut current = GenerationManifest::default();
+        current.record(Path::new("kept.rs"), "kept", Path::new("tmpl.j2"));
+        let report = current.prune(&previous, dir).unwrap();
+
+        assert_eq!(report.deleted, vec![dir.join("ns/gone.rs")]);
+        assert_eq!(report.modified, vec![dir.join("edited.rs")]);
+        assert!(!dir.join("ns").exists());
+        assert!(dir.join("kept.rs").exists());
+        assert!(dir.join("edited.rs").exists());
+    }
+}
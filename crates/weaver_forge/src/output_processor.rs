@@ -2,9 +2,11 @@
 
 //! General-purpose output processor supporting builtin formats and templates.
 
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::Write;
 use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 
 use include_dir::Dir;
 use serde::Serialize;
@@ -12,7 +14,9 @@ use serde::Serialize;
 use crate::config::{Params, WeaverConfig};
 use crate::error::Error;
 use crate::file_loader::{EmbeddedFileLoader, FileLoader};
-use crate::{OutputDirective, TemplateEngine};
+use crate::manifest::GenerationManifest;
+use crate::plan::GenerationPlan;
+use crate::{IncrementalState, OutputDirective, TemplateEngine};
 
 /// Specifies where output should be written.
 #[derive(Debug, Clone)]
@@ -319,6 +323,127 @@ impl OutputProcessor {
         }
     }
 
+    /// Generate output for serializable data and return the manifest of the
+    /// generated files.
+    ///
+    /// Only valid for `Template` variants writing to a file or a directory. The
+    /// manifest is not saved, see [`GenerationManifest::save`].
+    pub fn generate_with_manifest<T: Serialize>(
+        &self,
+        data: &T,
+    ) -> Result<GenerationManifest, Error> {
+        match &self.kind {
+            OutputKind::Template(t) => match &t.target {
+                OutputTarget::File(p) | OutputTarget::Directory(p) => {
+                    let manifest = Arc::new(Mutex::new(GenerationManifest::default()));
+                    t.engine.generate(
+                        data,
+                        p,
+                        &OutputDirective::FileWithManifest(Arc::clone(&manifest)),
+                    )?;
+                    let manifest = manifest
+                        .lock()
+                        .map_err(|e| Error::InternalError(e.to_string()))?;
+                    Ok(manifest.clone())
+                }
+                _ => Err(Error::InternalError(
+                    "A generation manifest requires a file or directory target".to_owned(),
+                )),
+            },
+            OutputKind::Builtin { .. } | OutputKind::Mute => Err(Error::InvalidTemplateDir {
+                template_dir: PathBuf::from("(not a template)"),
+                error:
+                    "generate_with_manifest is only supported for template-based OutputProcessor"
+                        .to_owned(),
+            }),
+        }
+    }
+
+    /// Render the files that `generate` would write, without writing them.
+    ///
+    /// Returns the content of each generated file by path relative to the output
+    /// directory. Only valid for `Template` variants writing to a file or a directory.
+    pub fn generate_in_memory<T: Serialize>(
+        &self,
+        data: &T,
+    ) -> Result<BTreeMap<PathBuf, String>, Error> {
+        match &self.kind {
+            OutputKind::Template(t) => match &t.target {
+                OutputTarget::File(p) | OutputTarget::Directory(p) => {
+                    let files = Arc::new(Mutex::new(BTreeMap::new()));
+                    t.engine
+                        .generate(data, p, &OutputDirective::Memory(Arc::clone(&files)))?;
+                    let files = files
+                        .lock()
+                        .map_err(|e| Error::InternalError(e.to_string()))?;
+                    Ok(files.clone())
+                }
+                _ => Err(Error::InternalError(
+                    "In-memory generation requires a file or directory target".to_owned(),
+                )),
+            },
+            OutputKind::Builtin { .. } | OutputKind::Mute => Err(Error::InvalidTemplateDir {
+                template_dir: PathBuf::from("(not a template)"),
+                error: "generate_in_memory is only supported for template-based OutputProcessor"
+                    .to_owned(),
+            }),
+        }
+    }
+
+    /// Plan the generation of serializable data without writing anything.
+    ///
+    /// Returns, for each template, the number of items produced by its filter, the
+    /// files it would generate relative to the output directory and why it would be
+    /// skipped. Only valid for `Template` variants.
+    pub fn generate_plan<T: Serialize>(&self, data: &T) -> Result<GenerationPlan, Error> {
+        match &self.kind {
+            OutputKind::Template(t) => t.engine.plan(data),
+            OutputKind::Builtin { .. } | OutputKind::Mute => Err(Error::InvalidTemplateDir {
+                template_dir: PathBuf::from("(not a template)"),
+                error: "generate_plan is only supported for template-based OutputProcessor"
+                    .to_owned(),
+            }),
+        }
+    }
+
+    /// Incrementally generate output for serializable data.
+    ///
+    /// Only the templates whose filter output changed since the previous call
+    /// with the same `state`, or whose file is listed in `changed_templates`
+    /// (relative to the templates root), are rendered. A changed file that is not
+    /// a template renders every template. Returns the number of template files
+    /// rendered.
+    ///
+    /// Only valid for `Template` variants writing to a file or a directory.
+    pub fn generate_incremental<T: Serialize>(
+        &self,
+        data: &T,
+        state: &mut IncrementalState,
+        changed_templates: &[PathBuf],
+    ) -> Result<usize, Error> {
+        match &self.kind {
+            OutputKind::Template(t) => match &t.target {
+                OutputTarget::File(p) | OutputTarget::Directory(p) => {
+                    t.engine.generate_incremental(
+                        data,
+                        p,
+                        &OutputDirective::File,
+                        state,
+                        changed_templates,
+                    )
+                }
+                _ => Err(Error::InternalError(
+                    "Incremental generation requires a file or directory target".to_owned(),
+                )),
+            },
+            OutputKind::Builtin { .. } | OutputKind::Mute => Err(Error::InvalidTemplateDir {
+                template_dir: PathBuf::from("(not a template)"),
+                error: "generate_incremental is only supported for template-based OutputProcessor"
+                    .to_owned(),
+            }),
+        }
+    }
+
     /// Serialize/render data to a String without writing to stdout/file.
     pub fn generate_to_string<T: Serialize>(&self, data: &T) -> Result<String, Error> {
         match &self.kind {
@@ -0,0 +1,502 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Template packages.
+//!
+//! A templates directory with a `weaver-package.yaml` manifest is a template
+//! package. The manifest declares the name and the version of the package, the
+//! Weaver versions and the registry schemas it supports, and the packages it
+//! depends on. The dependencies are fetched as virtual directories (local folder
+//! or archive, remote archive, git repository) and their templates are layered
+//! under the templates of the package: a file of the package overrides the file
+//! with the same path in its dependencies.
+//!
+//! The resolved dependencies are recorded with the hash of their content in a
+//! `weaver-package.lock` file next to the manifest, so that a later generation
+//! fails if a dependency has changed.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use walkdir::WalkDir;
+use weaver_common::http_auth::HttpAuthResolver;
+use weaver_common::vdir::{VirtualDirectory, VirtualDirectoryPath};
+
+use crate::error::Error;
+use crate::error::Error::{
+    InvalidTemplatePackage, TemplatePackageLockMismatch, TemplatePackageLockMissing,
+};
+
+/// Name of the manifest of a template package.
+pub const PACKAGE_MANIFEST_FILE_NAME: &str = "weaver-package.yaml";
+
+/// Name of the lock file of the dependencies of a template package.
+pub const PACKAGE_LOCK_FILE_NAME: &str = "weaver-package.lock";
+
+/// The manifest of a template package.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct PackageManifest {
+    /// The name of the package.
+    pub name: String,
+    /// The version of the package.
+    pub version: Version,
+    /// An optional description of the package.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// The minimum version of Weaver required by the templates.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_weaver_version: Option<Version>,
+    /// The versions of the registry schema the templates support. All of them if empty.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub registry_schemas: Vec<RegistrySchema>,
+    /// The packages the templates depend on.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub dependencies: Vec<PackageDependency>,
+}
+
+/// How [`TemplatePackages::check_lock`] handles the lock file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockMode {
+    /// Only check the lock file, a missing one is reported.
+    Check,
+    /// Check the lock file, or write it if it doesn't exist.
+    WriteMissing,
+    /// Write the lock file with the resolved dependencies.
+    Update,
+}
+
+/// A version of the registry schema.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RegistrySchema {
+    /// The v1 resolved registry.
+    V1,
+    /// The v2 resolved registry.
+    V2,
+}
+
+/// A dependency of a template package.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct PackageDependency {
+    /// The name of the package.
+    pub name: String,
+    /// The versions of the package that are accepted. Any version by default.
+    #[serde(default)]
+    pub version: VersionReq,
+    /// Where to fetch the package from. A relative local path is relative to the
+    /// directory of the package declaring the dependency.
+    pub path: VirtualDirectoryPath,
+}
+
+/// The lock file of the dependencies of a template package.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct PackageLock {
+    /// The resolved dependencies, direct and transitive, by name.
+    pub packages: BTreeMap<String, LockedPackage>,
+}
+
+/// A resolved dependency recorded in the lock file.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LockedPackage {
+    /// The resolved version.
+    pub version: Version,
+    /// Where the package was fetched from.
+    pub source: String,
+    /// Hex-encoded SHA-256 of the files of the package.
+    pub sha256: String,
+}
+
+/// A templates directory and the template packages it depends on.
+pub struct TemplatePackages {
+    /// The templates directory, then its dependencies in the order they override
+    /// each other: a package always comes before its dependencies.
+    dirs: Vec<VirtualDirectory>,
+    /// The resolved dependencies.
+    lock: PackageLock,
+}
+
+impl TemplatePackages {
+    /// Resolve the dependencies of a templates directory. A templates directory
+    /// without a manifest has no dependencies. Fails if a package is not compatible
+    /// with this version of Weaver or with the registry schema, or if two packages
+    /// require incompatible versions of the same dependency.
+    pub fn resolve(
+        templates_dir: VirtualDirectory,
+        registry_schema: RegistrySchema,
+        auth: &HttpAuthResolver,
+    ) -> Result<Self, Error> {
+        let mut packages = Self {
+            dirs: Vec::new(),
+            lock: PackageLock::default(),
+        };
+        let manifest = load_manifest(templates_dir.path(), templates_dir.vdir_path_str())?;
+        let base_dir = templates_dir.path().to_path_buf();
+        packages.dirs.push(templates_dir);
+        if let Some(manifest) = manifest {
+            check_compatibility(&manifest, registry_schema)?;
+            packages.resolve_dependencies(&manifest, &base_dir, registry_schema, auth)?;
+        }
+        Ok(packages)
+    }
+
+    fn resolve_dependencies(
+        &mut self,
+        manifest: &PackageManifest,
+        base_dir: &Path,
+        registry_schema: RegistrySchema,
+        auth: &HttpAuthResolver,
+    ) -> Result<(), Error> {
+        for dependency in &manifest.dependencies {
+            if let Some(locked) = self.lock.packages.get(&dependency.name) {
+                if !dependency.version.matches(&locked.version) {
+                    return Err(InvalidTemplatePackage {
+                        package: dependency.name.clone(),
+                        error: format!(
+                            "`{}` requires the version `{}` but the version {} is already required by another package",
+                            manifest.name, dependency.version, locked.version
+                        ),
+                    });
+                }
+                continue;
+            }
+
+            // The declared path is recorded, a relative one is resolved in a
+            // directory that may be temporary.
+            let source = dependency.path.to_string();
+            let path = relative_to(&dependency.path, base_dir);
+            let dir = VirtualDirectory::try_new_with_auth(&path, auth).map_err(|e| {
+                InvalidTemplatePackage {
+                    package: dependency.name.clone(),
+                    error: format!("failed to fetch `{source}`: {e}"),
+                }
+            })?;
+            let dep_manifest =
+                load_manifest(dir.path(), &source)?.ok_or_else(|| InvalidTemplatePackage {
+                    package: dependency.name.clone(),
+                    error: format!("no `{PACKAGE_MANIFEST_FILE_NAME}` found in `{source}`"),
+                })?;
+            if dep_manifest.name != dependency.name {
+                return Err(InvalidTemplatePackage {
+                    package: dependency.name.clone(),
+                    error: format!("`{source}` contains the package `{}`", dep_manifest.name),
+                });
+            }
+            if !dependency.version.matches(&dep_manifest.version) {
+                return Err(InvalidTemplatePackage {
+                    package: dependency.name.clone(),
+                    error: format!(
+                        "`{}` requires the version `{}` but `{source}` contains the version {}",
+                        manifest.name, dependency.version, dep_manifest.version
+                    ),
+                });
+            }
+            check_compatibility(&dep_manifest, registry_schema)?;
+
+            _ = self.lock.packages.insert(
+                dependency.name.clone(),
+                LockedPackage {
+                    version: dep_manifest.version.clone(),
+                    source,
+                    sha256: hash_dir(dir.path())?,
+                },
+            );
+            let dep_dir = dir.path().to_path_buf();
+            self.dirs.push(dir);
+            self.resolve_dependencies(&dep_manifest, &dep_dir, registry_schema, auth)?;
+        }
+        Ok(())
+    }
+
+    /// The templates directory, then the directories of its dependencies, from
+    /// the highest to the lowest precedence.
+    #[must_use]
+    pub fn dirs(&self) -> &[VirtualDirectory] {
+        &self.dirs
+    }
+
+    /// The resolved dependencies.
+    #[must_use]
+    pub fn lock(&self) -> &PackageLock {
+        &self.lock
+    }
+
+    /// Check the resolved dependencies against the lock file of the templates
+    /// directory, or write it as allowed by `mode`. Only the lock file of a local
+    /// templates directory is written. Nothing is done for a templates directory
+    /// without dependencies.
+    pub fn check_lock(&self, mode: LockMode) -> Result<(), Error> {
+        let lock_file = self.dirs[0].path().join(PACKAGE_LOCK_FILE_NAME);
+        if self.lock.packages.is_empty() && !lock_file.exists() {
+            return Ok(());
+        }
+        if mode != LockMode::Update && lock_file.exists() {
+            let content = fs::read_to_string(&lock_file).map_err(|e| InvalidTemplatePackage {
+                package: lock_file.display().to_string(),
+                error: e.to_string(),
+            })?;
+            let locked: PackageLock =
+                serde_yaml::from_str(&content).map_err(|e| InvalidTemplatePackage {
+                    package: lock_file.display().to_string(),
+                    error: e.to_string(),
+                })?;
+            return match lock_difference(&locked, &self.lock) {
+                Some(error) => Err(TemplatePackageLockMismatch { lock_file, error }),
+                None => Ok(()),
+            };
+        }
+        let local = matches!(
+            self.dirs[0].vdir_path(),
+            VirtualDirectoryPath::LocalFolder { .. }
+        );
+        if mode == LockMode::Update && !local {
+            return Err(InvalidTemplatePackage {
+                package: self.dirs[0].vdir_path_str().to_owned(),
+                error: "the lock file of a remote templates directory can't be updated".to_owned(),
+            });
+        }
+        if mode == LockMode::Check || !local {
+            return Err(TemplatePackageLockMissing { lock_file });
+        }
+        let content = serde_yaml::to_string(&self.lock).map_err(|e| InvalidTemplatePackage {
+            package: lock_file.display().to_string(),
+            error: e.to_string(),
+        })?;
+        fs::write(&lock_file, content).map_err(|e| InvalidTemplatePackage {
+            package: lock_file.display().to_string(),
+            error: e.to_string(),
+        })
+    }
+}
+
+/// Load the manifest of the package in `dir`, if any.
+fn load_manifest(dir: &Path, source: &str) -> Result<Option<PackageManifest>, Error> {
+    let path = dir.join(PACKAGE_MANIFEST_FILE_NAME);
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let invalid = |error: String| InvalidTemplatePackage {
+        package: source.to_owned(),
+        error,
+    };
+    let content = fs::read_to_string(&path).map_err(|e| invalid(e.to_string()))?;
+    serde_yaml::from_str(&content)
+        .map(Some)
+        .map_err(|e| invalid(format!("invalid `{PACKAGE_MANIFEST_FILE_NAME}`: {e}")))
+}
+
+/// Check that a package supports this version of Weaver and the registry schema.
+fn check_compatibility(
+    manifest: &PackageManifest,
+    registry_schema: RegistrySchema,
+) -> Result<(), Error> {
+    let weaver_version =
+        Version::parse(env!("CARGO_PKG_VERSION")).expect("The crate version is valid semver");
+    if let Some(min_version) = &manifest.min_weaver_version {
+        if &weaver_version < min_version {
+            return Err(InvalidTemplatePackage {
+                package: manifest.name.clone(),
+                error: format!(
+                    "requires Weaver {min_version} or later, the current version is {weaver_version}"
+                ),
+            });
+        }
+    }
+    if !manifest.registry_schemas.is_empty()
+        && !manifest.registry_schemas.contains(&registry_schema)
+    {
+        return Err(InvalidTemplatePackage {
+            package: manifest.name.clone(),
+            error: format!(
+                "does not support the {registry_schema:?} registry schema, supported schemas: {:?}",
+                manifest.registry_schemas
+            ),
+        });
+    }
+    Ok(())
+}
+
+/// Resolve a relative local path against the directory of the declaring package.
+fn relative_to(path: &VirtualDirectoryPath, base_dir: &Path) -> VirtualDirectoryPath {
+    let join = |path: &str| {
+        if Path::new(path).is_relative() {
+            base_dir.join(path).display().to_string()
+        } else {
+            path.to_owned()
+        }
+    };
+    match path {
+        VirtualDirectoryPath::LocalFolder { path } => {
+            VirtualDirectoryPath::LocalFolder { path: join(path) }
+        }
+        VirtualDirectoryPath::LocalArchive { path, sub_folder } => {
+            VirtualDirectoryPath::LocalArchive {
+                path: join(path),
+                sub_folder: sub_folder.clone(),
+            }
+        }
+        _ => path.clone(),
+    }
+}
+
+/// Hash the files of a directory, with their relative paths, in a stable order.
+fn hash_dir(dir: &Path) -> Result<String, Error> {
+    let mut files: Vec<PathBuf> = WalkDir::new(dir)
+        .into_iter()
+        .filter_entry(|entry| entry.file_name() != ".git")
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.into_path())
+        .collect();
+    files.sort();
+    let mut hasher = Sha256::new();
+    for file in files {
+        let relative = file
+            .strip_prefix(dir)
+            .expect("A walked file is in the walked directory");
+        let content = fs::read(&file).map_err(|e| InvalidTemplatePackage {
+            package: dir.display().to_string(),
+            error: format!("failed to read `{}`: {e}", file.display()),
+        })?;
+        let relative = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        hasher.update(relative.as_bytes());
+        hasher.update([0]);
+        hasher.update(&content);
+        hasher.update([0]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Describe the first difference between the lock file and the resolved dependencies.
+fn lock_difference(locked: &PackageLock, resolved: &PackageLock) -> Option<String> {
+    for (name, package) in &resolved.packages {
+        match locked.packages.get(name) {
+            None => return Some(format!("`{name}` is not locked")),
+            Some(lock) if lock.version != package.version => {
+                return Some(format!(
+                    "`{name}` is locked at version {} but resolved to version {}",
+                    lock.version, package.version
+                ))
+            }
+            Some(lock) if lock.source != package.source => {
+                return Some(format!(
+                    "`{name}` is locked from `{}` but fetched from `{}`",
+                    lock.source, package.source
+                ))
+            }
+            Some(lock) if lock.sha256 != package.sha256 => {
+                return Some(format!(
+                    "the content of `{name}` {} has changed since it was locked",
+                    package.version
+                ))
+            }
+            Some(_) => {}
+        }
+    }
+    locked
+        .packages
+        .keys()
+        .find(|name| !resolved.packages.contains_key(*name))
+        .map(|name| format!("`{name}` is locked but not a dependency anymore"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, file: &str, content: &str) {
+        let path = dir.join(file);
+        fs::create_dir_all(path.parent().expect("The file has a parent")).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_resolve_and_lock() {
+        let tmp = tempfile::tempdir().unwrap();
+        let consumer = tmp.path().join("consumer");
+        let shared = tmp.path().join("shared");
+        let base = tmp.path().join("base");
+        write(
+            &consumer,
+            PACKAGE_MANIFEST_FILE_NAME,
+            "name: consumer\nversion: 0.1.0\ndependencies:\n  - name: shared\n    version: ^1.2\n    path: ../shared\n",
+        );
+        write(
+            &shared,
+            PACKAGE_MANIFEST_FILE_NAME,
+            "name: shared\nversion: 1.2.3\nregistry_schemas: [v2]\ndependencies:\n  - name: base\n    path: ../base\n",
+        );
+        write(&shared, "registry/go/a.j2", "shared a");
+        write(
+            &base,
+            PACKAGE_MANIFEST_FILE_NAME,
+            "name: base\nversion: 0.0.1\n",
+        );
+
+        let auth = HttpAuthResolver::default();
+        let resolve = || {
+            let dir = VirtualDirectory::try_new(&VirtualDirectoryPath::LocalFolder {
+                path: consumer.display().to_string(),
+            })
+            .unwrap();
+            TemplatePackages::resolve(dir, RegistrySchema::V2, &auth)
+        };
+
+        let packages = resolve().unwrap();
+        assert_eq!(packages.dirs().len(), 3);
+        assert_eq!(
+            packages.lock().packages.keys().collect::<Vec<_>>(),
+            vec!["base", "shared"]
+        );
+        assert_eq!(
+            packages.lock().packages["shared"].version,
+            Version::new(1, 2, 3)
+        );
+
+        // A missing lock file is only reported when checking, then it is
+        // written and checked.
+        assert!(matches!(
+            packages.check_lock(LockMode::Check),
+            Err(TemplatePackageLockMissing { .. })
+        ));
+        assert!(!consumer.join(PACKAGE_LOCK_FILE_NAME).exists());
+        packages.check_lock(LockMode::WriteMissing).unwrap();
+        assert!(consumer.join(PACKAGE_LOCK_FILE_NAME).exists());
+        resolve().unwrap().check_lock(LockMode::Check).unwrap();
+
+        // A changed dependency doesn't match the lock file anymore.
+        write(&shared, "registry/go/a.j2", "changed a");
+        let packages = resolve().unwrap();
+        assert!(matches!(
+            packages.check_lock(LockMode::WriteMissing),
+            Err(TemplatePackageLockMismatch { .. })
+        ));
+        packages.check_lock(LockMode::Update).unwrap();
+        resolve().unwrap().check_lock(LockMode::Check).unwrap();
+
+        // Incompatible registry schema and version requirement.
+        let dir = VirtualDirectory::try_new(&VirtualDirectoryPath::LocalFolder {
+            path: consumer.display().to_string(),
+        })
+        .unwrap();
+        assert!(TemplatePackages::resolve(dir, RegistrySchema::V1, &auth).is_err());
+        write(
+            &shared,
+            PACKAGE_MANIFEST_FILE_NAME,
+            "name: shared\nversion: 2.0.0\n",
+        );
+        assert!(resolve().is_err());
+    }
+}
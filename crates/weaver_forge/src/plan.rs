@@ -0,0 +1,119 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Plan of a generation.
+//!
+//! A plan describes what a generation would do without writing anything: for
+//! each template file and each template configuration applied to it, the
+//! number of items produced by the filter, the files that would be generated
+//! and, when nothing is generated, the reason why.
+
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::config::ApplicationMode;
+
+/// The plan of a generation.
+#[derive(Serialize, Debug, Default, Clone, PartialEq)]
+pub struct GenerationPlan {
+    /// The template configurations applied to each template file, sorted by
+    /// template file.
+    pub templates: Vec<TemplatePlan>,
+}
+
+/// The plan of a template file for one of the template configurations matching it.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct TemplatePlan {
+    /// The template file, relative to the templates of the target.
+    pub template: PathBuf,
+    /// The pattern of the template configuration matching the template file.
+    pub pattern: String,
+    /// The filter of the template configuration.
+    pub filter: String,
+    /// The application mode of the template configuration.
+    pub application_mode: ApplicationMode,
+    /// The `when` clause of the template configuration, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub when: Option<String>,
+    /// The number of items produced by the filter: the length of an array, 0 for
+    /// `null`, 1 for any other value. `None` when the template is skipped by its
+    /// `when` clause.
+    pub items: Option<usize>,
+    /// The files that would be generated, relative to the output directory.
+    pub files: Vec<PathBuf>,
+    /// Why the template generates nothing, if it is skipped.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skipped: Option<SkipReason>,
+}
+
+/// Why a template generates nothing.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum SkipReason {
+    /// The `when` clause of the template configuration evaluated to `false`.
+    When {
+        /// The `when` clause.
+        when: String,
+    },
+    /// The filter produced `null` or an empty array.
+    EmptyFilterResult,
+}
+
+impl GenerationPlan {
+    /// All the files that would be generated, sorted and deduplicated.
+    #[must_use]
+    pub fn files(&self) -> Vec<&PathBuf> {
+        let mut files: Vec<_> = self.templates.iter().flat_map(|t| &t.files).collect();
+        files.sort();
+        files.dedup();
+        files
+    }
+}
+
+impl Display for SkipReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SkipReason::When { when } => write!(f, "`when` clause `{when}` is not met"),
+            SkipReason::EmptyFilterResult => write!(f, "the filter produced no items"),
+        }
+    }
+}
+
+impl Display for GenerationPlan {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for template in &self.templates {
+            let mode = match template.application_mode {
+                ApplicationMode::Single => "single",
+                ApplicationMode::Each => "each",
+            };
+            writeln!(
+                f,
+                "{} (pattern: `{}`, filter: `{}`, mode: {mode})",
+                template.template.display(),
+                template.pattern,
+                template.filter
+            )?;
+            if let Some(when) = &template.when {
+                writeln!(f, "  when: `{when}`")?;
+            }
+            if let Some(items) = template.items {
+                writeln!(f, "  items: {items}")?;
+            }
+            match &template.skipped {
+                Some(reason) => writeln!(f, "  skipped: {reason}")?,
+                None => {
+                    for file in &template.files {
+                        writeln!(f, "  -> {}", file.display())?;
+                    }
+                }
+            }
+        }
+        let files = self.files().len();
+        write!(
+            f,
+            "{} template configuration(s), {files} file(s) would be generated",
+            self.templates.len()
+        )
+    }
+}
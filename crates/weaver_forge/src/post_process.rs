@@ -0,0 +1,249 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Post-processing of the generated files.
+
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::config::{BuiltinPostProcessor, PostProcessor, WeaverConfig};
+use crate::error::Error;
+use crate::error::Error::{
+    PostProcessCommandNotAllowed, PostProcessingFailed, SourceMapWithPostProcessCommand,
+};
+
+/// Placeholder of a command argument replaced by the path of the file to process.
+const FILE_PLACEHOLDER: &str = "{file}";
+
+/// Apply the post-processors, in order, to the content of a generated file.
+/// `relative_path` is the path of the file relative to the output directory.
+pub(crate) fn apply(
+    processors: &[&PostProcessor],
+    relative_path: &Path,
+    mut content: String,
+) -> Result<String, Error> {
+    for processor in processors {
+        content = match processor {
+            PostProcessor::Builtin(BuiltinPostProcessor::TrimTrailingWhitespace) => {
+                trim_trailing_whitespace(&content)
+            }
+            PostProcessor::LicenseHeader { license_header } => {
+                insert_header(license_header, content)
+            }
+            PostProcessor::Command { command } => run_command(command, relative_path, content)?,
+        };
+    }
+    Ok(content)
+}
+
+/// Check that the configuration doesn't use a `command` post-processor unless
+/// commands are allowed, nor along with source maps: a command, such as a code
+/// formatter, can move the lines mapped by the source map.
+pub(crate) fn check_commands(config: &WeaverConfig) -> Result<(), Error> {
+    let Some(command) = commands(config).next() else {
+        return Ok(());
+    };
+    let command = command.join(" ");
+    if !config.allow_post_process_commands {
+        return Err(PostProcessCommandNotAllowed { command });
+    }
+    if config.source_map.unwrap_or(false) {
+        return Err(SourceMapWithPostProcessCommand { command });
+    }
+    Ok(())
+}
+
+/// The commands of the `command` post-processors of the target and of its templates.
+fn commands(config: &WeaverConfig) -> impl Iterator<Item = &Vec<String>> {
+    config
+        .post_process
+        .iter()
+        .chain(
+            config
+                .templates
+                .iter()
+                .flatten()
+                .flat_map(|template| &template.post_process),
+        )
+        .flatten()
+        .filter_map(|processor| match processor {
+            PostProcessor::Command { command } => Some(command),
+            _ => None,
+        })
+}
+
+/// Remove the trailing spaces and tabs of each line.
+fn trim_trailing_whitespace(content: &str) -> String {
+    content
+        .split_inclusive('\n')
+        .map(|line| {
+            let (line, eol) = match line.strip_suffix("\r\n") {
+                Some(line) => (line, "\r\n"),
+                None => match line.strip_suffix('\n') {
+                    Some(line) => (line, "\n"),
+                    None => (line, ""),
+                },
+            };
+            format!("{}{eol}", line.trim_end_matches([' ', '\t']))
+        })
+        .collect()
+}
+
+/// Insert the header at the top of the content unless it already starts with it.
+fn insert_header(header: &str, content: String) -> String {
+    if content.starts_with(header.trim_end()) {
+        return content;
+    }
+    let mut with_header = header.trim_end().to_owned();
+    with_header.push('\n');
+    with_header.push_str(&content);
+    with_header
+}
+
+/// Run an external command on the content, through its standard input and output
+/// or through a temporary copy of the file when an argument is `{file}`.
+fn run_command(command: &[String], relative_path: &Path, content: String) -> Result<String, Error> {
+    let failed = |error: String| PostProcessingFailed {
+        file: relative_path.to_path_buf(),
+        command: command.join(" "),
+        error,
+    };
+    let (program, args) = command
+        .split_first()
+        .ok_or_else(|| failed("the command is empty".to_owned()))?;
+
+    if !args.iter().any(|arg| arg.contains(FILE_PLACEHOLDER)) {
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| failed(e.to_string()))?;
+        // The input is written from another thread so that a command writing a
+        // large output before reading all of its input doesn't block.
+        let mut stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| failed("the standard input is not available".to_owned()))?;
+        let writer = std::thread::spawn(move || stdin.write_all(content.as_bytes()));
+        let output = child
+            .wait_with_output()
+            .map_err(|e| failed(e.to_string()))?;
+        writer
+            .join()
+            .map_err(|_| failed("writing the standard input panicked".to_owned()))?
+            .map_err(|e| failed(e.to_string()))?;
+        if !output.status.success() {
+            return Err(failed(command_error(&output)));
+        }
+        return String::from_utf8(output.stdout).map_err(|e| failed(e.to_string()));
+    }
+
+    // The copy keeps the file name so that the command can detect the language
+    // from the extension.
+    let dir = tempfile::tempdir().map_err(|e| failed(e.to_string()))?;
+    let file = dir
+        .path()
+        .join(relative_path.file_name().unwrap_or("generated".as_ref()));
+    fs::write(&file, content).map_err(|e| failed(e.to_string()))?;
+    let file_arg = file.to_string_lossy();
+    let output = Command::new(program)
+        .args(
+            args.iter()
+                .map(|arg| arg.replace(FILE_PLACEHOLDER, &file_arg)),
+        )
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| failed(e.to_string()))?;
+    if !output.status.success() {
+        return Err(failed(command_error(&output)));
+    }
+    fs::read_to_string(&file).map_err(|e| failed(e.to_string()))
+}
+
+/// The error message of a failed command: its exit status and standard error.
+fn command_error(output: &std::process::Output) -> String {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stderr = stderr.trim();
+    if stderr.is_empty() {
+        format!("the command exited with {}", output.status)
+    } else {
+        format!("the command exited with {}: {stderr}", output.status)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::config::PostProcessor;
+
+    use super::apply;
+
+    #[test]
+    fn test_builtin_processors() {
+        let processors: Vec<PostProcessor> = serde_yaml::from_str(
+            "- trim_trailing_whitespace\n- license_header: \"// Copyright The Authors\\n\"\n",
+        )
+        .expect("Failed to parse the post-processors");
+        let processors: Vec<_> = processors.iter().collect();
+        let path = Path::new("main.go");
+
+        let content = apply(
+            &processors,
+            path,
+            "package main \t\n\nfunc f() {}  ".to_owned(),
+        )
+        .expect("Failed to post-process");
+        assert_eq!(
+            content,
+            "// Copyright The Authors\npackage main\n\nfunc f() {}"
+        );
+        // The header is inserted once.
+        assert_eq!(
+            apply(&processors, path, content.clone()).expect("Failed to post-process"),
+            content
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_command_processors() {
+        let path = Path::new("output.txt");
+        let stdin = PostProcessor::Command {
+            command: vec!["tr".to_owned(), "a-z".to_owned(), "A-Z".to_owned()],
+        };
+        assert_eq!(
+            apply(&[&stdin], path, "hello\n".to_owned()).expect("Failed to post-process"),
+            "HELLO\n"
+        );
+
+        let in_place = PostProcessor::Command {
+            command: vec![
+                "sh".to_owned(),
+                "-c".to_owned(),
+                "echo \"$(basename \"$0\")\" >> \"$0\"".to_owned(),
+                "{file}".to_owned(),
+            ],
+        };
+        assert_eq!(
+            apply(&[&in_place], path, "hello\n".to_owned()).expect("Failed to post-process"),
+            "hello\noutput.txt\n"
+        );
+
+        let failing = PostProcessor::Command {
+            command: vec![
+                "sh".to_owned(),
+                "-c".to_owned(),
+                "echo boom >&2; exit 3".to_owned(),
+            ],
+        };
+        let error = apply(&[&failing], path, String::new())
+            .expect_err("A failing command must fail the post-processing")
+            .to_string();
+        assert!(error.contains("boom"), "{error}");
+        assert!(error.contains("output.txt"), "{error}");
+    }
+}
@@ -0,0 +1,168 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Source maps of the generated files.
+//!
+//! When the `source_map` option of `weaver.yaml` is enabled, the `source_location`
+//! and `mark_source` filters insert an invisible marker in the rendered output.
+//! The markers are removed before the file is written and a sidecar
+//! `<file>.map.json` maps the line ranges of the file, each starting at a marker,
+//! to the semantic convention file and line of the marked definition.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// Extension appended to the name of a generated file to name its source map.
+pub const SOURCE_MAP_EXTENSION: &str = ".map.json";
+
+/// Delimiters of a marker, from a Unicode private use area so that they can't
+/// collide with the content of a template.
+const MARKER_START: char = '\u{F0000}';
+const MARKER_END: char = '\u{F0001}';
+/// Separator of the path and the line in a marker.
+const MARKER_LINE_SEPARATOR: char = '\u{F0002}';
+
+/// The source map of a generated file.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct SourceMap {
+    /// The generated file, as a `/`-separated path relative to the output directory.
+    pub file: String,
+    /// The line ranges of the generated file and the definitions they come from.
+    pub mappings: Vec<SourceMapping>,
+}
+
+/// A line range of a generated file and the definition it comes from.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SourceMapping {
+    /// The first line (1-based) of the range in the generated file.
+    pub start_line: usize,
+    /// The last line (1-based, inclusive) of the range in the generated file.
+    pub end_line: usize,
+    /// The semantic convention file of the definition.
+    pub source: String,
+    /// The line (1-based) of the definition in the semantic convention file, if known.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_line: Option<usize>,
+}
+
+/// The marker of a definition located at `path` and `line`.
+pub(crate) fn marker(path: &str, line: Option<usize>) -> String {
+    match line {
+        Some(line) => format!("{MARKER_START}{path}{MARKER_LINE_SEPARATOR}{line}{MARKER_END}"),
+        None => format!("{MARKER_START}{path}{MARKER_END}"),
+    }
+}
+
+/// Remove the markers from a rendered output. Returns the output without the
+/// markers and the mappings. A range starts at the line of a marker and ends
+/// before the line of the next one, or at the end of the output. When a line has
+/// several markers, the last one is kept.
+pub(crate) fn extract(output: &str) -> (String, Vec<SourceMapping>) {
+    if !output.contains(MARKER_START) {
+        return (output.to_owned(), Vec::new());
+    }
+    let mut stripped = String::with_capacity(output.len());
+    let mut mappings: Vec<SourceMapping> = Vec::new();
+    let mut line_count = 0;
+    for (index, line) in output.split_inclusive('\n').enumerate() {
+        let line_number = index + 1;
+        line_count = line_number;
+        let mut rest = line;
+        while let Some(start) = rest.find(MARKER_START) {
+            stripped.push_str(&rest[..start]);
+            let marked = &rest[start + MARKER_START.len_utf8()..];
+            let Some(end) = marked.find(MARKER_END) else {
+                // Not a marker, kept as is.
+                stripped.push(MARKER_START);
+                rest = marked;
+                continue;
+            };
+            let (source, source_line) = match marked[..end].split_once(MARKER_LINE_SEPARATOR) {
+                Some((source, line)) => (source, line.parse().ok()),
+                None => (&marked[..end], None),
+            };
+            if mappings
+                .last()
+                .is_some_and(|last| last.start_line == line_number)
+            {
+                _ = mappings.pop();
+            }
+            mappings.push(SourceMapping {
+                start_line: line_number,
+                end_line: line_number,
+                source: source.to_owned(),
+                source_line,
+            });
+            rest = &marked[end + MARKER_END.len_utf8()..];
+        }
+        stripped.push_str(rest);
+    }
+
+    // Each range ends before the next one.
+    let starts: Vec<usize> = mappings.iter().map(|m| m.start_line).collect();
+    for (mapping, next_start) in mappings
+        .iter_mut()
+        .zip(starts.into_iter().skip(1).map(Some).chain([None]))
+    {
+        mapping.end_line = next_start.map_or(line_count, |next| next - 1);
+    }
+    (stripped, mappings)
+}
+
+/// The path of the source map of a generated file.
+pub(crate) fn source_map_path(path: &Path) -> PathBuf {
+    let mut source_map_path = path.as_os_str().to_owned();
+    source_map_path.push(SOURCE_MAP_EXTENSION);
+    PathBuf::from(source_map_path)
+}
+
+/// Shift the line ranges of the mappings by `lines` lines, e.g. after a header is
+/// inserted at the top of the file.
+pub(crate) fn shift(mappings: &mut [SourceMapping], lines: usize) {
+    for mapping in mappings {
+        mapping.start_line += lines;
+        mapping.end_line += lines;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract() {
+        let output = format!(
+            "header\n// {0}a.yaml:3\nconst A = 1;\n\n{2}{1}const B = 2;\nfooter",
+            marker("a.yaml", Some(3)),
+            marker("b.yaml", None),
+            marker("ignored.yaml", Some(1)),
+        );
+        let (stripped, mappings) = extract(&output);
+        assert_eq!(
+            stripped,
+            "header\n// a.yaml:3\nconst A = 1;\n\nconst B = 2;\nfooter"
+        );
+        assert_eq!(
+            mappings,
+            vec![
+                SourceMapping {
+                    start_line: 2,
+                    end_line: 4,
+                    source: "a.yaml".to_owned(),
+                    source_line: Some(3),
+                },
+                SourceMapping {
+                    start_line: 5,
+                    end_line: 6,
+                    source: "b.yaml".to_owned(),
+                    source_line: None,
+                },
+            ]
+        );
+
+        let (stripped, mappings) = extract("no marker\n");
+        assert_eq!(stripped, "no marker\n");
+        assert!(mappings.is_empty());
+    }
+}
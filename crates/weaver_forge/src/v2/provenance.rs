@@ -24,6 +24,13 @@ pub struct Provenance {
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub path: Option<String>,
+
+    /// The line (1-based) of the file where this attribute or signal is defined.
+    ///
+    /// Empty if the path is empty or if the definition could not be located.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
 }
 
 impl Provenance {
@@ -93,19 +93,18 @@ impl ForgeResolvedRegistry {
         let mut errors = Vec::new();
 
         let deps_list: Vec<_> = schema.dependencies.iter().cloned().collect();
-        let resolve_provenance = |prov: &weaver_resolved_schema::v2::provenance::Provenance| {
-            let source = prov
-                .source
-                .and_then(|r| deps_list.get(r.0 as usize).cloned());
-            Provenance {
-                source,
-                path: if prov.path.is_empty() {
-                    None
+        let resolve_provenance =
+            |prov: &weaver_resolved_schema::v2::provenance::Provenance, section: &str, id: &str| {
+                let source = prov
+                    .source
+                    .and_then(|r| deps_list.get(r.0 as usize).cloned());
+                let (path, line) = if prov.path.is_empty() {
+                    (None, None)
                 } else {
-                    Some(prov.path.clone())
-                },
-            }
-        };
+                    (Some(prov.path.clone()), prov.lines.line(section, id))
+                };
+                Provenance { source, path, line }
+            };
 
         let attribute_lookup = |r: &weaver_resolved_schema::v2::attribute::AttributeRef| {
             schema.attribute_catalog.attribute(r)
@@ -121,7 +120,7 @@ impl ForgeResolvedRegistry {
                 r#type: a.r#type.clone(),
                 examples: a.examples.clone(),
                 common: a.common.clone(),
-                provenance: resolve_provenance(&a.provenance),
+                provenance: resolve_provenance(&a.provenance, "attributes", &a.key),
             })
             .collect();
 
@@ -137,7 +136,7 @@ impl ForgeResolvedRegistry {
                             r#type: a.r#type.clone(),
                             examples: a.examples.clone(),
                             common: a.common.clone(),
-                            provenance: resolve_provenance(&a.provenance),
+                            provenance: resolve_provenance(&a.provenance, "attributes", &a.key),
                         },
                         requirement_level: ar.requirement_level.clone(),
                     });
@@ -150,6 +149,7 @@ impl ForgeResolvedRegistry {
                     attr
                 })
                 .collect();
+            let provenance = resolve_provenance(&metric.provenance, "metrics", &metric.name);
             metrics.push(Metric {
                 name: metric.name,
                 instrument: metric.instrument,
@@ -158,7 +158,7 @@ impl ForgeResolvedRegistry {
                 entity_associations: to_named_associations(&metric.entity_associations),
                 requirement_level: metric.requirement_level,
                 common: metric.common,
-                provenance: resolve_provenance(&metric.provenance),
+                provenance,
             });
         }
         metrics.sort_by(|l, r| l.name.cmp(&r.name));
@@ -176,7 +176,7 @@ impl ForgeResolvedRegistry {
                             r#type: a.r#type.clone(),
                             examples: a.examples.clone(),
                             common: a.common.clone(),
-                            provenance: resolve_provenance(&a.provenance),
+                            provenance: resolve_provenance(&a.provenance, "attributes", &a.key),
                         },
                         requirement_level: ar.requirement_level.clone(),
                     });
@@ -189,6 +189,8 @@ impl ForgeResolvedRegistry {
                     attr
                 })
                 .collect();
+            let provenance =
+                resolve_provenance(&metric.metric.provenance, "metric_refinements", &metric.id);
             metric_refinements.push(MetricRefinement {
                 id: metric.id.clone(),
                 metric: Metric {
@@ -199,7 +201,7 @@ impl ForgeResolvedRegistry {
                     entity_associations: to_named_associations(&metric.metric.entity_associations),
                     requirement_level: metric.metric.requirement_level,
                     common: metric.metric.common,
-                    provenance: resolve_provenance(&metric.metric.provenance),
+                    provenance,
                 },
             });
         }
@@ -217,7 +219,7 @@ impl ForgeResolvedRegistry {
                             r#type: a.r#type.clone(),
                             examples: a.examples.clone(),
                             common: a.common.clone(),
-                            provenance: resolve_provenance(&a.provenance),
+                            provenance: resolve_provenance(&a.provenance, "attributes", &a.key),
                         },
                         requirement_level: ar.requirement_level.clone(),
                         sampling_relevant: ar.sampling_relevant,
@@ -231,6 +233,7 @@ impl ForgeResolvedRegistry {
                     attr
                 })
                 .collect();
+            let provenance = resolve_provenance(&span.provenance, "spans", &span.r#type);
             spans.push(Span {
                 r#type: span.r#type,
                 kind: span.kind,
@@ -239,7 +242,7 @@ impl ForgeResolvedRegistry {
                 entity_associations: to_named_associations(&span.entity_associations),
                 requirement_level: span.requirement_level,
                 common: span.common,
-                provenance: resolve_provenance(&span.provenance),
+                provenance,
             });
         }
         spans.sort_by(|l, r| l.r#type.cmp(&r.r#type));
@@ -256,7 +259,7 @@ impl ForgeResolvedRegistry {
                             r#type: a.r#type.clone(),
                             examples: a.examples.clone(),
                             common: a.common.clone(),
-                            provenance: resolve_provenance(&a.provenance),
+                            provenance: resolve_provenance(&a.provenance, "attributes", &a.key),
                         },
                         requirement_level: ar.requirement_level.clone(),
                         sampling_relevant: ar.sampling_relevant,
@@ -270,6 +273,8 @@ impl ForgeResolvedRegistry {
                     attr
                 })
                 .collect();
+            let provenance =
+                resolve_provenance(&span.span.provenance, "span_refinements", &span.id);
             span_refinements.push(SpanRefinement {
                 id: span.id,
                 span: Span {
@@ -280,7 +285,7 @@ impl ForgeResolvedRegistry {
                     entity_associations: to_named_associations(&span.span.entity_associations),
                     requirement_level: span.span.requirement_level,
                     common: span.span.common,
-                    provenance: resolve_provenance(&span.span.provenance),
+                    provenance,
                 },
             });
         }
@@ -298,7 +303,7 @@ impl ForgeResolvedRegistry {
                             r#type: a.r#type.clone(),
                             examples: a.examples.clone(),
                             common: a.common.clone(),
-                            provenance: resolve_provenance(&a.provenance),
+                            provenance: resolve_provenance(&a.provenance, "attributes", &a.key),
                         },
                         requirement_level: ar.requirement_level.clone(),
                     });
@@ -311,13 +316,14 @@ impl ForgeResolvedRegistry {
                     attr
                 })
                 .collect();
+            let provenance = resolve_provenance(&event.provenance, "events", &event.name);
             events.push(Event {
                 name: event.name,
                 attributes,
                 entity_associations: to_named_associations(&event.entity_associations),
                 requirement_level: event.requirement_level,
                 common: event.common,
-                provenance: resolve_provenance(&event.provenance),
+                provenance,
             });
         }
         events.sort_by(|l, r| l.name.cmp(&r.name));
@@ -336,7 +342,7 @@ impl ForgeResolvedRegistry {
                             r#type: a.r#type.clone(),
                             examples: a.examples.clone(),
                             common: a.common.clone(),
-                            provenance: resolve_provenance(&a.provenance),
+                            provenance: resolve_provenance(&a.provenance, "attributes", &a.key),
                         },
                         requirement_level: ar.requirement_level.clone(),
                     });
@@ -349,6 +355,8 @@ impl ForgeResolvedRegistry {
                     attr
                 })
                 .collect();
+            let provenance =
+                resolve_provenance(&event.event.provenance, "event_refinements", &event.id);
             event_refinements.push(EventRefinement {
                 id: event.id,
                 event: Event {
@@ -357,7 +365,7 @@ impl ForgeResolvedRegistry {
                     entity_associations: to_named_associations(&event.event.entity_associations),
                     requirement_level: event.event.requirement_level,
                     common: event.event.common,
-                    provenance: resolve_provenance(&event.event.provenance),
+                    provenance,
                 },
             });
         }
@@ -376,7 +384,7 @@ impl ForgeResolvedRegistry {
                             r#type: a.r#type.clone(),
                             examples: a.examples.clone(),
                             common: a.common.clone(),
-                            provenance: resolve_provenance(&a.provenance),
+                            provenance: resolve_provenance(&a.provenance, "attributes", &a.key),
                         },
                         requirement_level: ar.requirement_level.clone(),
                     });
@@ -396,13 +404,14 @@ impl ForgeResolvedRegistry {
             let group_id = format!("entity.{}", &e.r#type);
             let identity = convert_entity_attrs(&e.identity, &group_id, &mut errors);
             let description = convert_entity_attrs(&e.description, &group_id, &mut errors);
+            let provenance = resolve_provenance(&e.provenance, "entities", &e.r#type);
             entities.push(Entity {
                 r#type: e.r#type,
                 identity,
                 description,
                 requirement_level: e.requirement_level,
                 common: e.common,
-                provenance: resolve_provenance(&e.provenance),
+                provenance,
             });
         }
         entities.sort_by(|l, r| l.r#type.cmp(&r.r#type));
@@ -412,6 +421,7 @@ impl ForgeResolvedRegistry {
             let group_id = format!("entity.{}", &e.id);
             let identity = convert_entity_attrs(&e.entity.identity, &group_id, &mut errors);
             let description = convert_entity_attrs(&e.entity.description, &group_id, &mut errors);
+            let provenance = resolve_provenance(&e.entity.provenance, "entity_refinements", &e.id);
             entity_refinements.push(EntityRefinement {
                 id: e.id,
                 entity: Entity {
@@ -420,7 +430,7 @@ impl ForgeResolvedRegistry {
                     description,
                     requirement_level: e.entity.requirement_level,
                     common: e.entity.common,
-                    provenance: resolve_provenance(&e.entity.provenance),
+                    provenance,
                 },
             });
         }
@@ -438,7 +448,7 @@ impl ForgeResolvedRegistry {
                             r#type: a.r#type.clone(),
                             examples: a.examples.clone(),
                             common: a.common.clone(),
-                            provenance: resolve_provenance(&a.provenance),
+                            provenance: resolve_provenance(&a.provenance, "attributes", &a.key),
                         },
                         requirement_level: ar.requirement_level.clone(),
                     });
@@ -451,11 +461,12 @@ impl ForgeResolvedRegistry {
                     attr
                 })
                 .collect();
+            let provenance = resolve_provenance(&ag.provenance, "attribute_groups", &ag.id);
             attribute_groups.push(AttributeGroup {
                 id: ag.id,
                 attributes,
                 common: ag.common.clone(),
-                provenance: resolve_provenance(&ag.provenance),
+                provenance,
             });
         }
 
@@ -651,6 +662,7 @@ mod tests {
                     provenance: provenance::Provenance {
                         source: Some(provenance::DependencyRef(0)),
                         path: "some/path.yaml".to_owned(),
+                        ..Default::default()
                     },
                 },
                 attribute::Attribute {
@@ -661,6 +673,7 @@ mod tests {
                     provenance: provenance::Provenance {
                         source: None,
                         path: "".to_owned(),
+                        ..Default::default()
                     },
                 },
             ],
@@ -692,6 +705,7 @@ mod tests {
                     provenance: provenance::Provenance {
                         source: Some(provenance::DependencyRef(0)),
                         path: "span.yaml".to_owned(),
+                        ..Default::default()
                     },
                 }],
                 metrics: vec![metric::Metric {
@@ -1332,6 +1346,7 @@ mod tests {
                     provenance: provenance::Provenance {
                         source: Some(provenance::DependencyRef(999)),
                         path: "path.yaml".to_owned(),
+                        ..Default::default()
                     },
                 },
                 // Empty path -> path should be None
@@ -1343,6 +1358,7 @@ mod tests {
                     provenance: provenance::Provenance {
                         source: None,
                         path: "".to_owned(),
+                        ..Default::default()
                     },
                 },
             ],
@@ -1149,6 +1149,7 @@ mod tests {
             instrument: SampleInstrument::Supported(InstrumentSpec::Counter),
             unit: "{request}".to_owned(),
             data_points: None,
+            aggregation_temporality: None,
             instrumentation_scope: None,
             live_check_result: None,
             resource: None,
@@ -1192,6 +1193,7 @@ mod tests {
             instrument: SampleInstrument::Unsupported("Summary".to_owned()),
             unit: String::new(),
             data_points: None,
+            aggregation_temporality: None,
             instrumentation_scope: None,
             live_check_result: None,
             resource: None,
@@ -1217,10 +1219,12 @@ mod tests {
                     live_check_result: None,
                 }],
                 value: json!(3),
+                start_time_unix_nano: None,
                 flags: 0,
                 exemplars: vec![],
                 live_check_result: None,
             }])),
+            aggregation_temporality: None,
             instrumentation_scope: None,
             live_check_result: None,
             resource: None,
@@ -1246,6 +1250,7 @@ mod tests {
                 exemplars: vec![],
                 live_check_result: None,
             }])),
+            aggregation_temporality: None,
             instrumentation_scope: None,
             live_check_result: None,
             resource: None,
@@ -1276,6 +1281,7 @@ mod tests {
                     live_check_result: None,
                 },
             ])),
+            aggregation_temporality: None,
             instrumentation_scope: None,
             live_check_result: None,
             resource: None,
@@ -1378,6 +1384,7 @@ mod tests {
             instrument: SampleInstrument::Supported(InstrumentSpec::Histogram),
             unit: "ms".to_owned(),
             data_points: None,
+            aggregation_temporality: None,
             instrumentation_scope: None,
             live_check_result: None,
             resource: None,
@@ -1404,6 +1411,7 @@ mod tests {
             instrument: SampleInstrument::Supported(InstrumentSpec::Counter),
             unit: String::new(), // Empty unit
             data_points: None,
+            aggregation_temporality: None,
             instrumentation_scope: None,
             live_check_result: None,
             resource: None,
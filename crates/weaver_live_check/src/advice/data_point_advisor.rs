@@ -0,0 +1,578 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Metric data point advisor
+
+use serde_json::json;
+use std::collections::HashMap;
+use std::rc::Rc;
+use weaver_checker::{FindingLevel, PolicyFinding};
+use weaver_semconv::group::InstrumentSpec;
+
+use super::{Advisor, FindingBuilder};
+use crate::{
+    otlp_logger::OtlpEmitter,
+    sample_attribute::SampleAttribute,
+    sample_metric::{SampleAggregationTemporality, SampleMetric, SampleNumberDataPoint},
+    Error, FindingId, Sample, SampleRef, VersionedAttribute, VersionedSignal,
+    BUCKET_COUNTS_SUM_ADVICE_CONTEXT_KEY, COUNT_ADVICE_CONTEXT_KEY,
+    EXPECTED_VALUE_ADVICE_CONTEXT_KEY, INSTRUMENT_ADVICE_CONTEXT_KEY,
+    METRIC_NAME_ADVICE_CONTEXT_KEY, PREVIOUS_VALUE_ADVICE_CONTEXT_KEY,
+    TEMPORALITY_ADVICE_CONTEXT_KEY, UNIT_ADVICE_CONTEXT_KEY, VALUE_ADVICE_CONTEXT_KEY,
+};
+
+/// An advisor that checks the data of metrics against their instrument and unit:
+/// - sums and histograms have a delta or cumulative temporality, and gauges are not
+///   cumulative
+/// - counter values are non-negative, and a cumulative counter never decreases between
+///   two exports of the same time series (same metric, resource and attributes). A new
+///   start time is a reset of the time series, and a decrease without start time is
+///   reported as a probable reset, at the information level
+/// - histogram bucket counts add up to the data point count (bucket counts are unsigned,
+///   so they can't be negative)
+/// - values are within the range implied by the unit: `1` within [0, 1] and `%` within
+///   [0, 100]
+///
+/// The instrument and unit checks need the metric to be in the registry. A counter
+/// exported as a non-monotonic sum, or an updowncounter exported as a monotonic sum, is
+/// already reported by the `TypeAdvisor` as an unexpected instrument.
+#[derive(Default)]
+pub struct DataPointAdvisor {
+    /// The start time and the last value of each cumulative counter time series, at
+    /// most `MAX_COUNTER_SERIES`
+    cumulative_counters: HashMap<String, (Option<u64>, f64)>,
+}
+
+/// The maximum number of cumulative counter time series tracked. The time series seen
+/// once the limit is reached are not checked for decreases.
+const MAX_COUNTER_SERIES: usize = 10_000;
+
+impl Advisor for DataPointAdvisor {
+    fn advise(
+        &mut self,
+        sample: SampleRef<'_>,
+        parent_signal: &Sample,
+        _registry_attribute: Option<Rc<VersionedAttribute>>,
+        registry_group: Option<Rc<VersionedSignal>>,
+        otlp_emitter: Option<Rc<OtlpEmitter>>,
+    ) -> Result<Vec<PolicyFinding>, Error> {
+        let Sample::Metric(metric) = parent_signal else {
+            return Ok(Vec::new());
+        };
+        let instrument = registry_group
+            .as_deref()
+            .and_then(VersionedSignal::instrument);
+        let range = registry_group
+            .as_deref()
+            .and_then(VersionedSignal::unit)
+            .and_then(|unit| unit_range(unit).map(|range| (unit, range)));
+
+        let mut findings = Vec::new();
+        match &sample {
+            SampleRef::Metric(_) => {
+                findings.extend(check_temporality(metric, instrument));
+            }
+            SampleRef::NumberDataPoint(point) => {
+                if let Some(value) = point.value.as_f64() {
+                    if instrument == Some(&InstrumentSpec::Counter) {
+                        findings.extend(self.check_counter(metric, point, value));
+                    }
+                    findings.extend(check_range(value, range));
+                }
+            }
+            SampleRef::HistogramDataPoint(point) => {
+                let bucket_counts_sum = sum(&point.bucket_counts);
+                if !point.bucket_counts.is_empty() && bucket_counts_sum != point.count {
+                    findings.push(bucket_counts_finding(bucket_counts_sum, point.count));
+                }
+                if !point.bucket_counts.is_empty()
+                    && point.bucket_counts.len() != point.explicit_bounds.len() + 1
+                {
+                    let expected = point.explicit_bounds.len() + 1;
+                    findings.push(
+                        FindingBuilder::new(FindingId::InvalidBucketCounts)
+                            .level(FindingLevel::Violation)
+                            .context(json!({
+                                COUNT_ADVICE_CONTEXT_KEY: point.bucket_counts.len(),
+                                EXPECTED_VALUE_ADVICE_CONTEXT_KEY: expected,
+                            }))
+                            .message(format!(
+                                "Histogram should have {expected} bucket counts for {} explicit bounds, but found {}.",
+                                point.explicit_bounds.len(),
+                                point.bucket_counts.len()
+                            )),
+                    );
+                }
+                for value in [point.min, point.max].into_iter().flatten() {
+                    findings.extend(check_range(value, range));
+                }
+            }
+            SampleRef::ExponentialHistogramDataPoint(point) => {
+                let bucket_counts_sum = [&point.positive, &point.negative]
+                    .into_iter()
+                    .flatten()
+                    .fold(point.zero_count, |total, buckets| {
+                        total.saturating_add(sum(&buckets.bucket_counts))
+                    });
+                if bucket_counts_sum != point.count {
+                    findings.push(bucket_counts_finding(bucket_counts_sum, point.count));
+                }
+                for value in [point.min, point.max].into_iter().flatten() {
+                    findings.extend(check_range(value, range));
+                }
+            }
+            _ => {}
+        }
+
+        Ok(findings
+            .into_iter()
+            .map(|finding| {
+                finding.signal(parent_signal).build_and_emit(
+                    &sample,
+                    otlp_emitter.as_deref(),
+                    parent_signal,
+                )
+            })
+            .collect())
+    }
+}
+
+impl DataPointAdvisor {
+    /// Counters only increase: their values are never negative, and a cumulative
+    /// counter never goes below its value in the previous export with the same start
+    /// time.
+    fn check_counter(
+        &mut self,
+        metric: &SampleMetric,
+        point: &SampleNumberDataPoint,
+        value: f64,
+    ) -> Option<FindingBuilder> {
+        if value < 0.0 {
+            return Some(
+                FindingBuilder::new(FindingId::NonMonotonicCounter)
+                    .level(FindingLevel::Violation)
+                    .context(json!({
+                        METRIC_NAME_ADVICE_CONTEXT_KEY: metric.name,
+                        VALUE_ADVICE_CONTEXT_KEY: value,
+                    }))
+                    .message(format!(
+                        "Counter value should not be negative, but found {value}."
+                    )),
+            );
+        }
+        if metric.aggregation_temporality != Some(SampleAggregationTemporality::Cumulative) {
+            return None;
+        }
+        let key = series_key(metric, &point.attributes);
+        if self.cumulative_counters.len() >= MAX_COUNTER_SERIES
+            && !self.cumulative_counters.contains_key(&key)
+        {
+            return None;
+        }
+        let start_time = point.start_time_unix_nano;
+        let (previous_start_time, previous) =
+            self.cumulative_counters.insert(key, (start_time, value))?;
+        if value >= previous || (start_time.is_some() && start_time != previous_start_time) {
+            return None;
+        }
+        let finding = FindingBuilder::new(FindingId::NonMonotonicCounter).context(json!({
+            METRIC_NAME_ADVICE_CONTEXT_KEY: metric.name,
+            VALUE_ADVICE_CONTEXT_KEY: value,
+            PREVIOUS_VALUE_ADVICE_CONTEXT_KEY: previous,
+        }));
+        Some(match start_time {
+            Some(_) => finding.level(FindingLevel::Violation).message(format!(
+                "Cumulative counter should not decrease, but went from {previous} to {value}."
+            )),
+            // Without start time, a reset can't be told apart from a decrease.
+            None => finding.level(FindingLevel::Information).message(format!(
+                "Cumulative counter went from {previous} to {value} without start time, it was probably reset."
+            )),
+        })
+    }
+}
+
+/// Sums and histograms must set their temporality, and a gauge has none: a gauge
+/// exported as a cumulative sum is reported.
+fn check_temporality(
+    metric: &SampleMetric,
+    instrument: Option<&InstrumentSpec>,
+) -> Option<FindingBuilder> {
+    let temporality = metric.aggregation_temporality?;
+    let message = match (temporality, instrument) {
+        (SampleAggregationTemporality::Unspecified, _) => {
+            "Aggregation temporality should be 'delta' or 'cumulative', but it is unspecified."
+                .to_owned()
+        }
+        (SampleAggregationTemporality::Cumulative, Some(InstrumentSpec::Gauge)) => {
+            "Gauge should not have a cumulative aggregation temporality.".to_owned()
+        }
+        _ => return None,
+    };
+    Some(
+        FindingBuilder::new(FindingId::UnexpectedTemporality)
+            .level(FindingLevel::Violation)
+            .context(json!({
+                METRIC_NAME_ADVICE_CONTEXT_KEY: metric.name,
+                TEMPORALITY_ADVICE_CONTEXT_KEY: temporality,
+                INSTRUMENT_ADVICE_CONTEXT_KEY: instrument,
+            }))
+            .message(message),
+    )
+}
+
+/// The values allowed by a unit, if it implies a range
+fn unit_range(unit: &str) -> Option<(f64, f64)> {
+    match unit {
+        "1" => Some((0.0, 1.0)),
+        "%" => Some((0.0, 100.0)),
+        _ => None,
+    }
+}
+
+fn check_range(value: f64, range: Option<(&String, (f64, f64))>) -> Option<FindingBuilder> {
+    let (unit, (min, max)) = range?;
+    (value < min || value > max).then(|| {
+        FindingBuilder::new(FindingId::ValueOutOfRange)
+            .level(FindingLevel::Violation)
+            .context(json!({
+                VALUE_ADVICE_CONTEXT_KEY: value,
+                UNIT_ADVICE_CONTEXT_KEY: unit,
+                EXPECTED_VALUE_ADVICE_CONTEXT_KEY: [min, max],
+            }))
+            .message(format!(
+                "Value should be within [{min}, {max}] for unit '{unit}', but found {value}."
+            ))
+    })
+}
+
+fn bucket_counts_finding(bucket_counts_sum: u64, count: u64) -> FindingBuilder {
+    FindingBuilder::new(FindingId::InvalidBucketCounts)
+        .level(FindingLevel::Violation)
+        .context(json!({
+            BUCKET_COUNTS_SUM_ADVICE_CONTEXT_KEY: bucket_counts_sum,
+            COUNT_ADVICE_CONTEXT_KEY: count,
+        }))
+        .message(format!(
+            "Bucket counts should add up to the count {count}, but add up to {bucket_counts_sum}."
+        ))
+}
+
+fn sum(bucket_counts: &[u64]) -> u64 {
+    bucket_counts
+        .iter()
+        .fold(0, |total, count| total.saturating_add(*count))
+}
+
+/// Identifies a time series: the metric name, the resource attributes and the data
+/// point attributes.
+fn series_key(metric: &SampleMetric, attributes: &[SampleAttribute]) -> String {
+    let mut key = metric.name.clone();
+    let resource_attributes = metric
+        .resource
+        .as_deref()
+        .map(|resource| resource.attributes.as_slice())
+        .unwrap_or_default();
+    for attributes in [resource_attributes, attributes] {
+        let mut pairs: Vec<String> = attributes
+            .iter()
+            .map(|attribute| {
+                format!(
+                    "{}={}",
+                    attribute.name,
+                    attribute.value.clone().unwrap_or_default()
+                )
+            })
+            .collect();
+        pairs.sort();
+        key.push('|');
+        key.push_str(&pairs.join(","));
+    }
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::sample_metric::{
+        SampleExponentialHistogramBuckets, SampleExponentialHistogramDataPoint,
+        SampleHistogramDataPoint, SampleInstrument, SampleNumberDataPoint,
+    };
+    use serde_json::Value;
+    use weaver_forge::v2::metric::Metric as V2Metric;
+    use weaver_semconv::stability::Stability;
+    use weaver_semconv::v2::CommonFields;
+
+    fn registry_metric(instrument: InstrumentSpec, unit: &str) -> Rc<VersionedSignal> {
+        Rc::new(VersionedSignal::Metric(V2Metric {
+            name: "test.metric".to_owned().into(),
+            instrument,
+            unit: unit.to_owned(),
+            requirement_level: None,
+            attributes: vec![],
+            entity_associations: vec![],
+            common: CommonFields {
+                brief: "A test metric".to_owned(),
+                note: "".to_owned(),
+                stability: Stability::Stable,
+                deprecated: None,
+                annotations: BTreeMap::new(),
+            },
+            provenance: Default::default(),
+        }))
+    }
+
+    fn sample_metric(
+        instrument: InstrumentSpec,
+        unit: &str,
+        temporality: Option<SampleAggregationTemporality>,
+    ) -> Sample {
+        Sample::Metric(SampleMetric {
+            name: "test.metric".to_owned(),
+            instrument: SampleInstrument::Supported(instrument),
+            unit: unit.to_owned(),
+            data_points: None,
+            aggregation_temporality: temporality,
+            instrumentation_scope: None,
+            live_check_result: None,
+            resource: None,
+        })
+    }
+
+    fn number_point(value: Value) -> SampleNumberDataPoint {
+        SampleNumberDataPoint {
+            attributes: vec![],
+            value,
+            start_time_unix_nano: None,
+            flags: 0,
+            exemplars: vec![],
+            live_check_result: None,
+        }
+    }
+
+    fn finding_ids(
+        advisor: &mut DataPointAdvisor,
+        sample: SampleRef<'_>,
+        parent_signal: &Sample,
+        registry_metric: &Rc<VersionedSignal>,
+    ) -> Vec<String> {
+        advisor
+            .advise(
+                sample,
+                parent_signal,
+                None,
+                Some(Rc::clone(registry_metric)),
+                None,
+            )
+            .expect("advise should not fail")
+            .into_iter()
+            .map(|finding| finding.id)
+            .collect()
+    }
+
+    #[test]
+    fn test_counter_and_unit_range() {
+        let mut advisor = DataPointAdvisor::default();
+        let counter = registry_metric(InstrumentSpec::Counter, "{request}");
+        let signal = sample_metric(
+            InstrumentSpec::Counter,
+            "{request}",
+            Some(SampleAggregationTemporality::Cumulative),
+        );
+
+        for (value, expected) in [
+            (json!(5), vec![]),
+            (json!(7.5), vec![]),
+            (json!(3), vec!["non_monotonic_counter"]),
+            (json!(-1), vec!["non_monotonic_counter"]),
+        ] {
+            let point = number_point(value);
+            assert_eq!(
+                finding_ids(
+                    &mut advisor,
+                    SampleRef::NumberDataPoint(&point),
+                    &signal,
+                    &counter
+                ),
+                expected
+            );
+        }
+
+        let ratio = registry_metric(InstrumentSpec::Gauge, "1");
+        let signal = sample_metric(InstrumentSpec::Gauge, "1", None);
+        for (value, expected) in [
+            (json!(0.5), vec![]),
+            (json!(1.5), vec!["value_out_of_range"]),
+        ] {
+            let point = number_point(value);
+            assert_eq!(
+                finding_ids(
+                    &mut advisor,
+                    SampleRef::NumberDataPoint(&point),
+                    &signal,
+                    &ratio
+                ),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_counter_resets() {
+        let mut advisor = DataPointAdvisor::default();
+        let counter = registry_metric(InstrumentSpec::Counter, "{request}");
+        let signal = sample_metric(
+            InstrumentSpec::Counter,
+            "{request}",
+            Some(SampleAggregationTemporality::Cumulative),
+        );
+        let mut levels = |value: Value, start_time_unix_nano: Option<u64>| {
+            let point = SampleNumberDataPoint {
+                start_time_unix_nano,
+                ..number_point(value)
+            };
+            advisor
+                .advise(
+                    SampleRef::NumberDataPoint(&point),
+                    &signal,
+                    None,
+                    Some(Rc::clone(&counter)),
+                    None,
+                )
+                .expect("advise should not fail")
+                .into_iter()
+                .map(|finding| finding.level)
+                .collect::<Vec<_>>()
+        };
+
+        assert!(levels(json!(10), Some(1)).is_empty());
+        // A decrease with the same start time.
+        assert_eq!(levels(json!(8), Some(1)), vec![FindingLevel::Violation]);
+        // A new start time is a reset.
+        assert!(levels(json!(2), Some(2)).is_empty());
+        assert!(levels(json!(4), Some(2)).is_empty());
+        // Without start time, a decrease is probably a reset.
+        assert_eq!(levels(json!(1), None), vec![FindingLevel::Information]);
+        assert!(levels(json!(3), None).is_empty());
+    }
+
+    #[test]
+    fn test_counter_series_limit() {
+        let mut advisor = DataPointAdvisor::default();
+        let signal = sample_metric(
+            InstrumentSpec::Counter,
+            "{request}",
+            Some(SampleAggregationTemporality::Cumulative),
+        );
+        let Sample::Metric(metric) = &signal else {
+            unreachable!()
+        };
+        let point = |series: usize, value: Value| SampleNumberDataPoint {
+            attributes: vec![SampleAttribute {
+                name: "series".to_owned(),
+                r#type: None,
+                value: Some(json!(series)),
+                live_check_result: None,
+            }],
+            ..number_point(value)
+        };
+        for series in 0..=MAX_COUNTER_SERIES {
+            assert!(advisor
+                .check_counter(metric, &point(series, json!(5)), 5.0)
+                .is_none());
+        }
+        assert_eq!(advisor.cumulative_counters.len(), MAX_COUNTER_SERIES);
+        // The time series seen after the limit are not tracked.
+        assert!(advisor
+            .check_counter(metric, &point(MAX_COUNTER_SERIES, json!(1)), 1.0)
+            .is_none());
+        assert!(advisor
+            .check_counter(metric, &point(0, json!(1)), 1.0)
+            .is_some());
+    }
+
+    #[test]
+    fn test_temporality_and_bucket_counts() {
+        let mut advisor = DataPointAdvisor::default();
+
+        let gauge = registry_metric(InstrumentSpec::Gauge, "s");
+        let signal = sample_metric(
+            InstrumentSpec::UpDownCounter,
+            "s",
+            Some(SampleAggregationTemporality::Cumulative),
+        );
+        let Sample::Metric(metric) = &signal else {
+            unreachable!()
+        };
+        assert_eq!(
+            finding_ids(&mut advisor, SampleRef::Metric(metric), &signal, &gauge),
+            vec!["unexpected_temporality"]
+        );
+
+        let histogram = registry_metric(InstrumentSpec::Histogram, "s");
+        let signal = sample_metric(
+            InstrumentSpec::Histogram,
+            "s",
+            Some(SampleAggregationTemporality::Unspecified),
+        );
+        let Sample::Metric(metric) = &signal else {
+            unreachable!()
+        };
+        assert_eq!(
+            finding_ids(&mut advisor, SampleRef::Metric(metric), &signal, &histogram),
+            vec!["unexpected_temporality"]
+        );
+
+        let point = SampleHistogramDataPoint {
+            attributes: vec![],
+            count: 4,
+            sum: Some(10.0),
+            bucket_counts: vec![1, 2],
+            explicit_bounds: vec![1.0, 5.0],
+            min: None,
+            max: None,
+            flags: 0,
+            exemplars: vec![],
+            live_check_result: None,
+        };
+        assert_eq!(
+            finding_ids(
+                &mut advisor,
+                SampleRef::HistogramDataPoint(&point),
+                &signal,
+                &histogram
+            ),
+            vec!["invalid_bucket_counts", "invalid_bucket_counts"]
+        );
+
+        let point = SampleExponentialHistogramDataPoint {
+            attributes: vec![],
+            count: 6,
+            sum: Some(10.0),
+            scale: 1,
+            zero_count: 1,
+            positive: Some(SampleExponentialHistogramBuckets {
+                offset: 0,
+                bucket_counts: vec![2, 2],
+            }),
+            negative: Some(SampleExponentialHistogramBuckets {
+                offset: 0,
+                bucket_counts: vec![1],
+            }),
+            flags: 0,
+            min: None,
+            max: None,
+            zero_threshold: 0.0,
+            exemplars: vec![],
+            live_check_result: None,
+        };
+        assert!(finding_ids(
+            &mut advisor,
+            SampleRef::ExponentialHistogramDataPoint(&point),
+            &signal,
+            &histogram
+        )
+        .is_empty());
+    }
+}
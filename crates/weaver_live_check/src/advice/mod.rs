@@ -11,6 +11,7 @@ use crate::{
 };
 
 // Internal modules
+mod data_point_advisor;
 mod deprecated_advisor;
 mod enum_advisor;
 mod rego_advisor;
@@ -18,6 +19,7 @@ mod stability_advisor;
 mod type_advisor;
 
 // Public re-exports
+pub use data_point_advisor::DataPointAdvisor;
 pub use deprecated_advisor::DeprecatedAdvisor;
 pub use enum_advisor::EnumAdvisor;
 pub use rego_advisor::RegoAdvisor;
@@ -714,6 +714,7 @@ mod tests {
             unit: "".to_owned(),
             data_points: None,
             instrument: SampleInstrument::Supported(weaver_semconv::group::InstrumentSpec::Counter),
+            aggregation_temporality: None,
             instrumentation_scope: None,
             live_check_result: None,
             resource: None,
@@ -784,6 +785,7 @@ mod tests {
             unit: "".to_owned(),
             data_points: None,
             instrument: SampleInstrument::Supported(weaver_semconv::group::InstrumentSpec::Counter),
+            aggregation_temporality: None,
             instrumentation_scope: None,
             live_check_result: None,
             resource: None,
@@ -828,6 +830,7 @@ mod tests {
             unit: "".to_owned(),
             data_points: None,
             instrument: SampleInstrument::Supported(weaver_semconv::group::InstrumentSpec::Counter),
+            aggregation_temporality: None,
             instrumentation_scope: None,
             live_check_result: None,
             resource: None,
@@ -0,0 +1,448 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Aggregation of the findings of a live-check session.
+//!
+//! A single issue on a hot span (e.g. a missing attribute) produces the same
+//! finding on every occurrence of the span. In aggregated mode, the checked
+//! samples are not kept: their findings are grouped by
+//! `(id, signal_type, signal_name, attribute)` and only a count, a few example
+//! contexts, and the resources and instrumentation scopes where the finding
+//! appeared are reported.
+
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+
+use serde::Serialize;
+use serde_json::Value;
+use weaver_checker::{FindingLevel, PolicyFinding};
+
+use crate::sample_attribute::SampleAttribute;
+use crate::sample_metric::{DataPoints, SampleExemplar};
+use crate::sample_resource::SampleResource;
+use crate::{LiveCheckResult, LiveCheckStatistics, Sample, ATTRIBUTE_KEY_ADVICE_CONTEXT_KEY};
+
+/// The key findings are grouped by.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct FindingKey {
+    id: String,
+    signal_type: Option<String>,
+    signal_name: Option<String>,
+    attribute: Option<String>,
+}
+
+/// The maximum number of distinct resources and of distinct instrumentation scopes
+/// recorded per finding, the next ones are not reported.
+const MAX_DISTINCT_ORIGINS: usize = 100;
+
+/// An instrumentation scope where a finding appeared.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct AggregatedScope {
+    /// The name of the instrumentation scope
+    pub name: String,
+    /// The version of the instrumentation scope
+    pub version: String,
+}
+
+/// A group of identical findings.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AggregatedFinding {
+    /// The id of the finding e.g. "missing_attribute"
+    pub id: String,
+    /// The highest level seen for this finding
+    pub level: FindingLevel,
+    /// The message of the first occurrence
+    pub message: String,
+    /// The signal type the finding applies to
+    pub signal_type: Option<String>,
+    /// The signal name the finding applies to
+    pub signal_name: Option<String>,
+    /// The attribute key the finding applies to, if any
+    pub attribute: Option<String>,
+    /// The number of occurrences
+    pub count: usize,
+    /// The contexts of the first occurrences
+    pub first_contexts: Vec<Value>,
+    /// The contexts of the last occurrences (not including the first ones)
+    pub last_contexts: VecDeque<Value>,
+    /// The distinct resources (attribute name to value) where the finding appeared, at
+    /// most 100
+    pub resources: Vec<BTreeMap<String, Value>>,
+    /// The distinct instrumentation scopes where the finding appeared, at most 100
+    pub instrumentation_scopes: Vec<AggregatedScope>,
+}
+
+/// Groups the findings of the checked samples.
+#[derive(Debug)]
+pub struct FindingAggregator {
+    max_examples: usize,
+    index: HashMap<FindingKey, usize>,
+    findings: Vec<AggregatedFinding>,
+    /// The resources and scopes already recorded, by finding index.
+    origins: Vec<SeenOrigins>,
+}
+
+/// The resources, by canonical JSON serialization, and the scopes recorded for a finding.
+#[derive(Debug, Default)]
+struct SeenOrigins {
+    resources: HashSet<String>,
+    scopes: HashSet<AggregatedScope>,
+}
+
+impl FindingAggregator {
+    /// Create an aggregator keeping at most `max_examples` first and
+    /// `max_examples` last contexts per finding.
+    #[must_use]
+    pub fn new(max_examples: usize) -> Self {
+        Self {
+            max_examples,
+            index: HashMap::new(),
+            findings: Vec::new(),
+            origins: Vec::new(),
+        }
+    }
+
+    /// Add the findings of a checked sample, including the findings of its
+    /// attributes, events, links, data points and exemplars.
+    pub fn add_sample(&mut self, sample: &Sample) {
+        let resource = match sample {
+            Sample::Resource(resource) => Some(resource),
+            _ => sample.resource(),
+        }
+        .map(resource_attributes);
+        let scope = sample.instrumentation_scope().map(|scope| AggregatedScope {
+            name: scope.name.clone(),
+            version: scope.version.clone(),
+        });
+
+        for_each_result(sample, &mut |result| {
+            for finding in &result.all_advice {
+                self.add_finding(finding, resource.as_ref(), scope.as_ref());
+            }
+        });
+    }
+
+    fn add_finding(
+        &mut self,
+        finding: &PolicyFinding,
+        resource: Option<&BTreeMap<String, Value>>,
+        scope: Option<&AggregatedScope>,
+    ) {
+        let attribute = finding
+            .context
+            .as_ref()
+            .and_then(|context| context.get(ATTRIBUTE_KEY_ADVICE_CONTEXT_KEY))
+            .and_then(Value::as_str)
+            .map(str::to_owned);
+        let key = FindingKey {
+            id: finding.id.clone(),
+            signal_type: finding.signal_type.clone(),
+            signal_name: finding.signal_name.clone(),
+            attribute,
+        };
+        let index = if let Some(index) = self.index.get(&key) {
+            *index
+        } else {
+            self.findings.push(AggregatedFinding {
+                id: key.id.clone(),
+                level: finding.level,
+                message: finding.message.clone(),
+                signal_type: key.signal_type.clone(),
+                signal_name: key.signal_name.clone(),
+                attribute: key.attribute.clone(),
+                count: 0,
+                first_contexts: Vec::new(),
+                last_contexts: VecDeque::new(),
+                resources: Vec::new(),
+                instrumentation_scopes: Vec::new(),
+            });
+            self.origins.push(SeenOrigins::default());
+            let _ = self.index.insert(key, self.findings.len() - 1);
+            self.findings.len() - 1
+        };
+
+        let aggregated = &mut self.findings[index];
+        aggregated.count += 1;
+        aggregated.level = aggregated.level.max(finding.level);
+        if let Some(context) = &finding.context {
+            if aggregated.first_contexts.len() < self.max_examples {
+                aggregated.first_contexts.push(context.clone());
+            } else if self.max_examples > 0 {
+                if aggregated.last_contexts.len() == self.max_examples {
+                    let _ = aggregated.last_contexts.pop_front();
+                }
+                aggregated.last_contexts.push_back(context.clone());
+            }
+        }
+        let origins = &mut self.origins[index];
+        if let Some(resource) = resource {
+            // The keys of a `BTreeMap` are sorted, its serialization is canonical.
+            if origins.resources.len() < MAX_DISTINCT_ORIGINS {
+                if let Ok(canonical) = serde_json::to_string(resource) {
+                    if origins.resources.insert(canonical) {
+                        aggregated.resources.push(resource.clone());
+                    }
+                }
+            }
+        }
+        if let Some(scope) = scope {
+            if origins.scopes.len() < MAX_DISTINCT_ORIGINS && origins.scopes.insert(scope.clone()) {
+                aggregated.instrumentation_scopes.push(scope.clone());
+            }
+        }
+    }
+
+    /// The aggregated findings, most frequent first.
+    #[must_use]
+    pub fn into_findings(self) -> Vec<AggregatedFinding> {
+        let mut findings = self.findings;
+        findings.sort_by_key(|finding| std::cmp::Reverse(finding.count));
+        findings
+    }
+}
+
+/// A live check report where the findings are aggregated instead of being
+/// attached to every sample.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AggregatedLiveCheckReport {
+    /// The aggregated findings, most frequent first
+    pub findings: Vec<AggregatedFinding>,
+    /// The statistics for the report
+    pub statistics: LiveCheckStatistics,
+}
+
+fn resource_attributes(resource: &SampleResource) -> BTreeMap<String, Value> {
+    resource
+        .attributes
+        .iter()
+        .map(|attribute| {
+            (
+                attribute.name.clone(),
+                attribute.value.clone().unwrap_or(Value::Null),
+            )
+        })
+        .collect()
+}
+
+/// Call `f` on every live check result of the sample and of its children.
+fn for_each_result<'a>(sample: &'a Sample, f: &mut impl FnMut(&'a LiveCheckResult)) {
+    fn attributes<'a>(attributes: &'a [SampleAttribute], f: &mut impl FnMut(&'a LiveCheckResult)) {
+        attributes
+            .iter()
+            .filter_map(|attribute| attribute.live_check_result.as_ref())
+            .for_each(&mut *f);
+    }
+    fn exemplars<'a>(exemplars: &'a [SampleExemplar], f: &mut impl FnMut(&'a LiveCheckResult)) {
+        for exemplar in exemplars {
+            exemplar.live_check_result.iter().for_each(&mut *f);
+            attributes(&exemplar.filtered_attributes, &mut *f);
+        }
+    }
+
+    match sample {
+        Sample::Attribute(attribute) => attribute.live_check_result.iter().for_each(f),
+        Sample::Span(span) => {
+            span.live_check_result.iter().for_each(&mut *f);
+            attributes(&span.attributes, f);
+            for event in &span.span_events {
+                event.live_check_result.iter().for_each(&mut *f);
+                attributes(&event.attributes, f);
+            }
+            for link in &span.span_links {
+                link.live_check_result.iter().for_each(&mut *f);
+                attributes(&link.attributes, f);
+            }
+        }
+        Sample::SpanEvent(event) => {
+            event.live_check_result.iter().for_each(&mut *f);
+            attributes(&event.attributes, f);
+        }
+        Sample::SpanLink(link) => {
+            link.live_check_result.iter().for_each(&mut *f);
+            attributes(&link.attributes, f);
+        }
+        Sample::Resource(resource) => {
+            resource.live_check_result.iter().for_each(&mut *f);
+            attributes(&resource.attributes, f);
+        }
+        Sample::InstrumentationScope(scope) => {
+            scope.live_check_result.iter().for_each(&mut *f);
+            attributes(&scope.attributes, f);
+        }
+        Sample::Metric(metric) => {
+            metric.live_check_result.iter().for_each(&mut *f);
+            match &metric.data_points {
+                Some(DataPoints::Number(points)) => {
+                    for point in points {
+                        point.live_check_result.iter().for_each(&mut *f);
+                        attributes(&point.attributes, f);
+                        exemplars(&point.exemplars, f);
+                    }
+                }
+                Some(DataPoints::Histogram(points)) => {
+                    for point in points {
+                        point.live_check_result.iter().for_each(&mut *f);
+                        attributes(&point.attributes, f);
+                        exemplars(&point.exemplars, f);
+                    }
+                }
+                Some(DataPoints::ExponentialHistogram(points)) => {
+                    for point in points {
+                        point.live_check_result.iter().for_each(&mut *f);
+                        attributes(&point.attributes, f);
+                        exemplars(&point.exemplars, f);
+                    }
+                }
+                None => {}
+            }
+        }
+        Sample::Log(log) => {
+            log.live_check_result.iter().for_each(&mut *f);
+            attributes(&log.attributes, f);
+        }
+        Sample::Profile(profile) => {
+            profile.live_check_result.iter().for_each(&mut *f);
+            attributes(&profile.attributes, f);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use serde_json::json;
+    use weaver_semconv::group::SpanKindSpec;
+
+    use super::*;
+    use crate::sample_instrumentation_scope::SampleInstrumentationScope;
+    use crate::sample_span::SampleSpan;
+
+    fn finding(attribute: &str, value: i64) -> PolicyFinding {
+        PolicyFinding {
+            id: "missing_attribute".to_owned(),
+            context: Some(json!({ "attribute_key": attribute, "value": value })),
+            message: format!("Attribute `{attribute}` does not exist in the registry."),
+            level: FindingLevel::Violation,
+            signal_type: Some("span".to_owned()),
+            signal_name: Some("GET /".to_owned()),
+        }
+    }
+
+    fn attribute(name: &str, findings: Vec<PolicyFinding>) -> SampleAttribute {
+        SampleAttribute {
+            name: name.to_owned(),
+            value: Some(json!("value")),
+            r#type: None,
+            live_check_result: Some(LiveCheckResult {
+                all_advice: findings,
+                highest_advice_level: Some(FindingLevel::Violation),
+            }),
+        }
+    }
+
+    fn span(service: &str, value: i64) -> Sample {
+        Sample::Span(SampleSpan {
+            name: "GET /".to_owned(),
+            kind: SpanKindSpec::Server,
+            status: None,
+            attributes: vec![
+                attribute("foo", vec![finding("foo", value)]),
+                attribute("bar", vec![finding("bar", value)]),
+            ],
+            span_events: vec![],
+            span_links: vec![],
+            live_check_result: None,
+            resource: Some(Rc::new(SampleResource {
+                attributes: vec![SampleAttribute {
+                    name: "service.name".to_owned(),
+                    value: Some(json!(service)),
+                    r#type: None,
+                    live_check_result: None,
+                }],
+                live_check_result: None,
+            })),
+            instrumentation_scope: Some(Rc::new(SampleInstrumentationScope {
+                name: "scope".to_owned(),
+                version: "1.0".to_owned(),
+                schema_url: String::new(),
+                attributes: vec![],
+                dropped_attributes_count: 0,
+                live_check_result: None,
+            })),
+        })
+    }
+
+    #[test]
+    fn test_group_by_attribute() {
+        let mut aggregator = FindingAggregator::new(2);
+        for value in 0..5 {
+            aggregator.add_sample(&span("checkout", value));
+        }
+        aggregator.add_sample(&span("cart", 5));
+        let findings = aggregator.into_findings();
+
+        assert_eq!(findings.len(), 2);
+        let foo = findings
+            .iter()
+            .find(|f| f.attribute.as_deref() == Some("foo"))
+            .unwrap();
+        assert_eq!(foo.count, 6);
+        assert_eq!(foo.signal_name.as_deref(), Some("GET /"));
+        let values = |contexts: Vec<&Value>| -> Vec<i64> {
+            contexts
+                .iter()
+                .map(|c| c["value"].as_i64().unwrap())
+                .collect()
+        };
+        assert_eq!(values(foo.first_contexts.iter().collect()), vec![0, 1]);
+        assert_eq!(values(foo.last_contexts.iter().collect()), vec![4, 5]);
+        assert_eq!(
+            foo.resources,
+            vec![
+                BTreeMap::from([("service.name".to_owned(), json!("checkout"))]),
+                BTreeMap::from([("service.name".to_owned(), json!("cart"))]),
+            ]
+        );
+        assert_eq!(
+            foo.instrumentation_scopes,
+            vec![AggregatedScope {
+                name: "scope".to_owned(),
+                version: "1.0".to_owned(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_most_frequent_first() {
+        let mut aggregator = FindingAggregator::new(0);
+        aggregator.add_sample(&Sample::Attribute(attribute(
+            "foo",
+            vec![finding("foo", 0)],
+        )));
+        for value in 0..3 {
+            aggregator.add_sample(&Sample::Attribute(attribute(
+                "bar",
+                vec![finding("bar", value)],
+            )));
+        }
+        let findings = aggregator.into_findings();
+        assert_eq!(findings[0].attribute.as_deref(), Some("bar"));
+        assert_eq!(findings[0].count, 3);
+        assert!(findings[0].first_contexts.is_empty());
+        assert!(findings[0].last_contexts.is_empty());
+        assert!(findings[0].resources.is_empty());
+        assert_eq!(findings[1].count, 1);
+    }
+
+    #[test]
+    fn test_distinct_resources_are_bounded() {
+        let mut aggregator = FindingAggregator::new(0);
+        for value in 0..(MAX_DISTINCT_ORIGINS as i64 + 10) {
+            aggregator.add_sample(&span(&format!("service-{value}"), value));
+            aggregator.add_sample(&span("checkout", value));
+        }
+        let findings = aggregator.into_findings();
+        assert_eq!(findings[0].count, 2 * (MAX_DISTINCT_ORIGINS + 10));
+        assert_eq!(findings[0].resources.len(), MAX_DISTINCT_ORIGINS);
+        assert_eq!(findings[0].instrumentation_scopes.len(), 1);
+    }
+}
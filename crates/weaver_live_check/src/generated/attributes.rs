@@ -71,6 +71,14 @@ pub enum FindingId {
     UnitMismatch,
     /// A metric instrument type does not match or is not supported by the registry
     UnexpectedInstrument,
+    /// A metric aggregation temporality is not expected for its instrument
+    UnexpectedTemporality,
+    /// A counter data point is negative or decreased since the previous export
+    NonMonotonicCounter,
+    /// Histogram bucket counts do not add up to the data point count
+    InvalidBucketCounts,
+    /// A metric value is outside the range implied by its unit
+    ValueOutOfRange,
     /// An enum attribute value is not in the set of allowed members
     UndefinedEnumVariant,
     /// A required attribute is absent from the sample
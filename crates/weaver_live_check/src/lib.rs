@@ -31,6 +31,8 @@ use weaver_semconv::{
 
 /// Advisors for live checks
 pub mod advice;
+/// Aggregation of the findings of a live check session
+pub mod aggregation;
 /// Finding modifier engine (overrides and filters).
 pub mod finding_modifier;
 /// Generated types, constants, and log record builders for live check findings
@@ -58,6 +60,8 @@ pub mod sample_profile;
 pub mod sample_resource;
 /// The intermediary format for spans
 pub mod sample_span;
+/// Sampling of the samples checked by live-check
+pub mod sampler;
 /// Statistics tracking for live check reports
 mod stats;
 /// An ingester that reads attribute names from a text file.
@@ -66,7 +70,9 @@ pub mod text_file_ingester;
 pub mod text_stdin_ingester;
 
 // Re-export statistics types from stats module
-pub use stats::{CumulativeStatistics, DisabledStatistics, LiveCheckStatistics};
+pub use stats::{
+    BreakdownStatistics, CumulativeStatistics, DisabledStatistics, LiveCheckStatistics,
+};
 
 /// Attribute key in advice context
 pub const ATTRIBUTE_KEY_ADVICE_CONTEXT_KEY: &str = "attribute_key";
@@ -92,6 +98,16 @@ pub const EVENT_NAME_ADVICE_CONTEXT_KEY: &str = "event_name";
 pub const METRIC_NAME_ADVICE_CONTEXT_KEY: &str = "metric_name";
 /// Entity type key in advice context
 pub const ENTITY_TYPE_ADVICE_CONTEXT_KEY: &str = "entity_type";
+/// Aggregation temporality key in advice context
+pub const TEMPORALITY_ADVICE_CONTEXT_KEY: &str = "temporality";
+/// Metric value key in advice context
+pub const VALUE_ADVICE_CONTEXT_KEY: &str = "value";
+/// Previous metric value key in advice context
+pub const PREVIOUS_VALUE_ADVICE_CONTEXT_KEY: &str = "previous_value";
+/// Data point count key in advice context
+pub const COUNT_ADVICE_CONTEXT_KEY: &str = "count";
+/// Sum of the bucket counts key in advice context
+pub const BUCKET_COUNTS_SUM_ADVICE_CONTEXT_KEY: &str = "bucket_counts_sum";
 
 /// Embedded default live check rego policies
 pub const DEFAULT_LIVE_CHECK_REGO: &str =
@@ -449,6 +465,7 @@ impl LiveCheckRunner for Sample {
         parent_group: Option<Rc<VersionedSignal>>,
         parent_signal: &Sample,
     ) -> Result<(), Error> {
+        stats.set_sample_context(self);
         match self {
             Sample::Attribute(attribute) => {
                 attribute.run_live_check(live_checker, stats, parent_group, parent_signal)
@@ -1958,6 +1958,7 @@ mod tests {
                     exemplars: vec![],
                 },
             ])),
+            aggregation_temporality: None,
             instrumentation_scope: None,
             live_check_result: None,
             resource: None,
@@ -2025,6 +2026,7 @@ mod tests {
             data_points: Some(DataPoints::Number(vec![SampleNumberDataPoint {
                 attributes: vec![],
                 value: json!(0.0),
+                start_time_unix_nano: None,
                 flags: 0,
                 live_check_result: None,
                 exemplars: vec![SampleExemplar {
@@ -2036,6 +2038,7 @@ mod tests {
                     live_check_result: None,
                 }],
             }])),
+            aggregation_temporality: None,
             instrumentation_scope: None,
             live_check_result: None,
             resource: None,
@@ -2087,6 +2090,7 @@ mod tests {
                 instrument: SampleInstrument::Unsupported("Summary".to_owned()),
                 unit: "By".to_owned(),
                 data_points: None,
+                aggregation_temporality: None,
                 instrumentation_scope: None,
                 live_check_result: None,
                 resource: None,
@@ -2096,6 +2100,7 @@ mod tests {
                 instrument: SampleInstrument::Unsupported("Unspecified".to_owned()),
                 unit: "By".to_owned(),
                 data_points: None,
+                aggregation_temporality: None,
                 instrumentation_scope: None,
                 live_check_result: None,
                 resource: None,
@@ -3241,10 +3246,12 @@ mod tests {
                 data_points: Some(DataPoints::Number(vec![SampleNumberDataPoint {
                     attributes: vec![],
                     value: serde_json::json!(42.0),
+                    start_time_unix_nano: None,
                     flags: 0,
                     exemplars: vec![],
                     live_check_result: None,
                 }])),
+                aggregation_temporality: None,
                 instrumentation_scope: None,
                 live_check_result: None,
                 resource: Some(resource),
@@ -306,6 +306,7 @@ mod tests {
             instrument: SampleInstrument::Supported(InstrumentSpec::Gauge),
             unit: "ms".to_owned(),
             data_points: None,
+            aggregation_temporality: None,
             instrumentation_scope: None,
             live_check_result: None,
             resource: None,
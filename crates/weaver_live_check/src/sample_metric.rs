@@ -31,6 +31,19 @@ pub enum SampleInstrument {
     Unsupported(String),
 }
 
+/// The aggregation temporality of the sum and histogram data points of a metric
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, strum::Display)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum SampleAggregationTemporality {
+    /// The temporality is not set, which is invalid for sums and histograms
+    Unspecified,
+    /// Each data point reports the change since the previous report
+    Delta,
+    /// Each data point reports the total since a fixed start time
+    Cumulative,
+}
+
 /// The data point types of a metric
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(untagged)]
@@ -51,6 +64,11 @@ pub struct SampleNumberDataPoint {
     pub attributes: Vec<SampleAttribute>,
     /// The value of the data point, can be a double or int64
     pub value: Value,
+    /// The start of the time window of a cumulative data point, in nanoseconds since
+    /// the Unix epoch. A new start time marks a reset of the time series.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_time_unix_nano: Option<u64>,
     /// Flags that apply to this specific data point
     #[serde(default)]
     pub flags: u32,
@@ -289,6 +307,9 @@ pub struct SampleMetric {
     pub unit: String,
     /// Data points of the metric.
     pub data_points: Option<DataPoints>,
+    /// Aggregation temporality of the data points (sums and histograms only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aggregation_temporality: Option<SampleAggregationTemporality>,
     /// Shared instrumentation scope that produced this metric (not serialized).
     #[serde(skip)]
     pub instrumentation_scope: Option<Rc<SampleInstrumentationScope>>,
@@ -0,0 +1,351 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Sampling of the samples checked by live-check.
+//!
+//! Checking every sample of a high-volume stream runs every `Advisor` (including
+//! the Rego one) on it, which can fall behind. A [`Sampler`] sits between the
+//! ingester and the `LiveChecker` and skips samples according to the configured
+//! strategies:
+//! - `ratio`: keep a random fraction of the samples,
+//! - `first_n`: keep the first N samples of each signal name (the name of the
+//!   parent span for the span links, which have none),
+//! - `rate_limit`: keep at most N samples per second for each signal type
+//!   (token bucket).
+//!
+//! The strategies are combined; a sample is kept only if all of them keep it.
+//! Resources and instrumentation scopes provide the context of the following
+//! signals, so they are never skipped.
+
+use std::collections::HashMap;
+use std::time::Instant;
+
+use weaver_config::LiveCheckSamplingConfig;
+
+use crate::{Error, LiveCheckStatistics, Sample};
+
+/// Decides which samples are checked.
+#[derive(Debug)]
+pub struct Sampler {
+    ratio: Option<f64>,
+    first_n: Option<usize>,
+    rate_limit: Option<f64>,
+    /// Number of samples kept per (signal type, signal name).
+    kept_per_name: HashMap<(&'static str, String), usize>,
+    /// Token bucket per signal type.
+    buckets: HashMap<&'static str, TokenBucket>,
+    /// Name of the last span sample, the parent of the following span links.
+    parent_span_name: String,
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl Sampler {
+    /// Create a sampler from the configuration. Returns `None` when no
+    /// strategy is configured, i.e. every sample is checked.
+    pub fn from_config(config: &LiveCheckSamplingConfig) -> Result<Option<Self>, Error> {
+        if let Some(ratio) = config.ratio {
+            if !(0.0..=1.0).contains(&ratio) {
+                return Err(Error::ConfigError {
+                    error: format!("The sampling ratio must be between 0.0 and 1.0, got {ratio}"),
+                });
+            }
+        }
+        if let Some(rate_limit) = config.rate_limit {
+            if rate_limit.is_nan() || rate_limit <= 0.0 {
+                return Err(Error::ConfigError {
+                    error: format!("The sampling rate limit must be positive, got {rate_limit}"),
+                });
+            }
+        }
+        if config.ratio.is_none() && config.first_n.is_none() && config.rate_limit.is_none() {
+            return Ok(None);
+        }
+        Ok(Some(Self {
+            ratio: config.ratio,
+            first_n: config.first_n,
+            rate_limit: config.rate_limit,
+            kept_per_name: HashMap::new(),
+            buckets: HashMap::new(),
+            parent_span_name: String::new(),
+        }))
+    }
+
+    /// Returns true if the sample must be checked. A skipped sample is
+    /// counted in the statistics.
+    pub fn keep(&mut self, sample: &Sample, stats: &mut LiveCheckStatistics) -> bool {
+        self.keep_at(sample, stats, Instant::now())
+    }
+
+    fn keep_at(&mut self, sample: &Sample, stats: &mut LiveCheckStatistics, now: Instant) -> bool {
+        if let Sample::Span(span) = sample {
+            self.parent_span_name.clone_from(&span.name);
+        }
+        let Some((sample_type, name)) = signal_type_and_name(sample, &self.parent_span_name) else {
+            return true;
+        };
+        let name = name.to_owned();
+        if self.should_keep(sample_type, &name, now) {
+            true
+        } else {
+            stats.inc_skipped_entity_count(sample_type);
+            false
+        }
+    }
+
+    fn should_keep(&mut self, sample_type: &'static str, name: &str, now: Instant) -> bool {
+        if let Some(ratio) = self.ratio {
+            if rand::random::<f64>() >= ratio {
+                return false;
+            }
+        }
+
+        // The first-N count is only incremented once the rate limit has kept
+        // the sample, so that rate-limited samples don't use up the quota.
+        if let Some(first_n) = self.first_n {
+            let kept = self
+                .kept_per_name
+                .get(&(sample_type, name.to_owned()))
+                .copied()
+                .unwrap_or(0);
+            if kept >= first_n {
+                return false;
+            }
+        }
+
+        if let Some(rate_limit) = self.rate_limit {
+            // Bursts of up to one second of samples are allowed.
+            let capacity = rate_limit.max(1.0);
+            let bucket = self.buckets.entry(sample_type).or_insert(TokenBucket {
+                tokens: capacity,
+                last_refill: now,
+            });
+            let elapsed = now.saturating_duration_since(bucket.last_refill);
+            bucket.tokens = (bucket.tokens + elapsed.as_secs_f64() * rate_limit).min(capacity);
+            bucket.last_refill = now;
+            if bucket.tokens < 1.0 {
+                return false;
+            }
+            bucket.tokens -= 1.0;
+        }
+
+        if self.first_n.is_some() {
+            *self
+                .kept_per_name
+                .entry((sample_type, name.to_owned()))
+                .or_insert(0) += 1;
+        }
+        true
+    }
+}
+
+/// The signal type (as reported in the statistics) and name of a sample, or
+/// `None` for the samples that are never skipped. Span links are named after
+/// their parent span.
+fn signal_type_and_name<'a>(
+    sample: &'a Sample,
+    parent_span_name: &'a str,
+) -> Option<(&'static str, &'a str)> {
+    match sample {
+        Sample::Attribute(attribute) => Some(("attribute", &attribute.name)),
+        Sample::Span(span) => Some(("span", &span.name)),
+        Sample::SpanEvent(event) => Some(("span_event", &event.name)),
+        Sample::SpanLink(_) => Some(("span_link", parent_span_name)),
+        Sample::Metric(metric) => Some(("metric", &metric.name)),
+        Sample::Log(log) => Some(("log", &log.event_name)),
+        Sample::Profile(profile) => Some(("profile", &profile.original_payload_format)),
+        Sample::Resource(_) | Sample::InstrumentationScope(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::sample_resource::SampleResource;
+    use crate::sample_span::{SampleSpan, SampleSpanLink};
+    use crate::{CumulativeStatistics, VersionedRegistry};
+    use weaver_forge::registry::ResolvedRegistry;
+    use weaver_semconv::group::SpanKindSpec;
+
+    fn stats() -> LiveCheckStatistics {
+        let registry = VersionedRegistry::V1(Box::new(ResolvedRegistry {
+            groups: vec![],
+            registry_url: String::new(),
+        }));
+        LiveCheckStatistics::Cumulative(CumulativeStatistics::new(&registry))
+    }
+
+    fn span(name: &str) -> Sample {
+        Sample::Span(SampleSpan {
+            name: name.to_owned(),
+            kind: SpanKindSpec::Internal,
+            status: None,
+            attributes: vec![],
+            span_events: vec![],
+            span_links: vec![],
+            live_check_result: None,
+            resource: None,
+            instrumentation_scope: None,
+        })
+    }
+
+    fn skipped(stats: &LiveCheckStatistics) -> (usize, Option<usize>) {
+        match stats {
+            LiveCheckStatistics::Cumulative(stats) => (
+                stats.total_skipped_entities,
+                stats.total_skipped_entities_by_type.get("span").copied(),
+            ),
+            LiveCheckStatistics::Disabled(_) => panic!("Expected Cumulative statistics"),
+        }
+    }
+
+    #[test]
+    fn test_no_strategy() {
+        assert!(Sampler::from_config(&LiveCheckSamplingConfig::default())
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        for config in [
+            LiveCheckSamplingConfig {
+                ratio: Some(1.5),
+                ..Default::default()
+            },
+            LiveCheckSamplingConfig {
+                rate_limit: Some(0.0),
+                ..Default::default()
+            },
+        ] {
+            assert!(matches!(
+                Sampler::from_config(&config),
+                Err(Error::ConfigError { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_ratio() {
+        let mut stats = stats();
+        let mut none = Sampler::from_config(&LiveCheckSamplingConfig {
+            ratio: Some(0.0),
+            ..Default::default()
+        })
+        .unwrap()
+        .unwrap();
+        let mut all = Sampler::from_config(&LiveCheckSamplingConfig {
+            ratio: Some(1.0),
+            ..Default::default()
+        })
+        .unwrap()
+        .unwrap();
+        for _ in 0..10 {
+            assert!(!none.keep(&span("span"), &mut stats));
+            assert!(all.keep(&span("span"), &mut stats));
+        }
+        assert_eq!(skipped(&stats), (10, Some(10)));
+
+        // Context samples are never skipped
+        let resource = Sample::Resource(SampleResource {
+            attributes: vec![],
+            live_check_result: None,
+        });
+        assert!(none.keep(&resource, &mut stats));
+        assert_eq!(skipped(&stats), (10, Some(10)));
+    }
+
+    #[test]
+    fn test_first_n_per_name() {
+        let mut stats = stats();
+        let mut sampler = Sampler::from_config(&LiveCheckSamplingConfig {
+            first_n: Some(2),
+            ..Default::default()
+        })
+        .unwrap()
+        .unwrap();
+        let kept: Vec<bool> = ["a", "a", "b", "a", "b", "b"]
+            .iter()
+            .map(|name| sampler.keep(&span(name), &mut stats))
+            .collect();
+        assert_eq!(kept, vec![true, true, true, false, true, false]);
+        assert_eq!(skipped(&stats), (2, Some(2)));
+    }
+
+    #[test]
+    fn test_first_n_span_links_per_parent_span() {
+        let mut stats = stats();
+        let mut sampler = Sampler::from_config(&LiveCheckSamplingConfig {
+            first_n: Some(1),
+            ..Default::default()
+        })
+        .unwrap()
+        .unwrap();
+        let link = Sample::SpanLink(SampleSpanLink {
+            attributes: vec![],
+            live_check_result: None,
+        });
+        let kept: Vec<bool> = ["a", "b", "a"]
+            .iter()
+            .flat_map(|name| {
+                [
+                    sampler.keep(&span(name), &mut stats),
+                    sampler.keep(&link, &mut stats),
+                ]
+            })
+            .collect();
+        assert_eq!(kept, vec![true, true, true, true, false, false]);
+    }
+
+    #[test]
+    fn test_rate_limit_per_signal_type() {
+        let mut stats = stats();
+        let mut sampler = Sampler::from_config(&LiveCheckSamplingConfig {
+            rate_limit: Some(2.0),
+            ..Default::default()
+        })
+        .unwrap()
+        .unwrap();
+        let start = Instant::now();
+
+        // The bucket starts full (2 tokens)
+        let kept = (0..3)
+            .filter(|_| sampler.keep_at(&span("span"), &mut stats, start))
+            .count();
+        assert_eq!(kept, 2);
+        // Each signal type has its own bucket
+        let link = Sample::SpanLink(SampleSpanLink {
+            attributes: vec![],
+            live_check_result: None,
+        });
+        assert!(sampler.keep_at(&link, &mut stats, start));
+        // Half a second later, one token has been refilled
+        let later = start + Duration::from_millis(500);
+        assert!(sampler.keep_at(&span("span"), &mut stats, later));
+        assert!(!sampler.keep_at(&span("span"), &mut stats, later));
+        assert_eq!(skipped(&stats), (2, Some(2)));
+    }
+
+    #[test]
+    fn test_rate_limited_samples_do_not_use_first_n_quota() {
+        let mut stats = stats();
+        let mut sampler = Sampler::from_config(&LiveCheckSamplingConfig {
+            first_n: Some(1),
+            rate_limit: Some(1.0),
+            ..Default::default()
+        })
+        .unwrap()
+        .unwrap();
+        let start = Instant::now();
+        assert!(sampler.keep_at(&span("a"), &mut stats, start));
+        // No token left: "b" is skipped but keeps its quota
+        assert!(!sampler.keep_at(&span("b"), &mut stats, start));
+        let later = start + Duration::from_secs(1);
+        assert!(sampler.keep_at(&span("b"), &mut stats, later));
+    }
+}
@@ -7,11 +7,16 @@
 //! - `Disabled`: No-op mode for long-running sessions to prevent memory growth
 
 use serde::Serialize;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-use crate::{FindingLevel, LiveCheckResult, PolicyFinding, VersionedRegistry};
+use crate::{FindingLevel, LiveCheckResult, PolicyFinding, Sample, VersionedRegistry};
 use weaver_semconv::group::GroupType;
 
+/// The resource attribute identifying the service
+const SERVICE_NAME: &str = "service.name";
+/// The service name used when the resource doesn't have one
+const UNKNOWN_SERVICE: &str = "unknown_service";
+
 /// Cumulative statistics that track all telemetry data
 #[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct CumulativeStatistics {
@@ -19,6 +24,10 @@ pub struct CumulativeStatistics {
     pub(crate) total_entities: usize,
     /// The total number of sample entities by type
     pub(crate) total_entities_by_type: HashMap<String, usize>,
+    /// The total number of sample entities skipped by sampling
+    pub(crate) total_skipped_entities: usize,
+    /// The total number of sample entities skipped by sampling, by type
+    pub(crate) total_skipped_entities_by_type: HashMap<String, usize>,
     /// The total number of advisories
     pub(crate) total_advisories: usize,
     /// The number of each advice level
@@ -45,6 +54,90 @@ pub struct CumulativeStatistics {
     pub(crate) seen_non_registry_events: HashMap<String, usize>,
     /// Fraction of the registry covered by the attributes, metrics, and events
     pub(crate) registry_coverage: f32,
+    /// The statistics of each service (`service.name` of the resource)
+    pub(crate) by_service: HashMap<String, BreakdownStatistics>,
+    /// The statistics of each instrumentation scope (`name` or `name@version`)
+    pub(crate) by_instrumentation_scope: HashMap<String, BreakdownStatistics>,
+    /// The service of the sample being checked
+    #[serde(skip)]
+    current_service: Option<String>,
+    /// The instrumentation scope of the sample being checked
+    #[serde(skip)]
+    current_scope: Option<String>,
+}
+
+/// The statistics of the samples of a service or an instrumentation scope
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct BreakdownStatistics {
+    /// The total number of sample entities
+    pub(crate) total_entities: usize,
+    /// The total number of advisories
+    pub(crate) total_advisories: usize,
+    /// The number of each advice level
+    pub(crate) advice_level_counts: HashMap<FindingLevel, usize>,
+    /// The number of entities with each highest advice level
+    pub(crate) highest_advice_level_counts: HashMap<FindingLevel, usize>,
+    /// The number of entities with each advice type
+    pub(crate) advice_type_counts: HashMap<String, usize>,
+    /// The registry attributes seen
+    #[serde(skip)]
+    seen_registry_attributes: HashSet<String>,
+    /// The registry metrics seen
+    #[serde(skip)]
+    seen_registry_metrics: HashSet<String>,
+    /// The registry events seen
+    #[serde(skip)]
+    seen_registry_events: HashSet<String>,
+    /// Fraction of the registry covered by the attributes, metrics, and events
+    pub(crate) registry_coverage: f32,
+}
+
+impl BreakdownStatistics {
+    fn add_advice(&mut self, advice: &PolicyFinding) {
+        *self.advice_level_counts.entry(advice.level).or_insert(0) += 1;
+        *self
+            .advice_type_counts
+            .entry(advice.id.clone())
+            .or_insert(0) += 1;
+        self.total_advisories += 1;
+    }
+
+    fn seen_registry_items(&self) -> usize {
+        self.seen_registry_attributes.len()
+            + self.seen_registry_metrics.len()
+            + self.seen_registry_events.len()
+    }
+}
+
+/// The name of the service that produced the sample: the `service.name`
+/// attribute of its resource, or `unknown_service` (the OpenTelemetry default)
+/// when the resource doesn't have one. `None` when the sample has no resource.
+fn service_name(sample: &Sample) -> Option<String> {
+    let resource = match sample {
+        Sample::Resource(resource) => Some(resource),
+        _ => sample.resource(),
+    }?;
+    let service_name = resource
+        .attributes
+        .iter()
+        .find(|attribute| attribute.name == SERVICE_NAME)
+        .and_then(|attribute| attribute.value.as_ref())
+        .and_then(|value| value.as_str())
+        .unwrap_or(UNKNOWN_SERVICE);
+    Some(service_name.to_owned())
+}
+
+/// The `name` or `name@version` of the instrumentation scope of the sample.
+fn scope_name(sample: &Sample) -> Option<String> {
+    let scope = match sample {
+        Sample::InstrumentationScope(scope) => Some(scope),
+        _ => sample.instrumentation_scope(),
+    }?;
+    if scope.version.is_empty() {
+        Some(scope.name.clone())
+    } else {
+        Some(format!("{}@{}", scope.name, scope.version))
+    }
 }
 
 impl CumulativeStatistics {
@@ -56,6 +149,8 @@ impl CumulativeStatistics {
         CumulativeStatistics {
             total_entities: 0,
             total_entities_by_type: HashMap::new(),
+            total_skipped_entities: 0,
+            total_skipped_entities_by_type: HashMap::new(),
             total_advisories: 0,
             advice_level_counts: HashMap::new(),
             highest_advice_level_counts: HashMap::new(),
@@ -69,6 +164,30 @@ impl CumulativeStatistics {
             seen_registry_events: seen_events,
             seen_non_registry_events: HashMap::new(),
             registry_coverage: 0.0,
+            by_service: HashMap::new(),
+            by_instrumentation_scope: HashMap::new(),
+            current_service: None,
+            current_scope: None,
+        }
+    }
+
+    /// Set the service and instrumentation scope the following updates are
+    /// attributed to.
+    pub(crate) fn set_sample_context(&mut self, sample: &Sample) {
+        self.current_service = service_name(sample);
+        self.current_scope = scope_name(sample);
+    }
+
+    /// Apply `f` to the statistics of the current service and instrumentation scope.
+    fn update_breakdowns(&mut self, f: impl Fn(&mut BreakdownStatistics)) {
+        if let Some(service) = &self.current_service {
+            f(self.by_service.entry(service.clone()).or_default());
+        }
+        if let Some(scope) = &self.current_scope {
+            f(self
+                .by_instrumentation_scope
+                .entry(scope.clone())
+                .or_default());
         }
     }
 
@@ -133,6 +252,16 @@ impl CumulativeStatistics {
             .entry(entity_type.to_owned())
             .or_insert(0) += 1;
         self.total_entities += 1;
+        self.update_breakdowns(|breakdown| breakdown.total_entities += 1);
+    }
+
+    /// Increment the number of entities skipped by sampling by type
+    pub(crate) fn inc_skipped_entity_count(&mut self, entity_type: &str) {
+        *self
+            .total_skipped_entities_by_type
+            .entry(entity_type.to_owned())
+            .or_insert(0) += 1;
+        self.total_skipped_entities += 1;
     }
 
     /// Add an advice to the statistics
@@ -147,11 +276,18 @@ impl CumulativeStatistics {
             .entry(advice.message.clone())
             .or_insert(0) += 1;
         self.total_advisories += 1;
+        self.update_breakdowns(|breakdown| breakdown.add_advice(advice));
     }
 
     /// Add a highest advice level to the statistics
     pub(crate) fn add_highest_advice_level(&mut self, advice: &FindingLevel) {
         *self.highest_advice_level_counts.entry(*advice).or_insert(0) += 1;
+        self.update_breakdowns(|breakdown| {
+            *breakdown
+                .highest_advice_level_counts
+                .entry(*advice)
+                .or_insert(0) += 1;
+        });
     }
 
     /// Increment the no advice count in the statistics
@@ -164,6 +300,11 @@ impl CumulativeStatistics {
         if let Some(count) = self.seen_registry_attributes.get_mut(&seen_attribute_name) {
             // This is a registry attribute
             *count += 1;
+            self.update_breakdowns(|breakdown| {
+                let _ = breakdown
+                    .seen_registry_attributes
+                    .insert(seen_attribute_name.clone());
+            });
         } else {
             // This is a non-registry attribute
             *self
@@ -178,6 +319,11 @@ impl CumulativeStatistics {
         if let Some(count) = self.seen_registry_metrics.get_mut(&seen_metric_name) {
             // This is a registry metric
             *count += 1;
+            self.update_breakdowns(|breakdown| {
+                let _ = breakdown
+                    .seen_registry_metrics
+                    .insert(seen_metric_name.clone());
+            });
         } else {
             // This is a non-registry metric
             *self
@@ -196,6 +342,11 @@ impl CumulativeStatistics {
         if let Some(count) = self.seen_registry_events.get_mut(&seen_event_name) {
             // This is a registry event
             *count += 1;
+            self.update_breakdowns(|breakdown| {
+                let _ = breakdown
+                    .seen_registry_events
+                    .insert(seen_event_name.clone());
+            });
         } else {
             // This is a non-registry event
             *self
@@ -217,42 +368,127 @@ impl CumulativeStatistics {
         self.max_level().is_some_and(|level| level >= threshold)
     }
 
-    /// Finalize the statistics by calculating registry coverage
-    pub(crate) fn finalize(&mut self) {
-        // Calculate the registry coverage
-        // (non-zero attributes + non-zero metrics + non-zero events) / (total attributes + total metrics + total events)
-        let non_zero_attributes = self
-            .seen_registry_attributes
-            .values()
-            .filter(|&&count| count > 0)
-            .count();
-        let total_registry_attributes = self.seen_registry_attributes.len();
-
-        let non_zero_metrics = self
-            .seen_registry_metrics
-            .values()
-            .filter(|&&count| count > 0)
-            .count();
-        let total_registry_metrics = self.seen_registry_metrics.len();
+    /// The number of registry items (attributes, metrics and events).
+    fn total_registry_items(&self) -> usize {
+        self.seen_registry_attributes.len()
+            + self.seen_registry_metrics.len()
+            + self.seen_registry_events.len()
+    }
 
-        let non_zero_events = self
-            .seen_registry_events
+    /// Calculate the registry coverage:
+    /// (non-zero attributes + non-zero metrics + non-zero events) / (total attributes + total metrics + total events)
+    fn registry_coverage(&self) -> f32 {
+        let non_zero_items = self
+            .seen_registry_attributes
             .values()
+            .chain(self.seen_registry_metrics.values())
+            .chain(self.seen_registry_events.values())
             .filter(|&&count| count > 0)
             .count();
-        let total_registry_events = self.seen_registry_events.len();
-
-        let total_registry_items =
-            total_registry_attributes + total_registry_metrics + total_registry_events;
-
+        let total_registry_items = self.total_registry_items();
         if total_registry_items > 0 {
-            self.registry_coverage = ((non_zero_attributes + non_zero_metrics + non_zero_events)
-                as f32)
-                / (total_registry_items as f32);
+            non_zero_items as f32 / total_registry_items as f32
         } else {
-            self.registry_coverage = 0.0;
+            0.0
+        }
+    }
+
+    /// Finalize the statistics by calculating registry coverage
+    pub(crate) fn finalize(&mut self) {
+        self.registry_coverage = self.registry_coverage();
+
+        let total_registry_items = self.total_registry_items();
+        for breakdown in self
+            .by_service
+            .values_mut()
+            .chain(self.by_instrumentation_scope.values_mut())
+        {
+            breakdown.registry_coverage = if total_registry_items > 0 {
+                breakdown.seen_registry_items() as f32 / total_registry_items as f32
+            } else {
+                0.0
+            };
         }
     }
+
+    /// Render the statistics in the Prometheus text exposition format.
+    fn write_prometheus(&self, out: &mut String) {
+        write_metric(
+            out,
+            "weaver_live_check_samples_total",
+            "counter",
+            "Samples checked, by type.",
+            "type",
+            &self.total_entities_by_type,
+        );
+        write_metric(
+            out,
+            "weaver_live_check_skipped_samples_total",
+            "counter",
+            "Samples skipped by sampling, by type.",
+            "type",
+            &self.total_skipped_entities_by_type,
+        );
+        let findings_by_level: HashMap<String, usize> = self
+            .advice_level_counts
+            .iter()
+            .map(|(level, count)| (level.to_string(), *count))
+            .collect();
+        write_metric(
+            out,
+            "weaver_live_check_findings_total",
+            "counter",
+            "Findings, by level.",
+            "level",
+            &findings_by_level,
+        );
+        write_metric(
+            out,
+            "weaver_live_check_findings_by_id_total",
+            "counter",
+            "Findings, by finding id.",
+            "id",
+            &self.advice_type_counts,
+        );
+        out.push_str(
+            "# HELP weaver_live_check_registry_coverage_ratio Fraction of the registry seen in the samples.\n\
+             # TYPE weaver_live_check_registry_coverage_ratio gauge\n",
+        );
+        out.push_str(&format!(
+            "weaver_live_check_registry_coverage_ratio {}\n",
+            self.registry_coverage()
+        ));
+    }
+}
+
+/// Write a labelled metric family, sorted by label value.
+fn write_metric(
+    out: &mut String,
+    name: &str,
+    metric_type: &str,
+    help: &str,
+    label: &str,
+    values: &HashMap<String, usize>,
+) {
+    out.push_str(&format!(
+        "# HELP {name} {help}\n# TYPE {name} {metric_type}\n"
+    ));
+    let mut values: Vec<_> = values.iter().collect();
+    values.sort();
+    for (label_value, value) in values {
+        out.push_str(&format!(
+            "{name}{{{label}=\"{}\"}} {value}\n",
+            escape_label_value(label_value)
+        ));
+    }
+}
+
+/// Escape a Prometheus label value (backslash, double quote and line feed).
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
 }
 
 /// Disabled statistics that perform no accumulation (for long-running sessions)
@@ -295,6 +531,14 @@ impl LiveCheckStatistics {
         }
     }
 
+    /// Attribute the following updates to the service and instrumentation
+    /// scope of this (top-level) sample
+    pub fn set_sample_context(&mut self, sample: &Sample) {
+        if let Self::Cumulative(stats) = self {
+            stats.set_sample_context(sample);
+        }
+    }
+
     /// Increment the total number of entities by type
     pub fn inc_entity_count(&mut self, entity_type: &str) {
         if let Self::Cumulative(stats) = self {
@@ -302,6 +546,13 @@ impl LiveCheckStatistics {
         }
     }
 
+    /// Increment the number of entities skipped by sampling by type
+    pub fn inc_skipped_entity_count(&mut self, entity_type: &str) {
+        if let Self::Cumulative(stats) = self {
+            stats.inc_skipped_entity_count(entity_type);
+        }
+    }
+
     /// Add attribute name to coverage
     pub fn add_attribute_name_to_coverage(&mut self, seen_attribute_name: String) {
         if let Self::Cumulative(stats) = self {
@@ -341,6 +592,17 @@ impl LiveCheckStatistics {
             stats.finalize();
         }
     }
+
+    /// Render the statistics in the Prometheus text exposition format.
+    /// Empty for [`LiveCheckStatistics::Disabled`].
+    #[must_use]
+    pub fn to_prometheus(&self) -> String {
+        let mut out = String::new();
+        if let Self::Cumulative(stats) = self {
+            stats.write_prometheus(&mut out);
+        }
+        out
+    }
 }
 
 #[cfg(test)]
@@ -457,4 +719,135 @@ mod tests {
         assert!(!disabled.should_fail(FindingLevel::Improvement));
         assert!(!disabled.should_fail(FindingLevel::Violation));
     }
+
+    #[test]
+    fn test_breakdown_by_service_and_scope() {
+        use crate::sample_attribute::SampleAttribute;
+        use crate::sample_instrumentation_scope::SampleInstrumentationScope;
+        use crate::sample_resource::SampleResource;
+        use crate::sample_span::SampleSpan;
+        use serde_json::json;
+        use std::rc::Rc;
+        use weaver_semconv::group::SpanKindSpec;
+
+        // A registry of two attributes
+        let mut stats = empty_cumulative();
+        for name in ["test.a", "test.b"] {
+            let _ = stats.seen_registry_attributes.insert(name.to_owned(), 0);
+        }
+
+        let span = |service: Option<&str>| {
+            Sample::Span(SampleSpan {
+                name: "span".to_owned(),
+                kind: SpanKindSpec::Internal,
+                status: None,
+                attributes: vec![],
+                span_events: vec![],
+                span_links: vec![],
+                live_check_result: None,
+                resource: Some(Rc::new(SampleResource {
+                    attributes: service
+                        .map(|service| SampleAttribute {
+                            name: "service.name".to_owned(),
+                            value: Some(json!(service)),
+                            r#type: None,
+                            live_check_result: None,
+                        })
+                        .into_iter()
+                        .collect(),
+                    live_check_result: None,
+                })),
+                instrumentation_scope: Some(Rc::new(SampleInstrumentationScope {
+                    name: "scope".to_owned(),
+                    version: "1.0".to_owned(),
+                    schema_url: String::new(),
+                    attributes: vec![],
+                    dropped_attributes_count: 0,
+                    live_check_result: None,
+                })),
+            })
+        };
+
+        stats.set_sample_context(&span(Some("checkout")));
+        stats.inc_entity_count("span");
+        stats.add_attribute_name_to_coverage("test.a".to_owned());
+        stats.add_advice(&PolicyFinding {
+            id: "missing_attribute".to_owned(),
+            context: None,
+            message: String::new(),
+            level: FindingLevel::Violation,
+            signal_type: None,
+            signal_name: None,
+        });
+        stats.add_highest_advice_level(&FindingLevel::Violation);
+
+        stats.set_sample_context(&span(None));
+        stats.inc_entity_count("span");
+        stats.add_attribute_name_to_coverage("test.a".to_owned());
+        stats.add_attribute_name_to_coverage("test.b".to_owned());
+        stats.finalize();
+
+        let checkout = &stats.by_service["checkout"];
+        assert_eq!(checkout.total_entities, 1);
+        assert_eq!(checkout.total_advisories, 1);
+        assert_eq!(checkout.advice_level_counts[&FindingLevel::Violation], 1);
+        assert_eq!(checkout.advice_type_counts["missing_attribute"], 1);
+        assert!((checkout.registry_coverage - 0.5).abs() < f32::EPSILON);
+
+        let unknown = &stats.by_service["unknown_service"];
+        assert_eq!(unknown.total_entities, 1);
+        assert_eq!(unknown.total_advisories, 0);
+        assert!((unknown.registry_coverage - 1.0).abs() < f32::EPSILON);
+
+        let scope = &stats.by_instrumentation_scope["scope@1.0"];
+        assert_eq!(scope.total_entities, 2);
+        assert_eq!(
+            scope.highest_advice_level_counts[&FindingLevel::Violation],
+            1
+        );
+        assert!((scope.registry_coverage - 1.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_to_prometheus() {
+        let mut stats = empty_cumulative();
+        for name in ["test.a", "test.b"] {
+            let _ = stats.seen_registry_attributes.insert(name.to_owned(), 0);
+        }
+        stats.inc_entity_count("span");
+        stats.inc_entity_count("attribute");
+        stats.inc_entity_count("attribute");
+        stats.inc_skipped_entity_count("span");
+        stats.add_attribute_name_to_coverage("test.a".to_owned());
+        stats.add_advice(&PolicyFinding {
+            id: "missing_\"attribute\"".to_owned(),
+            context: None,
+            message: String::new(),
+            level: FindingLevel::Violation,
+            signal_type: None,
+            signal_name: None,
+        });
+        let stats = LiveCheckStatistics::Cumulative(stats);
+
+        let text = stats.to_prometheus();
+        for line in [
+            "# TYPE weaver_live_check_samples_total counter",
+            "weaver_live_check_samples_total{type=\"attribute\"} 2",
+            "weaver_live_check_samples_total{type=\"span\"} 1",
+            "weaver_live_check_skipped_samples_total{type=\"span\"} 1",
+            "weaver_live_check_findings_total{level=\"violation\"} 1",
+            "weaver_live_check_findings_by_id_total{id=\"missing_\\\"attribute\\\"\"} 1",
+            "# TYPE weaver_live_check_registry_coverage_ratio gauge",
+            "weaver_live_check_registry_coverage_ratio 0.5",
+        ] {
+            assert!(
+                text.lines().any(|l| l == line),
+                "missing `{line}` in:\n{text}"
+            );
+        }
+
+        assert!(LiveCheckStatistics::Disabled(DisabledStatistics)
+            .to_prometheus()
+            .is_empty());
+    }
 }
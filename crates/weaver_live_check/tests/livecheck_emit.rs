@@ -231,6 +231,7 @@ async fn test_livecheck_emit_roundtrip() {
             instrument: SampleInstrument::Supported(InstrumentSpec::Histogram),
             unit: "s".to_owned(),
             data_points: None,
+            aggregation_temporality: None,
             instrumentation_scope: None,
             live_check_result: None,
             resource: None,
@@ -16,7 +16,8 @@ use serde::Deserialize;
 use serde_json::json;
 use weaver_forge::v2::registry::ForgeResolvedRegistry;
 use weaver_live_check::advice::{
-    Advisor, DeprecatedAdvisor, EnumAdvisor, RegoAdvisor, StabilityAdvisor, TypeAdvisor,
+    Advisor, DataPointAdvisor, DeprecatedAdvisor, EnumAdvisor, RegoAdvisor, StabilityAdvisor,
+    TypeAdvisor,
 };
 use weaver_live_check::live_checker::LiveChecker;
 use weaver_live_check::{
@@ -102,6 +103,7 @@ fn default_advisors() -> Vec<Box<dyn Advisor>> {
         Box::new(StabilityAdvisor),
         Box::new(TypeAdvisor),
         Box::new(EnumAdvisor),
+        Box::new(DataPointAdvisor::default()),
     ]
 }
 
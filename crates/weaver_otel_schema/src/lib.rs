@@ -4,11 +4,16 @@
 //! Please refer to the [OpenTelemetry Schema OTEP](https://github.com/open-telemetry/oteps/blob/main/text/0152-telemetry-schemas.md)
 //! for more information.
 
-use crate::Error::{InvalidTelemetrySchema, TelemetrySchemaNotFound};
+use crate::Error::{InvalidTelemetrySchema, TelemetrySchemaNotFound, TelemetrySchemaWriteFailed};
 use miette::Diagnostic;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
 use weaver_common::diagnostic::{DiagnosticMessage, DiagnosticMessages};
-use weaver_version::Versions;
+use weaver_version::{Version, VersionSpec, Versions};
+
+/// The version of the file structure written by weaver.
+pub const FILE_FORMAT: &str = "1.1.0";
 
 /// Errors emitted by this crate.
 #[derive(thiserror::Error, Debug, Clone, Deserialize, Serialize, Diagnostic)]
@@ -28,6 +33,24 @@ pub enum Error {
         /// The error that occurred.
         error: String,
     },
+
+    /// Invalid version of an OTel Telemetry schema.
+    #[error("Invalid version {version:?} of the OTel telemetry schema. {error}")]
+    InvalidVersion {
+        /// The invalid version.
+        version: String,
+        /// The error that occurred.
+        error: String,
+    },
+
+    /// The OTel Telemetry schema can't be written.
+    #[error("Failed to write the OTel telemetry schema (path: {path:?}). {error}")]
+    TelemetrySchemaWriteFailed {
+        /// The path to the telemetry schema file.
+        path: String,
+        /// The error that occurred.
+        error: String,
+    },
 }
 
 impl From<Error> for DiagnosticMessages {
@@ -55,8 +78,94 @@ pub struct TelemetrySchema {
 }
 
 impl TelemetrySchema {
+    /// Creates a telemetry schema without any version.
+    #[must_use]
+    pub fn new(schema_url: &str) -> Self {
+        Self {
+            file_format: FILE_FORMAT.to_owned(),
+            schema_url: schema_url.to_owned(),
+            versions: None,
+        }
+    }
+
+    /// Adds the changes of a version, replacing the changes previously defined for
+    /// this version, if any.
+    pub fn add_version(&mut self, version: &str, spec: VersionSpec) -> Result<(), Error> {
+        _ = self
+            .versions
+            .get_or_insert_with(Versions::default)
+            .insert(version, spec)
+            .map_err(|e| Error::InvalidVersion {
+                version: version.to_owned(),
+                error: e.to_string(),
+            })?;
+        Ok(())
+    }
+
+    /// Adds the changes of a version to a telemetry schema file, replacing the changes
+    /// previously defined for this version, if any. The file is edited as text to keep
+    /// its comments and layout: the entry of the version replaces the previous one or is
+    /// appended to the `versions` section. The schema URL of the file is replaced with
+    /// `schema_url` only if the version is the most recent one of the file.
+    ///
+    /// A `versions` section in flow style, e.g. `versions: {}`, can't be edited as text,
+    /// the whole file is then rewritten.
+    pub fn add_version_to_file<P: AsRef<Path>>(
+        path: P,
+        schema_url: &str,
+        version: &str,
+        spec: VersionSpec,
+    ) -> Result<(), Error> {
+        let path = path.as_ref();
+        let mut schema = Self::try_from_file(path)?;
+        let new_version = Version::parse(version).map_err(|e| Error::InvalidVersion {
+            version: version.to_owned(),
+            error: e.to_string(),
+        })?;
+        let is_latest = schema
+            .versions
+            .as_ref()
+            .and_then(Versions::latest_version)
+            .is_none_or(|latest| latest <= new_version);
+        let text = std::fs::read_to_string(path).map_err(|e| InvalidTelemetrySchema {
+            path_or_url: path.to_string_lossy().to_string(),
+            error: e.to_string(),
+        })?;
+        let entry = serde_yaml::to_string(&BTreeMap::from([(new_version.to_string(), &spec)]))
+            .map_err(|e| TelemetrySchemaWriteFailed {
+                path: path.to_string_lossy().to_string(),
+                error: e.to_string(),
+            })?;
+
+        let Some(mut text) = insert_version(&text, &new_version, &entry) else {
+            if is_latest {
+                schema_url.clone_into(&mut schema.schema_url);
+            }
+            schema.add_version(version, spec)?;
+            return schema.write_to_file(path);
+        };
+        if is_latest {
+            text = replace_schema_url(&text, schema_url);
+        }
+        std::fs::write(path, text).map_err(|e| TelemetrySchemaWriteFailed {
+            path: path.to_string_lossy().to_string(),
+            error: e.to_string(),
+        })
+    }
+
+    /// Writes the telemetry schema to a file in YAML.
+    pub fn write_to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let path = path.as_ref();
+        let write_failed = |error: String| TelemetrySchemaWriteFailed {
+            path: path.to_string_lossy().to_string(),
+            error,
+        };
+        let yaml = serde_yaml::to_string(self).map_err(|e| write_failed(e.to_string()))?;
+        std::fs::write(path, yaml).map_err(|e| write_failed(e.to_string()))
+    }
+
     /// Attempts to load a telemetry schema from a file.
-    pub fn try_from_file<P: AsRef<std::path::Path>>(path: P) -> Result<Self, Error> {
+    pub fn try_from_file<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
         let schema_path_buf = path.as_ref().to_path_buf();
 
         if !schema_path_buf.exists() {
@@ -80,9 +189,133 @@ impl TelemetrySchema {
     }
 }
 
+/// Returns the number of leading whitespaces of a line.
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// Returns true if a line is neither blank nor a comment.
+fn is_content(line: &str) -> bool {
+    let line = line.trim();
+    !line.is_empty() && !line.starts_with('#')
+}
+
+/// Inserts the YAML `entry` of a version in the block style `versions` section of a
+/// telemetry schema, in place of the entry of the same version or else following the
+/// order, ascending or descending, of the entries. Returns `None` if the `versions`
+/// section is in flow style.
+fn insert_version(text: &str, version: &Version, entry: &str) -> Option<String> {
+    let mut lines: Vec<String> = text.split_inclusive('\n').map(str::to_owned).collect();
+    // The last line is only terminated if something is appended after it.
+    let terminate_last = |lines: &mut Vec<String>| {
+        if let Some(last) = lines.last_mut() {
+            if !last.ends_with('\n') {
+                last.push('\n');
+            }
+        }
+    };
+    let Some(start) = lines.iter().position(|line| line.starts_with("versions:")) else {
+        terminate_last(&mut lines);
+        lines.push("versions:\n".to_owned());
+        lines.push(indent_entry(entry, "  "));
+        return Some(lines.concat());
+    };
+    if is_content(&lines[start]["versions:".len()..]) {
+        return None;
+    }
+    // The section ends with the next top-level key.
+    let end = lines[start + 1..]
+        .iter()
+        .position(|line| is_content(line) && indentation(line) == 0)
+        .map_or(lines.len(), |i| start + 1 + i);
+    let section = start + 1..end;
+    let indent = lines[section.clone()]
+        .iter()
+        .find(|line| is_content(line))
+        .map_or("  ", |line| &line[..indentation(line)])
+        .to_owned();
+    // The line and the version of each entry.
+    let entries: Vec<(usize, Version)> = section
+        .clone()
+        .filter(|&i| is_content(&lines[i]) && indentation(&lines[i]) == indent.len())
+        .filter_map(|i| {
+            let (key, _) = lines[i].split_once(':')?;
+            let key = Version::parse(key.trim().trim_matches(['"', '\''])).ok()?;
+            Some((i, key))
+        })
+        .collect();
+    let existing = entries
+        .iter()
+        .find(|(_, key)| key == version)
+        .map(|(i, _)| *i);
+    let descending = matches!((entries.first(), entries.last()), (Some((_, first)), Some((_, last))) if first > last);
+    let next_entry = entries
+        .iter()
+        .find(|(_, key)| {
+            if descending {
+                key < version
+            } else {
+                key > version
+            }
+        })
+        .map(|(i, _)| *i);
+    let entry = indent_entry(entry, &indent);
+    if let Some(i) = existing {
+        // The blank lines and comments before the next entry are kept.
+        let mut entry_end = (i + 1..end)
+            .find(|&j| is_content(&lines[j]) && indentation(&lines[j]) <= indent.len())
+            .unwrap_or(end);
+        while entry_end > i + 1 && !is_content(&lines[entry_end - 1]) {
+            entry_end -= 1;
+        }
+        _ = lines.splice(i..entry_end, [entry]);
+        return Some(lines.concat());
+    }
+    let position = if let Some(mut i) = next_entry {
+        // Before the comments of the next entry.
+        while i > start + 1 && lines[i - 1].trim_start().starts_with('#') {
+            i -= 1;
+        }
+        i
+    } else {
+        section
+            .rev()
+            .find(|&i| is_content(&lines[i]))
+            .unwrap_or(start)
+            + 1
+    };
+    if position == lines.len() {
+        terminate_last(&mut lines);
+    }
+    lines.insert(position, entry);
+    Some(lines.concat())
+}
+
+/// Indents each line of a YAML entry.
+fn indent_entry(entry: &str, indent: &str) -> String {
+    entry
+        .lines()
+        .map(|line| format!("{indent}{line}\n"))
+        .collect()
+}
+
+/// Replaces the value of the top-level `schema_url` key, keeping its trailing comment.
+fn replace_schema_url(text: &str, schema_url: &str) -> String {
+    text.split_inclusive('\n')
+        .map(|line| match line.strip_prefix("schema_url:") {
+            Some(value) => {
+                let comment = value.find(" #").map_or("", |i| value[i..].trim_end());
+                format!("schema_url: {schema_url}{comment}\n")
+            }
+            None => line.to_owned(),
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use crate::TelemetrySchema;
+    use weaver_version::VersionSpec;
 
     #[test]
     fn test_try_from_file() {
@@ -90,4 +323,89 @@ mod tests {
         assert_eq!(schema.file_format, "1.1.0");
         assert_eq!(schema.schema_url, "https://opentelemetry.io/schemas/1.27.0");
     }
+
+    #[test]
+    fn test_write_to_file() {
+        let mut schema = TelemetrySchema::try_from_file("tests/test_data/1.27.0.yaml").unwrap();
+        schema.schema_url = "https://opentelemetry.io/schemas/1.28.0".to_owned();
+        schema.add_version("1.28", VersionSpec::default()).unwrap();
+        assert!(schema
+            .add_version("latest", VersionSpec::default())
+            .is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("1.28.0.yaml");
+        schema.write_to_file(&path).unwrap();
+
+        let written = TelemetrySchema::try_from_file(&path).unwrap();
+        assert_eq!(
+            written.schema_url,
+            "https://opentelemetry.io/schemas/1.28.0"
+        );
+        let versions = written.versions.unwrap();
+        assert_eq!(versions.len(), schema.versions.unwrap().len());
+        assert!(std::fs::read_to_string(&path)
+            .unwrap()
+            .contains("\n  1.28.0: {}\n"));
+    }
+
+    #[test]
+    fn test_add_version_to_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("schema.yaml");
+        let original = std::fs::read_to_string("tests/test_data/1.27.0.yaml").unwrap();
+        std::fs::write(&path, &original).unwrap();
+
+        // The comments and the other versions are kept byte for byte.
+        let spec: VersionSpec = serde_yaml::from_str(
+            "spans:\n  changes:\n    - rename_attributes:\n        attribute_map:\n          a: b\n",
+        )
+        .unwrap();
+        TelemetrySchema::add_version_to_file(
+            &path,
+            "https://opentelemetry.io/schemas/1.28.0",
+            "1.28",
+            spec,
+        )
+        .unwrap();
+        // The versions of the file are in descending order.
+        let expected = original.replacen(
+            "schemas/1.27.0\nversions:\n",
+            "schemas/1.28.0\nversions:\n  1.28.0:\n    spans:\n      changes:\n      - rename_attributes:\n          attribute_map:\n            a: b\n",
+            1,
+        );
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(written, expected);
+
+        // The entry of an existing version is replaced.
+        TelemetrySchema::add_version_to_file(
+            &path,
+            "https://opentelemetry.io/schemas/1.28.0",
+            "1.28.0",
+            VersionSpec::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            original.replacen(
+                "schemas/1.27.0\nversions:\n",
+                "schemas/1.28.0\nversions:\n  1.28.0: {}\n",
+                1
+            )
+        );
+
+        // An older version doesn't change the schema URL.
+        TelemetrySchema::add_version_to_file(
+            &path,
+            "https://opentelemetry.io/schemas/1.0.0",
+            "1.0.0",
+            VersionSpec::default(),
+        )
+        .unwrap();
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert!(written.starts_with(
+            "file_format: 1.1.0\nschema_url: https://opentelemetry.io/schemas/1.28.0\n"
+        ));
+        assert!(written.ends_with("  1.4.0:\n  1.0.0: {}\n"));
+    }
 }
@@ -11,12 +11,15 @@ use crate::instrumentation_library::InstrumentationLibrary;
 use crate::registry::{Group, Registry};
 use crate::resource::Resource;
 use serde::Serialize;
+use serde_json::{json, Value};
 use std::collections::{BTreeSet, HashMap};
 use weaver_semconv::deprecated::Deprecated;
 use weaver_semconv::group::GroupType;
 use weaver_semconv::manifest::RegistryManifest;
 use weaver_semconv::schema_url::SchemaUrl;
-use weaver_version::schema_changes::{SchemaChanges, SchemaItemChange, SchemaItemType};
+use weaver_version::schema_changes::{
+    diff_fields, FieldChange, SchemaChanges, SchemaItemChange, SchemaItemType,
+};
 use weaver_version::Versions;
 
 pub mod attribute;
@@ -327,6 +330,7 @@ impl ResolvedTelemetrySchema {
         let latest_signals = self.groups_by_name(GroupType::Metric);
         let baseline_signals = baseline_schema.groups_by_name(GroupType::Metric);
         self.diff_signals(
+            baseline_schema,
             SchemaItemType::Metrics,
             &latest_signals,
             &baseline_signals,
@@ -335,6 +339,7 @@ impl ResolvedTelemetrySchema {
         let latest_signals = self.groups_by_name(GroupType::Event);
         let baseline_signals = baseline_schema.groups_by_name(GroupType::Event);
         self.diff_signals(
+            baseline_schema,
             SchemaItemType::Events,
             &latest_signals,
             &baseline_signals,
@@ -346,6 +351,7 @@ impl ResolvedTelemetrySchema {
         let latest_signals = self.groups_by_name(GroupType::Span);
         let baseline_signals = baseline_schema.groups_by_name(GroupType::Span);
         self.diff_signals(
+            baseline_schema,
             SchemaItemType::Spans,
             &latest_signals,
             &baseline_signals,
@@ -354,6 +360,7 @@ impl ResolvedTelemetrySchema {
         let latest_signals = self.groups_by_name(GroupType::Entity);
         let baseline_signals = baseline_schema.groups_by_name(GroupType::Entity);
         self.diff_signals(
+            baseline_schema,
             SchemaItemType::Entities,
             &latest_signals,
             &baseline_signals,
@@ -363,6 +370,25 @@ impl ResolvedTelemetrySchema {
         changes
     }
 
+    /// The fields of a signal compared by the diff, including the requirement
+    /// level of its attributes.
+    fn signal_fields(&self, group: &Group) -> Value {
+        let attributes: Vec<Value> = group
+            .attributes
+            .iter()
+            .filter_map(|attr_ref| self.catalog.attribute(attr_ref))
+            .map(|attr| json!({"id": attr.name, "requirement_level": attr.requirement_level}))
+            .collect();
+        json!({
+            "brief": group.brief,
+            "stability": group.stability,
+            "instrument": group.instrument,
+            "unit": group.unit,
+            "span_kind": group.span_kind,
+            "attributes": attributes,
+        })
+    }
+
     fn diff_attributes(
         &self,
         baseline_schema: &ResolvedTelemetrySchema,
@@ -371,57 +397,19 @@ impl ResolvedTelemetrySchema {
         let latest_attributes = self.registry_attribute_map();
         let baseline_attributes = baseline_schema.registry_attribute_map();
 
-        // ToDo for future PR, process differences at the field level (not required for the schema update)
-
         // Collect all the information related to the attributes that have been
         // deprecated in the latest schema.
         for (attr_name, attr) in latest_attributes.iter() {
             let baseline_attr = baseline_attributes.get(attr_name);
 
             if let Some(baseline_attr) = baseline_attr {
-                if let Some(deprecated) = attr.deprecated.as_ref() {
-                    // is this a change from the baseline?
-                    if let Some(baseline_deprecated) = baseline_attr.deprecated.as_ref() {
-                        if deprecated == baseline_deprecated {
-                            // This attribute was already deprecated in the baseline.
-                            // We can skip it.
-                            continue;
-                        }
-                    }
-
-                    match deprecated {
-                        Deprecated::Renamed {
-                            renamed_to: rename_to,
-                            ..
-                        } => {
-                            changes.add_change(
-                                SchemaItemType::RegistryAttributes,
-                                SchemaItemChange::Renamed {
-                                    old_name: baseline_attr.name.clone(),
-                                    new_name: rename_to.clone(),
-                                    note: deprecated.note(),
-                                },
-                            );
-                        }
-                        Deprecated::Obsoleted { note } => {
-                            changes.add_change(
-                                SchemaItemType::RegistryAttributes,
-                                SchemaItemChange::Obsoleted {
-                                    name: attr.name.clone(),
-                                    note: note.clone(),
-                                },
-                            );
-                        }
-                        Deprecated::Unspecified { note } | Deprecated::Uncategorized { note } => {
-                            changes.add_change(
-                                SchemaItemType::RegistryAttributes,
-                                SchemaItemChange::Uncategorized {
-                                    name: attr.name.clone(),
-                                    note: note.clone(),
-                                },
-                            );
-                        }
-                    }
+                if let Some(change) = diff_existing_item(
+                    &attr.name,
+                    attr.deprecated.as_ref(),
+                    baseline_attr.deprecated.as_ref(),
+                    || diff_fields(&attribute_fields(baseline_attr), &attribute_fields(attr)),
+                ) {
+                    changes.add_change(SchemaItemType::RegistryAttributes, change);
                 }
             } else {
                 changes.add_change(
@@ -451,6 +439,7 @@ impl ResolvedTelemetrySchema {
 
     fn diff_signals(
         &self,
+        baseline_schema: &ResolvedTelemetrySchema,
         schema_item_type: SchemaItemType,
         latest_signals: &HashMap<&str, &Group>,
         baseline_signals: &HashMap<&str, &Group>,
@@ -462,47 +451,18 @@ impl ResolvedTelemetrySchema {
             let baseline_group = baseline_signals.get(signal_name);
 
             if let Some(baseline_group) = baseline_group {
-                if let Some(deprecated) = group.deprecated.as_ref() {
-                    // is this a change from the baseline?
-                    if let Some(baseline_deprecated) = baseline_group.deprecated.as_ref() {
-                        if deprecated == baseline_deprecated {
-                            continue;
-                        }
-                    }
-
-                    match deprecated {
-                        Deprecated::Renamed {
-                            renamed_to: rename_to,
-                            ..
-                        } => {
-                            changes.add_change(
-                                schema_item_type,
-                                SchemaItemChange::Renamed {
-                                    old_name: (*signal_name).to_owned(),
-                                    new_name: rename_to.clone(),
-                                    note: deprecated.note(),
-                                },
-                            );
-                        }
-                        Deprecated::Obsoleted { note } => {
-                            changes.add_change(
-                                schema_item_type,
-                                SchemaItemChange::Obsoleted {
-                                    name: (*signal_name).to_owned(),
-                                    note: note.clone(),
-                                },
-                            );
-                        }
-                        Deprecated::Unspecified { note } | Deprecated::Uncategorized { note } => {
-                            changes.add_change(
-                                schema_item_type,
-                                SchemaItemChange::Uncategorized {
-                                    name: (*signal_name).to_owned(),
-                                    note: note.clone(),
-                                },
-                            );
-                        }
-                    }
+                if let Some(change) = diff_existing_item(
+                    signal_name,
+                    group.deprecated.as_ref(),
+                    baseline_group.deprecated.as_ref(),
+                    || {
+                        diff_fields(
+                            &baseline_schema.signal_fields(baseline_group),
+                            &self.signal_fields(group),
+                        )
+                    },
+                ) {
+                    changes.add_change(schema_item_type, change);
                 }
             } else {
                 changes.add_change(
@@ -531,6 +491,57 @@ impl ResolvedTelemetrySchema {
     }
 }
 
+/// The change of a telemetry object present in both the baseline and the latest
+/// schemas, if any.
+///
+/// A deprecation is only reported when it differs from the baseline one, as a
+/// rename, an obsoletion or an uncategorized change. A newly deprecated object is
+/// only reported as such: its other changes are ignored. Otherwise, the fields
+/// that changed, computed by `field_changes`, are reported as an update.
+pub(crate) fn diff_existing_item(
+    name: &str,
+    deprecated: Option<&Deprecated>,
+    baseline_deprecated: Option<&Deprecated>,
+    field_changes: impl FnOnce() -> Vec<FieldChange>,
+) -> Option<SchemaItemChange> {
+    if let Some(deprecated) =
+        deprecated.filter(|deprecated| baseline_deprecated != Some(*deprecated))
+    {
+        return Some(match deprecated {
+            Deprecated::Renamed { renamed_to, .. } => SchemaItemChange::Renamed {
+                old_name: name.to_owned(),
+                new_name: renamed_to.clone(),
+                note: deprecated.note(),
+            },
+            Deprecated::Obsoleted { note } => SchemaItemChange::Obsoleted {
+                name: name.to_owned(),
+                note: note.clone(),
+            },
+            Deprecated::Unspecified { note } | Deprecated::Uncategorized { note } => {
+                SchemaItemChange::Uncategorized {
+                    name: name.to_owned(),
+                    note: note.clone(),
+                }
+            }
+        });
+    }
+    let changes = field_changes();
+    (!changes.is_empty()).then(|| SchemaItemChange::Updated {
+        name: name.to_owned(),
+        changes,
+    })
+}
+
+/// The fields of an attribute compared by the diff.
+fn attribute_fields(attr: &Attribute) -> Value {
+    json!({
+        "brief": attr.brief,
+        "type": attr.r#type,
+        "requirement_level": attr.requirement_level,
+        "stability": attr.stability,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use crate::attribute::Attribute;
@@ -805,6 +816,44 @@ mod tests {
         assert_eq!(changes.count_removed_registry_attributes(), 2);
     }
 
+    #[test]
+    fn detect_updated_attribute_fields() {
+        let mut prior_schema = ResolvedTelemetrySchema::new("1.0", "", "");
+        prior_schema.add_attribute_group(
+            "registry.group1",
+            [
+                Attribute::boolean("attr1", "brief1", "note1"),
+                Attribute::string("attr2", "brief2", "note2"),
+            ],
+        );
+
+        let mut latest_schema = ResolvedTelemetrySchema::new("1.0", "", "");
+        latest_schema.add_attribute_group(
+            "registry.group1",
+            [
+                Attribute::boolean("attr1", "brief1", "a different note"),
+                Attribute::string("attr2", "brief2", "note2").brief("new brief2"),
+            ],
+        );
+
+        let changes = latest_schema.diff(&prior_schema);
+        assert_eq!(changes.count_changes(), 1);
+        assert_eq!(changes.count_updated_registry_attributes(), 1);
+        match &changes
+            .changes_by_type(SchemaItemType::RegistryAttributes)
+            .unwrap()[0]
+        {
+            SchemaItemChange::Updated { name, changes } => {
+                assert_eq!(name, "attr2");
+                assert_eq!(changes.len(), 1);
+                assert_eq!(changes[0].path, "brief");
+                assert_eq!(changes[0].old_value, "brief2");
+                assert_eq!(changes[0].new_value, "new brief2");
+            }
+            _ => panic!("Unexpected change type."),
+        }
+    }
+
     // TODO add many more group diff checks for various capabilities.
     #[test]
     fn detect_metric_name_change() {
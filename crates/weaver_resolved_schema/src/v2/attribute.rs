@@ -60,4 +60,12 @@ impl Signal for Attribute {
     fn common(&self) -> &CommonFields {
         &self.common
     }
+
+    fn diff_fields(&self, _catalog: &[Attribute]) -> serde_json::Value {
+        serde_json::json!({
+            "brief": self.common.brief,
+            "type": self.r#type,
+            "stability": self.common.stability,
+        })
+    }
 }
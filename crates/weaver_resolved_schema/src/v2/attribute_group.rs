@@ -9,7 +9,12 @@ use weaver_semconv::{
     v2::{signal_id::SignalId, CommonFields},
 };
 
-use crate::v2::{attribute::AttributeRef, provenance::Provenance, Signal};
+use crate::v2::{
+    attribute::{Attribute, AttributeRef},
+    attribute_ref_fields,
+    provenance::Provenance,
+    Signal,
+};
 
 /// Public attribute group.
 ///
@@ -61,4 +66,15 @@ impl Signal for AttributeGroup {
     fn common(&self) -> &CommonFields {
         &self.common
     }
+
+    fn diff_fields(&self, catalog: &[Attribute]) -> serde_json::Value {
+        serde_json::json!({
+            "brief": self.common.brief,
+            "stability": self.common.stability,
+            "attributes": attribute_ref_fields(
+                catalog,
+                self.attributes.iter().map(|a| (&a.base, &a.requirement_level)),
+            ),
+        })
+    }
 }
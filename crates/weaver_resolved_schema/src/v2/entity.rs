@@ -8,7 +8,12 @@ use weaver_semconv::{
     v2::{signal_id::SignalId, CommonFields},
 };
 
-use crate::v2::{attribute::AttributeRef, provenance::Provenance, Signal};
+use crate::v2::{
+    attribute::{Attribute, AttributeRef},
+    attribute_ref_fields,
+    provenance::Provenance,
+    Signal,
+};
 
 /// The definition of an Entity signal.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
@@ -178,6 +183,22 @@ impl Signal for Entity {
     fn common(&self) -> &CommonFields {
         &self.common
     }
+
+    fn diff_fields(&self, catalog: &[Attribute]) -> serde_json::Value {
+        serde_json::json!({
+            "brief": self.common.brief,
+            "stability": self.common.stability,
+            "requirement_level": self.requirement_level,
+            "identity": attribute_ref_fields(
+                catalog,
+                self.identity.iter().map(|a| (&a.base, &a.requirement_level)),
+            ),
+            "description": attribute_ref_fields(
+                catalog,
+                self.description.iter().map(|a| (&a.base, &a.requirement_level)),
+            ),
+        })
+    }
 }
 
 #[cfg(test)]
@@ -196,6 +217,7 @@ mod tests {
                         provenance: Provenance {
                             source: Some(DependencyRef(2)),
                             path: String::new(),
+                            ..Default::default()
                         },
                     })],
                 },
@@ -9,7 +9,11 @@ use weaver_semconv::{
 };
 
 use crate::v2::{
-    attribute::AttributeRef, entity::EntityAssociation, provenance::Provenance, Signal,
+    attribute::{Attribute, AttributeRef},
+    attribute_ref_fields,
+    entity::EntityAssociation,
+    provenance::Provenance,
+    Signal,
 };
 
 /// The definition of an Event signal.
@@ -90,4 +94,16 @@ impl Signal for Event {
     fn common(&self) -> &CommonFields {
         &self.common
     }
+
+    fn diff_fields(&self, catalog: &[Attribute]) -> serde_json::Value {
+        serde_json::json!({
+            "brief": self.common.brief,
+            "stability": self.common.stability,
+            "requirement_level": self.requirement_level,
+            "attributes": attribute_ref_fields(
+                catalog,
+                self.attributes.iter().map(|a| (&a.base, &a.requirement_level)),
+            ),
+        })
+    }
 }
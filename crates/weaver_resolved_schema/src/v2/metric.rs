@@ -1,7 +1,11 @@
 //! Metric related definitions structs.
 
 use crate::v2::{
-    attribute::AttributeRef, entity::EntityAssociation, provenance::Provenance, Signal,
+    attribute::{Attribute, AttributeRef},
+    attribute_ref_fields,
+    entity::EntityAssociation,
+    provenance::Provenance,
+    Signal,
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -102,4 +106,18 @@ impl Signal for Metric {
     fn common(&self) -> &CommonFields {
         &self.common
     }
+
+    fn diff_fields(&self, catalog: &[Attribute]) -> serde_json::Value {
+        serde_json::json!({
+            "brief": self.common.brief,
+            "stability": self.common.stability,
+            "requirement_level": self.requirement_level,
+            "instrument": self.instrument,
+            "unit": self.unit,
+            "attributes": attribute_ref_fields(
+                catalog,
+                self.attributes.iter().map(|a| (&a.base, &a.requirement_level)),
+            ),
+        })
+    }
 }
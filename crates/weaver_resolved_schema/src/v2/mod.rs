@@ -4,8 +4,8 @@ use std::collections::{BTreeSet, HashMap, HashSet};
 
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use weaver_semconv::stability::Stability;
 use weaver_semconv::{
-    deprecated::Deprecated,
     group::GroupType,
     schema_url::SchemaUrl,
     v2::{
@@ -13,9 +13,12 @@ use weaver_semconv::{
         CommonFields,
     },
 };
-use weaver_version::v2::{RegistryChanges, SchemaChanges, SchemaItemChange};
+use weaver_version::v2::{
+    compatibility::RegistryItemType, diff_fields, RegistryChanges, SchemaChanges, SchemaItemChange,
+};
 
 use crate::{
+    diff_existing_item,
     v2::{
         attribute::Attribute,
         attribute_group::AttributeGroup,
@@ -89,19 +92,37 @@ impl ResolvedTelemetrySchema {
 
     #[must_use]
     fn registry_diff(&self, baseline_schema: &ResolvedTelemetrySchema) -> RegistryChanges {
+        let catalogs = DiffCatalogs {
+            latest: &self.attribute_catalog,
+            baseline: &baseline_schema.attribute_catalog,
+        };
         RegistryChanges {
             attribute_changes: self.registry_attribute_diff(baseline_schema),
             attribute_group_changes: diff_signals(
                 &self.registry.attribute_groups,
                 &baseline_schema.registry.attribute_groups,
+                catalogs,
             ),
             entity_changes: diff_signals(
                 &self.registry.entities,
                 &baseline_schema.registry.entities,
+                catalogs,
+            ),
+            event_changes: diff_signals(
+                &self.registry.events,
+                &baseline_schema.registry.events,
+                catalogs,
+            ),
+            metric_changes: diff_signals(
+                &self.registry.metrics,
+                &baseline_schema.registry.metrics,
+                catalogs,
+            ),
+            span_changes: diff_signals(
+                &self.registry.spans,
+                &baseline_schema.registry.spans,
+                catalogs,
             ),
-            event_changes: diff_signals(&self.registry.events, &baseline_schema.registry.events),
-            metric_changes: diff_signals(&self.registry.metrics, &baseline_schema.registry.metrics),
-            span_changes: diff_signals(&self.registry.spans, &baseline_schema.registry.spans),
         }
     }
 
@@ -112,7 +133,34 @@ impl ResolvedTelemetrySchema {
     ) -> Vec<SchemaItemChange> {
         let latest_attributes = self.registry_attribute_map();
         let baseline_attributes = baseline_schema.registry_attribute_map();
-        diff_signals_by_hash(&latest_attributes, &baseline_attributes)
+        let catalogs = DiffCatalogs {
+            latest: &self.attribute_catalog,
+            baseline: &baseline_schema.attribute_catalog,
+        };
+        diff_signals_by_hash(&latest_attributes, &baseline_attributes, catalogs)
+    }
+
+    /// The stability of a telemetry object of the registry, identified by its
+    /// type and the name reported in the diffs.
+    #[must_use]
+    pub fn stability(&self, item_type: RegistryItemType, name: &str) -> Option<Stability> {
+        fn find<T: Signal>(signals: &[T], name: &str) -> Option<Stability> {
+            signals
+                .iter()
+                .find(|s| s.id() == name)
+                .map(|s| s.common().stability.clone())
+        }
+        match item_type {
+            RegistryItemType::Attribute => self
+                .registry_attribute_map()
+                .get(name)
+                .map(|a| a.common.stability.clone()),
+            RegistryItemType::AttributeGroup => find(&self.registry.attribute_groups, name),
+            RegistryItemType::Entity => find(&self.registry.entities, name),
+            RegistryItemType::Event => find(&self.registry.events, name),
+            RegistryItemType::Metric => find(&self.registry.metrics, name),
+            RegistryItemType::Span => find(&self.registry.spans, name),
+        }
     }
 
     /// Get the registry attributes of the resolved telemetry schema in a fast lookup map.
@@ -337,6 +385,7 @@ pub fn convert_v1_to_v2(
         let mut prov = provenance::Provenance::default();
         if let Some(p) = g.provenance() {
             prov.path = p.path.clone();
+            prov.lines = p.lines.clone();
             if p.schema_url.to_string() != r.registry_url {
                 // Note: if idx is not found, it means this came from *ourselves* not from a dependency.
                 // In that instance we don't fill out dependency provenance.
@@ -794,57 +843,72 @@ pub trait Signal {
     fn id(&self) -> &str;
     /// The common fields for the signal.
     fn common(&self) -> &CommonFields;
+    /// The fields compared by the diff. The attribute references of the signal
+    /// are looked up in the attribute catalog of its schema.
+    fn diff_fields(&self, catalog: &[Attribute]) -> serde_json::Value;
+}
+
+/// The fields of attribute references compared by the diff: the key and the
+/// requirement level of each attribute.
+pub(crate) fn attribute_ref_fields<'a>(
+    catalog: &[Attribute],
+    refs: impl IntoIterator<
+        Item = (
+            &'a attribute::AttributeRef,
+            &'a weaver_semconv::attribute::RequirementLevel,
+        ),
+    >,
+) -> serde_json::Value {
+    refs.into_iter()
+        .filter_map(|(attr_ref, requirement_level)| {
+            catalog
+                .attribute_key(attr_ref)
+                .map(|key| serde_json::json!({"id": key, "requirement_level": requirement_level}))
+        })
+        .collect()
 }
 
 /// Diffs signal registries.
 #[must_use]
-fn diff_signals<T: Signal>(latest: &[T], baseline: &[T]) -> Vec<SchemaItemChange> {
+fn diff_signals<T: Signal>(
+    latest: &[T],
+    baseline: &[T],
+    catalogs: DiffCatalogs<'_>,
+) -> Vec<SchemaItemChange> {
     let baseline_signals: HashMap<&str, &T> = baseline.iter().map(|s| (s.id(), s)).collect();
     let latest_signals: HashMap<&str, &T> = latest.iter().map(|s| (s.id(), s)).collect();
-    diff_signals_by_hash(&latest_signals, &baseline_signals)
+    diff_signals_by_hash(&latest_signals, &baseline_signals, catalogs)
+}
+
+/// The attribute catalogs of the latest and baseline schemas of a diff.
+#[derive(Clone, Copy)]
+struct DiffCatalogs<'a> {
+    latest: &'a [Attribute],
+    baseline: &'a [Attribute],
 }
 
 /// Finds the difference between two signal registries using a hash into the signal id.
 fn diff_signals_by_hash<T: Signal>(
     latest: &HashMap<&str, &T>,
     baseline: &HashMap<&str, &T>,
+    catalogs: DiffCatalogs<'_>,
 ) -> Vec<SchemaItemChange> {
     let mut changes: Vec<SchemaItemChange> = Vec::new();
     for (&signal_id, latest_signal) in latest.iter() {
         let baseline_signal = baseline.get(signal_id);
         if let Some(baseline_signal) = baseline_signal {
-            if let Some(deprecated) = latest_signal.common().deprecated.as_ref() {
-                // is this a change from the baseline?
-                if let Some(baseline_deprecated) = baseline_signal.common().deprecated.as_ref() {
-                    if deprecated == baseline_deprecated {
-                        continue;
-                    }
-                }
-
-                match deprecated {
-                    Deprecated::Renamed {
-                        renamed_to: rename_to,
-                        ..
-                    } => {
-                        changes.push(SchemaItemChange::Renamed {
-                            old_name: signal_id.to_owned(),
-                            new_name: rename_to.clone(),
-                            note: deprecated.note(),
-                        });
-                    }
-                    Deprecated::Obsoleted { note } => {
-                        changes.push(SchemaItemChange::Obsoleted {
-                            name: signal_id.to_owned(),
-                            note: note.clone(),
-                        });
-                    }
-                    Deprecated::Unspecified { note } | Deprecated::Uncategorized { note } => {
-                        changes.push(SchemaItemChange::Uncategorized {
-                            name: signal_id.to_owned(),
-                            note: note.clone(),
-                        });
-                    }
-                }
+            if let Some(change) = diff_existing_item(
+                signal_id,
+                latest_signal.common().deprecated.as_ref(),
+                baseline_signal.common().deprecated.as_ref(),
+                || {
+                    diff_fields(
+                        &baseline_signal.diff_fields(catalogs.baseline),
+                        &latest_signal.diff_fields(catalogs.latest),
+                    )
+                },
+            ) {
+                changes.push(change);
             }
         } else {
             changes.push(SchemaItemChange::Added {
@@ -874,7 +938,7 @@ mod tests {
     use crate::V1_RESOLVED_FILE_FORMAT;
     use crate::{attribute::Attribute, lineage::GroupLineage, registry::Group};
     use std::collections::BTreeMap;
-    use weaver_semconv::{provenance::Provenance, stability::Stability};
+    use weaver_semconv::{deprecated::Deprecated, provenance::Provenance, stability::Stability};
 
     use crate::lineage::AttributeLineage;
 
@@ -1497,6 +1561,7 @@ mod tests {
                         provenance: provenance::Provenance {
                             source: Some(provenance::DependencyRef(0)),
                             path: String::new(),
+                            ..Default::default()
                         },
                     }),
                 ],
@@ -1993,6 +2058,37 @@ mod tests {
         }
     }
 
+    #[test]
+    fn v2_detect_metric_updated() {
+        // Test a user changing the unit of a metric.
+        let metric = |unit: &str| Metric {
+            name: "http".to_owned().into(),
+            instrument: weaver_semconv::group::InstrumentSpec::UpDownCounter,
+            unit: unit.to_owned(),
+            attributes: vec![],
+            entity_associations: vec![],
+            requirement_level: None,
+            common: CommonFields::default(),
+            provenance: Default::default(),
+        };
+        let mut baseline = empty_v2_schema();
+        baseline.registry.metrics.push(metric("s"));
+        let mut latest = empty_v2_schema();
+        latest.registry.metrics.push(metric("ms"));
+        let diff = latest.diff(&baseline);
+        assert_eq!(diff.registry.metric_changes.len(), 1);
+        match &diff.registry.metric_changes[0] {
+            SchemaItemChange::Updated { name, changes } => {
+                assert_eq!(name, "http");
+                assert_eq!(changes.len(), 1);
+                assert_eq!(changes[0].path, "unit");
+                assert_eq!(changes[0].old_value, "s");
+                assert_eq!(changes[0].new_value, "ms");
+            }
+            c => panic!("Unexpected change type: {:?}", c),
+        }
+    }
+
     // create an empty schema for testing.
     fn empty_v2_schema() -> ResolvedTelemetrySchema {
         ResolvedTelemetrySchema {
@@ -4,6 +4,7 @@ use std::fmt::Display;
 
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use weaver_semconv::source_lines::SharedSourceLines;
 
 /// The provenance of a semantic convention attribute or signal.
 #[derive(Serialize, Deserialize, Debug, Clone, JsonSchema, PartialEq, Hash, Eq, Default)]
@@ -23,6 +24,13 @@ pub struct Provenance {
     /// We use this for good error messages within Weaver.
     #[serde(skip)]
     pub path: String,
+
+    /// The lines where the items of the file at `path` are defined.
+    ///
+    /// Like the path, this is only available locally.
+    #[serde(skip)]
+    #[schemars(skip)]
+    pub lines: SharedSourceLines,
 }
 
 impl Provenance {
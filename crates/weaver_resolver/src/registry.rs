@@ -474,13 +474,14 @@ fn resolve_dependency_imports<C: crate::SchemaCacheLookup>(
                 }
             }
         }
-        let path = group
+        let (path, lines) = group
             .provenance()
-            .map(|p| p.path.clone())
+            .map(|p| (p.path.clone(), p.lines.clone()))
             .unwrap_or_default();
         let provenance = Some(Provenance {
             schema_url: prov_url.clone(),
             path,
+            lines,
         });
         ureg.groups.push(UnresolvedGroup {
             group,
@@ -1418,10 +1419,7 @@ mod tests {
             requirement_level: Default::default(),
             name: Default::default(),
             lineage: Some(weaver_resolved_schema::lineage::GroupLineage::new(
-                Provenance {
-                    schema_url: SchemaUrl::new_unknown(),
-                    path: path.to_owned(),
-                },
+                Provenance::new(SchemaUrl::new_unknown(), path),
             )),
             display_name: Default::default(),
             body: Default::default(),
@@ -1935,10 +1933,7 @@ groups:
                 include_groups: Default::default(),
                 visibility: Default::default(),
                 is_v2: false,
-                provenance: Some(Provenance {
-                    schema_url: SchemaUrl::new_unknown(),
-                    path: Default::default(),
-                }),
+                provenance: Some(Provenance::new(SchemaUrl::new_unknown(), "")),
             }],
             imports: vec![],
             dependencies: vec![],
@@ -2036,10 +2031,7 @@ groups:
                     include_groups: Default::default(),
                     visibility: Default::default(),
                     is_v2: false,
-                    provenance: Some(Provenance {
-                        schema_url: SchemaUrl::new_unknown(),
-                        path: Default::default(),
-                    }),
+                    provenance: Some(Provenance::new(SchemaUrl::new_unknown(), "")),
                 },
                 UnresolvedGroup {
                     group: Group {
@@ -2072,10 +2064,7 @@ groups:
                     include_groups: Default::default(),
                     visibility: Default::default(),
                     is_v2: false,
-                    provenance: Some(Provenance {
-                        schema_url: SchemaUrl::new_unknown(),
-                        path: Default::default(),
-                    }),
+                    provenance: Some(Provenance::new(SchemaUrl::new_unknown(), "")),
                 },
                 UnresolvedGroup {
                     group: Group {
@@ -2108,10 +2097,7 @@ groups:
                     include_groups: Default::default(),
                     visibility: Default::default(),
                     is_v2: false,
-                    provenance: Some(Provenance {
-                        schema_url: SchemaUrl::new_unknown(),
-                        path: Default::default(),
-                    }),
+                    provenance: Some(Provenance::new(SchemaUrl::new_unknown(), "")),
                 },
             ],
             imports: vec![],
@@ -25,6 +25,7 @@ pub mod registry_repo;
 pub mod schema_url;
 pub mod semconv;
 pub mod signal_requirement_level;
+pub mod source_lines;
 pub mod stability;
 pub mod stats;
 pub mod v2;
@@ -3,6 +3,7 @@
 //! The provenance of a semantic convention specification file.
 
 use crate::schema_url::SchemaUrl;
+use crate::source_lines::SharedSourceLines;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
@@ -24,6 +25,13 @@ pub struct Provenance {
     /// This is the path is only available *locally*. When publishing resolved schemas,
     /// this field is not included.
     pub path: String,
+
+    /// The lines where the items of the specification file are defined.
+    ///
+    /// Only available for the files loaded by this process, never serialized.
+    #[serde(skip)]
+    #[schemars(skip)]
+    pub lines: SharedSourceLines,
 }
 
 impl Display for Provenance {
@@ -39,6 +47,14 @@ impl Provenance {
         Provenance {
             schema_url,
             path: path.replace('\\', "/"),
+            lines: SharedSourceLines::default(),
         }
     }
+
+    /// Sets the lines where the items of the specification file are defined.
+    #[must_use]
+    pub fn with_lines(mut self, lines: SharedSourceLines) -> Self {
+        self.lines = lines;
+        self
+    }
 }
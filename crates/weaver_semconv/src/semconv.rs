@@ -5,11 +5,11 @@
 use crate::group::{GroupSpec, GroupWildcard};
 use crate::json_schema::JsonSchemaValidator;
 use crate::provenance::Provenance;
+use crate::source_lines::{SharedSourceLines, SourceLines};
 use crate::v2::SemConvSpecV2;
 use crate::Error;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::fs::File;
 use std::path::Path;
 use std::sync::OnceLock;
 use weaver_common::result::WResult;
@@ -366,24 +366,31 @@ impl SemConvSpecWithProvenance {
         P: AsRef<Path>,
         F: Fn(String) -> String,
     {
-        fn read_yaml_file(path: &Path, provenance: &str) -> Result<serde_yaml::Value, Error> {
-            let semconv_file = File::open(path).map_err(|e| Error::RegistryNotFound {
+        fn read_yaml_file(
+            path: &Path,
+            provenance: &str,
+        ) -> Result<(serde_yaml::Value, Option<SourceLines>), Error> {
+            let content = std::fs::read_to_string(path).map_err(|e| Error::RegistryNotFound {
                 path_or_url: provenance.to_owned(),
                 error: e.to_string(),
             })?;
 
-            serde_yaml::from_reader(semconv_file).map_err(|e| Error::DeserializationError {
-                path_or_url: provenance.to_owned(),
-                error: e.to_string(),
-            })
+            let value =
+                serde_yaml::from_str(&content).map_err(|e| Error::DeserializationError {
+                    path_or_url: provenance.to_owned(),
+                    error: e.to_string(),
+                })?;
+            // The lines are located while the content is at hand.
+            Ok((value, SourceLines::from_yaml(&content)))
         }
 
         let path = path.as_ref().display().to_string();
-        let provenance = Provenance::new(schema_url, &path_fixer(path.clone()));
-        let yaml_value = match read_yaml_file(path.as_ref(), &path) {
+        let (yaml_value, lines) = match read_yaml_file(path.as_ref(), &path) {
             Ok(value) => value,
             Err(e) => return WResult::FatalErr(e),
         };
+        let provenance = Provenance::new(schema_url, &path_fixer(path.clone()))
+            .with_lines(SharedSourceLines::new(lines));
         let mut warnings = Vec::new();
 
         let raw_spec = match from_yaml_value(yaml_value, &path, &mut warnings) {
@@ -456,8 +463,26 @@ mod tests {
             SemConvSpecWithProvenance::from_file(crate::schema_url::SchemaUrl::new_unknown(), path)
                 .into_result_failing_non_fatal()
                 .unwrap();
+        assert_eq!(semconv_spec.provenance.lines.line("groups", "db"), Some(2));
         assert_eq!(semconv_spec.spec.into_v1("test").groups.len(), 10);
 
+        // The lines are captured at load, whatever the path recorded in the provenance.
+        let semconv_spec = SemConvSpecWithProvenance::from_file_with_mapped_path(
+            crate::schema_url::SchemaUrl::new_unknown(),
+            "data/database.yaml",
+            |_| "elsewhere/database.yaml".to_owned(),
+        )
+        .into_result_failing_non_fatal()
+        .unwrap();
+        assert_eq!(semconv_spec.provenance.path, "elsewhere/database.yaml");
+        assert_eq!(
+            semconv_spec
+                .provenance
+                .lines
+                .line("attributes", "db.system"),
+            Some(9)
+        );
+
         // Non-existing file
         let path = PathBuf::from("data/non-existing.yaml");
         let semconv_spec =
@@ -0,0 +1,203 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Lines where the items of a semantic convention file are defined.
+//!
+//! The semantic convention files are deserialized without location information,
+//! so the lines are recovered at load time by parsing the content of the file again
+//! with a span-preserving YAML parser. They are then shared by the provenance of the
+//! items of the file, see [`SharedSourceLines`].
+
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+use saphyr::{LoadableYamlNode, MarkedYaml};
+
+/// The line of each item defined in a semantic convention file, by section and
+/// identifier, e.g. (`metrics`, `http.server.request.duration`).
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SourceLines {
+    lines: HashMap<(String, String), usize>,
+}
+
+impl SourceLines {
+    /// Parse the content of a semantic convention file. Returns `None` if the
+    /// content is not a valid YAML document.
+    #[must_use]
+    pub fn from_yaml(content: &str) -> Option<Self> {
+        let docs = MarkedYaml::load_from_str(content).ok()?;
+        let doc = docs.first()?;
+        let mut lines = HashMap::new();
+        for (section, items) in doc.data.as_mapping()? {
+            let (Some(section), Some(items)) = (section.data.as_str(), items.data.as_sequence())
+            else {
+                continue;
+            };
+            for item in items {
+                record(&mut lines, section, item);
+                // The attributes of a v1 group are defined in the group.
+                if section == "groups" {
+                    let attributes = item
+                        .data
+                        .as_mapping_get("attributes")
+                        .and_then(|attributes| attributes.data.as_sequence());
+                    for attribute in attributes.into_iter().flatten() {
+                        if let Some(id) = attribute
+                            .data
+                            .as_mapping_get("id")
+                            .and_then(|id| id.data.as_str())
+                        {
+                            _ = lines
+                                .entry(("attributes".to_owned(), id.to_owned()))
+                                .or_insert(attribute.span.start.line());
+                        }
+                    }
+                }
+            }
+        }
+        Some(Self { lines })
+    }
+
+    /// The line (1-based) where the item `id` of the `section` is defined.
+    #[must_use]
+    pub fn line(&self, section: &str, id: &str) -> Option<usize> {
+        self.lines
+            .get(&(section.to_owned(), id.to_owned()))
+            .copied()
+    }
+}
+
+/// The [`SourceLines`] of a semantic convention file, shared by the provenance of the
+/// items of the file. Empty for the files not loaded by this process, e.g. those of a
+/// published resolved schema.
+///
+/// The lines don't take part in the comparison and the hash of a provenance.
+#[derive(Clone, Default)]
+pub struct SharedSourceLines(Option<Arc<SourceLines>>);
+
+impl SharedSourceLines {
+    /// Shares the lines of a file, `None` if they are unknown.
+    #[must_use]
+    pub fn new(lines: Option<SourceLines>) -> Self {
+        Self(lines.map(Arc::new))
+    }
+
+    /// The line (1-based) where the item `id` of the `section` is defined. The v1
+    /// groups are searched when the file has no such item in the `section`.
+    #[must_use]
+    pub fn line(&self, section: &str, id: &str) -> Option<usize> {
+        let lines = self.0.as_ref()?;
+        lines.line(section, id).or_else(|| lines.line("groups", id))
+    }
+}
+
+impl std::fmt::Debug for SharedSourceLines {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.0 {
+            Some(lines) => write!(f, "SharedSourceLines({} items)", lines.lines.len()),
+            None => write!(f, "SharedSourceLines(None)"),
+        }
+    }
+}
+
+impl PartialEq for SharedSourceLines {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for SharedSourceLines {}
+
+impl PartialOrd for SharedSourceLines {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SharedSourceLines {
+    fn cmp(&self, _other: &Self) -> std::cmp::Ordering {
+        std::cmp::Ordering::Equal
+    }
+}
+
+impl Hash for SharedSourceLines {
+    fn hash<H: Hasher>(&self, _state: &mut H) {}
+}
+
+/// Record the line of an item of a section.
+fn record(lines: &mut HashMap<(String, String), usize>, section: &str, item: &MarkedYaml<'_>) {
+    if let Some(id) = item
+        .data
+        .as_mapping_get(id_field(section))
+        .and_then(|id| id.data.as_str())
+    {
+        _ = lines
+            .entry((section.to_owned(), id.to_owned()))
+            .or_insert(item.span.start.line());
+    }
+}
+
+/// The field identifying the items of a section.
+fn id_field(section: &str) -> &'static str {
+    match section {
+        "attributes" => "key",
+        "metrics" | "events" => "name",
+        "spans" | "entities" => "type",
+        // Groups, attribute groups and refinements.
+        _ => "id",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_source_lines() {
+        let lines = SourceLines::from_yaml(
+            r#"file_format: definition/2
+attributes:
+  - key: my.attr
+    type: string
+  - key: other.attr
+    type: int
+metrics:
+  - name: my.attr
+    instrument: counter
+metric_refinements:
+  - id: my.refinement
+    ref: my.attr
+"#,
+        )
+        .expect("valid YAML");
+        assert_eq!(lines.line("attributes", "my.attr"), Some(3));
+        assert_eq!(lines.line("attributes", "other.attr"), Some(5));
+        assert_eq!(lines.line("metrics", "my.attr"), Some(8));
+        assert_eq!(lines.line("metric_refinements", "my.refinement"), Some(11));
+        assert_eq!(lines.line("spans", "my.attr"), None);
+
+        let lines = SourceLines::from_yaml(
+            r#"groups:
+  - id: registry.my
+    type: attribute_group
+    attributes:
+      - id: my.v1.attr
+        type: string
+"#,
+        )
+        .expect("valid YAML");
+        assert_eq!(lines.line("groups", "registry.my"), Some(2));
+        assert_eq!(lines.line("attributes", "my.v1.attr"), Some(5));
+        assert!(SourceLines::from_yaml("key: [").is_none());
+
+        let shared = SharedSourceLines::new(Some(lines));
+        assert_eq!(shared.line("attributes", "my.v1.attr"), Some(5));
+        // v1 groups are found whatever the section, e.g. a metric group.
+        assert_eq!(shared.line("metrics", "registry.my"), Some(2));
+        assert_eq!(
+            SharedSourceLines::default().line("groups", "registry.my"),
+            None
+        );
+        assert_eq!(shared, SharedSourceLines::default());
+    }
+}
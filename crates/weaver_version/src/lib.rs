@@ -13,6 +13,7 @@ use crate::logs_changes::LogsChanges;
 use crate::metrics_changes::MetricsChanges;
 use crate::resource_changes::ResourceChanges;
 use crate::spans_changes::SpansChanges;
+use crate::translator::Step;
 use logs_changes::LogsChange;
 use metrics_changes::MetricsChange;
 use resource_changes::ResourceChange;
@@ -25,6 +26,7 @@ pub mod metrics_changes;
 pub mod resource_changes;
 pub mod schema_changes;
 pub mod spans_changes;
+pub mod translator;
 pub mod v2;
 
 /// An error that can occur while loading or resolving version changes.
@@ -52,12 +54,49 @@ pub enum Error {
         /// The error that occurred.
         error: String,
     },
+
+    /// A version is not a valid semantic version.
+    #[error("Invalid version {version:?}: {error}")]
+    InvalidVersion {
+        /// The invalid version.
+        version: String,
+        /// The error that occurred.
+        error: String,
+    },
+}
+
+/// Parses a semantic version. A version missing its minor or patch number,
+/// e.g. `1.2`, is completed with zeros.
+pub(crate) fn parse_version(version: &str) -> Result<semver::Version, Error> {
+    let completed = match version.split('.').count() {
+        1 => format!("{version}.0.0"),
+        2 => format!("{version}.0"),
+        _ => version.to_owned(),
+    };
+    semver::Version::parse(&completed).map_err(|e| Error::InvalidVersion {
+        version: version.to_owned(),
+        error: e.to_string(),
+    })
 }
 
 /// A version of the schema.
 #[derive(PartialOrd, PartialEq)]
 pub struct Version(semver::Version);
 
+impl Version {
+    /// Parses a version. A version missing its minor or patch number, e.g. `1.2`, is
+    /// completed with zeros.
+    pub fn parse(version: &str) -> Result<Self, Error> {
+        parse_version(version).map(Version)
+    }
+}
+
+impl std::fmt::Display for Version {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
 /// List of versions with their changes.
 #[derive(Serialize, Deserialize, Debug, Default, Clone, JsonSchema)]
 #[serde(transparent)]
@@ -66,19 +105,24 @@ pub struct Versions {
 }
 
 /// An history of changes to apply to the schema for different versions.
-#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[derive(Serialize, Deserialize, Debug, Default, Clone, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct VersionSpec {
     /// The changes to apply to the following telemetry data: resource attributes,
     /// span attributes, span event attributes, log attributes, metric attributes.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub all: Option<AllChanges>,
     /// The changes to apply to the metrics specification for a specific version.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub metrics: Option<MetricsChanges>,
     /// The changes to apply to the logs specification for a specific version.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub logs: Option<LogsChanges>,
     /// The changes to apply to the spans specification for a specific version.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub spans: Option<SpansChanges>,
     /// The changes to apply to the resource specification for a specific version.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub resources: Option<ResourceChanges>,
 }
 
@@ -130,6 +174,16 @@ impl Versions {
         Ok(top_level.versions)
     }
 
+    /// Inserts the changes of a version, replacing and returning the changes
+    /// previously defined for this version, if any.
+    pub fn insert(
+        &mut self,
+        version: &str,
+        spec: VersionSpec,
+    ) -> Result<Option<VersionSpec>, Error> {
+        Ok(self.versions.insert(parse_version(version)?, spec))
+    }
+
     /// Returns the most recent version or None if there are no versions.
     #[must_use]
     pub fn latest_version(&self) -> Option<Version> {
@@ -185,94 +239,25 @@ impl Versions {
     /// - Renaming of metrics
     #[must_use]
     pub fn version_changes_for(&self, version: &Version) -> VersionChanges {
-        let mut resource_old_to_new_attributes: HashMap<String, String> = HashMap::new();
-        let mut metric_old_to_new_names: HashMap<String, String> = HashMap::new();
-        let mut metric_old_to_new_attributes: HashMap<String, String> = HashMap::new();
-        let mut log_old_to_new_attributes: HashMap<String, String> = HashMap::new();
-        let mut span_old_to_new_attributes: HashMap<String, String> = HashMap::new();
-
+        let mut changes = VersionChanges::default();
+        // The most recent renames take precedence.
         for (_, spec) in self.versions_desc_from(version) {
-            // Builds a map of old to new attribute names for the attributes that have been renamed
-            // in the different versions of the resources.
-            if let Some(resources) = spec.resources.as_ref() {
-                resources
-                    .changes
-                    .iter()
-                    .flat_map(|change| change.rename_attributes.attribute_map.iter())
-                    .for_each(|(old_name, new_name)| {
-                        if !resource_old_to_new_attributes.contains_key(old_name) {
-                            _ = resource_old_to_new_attributes
-                                .insert(old_name.clone(), new_name.clone());
-                        }
-                    });
-            }
-
-            // Builds a map of old to new metric names that have been renamed
-            // in the different versions.
-            if let Some(metrics) = spec.metrics.as_ref() {
-                metrics
-                    .changes
-                    .iter()
-                    .flat_map(|change| change.rename_metrics.iter())
-                    .for_each(|(old_name, new_name)| {
-                        if !metric_old_to_new_names.contains_key(old_name) {
-                            _ = metric_old_to_new_names.insert(old_name.clone(), new_name.clone());
-                        }
-                    });
-            }
-
-            // Builds a map of old to new attribute names for the attributes that have been renamed
-            // in the different versions of the metrics.
-            if let Some(metrics) = spec.metrics.as_ref() {
-                metrics
-                    .changes
-                    .iter()
-                    .flat_map(|change| change.rename_attributes.attribute_map.iter())
-                    .for_each(|(old_name, new_name)| {
-                        if !metric_old_to_new_attributes.contains_key(old_name) {
-                            _ = metric_old_to_new_attributes
-                                .insert(old_name.clone(), new_name.clone());
-                        }
-                    });
-            }
-
-            // Builds a map of old to new attribute names for the attributes that have been renamed
-            // in the different versions of the logs.
-            if let Some(logs) = spec.logs.as_ref() {
-                logs.changes
-                    .iter()
-                    .flat_map(|change| change.rename_attributes.attribute_map.iter())
-                    .for_each(|(old_name, new_name)| {
-                        if !log_old_to_new_attributes.contains_key(old_name) {
-                            _ = log_old_to_new_attributes
-                                .insert(old_name.clone(), new_name.clone());
-                        }
-                    });
-            }
-
-            // Builds a map of old to new attribute names for the attributes that have been renamed
-            // in the different versions of the spans.
-            if let Some(spans) = spec.spans.as_ref() {
-                spans
-                    .changes
-                    .iter()
-                    .flat_map(|change| change.rename_attributes.attribute_map.iter())
-                    .for_each(|(old_name, new_name)| {
-                        if !span_old_to_new_attributes.contains_key(old_name) {
-                            _ = span_old_to_new_attributes
-                                .insert(old_name.clone(), new_name.clone());
-                        }
-                    });
+            let step = Step::new(spec, false);
+            merge(
+                &mut changes.resource_old_to_new_attributes,
+                step.resource_attributes,
+            );
+            merge(&mut changes.metric_old_to_new_names, step.metric_names);
+            for (renames, _) in step.metric_attributes {
+                merge(&mut changes.metric_old_to_new_attributes, renames);
             }
+            merge(&mut changes.log_old_to_new_attributes, step.log_attributes);
+            merge(
+                &mut changes.span_old_to_new_attributes,
+                step.span_attributes,
+            );
         }
-
-        VersionChanges {
-            resource_old_to_new_attributes,
-            metric_old_to_new_attributes,
-            metric_old_to_new_names,
-            log_old_to_new_attributes,
-            span_old_to_new_attributes,
-        }
+        changes
     }
 
     /// Update the current `Versions` to include the transformations of the parent `Versions`.
@@ -490,6 +475,13 @@ impl VersionSpec {
     }
 }
 
+/// Adds the renames to the map, the renames already in the map take precedence.
+fn merge(map: &mut HashMap<String, String>, renames: HashMap<String, String>) {
+    for (old_name, new_name) in renames {
+        _ = map.entry(old_name).or_insert(new_name);
+    }
+}
+
 /// Wrapper around `VersionChanges` to get the new name of an attribute of resources.
 pub struct ResourcesVersionAttributeChanges<'a> {
     version_changes: &'a VersionChanges,
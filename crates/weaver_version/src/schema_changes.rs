@@ -4,6 +4,7 @@
 
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::collections::HashMap;
 
 /// The type of schema item.
@@ -68,8 +69,12 @@ pub enum SchemaItemChange {
         note: String,
     },
     /// One or more fields in a top-level telemetry object have been updated in the head registry.
-    /// Note: This is a placeholder for future use.
-    Updated {},
+    Updated {
+        /// The name of the updated telemetry object.
+        name: String,
+        /// The fields that changed.
+        changes: Vec<FieldChange>,
+    },
     /// A top-level telemetry object that is now discontinued without a valid replacement in the
     /// head registry.
     Obsoleted {
@@ -94,6 +99,106 @@ pub enum SchemaItemChange {
     },
 }
 
+/// A change of a field of a telemetry object present in both the baseline and the
+/// head registries.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct FieldChange {
+    /// The path of the field in the telemetry object, e.g. `unit`,
+    /// `type.members[get].value` or `attributes[http.request.method].requirement_level`.
+    pub path: String,
+    /// The value of the field in the baseline registry, `null` if the field was added.
+    pub old_value: Value,
+    /// The value of the field in the head registry, `null` if the field was removed.
+    pub new_value: Value,
+}
+
+/// Compare the fields of a telemetry object in the baseline and the head registries.
+///
+/// Objects are compared field by field, and arrays of objects having an `id` are
+/// compared item by item, by id. Any other value is compared as a whole.
+#[must_use]
+pub fn diff_fields(baseline: &Value, head: &Value) -> Vec<FieldChange> {
+    let mut changes = Vec::new();
+    diff_values("", baseline, head, &mut changes);
+    changes
+}
+
+fn diff_values(path: &str, baseline: &Value, head: &Value, changes: &mut Vec<FieldChange>) {
+    if baseline == head {
+        return;
+    }
+    match (baseline, head) {
+        (Value::Object(baseline), Value::Object(head)) => {
+            let keys: Vec<&String> = baseline
+                .keys()
+                .chain(head.keys().filter(|key| !baseline.contains_key(*key)))
+                .collect();
+            for key in keys {
+                let path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{path}.{key}")
+                };
+                diff_values(
+                    &path,
+                    baseline.get(key).unwrap_or(&Value::Null),
+                    head.get(key).unwrap_or(&Value::Null),
+                    changes,
+                );
+            }
+        }
+        (Value::Array(baseline), Value::Array(head)) => {
+            match (items_by_id(baseline), items_by_id(head)) {
+                (Some(baseline), Some(head)) => {
+                    let ids: Vec<&str> = baseline
+                        .iter()
+                        .map(|(id, _)| *id)
+                        .chain(
+                            head.iter()
+                                .map(|(id, _)| *id)
+                                .filter(|id| !baseline.iter().any(|(b, _)| b == id)),
+                        )
+                        .collect();
+                    let find = |items: &[(&str, &Value)], id: &str| {
+                        items
+                            .iter()
+                            .find(|(item_id, _)| *item_id == id)
+                            .map_or(Value::Null, |(_, item)| (*item).clone())
+                    };
+                    for id in ids {
+                        diff_values(
+                            &format!("{path}[{id}]"),
+                            &find(&baseline, id),
+                            &find(&head, id),
+                            changes,
+                        );
+                    }
+                }
+                _ => changes.push(FieldChange {
+                    path: path.to_owned(),
+                    old_value: Value::Array(baseline.clone()),
+                    new_value: Value::Array(head.clone()),
+                }),
+            }
+        }
+        _ => changes.push(FieldChange {
+            path: path.to_owned(),
+            old_value: baseline.clone(),
+            new_value: head.clone(),
+        }),
+    }
+}
+
+/// The items of an array by id, `None` if an item is not an object with a
+/// string `id`.
+fn items_by_id(items: &[Value]) -> Option<Vec<(&str, &Value)>> {
+    items
+        .iter()
+        .map(|item| item.get("id").and_then(Value::as_str).map(|id| (id, item)))
+        .collect()
+}
+
 impl SchemaChanges {
     /// Create a new instance of `SchemaChanges`.
     #[must_use]
@@ -196,6 +301,19 @@ impl SchemaChanges {
             .unwrap_or(0)
     }
 
+    /// Counts the number of updated registry attributes in the schema.
+    #[must_use]
+    pub fn count_updated_registry_attributes(&self) -> usize {
+        self.changes
+            .get(&SchemaItemType::RegistryAttributes)
+            .map(|v| {
+                v.iter()
+                    .filter(|c| matches!(c, SchemaItemChange::Updated { .. }))
+                    .count()
+            })
+            .unwrap_or(0)
+    }
+
     /// Counts the number of removed registry attributes in the schema.
     #[must_use]
     pub fn count_removed_registry_attributes(&self) -> usize {
@@ -313,6 +431,13 @@ impl SchemaChanges {
                         .filter(|c| matches!(c, SchemaItemChange::Renamed { .. }))
                         .count()
                 ));
+                result.push_str(&format!(
+                    "  Updated: {}\n",
+                    changes
+                        .iter()
+                        .filter(|c| matches!(c, SchemaItemChange::Updated { .. }))
+                        .count()
+                ));
                 result.push_str(&format!(
                     "  Deprecated: {}\n",
                     changes
@@ -363,3 +488,60 @@ impl SchemaChanges {
         result
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{diff_fields, FieldChange};
+
+    #[test]
+    fn test_diff_fields() {
+        let baseline = json!({
+            "brief": "A metric.",
+            "unit": "By",
+            "type": {"members": [{"id": "get", "value": "GET"}, {"id": "put", "value": "PUT"}]},
+            "attributes": [{"id": "http.method", "requirement_level": "required"}],
+        });
+        let head = json!({
+            "brief": "A metric.",
+            "unit": "s",
+            "type": {"members": [{"id": "get", "value": "get"}]},
+            "attributes": [
+                {"id": "http.method", "requirement_level": "recommended"},
+                {"id": "http.route", "requirement_level": "required"},
+            ],
+        });
+        assert!(diff_fields(&baseline, &baseline).is_empty());
+        assert_eq!(
+            diff_fields(&baseline, &head),
+            vec![
+                FieldChange {
+                    path: "attributes[http.method].requirement_level".to_owned(),
+                    old_value: json!("required"),
+                    new_value: json!("recommended"),
+                },
+                FieldChange {
+                    path: "attributes[http.route]".to_owned(),
+                    old_value: json!(null),
+                    new_value: json!({"id": "http.route", "requirement_level": "required"}),
+                },
+                FieldChange {
+                    path: "type.members[get].value".to_owned(),
+                    old_value: json!("GET"),
+                    new_value: json!("get"),
+                },
+                FieldChange {
+                    path: "type.members[put]".to_owned(),
+                    old_value: json!({"id": "put", "value": "PUT"}),
+                    new_value: json!(null),
+                },
+                FieldChange {
+                    path: "unit".to_owned(),
+                    old_value: json!("By"),
+                    new_value: json!("s"),
+                },
+            ]
+        );
+    }
+}
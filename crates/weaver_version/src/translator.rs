@@ -0,0 +1,369 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Translation of telemetry names from one version of a telemetry schema to another.
+//!
+//! The changes of a version describe how to go from the previous version to this one.
+//! A forward translation applies the changes of the versions after the source version,
+//! up to the target version, in ascending order. A backward translation applies the
+//! inverse of the changes of the versions after the target version, up to the source
+//! version, in descending order.
+
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Bound::{Excluded, Included};
+
+use crate::{parse_version, Error, VersionSpec, Versions};
+
+/// Translates the names of attributes and metrics between two versions of a
+/// telemetry schema.
+#[derive(Debug, Default)]
+pub struct Translator {
+    steps: Vec<Step>,
+    backward: bool,
+}
+
+/// The renames defined by one version, in the direction of the translation.
+#[derive(Debug, Default)]
+pub(crate) struct Step {
+    /// Renames of the attributes of every telemetry type (`all` section).
+    pub(crate) all_attributes: HashMap<String, String>,
+    pub(crate) resource_attributes: HashMap<String, String>,
+    pub(crate) span_attributes: HashMap<String, String>,
+    pub(crate) log_attributes: HashMap<String, String>,
+    /// Renames of the metric attributes with the metrics they apply to,
+    /// every metric when empty.
+    pub(crate) metric_attributes: Vec<(HashMap<String, String>, Vec<String>)>,
+    pub(crate) metric_names: HashMap<String, String>,
+}
+
+impl Step {
+    /// The renames of a version, inverted for a backward translation.
+    pub(crate) fn new(spec: &VersionSpec, backward: bool) -> Self {
+        let mut step = Step::default();
+        for change in spec.all.iter().flat_map(|all| &all.changes) {
+            extend(
+                &mut step.all_attributes,
+                &change.rename_attributes.attribute_map,
+                backward,
+            );
+        }
+        for change in spec.resources.iter().flat_map(|r| &r.changes) {
+            extend(
+                &mut step.resource_attributes,
+                &change.rename_attributes.attribute_map,
+                backward,
+            );
+        }
+        for change in spec.spans.iter().flat_map(|s| &s.changes) {
+            extend(
+                &mut step.span_attributes,
+                &change.rename_attributes.attribute_map,
+                backward,
+            );
+        }
+        for change in spec.logs.iter().flat_map(|l| &l.changes) {
+            extend(
+                &mut step.log_attributes,
+                &change.rename_attributes.attribute_map,
+                backward,
+            );
+        }
+        for change in spec.metrics.iter().flat_map(|m| &m.changes) {
+            if !change.rename_attributes.is_empty() {
+                let mut renames = HashMap::new();
+                extend(
+                    &mut renames,
+                    &change.rename_attributes.attribute_map,
+                    backward,
+                );
+                step.metric_attributes
+                    .push((renames, change.rename_attributes.apply_to_metrics.clone()));
+            }
+            extend(&mut step.metric_names, &change.rename_metrics, backward);
+        }
+        step
+    }
+}
+
+impl Versions {
+    /// Returns a translator of the telemetry names from the version `from` to the
+    /// version `to`, forward or backward. Versions missing their minor or patch
+    /// number, e.g. `1.2`, are completed with zeros.
+    pub fn translator(&self, from: &str, to: &str) -> Result<Translator, Error> {
+        let from = parse_version(from)?;
+        let to = parse_version(to)?;
+        let backward = to < from;
+        // The changes of the lowest version lead to it, so they are not part of the translation.
+        let specs: Vec<_> = if backward {
+            self.versions
+                .range((Excluded(&to), Included(&from)))
+                .rev()
+                .collect()
+        } else {
+            self.versions
+                .range((Excluded(&from), Included(&to)))
+                .collect()
+        };
+        let steps = specs
+            .into_iter()
+            .map(|(_, spec)| Step::new(spec, backward))
+            .collect();
+        Ok(Translator { steps, backward })
+    }
+}
+
+/// Adds the renames to the map, inverted for a backward translation. When several
+/// names are renamed to the same name, a backward translation restores the first one.
+/// The renames already in the map take precedence.
+pub(crate) fn extend(
+    map: &mut HashMap<String, String>,
+    renames: &BTreeMap<String, String>,
+    backward: bool,
+) {
+    for (old_name, new_name) in renames {
+        let (from, to) = if backward {
+            (new_name, old_name)
+        } else {
+            (old_name, new_name)
+        };
+        _ = map.entry(from.clone()).or_insert_with(|| to.clone());
+    }
+}
+
+impl Translator {
+    /// Returns true if the translation doesn't rename anything, e.g. when the
+    /// source and the target versions are the same.
+    #[must_use]
+    pub fn is_identity(&self) -> bool {
+        self.steps.iter().all(|step| {
+            step.all_attributes.is_empty()
+                && step.resource_attributes.is_empty()
+                && step.span_attributes.is_empty()
+                && step.log_attributes.is_empty()
+                && step.metric_attributes.is_empty()
+                && step.metric_names.is_empty()
+        })
+    }
+
+    /// Returns the translated name of a resource attribute.
+    #[must_use]
+    pub fn resource_attribute_name(&self, name: &str) -> String {
+        self.attribute_name(name, |step| Some(&step.resource_attributes))
+    }
+
+    /// Returns the translated name of a span attribute.
+    #[must_use]
+    pub fn span_attribute_name(&self, name: &str) -> String {
+        self.attribute_name(name, |step| Some(&step.span_attributes))
+    }
+
+    /// Returns the translated name of an attribute of a span event or a span link.
+    /// Only the `all` section of the changes applies to them.
+    #[must_use]
+    pub fn event_attribute_name(&self, name: &str) -> String {
+        self.attribute_name(name, |_| None)
+    }
+
+    /// Returns the translated name of a log attribute.
+    #[must_use]
+    pub fn log_attribute_name(&self, name: &str) -> String {
+        self.attribute_name(name, |step| Some(&step.log_attributes))
+    }
+
+    /// Returns the translated name of a metric.
+    #[must_use]
+    pub fn metric_name(&self, name: &str) -> String {
+        let mut name = name.to_owned();
+        for step in &self.steps {
+            if let Some(renamed) = step.metric_names.get(&name) {
+                name.clone_from(renamed);
+            }
+        }
+        name
+    }
+
+    /// Returns the translated name of an attribute of the given metric, named as in
+    /// the source version. The `apply_to_metrics` of a change refer to the metric
+    /// names of the version preceding the change.
+    #[must_use]
+    pub fn metric_attribute_name(&self, metric: &str, name: &str) -> String {
+        let mut metric = metric.to_owned();
+        let mut name = name.to_owned();
+        for step in &self.steps {
+            if self.backward {
+                if let Some(renamed) = step.metric_names.get(&metric) {
+                    metric.clone_from(renamed);
+                }
+            }
+            let renamed = step
+                .metric_attributes
+                .iter()
+                .filter(|(_, apply_to)| apply_to.is_empty() || apply_to.contains(&metric))
+                .find_map(|(renames, _)| renames.get(&name))
+                .or_else(|| step.all_attributes.get(&name));
+            if let Some(renamed) = renamed {
+                name.clone_from(renamed);
+            }
+            if !self.backward {
+                if let Some(renamed) = step.metric_names.get(&metric) {
+                    metric.clone_from(renamed);
+                }
+            }
+        }
+        name
+    }
+
+    /// Applies, step by step, the renames of a telemetry type then, if the name
+    /// is not renamed by them, the renames of the `all` section.
+    fn attribute_name(
+        &self,
+        name: &str,
+        renames: impl Fn(&Step) -> Option<&HashMap<String, String>>,
+    ) -> String {
+        let mut name = name.to_owned();
+        for step in &self.steps {
+            if let Some(renamed) = renames(step)
+                .and_then(|renames| renames.get(&name))
+                .or_else(|| step.all_attributes.get(&name))
+            {
+                name.clone_from(renamed);
+            }
+        }
+        name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Versions;
+
+    const VERSIONS: &str = r#"
+1.0.0: {}
+1.1.0:
+  all:
+    changes:
+      - rename_attributes:
+          attribute_map:
+            net.peer.name: server.address
+  spans:
+    changes:
+      - rename_attributes:
+          attribute_map:
+            http.method: http.request.method
+  metrics:
+    changes:
+      - rename_attributes:
+          attribute_map:
+            state: cpu.state
+          apply_to_metrics:
+            - system.cpu.time
+      - rename_metrics:
+          system.cpu.time: system.cpu.duration
+1.2.0:
+  spans:
+    changes:
+      - rename_attributes:
+          attribute_map:
+            http.request.method: http.method.name
+  resources:
+    changes:
+      - rename_attributes:
+          attribute_map:
+            browser.user_agent: user_agent.original
+"#;
+
+    #[test]
+    fn test_forward_translation() {
+        let versions: Versions = serde_yaml::from_str(VERSIONS).unwrap();
+        let translator = versions.translator("1.0.0", "1.2.0").unwrap();
+
+        // Renames are chained across versions.
+        assert_eq!(
+            translator.span_attribute_name("http.method"),
+            "http.method.name"
+        );
+        assert_eq!(
+            translator.span_attribute_name("net.peer.name"),
+            "server.address"
+        );
+        assert_eq!(
+            translator.event_attribute_name("net.peer.name"),
+            "server.address"
+        );
+        assert_eq!(
+            translator.event_attribute_name("http.method"),
+            "http.method"
+        );
+        assert_eq!(
+            translator.resource_attribute_name("browser.user_agent"),
+            "user_agent.original"
+        );
+        assert_eq!(translator.log_attribute_name("unknown"), "unknown");
+        assert_eq!(
+            translator.metric_name("system.cpu.time"),
+            "system.cpu.duration"
+        );
+        assert_eq!(
+            translator.metric_attribute_name("system.cpu.time", "state"),
+            "cpu.state"
+        );
+        assert_eq!(
+            translator.metric_attribute_name("system.memory.usage", "state"),
+            "state"
+        );
+
+        // The changes of the source version are already applied.
+        let translator = versions.translator("1.1", "1.2").unwrap();
+        assert_eq!(translator.span_attribute_name("http.method"), "http.method");
+        assert_eq!(
+            translator.span_attribute_name("http.request.method"),
+            "http.method.name"
+        );
+    }
+
+    #[test]
+    fn test_backward_translation() {
+        let versions: Versions = serde_yaml::from_str(VERSIONS).unwrap();
+        let translator = versions.translator("1.2.0", "1.0.0").unwrap();
+
+        assert_eq!(
+            translator.span_attribute_name("http.method.name"),
+            "http.method"
+        );
+        assert_eq!(
+            translator.span_attribute_name("server.address"),
+            "net.peer.name"
+        );
+        assert_eq!(
+            translator.resource_attribute_name("user_agent.original"),
+            "browser.user_agent"
+        );
+        assert_eq!(
+            translator.metric_name("system.cpu.duration"),
+            "system.cpu.time"
+        );
+        assert_eq!(
+            translator.metric_attribute_name("system.cpu.duration", "cpu.state"),
+            "state"
+        );
+
+        let translator = versions.translator("1.2.0", "1.1.0").unwrap();
+        assert_eq!(
+            translator.span_attribute_name("http.method.name"),
+            "http.request.method"
+        );
+        assert_eq!(
+            translator.span_attribute_name("server.address"),
+            "server.address"
+        );
+    }
+
+    #[test]
+    fn test_identity_translation() {
+        let versions: Versions = serde_yaml::from_str(VERSIONS).unwrap();
+        assert!(versions.translator("1.1.0", "1.1.0").unwrap().is_identity());
+        assert!(!versions.translator("1.1.0", "1.2.0").unwrap().is_identity());
+        // Versions beyond the known ones have no changes.
+        assert!(versions.translator("1.2.0", "1.5.0").unwrap().is_identity());
+        assert!(versions.translator("1.x", "1.2.0").is_err());
+    }
+}
@@ -0,0 +1,506 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Compatibility of the changes between two versions of a registry.
+//!
+//! Each change is classified from the stability of the telemetry object it
+//! applies to in the baseline registry:
+//! - `breaking`: a stable object is removed, or one of its fields changes in a way
+//!   that breaks its consumers (e.g. the type of an attribute, a removed enum
+//!   member, the instrument or the unit of a metric). Requires a major version.
+//! - `additive`: an object is added, deprecated or stabilized, or a stable object
+//!   gains an enum member or an attribute. Requires a minor version.
+//! - `non_breaking`: any other change, including every change to an object that
+//!   is not stable. Requires a patch version.
+
+use std::fmt::{Display, Formatter};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use weaver_semconv::stability::Stability;
+
+use crate::v2::{FieldChange, SchemaChanges, SchemaItemChange};
+use crate::{parse_version, Error};
+
+/// The compatibility of a change, from the least to the most disruptive.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Compatibility {
+    /// The change doesn't affect the consumers of the registry.
+    NonBreaking,
+    /// The change adds to the registry without breaking its consumers.
+    Additive,
+    /// The change breaks the consumers of the registry.
+    Breaking,
+}
+
+impl Compatibility {
+    /// The smallest version bump allowing the change.
+    #[must_use]
+    pub fn required_bump(self) -> VersionBump {
+        match self {
+            Compatibility::NonBreaking => VersionBump::Patch,
+            Compatibility::Additive => VersionBump::Minor,
+            Compatibility::Breaking => VersionBump::Major,
+        }
+    }
+}
+
+impl Display for Compatibility {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Compatibility::NonBreaking => write!(f, "non-breaking"),
+            Compatibility::Additive => write!(f, "additive"),
+            Compatibility::Breaking => write!(f, "breaking"),
+        }
+    }
+}
+
+/// A semantic version bump, from the smallest to the largest.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum VersionBump {
+    /// The version is unchanged or lower.
+    None,
+    /// The patch version is incremented.
+    Patch,
+    /// The minor version is incremented.
+    Minor,
+    /// The major version is incremented.
+    Major,
+}
+
+impl VersionBump {
+    /// The bump from the baseline version to the head version. A version missing
+    /// its minor or patch number, e.g. `1.2`, is completed with zeros.
+    ///
+    /// Before 1.0.0, the versions follow the Cargo convention: incrementing the
+    /// minor version allows breaking changes and incrementing the patch version
+    /// allows additive changes.
+    pub fn between(baseline: &str, head: &str) -> Result<VersionBump, Error> {
+        let baseline = parse_version(baseline)?;
+        let head = parse_version(head)?;
+
+        let bump = if head <= baseline {
+            VersionBump::None
+        } else if head.major > baseline.major {
+            VersionBump::Major
+        } else if head.minor > baseline.minor {
+            VersionBump::Minor
+        } else {
+            VersionBump::Patch
+        };
+        Ok(match (baseline.major, bump) {
+            (0, VersionBump::Minor) => VersionBump::Major,
+            (0, VersionBump::Patch) => VersionBump::Minor,
+            _ => bump,
+        })
+    }
+}
+
+impl Display for VersionBump {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VersionBump::None => write!(f, "none"),
+            VersionBump::Patch => write!(f, "patch"),
+            VersionBump::Minor => write!(f, "minor"),
+            VersionBump::Major => write!(f, "major"),
+        }
+    }
+}
+
+/// The types of telemetry objects of a registry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RegistryItemType {
+    /// An attribute.
+    Attribute,
+    /// A public attribute group.
+    AttributeGroup,
+    /// An entity.
+    Entity,
+    /// An event.
+    Event,
+    /// A metric.
+    Metric,
+    /// A span.
+    Span,
+}
+
+impl Display for RegistryItemType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RegistryItemType::Attribute => write!(f, "attribute"),
+            RegistryItemType::AttributeGroup => write!(f, "attribute group"),
+            RegistryItemType::Entity => write!(f, "entity"),
+            RegistryItemType::Event => write!(f, "event"),
+            RegistryItemType::Metric => write!(f, "metric"),
+            RegistryItemType::Span => write!(f, "span"),
+        }
+    }
+}
+
+/// The compatibility of a change of the registry.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ClassifiedChange {
+    /// The type of the changed telemetry object.
+    pub item_type: RegistryItemType,
+    /// The name of the changed telemetry object in the baseline registry.
+    pub name: String,
+    /// The compatibility of the change.
+    pub compatibility: Compatibility,
+    /// Why the change has this compatibility.
+    pub reason: String,
+}
+
+impl Display for ClassifiedChange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "[{}] {} `{}`: {}",
+            self.compatibility, self.item_type, self.name, self.reason
+        )
+    }
+}
+
+/// The compatibility of all the changes between two versions of a registry.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct CompatibilityReport {
+    /// The classified changes.
+    pub changes: Vec<ClassifiedChange>,
+}
+
+impl CompatibilityReport {
+    /// The most disruptive compatibility of the changes, `None` without changes.
+    #[must_use]
+    pub fn compatibility(&self) -> Option<Compatibility> {
+        self.changes.iter().map(|c| c.compatibility).max()
+    }
+
+    /// The smallest version bump allowing all the changes.
+    #[must_use]
+    pub fn required_bump(&self) -> VersionBump {
+        self.compatibility()
+            .map_or(VersionBump::None, Compatibility::required_bump)
+    }
+
+    /// The changes having the given compatibility.
+    pub fn changes_with(
+        &self,
+        compatibility: Compatibility,
+    ) -> impl Iterator<Item = &ClassifiedChange> {
+        self.changes
+            .iter()
+            .filter(move |c| c.compatibility == compatibility)
+    }
+}
+
+impl SchemaChanges {
+    /// Classify the changes by compatibility. `baseline_stability` returns the
+    /// stability of a telemetry object of the baseline registry; objects without
+    /// a known stability are considered not stable.
+    #[must_use]
+    pub fn classify(
+        &self,
+        baseline_stability: impl Fn(RegistryItemType, &str) -> Option<Stability>,
+    ) -> CompatibilityReport {
+        let registry = &self.registry;
+        let changes = [
+            (RegistryItemType::Attribute, &registry.attribute_changes),
+            (
+                RegistryItemType::AttributeGroup,
+                &registry.attribute_group_changes,
+            ),
+            (RegistryItemType::Entity, &registry.entity_changes),
+            (RegistryItemType::Event, &registry.event_changes),
+            (RegistryItemType::Metric, &registry.metric_changes),
+            (RegistryItemType::Span, &registry.span_changes),
+        ];
+        let mut report = CompatibilityReport::default();
+        for (item_type, item_changes) in changes {
+            for change in item_changes {
+                report
+                    .changes
+                    .push(classify_change(item_type, change, &baseline_stability));
+            }
+        }
+        report
+    }
+}
+
+fn classify_change(
+    item_type: RegistryItemType,
+    change: &SchemaItemChange,
+    baseline_stability: &impl Fn(RegistryItemType, &str) -> Option<Stability>,
+) -> ClassifiedChange {
+    let classified = |name: &str, compatibility, reason: String| ClassifiedChange {
+        item_type,
+        name: name.to_owned(),
+        compatibility,
+        reason,
+    };
+    let is_stable = |name: &str| baseline_stability(item_type, name) == Some(Stability::Stable);
+
+    match change {
+        SchemaItemChange::Added { name } => {
+            classified(name, Compatibility::Additive, "added".to_owned())
+        }
+        SchemaItemChange::Renamed {
+            old_name, new_name, ..
+        } => classified(
+            old_name,
+            Compatibility::Additive,
+            format!("deprecated in favor of `{new_name}`"),
+        ),
+        SchemaItemChange::Obsoleted { name, .. } | SchemaItemChange::Uncategorized { name, .. } => {
+            classified(name, Compatibility::Additive, "deprecated".to_owned())
+        }
+        SchemaItemChange::Removed { name } if is_stable(name) => classified(
+            name,
+            Compatibility::Breaking,
+            "removed while stable".to_owned(),
+        ),
+        SchemaItemChange::Removed { name } => classified(
+            name,
+            Compatibility::NonBreaking,
+            "removed while not stable".to_owned(),
+        ),
+        SchemaItemChange::Updated { name, changes } => {
+            let stable = is_stable(name);
+            let (compatibility, reason) = changes
+                .iter()
+                .map(|change| classify_field_change(item_type, stable, change))
+                // The first of the most disruptive field changes.
+                .rev()
+                .max_by_key(|(compatibility, _)| *compatibility)
+                .unwrap_or((Compatibility::NonBreaking, "updated".to_owned()));
+            classified(name, compatibility, reason)
+        }
+    }
+}
+
+/// Classify a field change of a telemetry object.
+fn classify_field_change(
+    item_type: RegistryItemType,
+    stable: bool,
+    change: &FieldChange,
+) -> (Compatibility, String) {
+    let FieldChange {
+        path,
+        old_value,
+        new_value,
+    } = change;
+    let stability = |value: &Value| serde_json::from_value::<Stability>(value.clone()).ok();
+
+    if path == "stability" {
+        let old = stability(old_value);
+        let new = stability(new_value);
+        return if old == Some(Stability::Stable) {
+            (
+                Compatibility::Breaking,
+                format!("stability lowered from stable to {new_value}"),
+            )
+        } else if new == Some(Stability::Stable) {
+            (Compatibility::Additive, "stabilized".to_owned())
+        } else {
+            (
+                Compatibility::NonBreaking,
+                format!("stability changed from {old_value} to {new_value}"),
+            )
+        };
+    }
+    if !stable {
+        return (
+            Compatibility::NonBreaking,
+            format!("`{path}` changed while not stable"),
+        );
+    }
+
+    let breaking = |reason: String| (Compatibility::Breaking, reason);
+    let (field, item) = split_item_path(path);
+    match (item_type, field, item) {
+        (RegistryItemType::Attribute, "type", None) => {
+            breaking(format!("type changed from {old_value} to {new_value}"))
+        }
+        (RegistryItemType::Attribute, "type.members", Some((member, rest))) => {
+            if new_value.is_null() {
+                // The removal of a member that is not stable doesn't break a stable enum.
+                let member_stability = old_value.get("stability").and_then(stability);
+                if member_stability.is_some_and(|s| s != Stability::Stable) {
+                    (
+                        Compatibility::NonBreaking,
+                        format!("enum member `{member}` removed while not stable"),
+                    )
+                } else {
+                    breaking(format!("enum member `{member}` removed"))
+                }
+            } else if old_value.is_null() {
+                (
+                    Compatibility::Additive,
+                    format!("enum member `{member}` added"),
+                )
+            } else if rest == "value" {
+                breaking(format!(
+                    "value of enum member `{member}` changed from {old_value} to {new_value}"
+                ))
+            } else {
+                (
+                    Compatibility::NonBreaking,
+                    format!("`{path}` of enum member `{member}` changed"),
+                )
+            }
+        }
+        (RegistryItemType::Metric, "instrument" | "unit", None)
+        | (RegistryItemType::Span, "kind", None) => {
+            breaking(format!("{path} changed from {old_value} to {new_value}"))
+        }
+        (RegistryItemType::Entity, "identity", Some((attribute, ""))) => {
+            breaking(format!("identity attribute `{attribute}` added or removed"))
+        }
+        (_, "attributes" | "description", Some((attribute, ""))) if new_value.is_null() => {
+            breaking(format!("attribute `{attribute}` removed"))
+        }
+        (_, "attributes" | "description", Some((attribute, ""))) if old_value.is_null() => (
+            Compatibility::Additive,
+            format!("attribute `{attribute}` added"),
+        ),
+        _ => (Compatibility::NonBreaking, format!("`{path}` changed")),
+    }
+}
+
+/// Split a field path of the form `field[id].rest` into `field` and the
+/// optional `(id, rest)`.
+fn split_item_path(path: &str) -> (&str, Option<(&str, &str)>) {
+    let Some((field, item)) = path.split_once('[') else {
+        return (path, None);
+    };
+    let Some((id, rest)) = item.split_once(']') else {
+        return (path, None);
+    };
+    (field, Some((id, rest.trim_start_matches('.'))))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn updated(name: &str, path: &str, old_value: Value, new_value: Value) -> SchemaItemChange {
+        SchemaItemChange::Updated {
+            name: name.to_owned(),
+            changes: vec![FieldChange {
+                path: path.to_owned(),
+                old_value,
+                new_value,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_classify() {
+        let mut changes = SchemaChanges::default();
+        changes.registry.attribute_changes = vec![
+            SchemaItemChange::Added {
+                name: "new".to_owned(),
+            },
+            SchemaItemChange::Removed {
+                name: "stable.removed".to_owned(),
+            },
+            SchemaItemChange::Removed {
+                name: "dev.removed".to_owned(),
+            },
+            updated("stable.retyped", "type", json!("string"), json!("int")),
+            updated("dev.retyped", "type", json!("string"), json!("int")),
+            updated(
+                "stable.enum",
+                "type.members[get]",
+                json!({"id": "get", "value": "GET"}),
+                Value::Null,
+            ),
+            updated(
+                "stable.enum.dev_member",
+                "type.members[get]",
+                json!({"id": "get", "value": "GET", "stability": "development"}),
+                Value::Null,
+            ),
+            updated(
+                "stable.enum.added",
+                "type.members[put]",
+                Value::Null,
+                json!({"id": "put", "value": "PUT"}),
+            ),
+            updated("stable.brief", "brief", json!("old"), json!("new")),
+            updated(
+                "dev.stabilized",
+                "stability",
+                json!("development"),
+                json!("stable"),
+            ),
+        ];
+        changes.registry.metric_changes = vec![
+            updated("stable.metric", "unit", json!("s"), json!("ms")),
+            SchemaItemChange::Obsoleted {
+                name: "stable.metric.obsoleted".to_owned(),
+                note: String::new(),
+            },
+        ];
+
+        let report = changes.classify(|_, name| {
+            Some(if name.starts_with("stable.") {
+                Stability::Stable
+            } else {
+                Stability::Development
+            })
+        });
+        let compatibilities: Vec<_> = report
+            .changes
+            .iter()
+            .map(|c| (c.name.as_str(), c.compatibility))
+            .collect();
+        assert_eq!(
+            compatibilities,
+            vec![
+                ("new", Compatibility::Additive),
+                ("stable.removed", Compatibility::Breaking),
+                ("dev.removed", Compatibility::NonBreaking),
+                ("stable.retyped", Compatibility::Breaking),
+                ("dev.retyped", Compatibility::NonBreaking),
+                ("stable.enum", Compatibility::Breaking),
+                ("stable.enum.dev_member", Compatibility::NonBreaking),
+                ("stable.enum.added", Compatibility::Additive),
+                ("stable.brief", Compatibility::NonBreaking),
+                ("dev.stabilized", Compatibility::Additive),
+                ("stable.metric", Compatibility::Breaking),
+                ("stable.metric.obsoleted", Compatibility::Additive),
+            ]
+        );
+        assert_eq!(report.compatibility(), Some(Compatibility::Breaking));
+        assert_eq!(report.required_bump(), VersionBump::Major);
+        assert_eq!(report.changes_with(Compatibility::Breaking).count(), 4);
+        assert_eq!(
+            CompatibilityReport::default().required_bump(),
+            VersionBump::None
+        );
+    }
+
+    #[test]
+    fn test_version_bump_between() {
+        let bump = |baseline, head| VersionBump::between(baseline, head).unwrap();
+        assert_eq!(bump("1.2.3", "2.0.0"), VersionBump::Major);
+        assert_eq!(bump("1.2.3", "1.3.0"), VersionBump::Minor);
+        assert_eq!(bump("1.2.3", "1.2.4"), VersionBump::Patch);
+        assert_eq!(bump("1.2.3", "1.2.3"), VersionBump::None);
+        assert_eq!(bump("1.2.3", "1.2.0"), VersionBump::None);
+        assert_eq!(bump("0.2.3", "0.3.0"), VersionBump::Major);
+        assert_eq!(bump("0.2.3", "0.2.4"), VersionBump::Minor);
+        assert_eq!(bump("1.0", "1.1"), VersionBump::Minor);
+        assert!(VersionBump::between("1.2.3", "latest").is_err());
+    }
+}
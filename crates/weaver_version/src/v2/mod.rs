@@ -1,11 +1,19 @@
 //! V2 diffs.
 
+use std::collections::BTreeMap;
+
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use weaver_semconv::schema_url::SchemaUrl;
 
+pub mod compatibility;
+
 // V2 Leverages the same nomenclature for diff as V1.
-pub use crate::schema_changes::SchemaItemChange;
+pub use crate::schema_changes::{diff_fields, FieldChange, SchemaItemChange};
+
+use crate::all_changes::{self, AllChange, AllChanges};
+use crate::metrics_changes::{MetricsChange, MetricsChanges};
+use crate::VersionSpec;
 
 /// A summary of schema changes between two versions of a schema.
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
@@ -68,6 +76,42 @@ impl SchemaChanges {
     pub fn is_empty(&self) -> bool {
         self.registry.is_empty()
     }
+
+    /// The changes of the head version in an OpenTelemetry telemetry schema
+    /// ([OTEP 0152](https://github.com/open-telemetry/oteps/blob/main/text/0152-telemetry-schemas.md)):
+    /// the attributes and the metrics renamed by a deprecation. The other changes
+    /// can't be expressed in a telemetry schema.
+    #[must_use]
+    pub fn version_spec(&self) -> VersionSpec {
+        let renames = |changes: &[SchemaItemChange]| -> BTreeMap<String, String> {
+            changes
+                .iter()
+                .filter_map(|change| match change {
+                    SchemaItemChange::Renamed {
+                        old_name, new_name, ..
+                    } => Some((old_name.clone(), new_name.clone())),
+                    _ => None,
+                })
+                .collect()
+        };
+        let attribute_map = renames(&self.registry.attribute_changes);
+        let rename_metrics = renames(&self.registry.metric_changes);
+
+        VersionSpec {
+            all: (!attribute_map.is_empty()).then(|| AllChanges {
+                changes: vec![AllChange {
+                    rename_attributes: all_changes::RenameAttributes { attribute_map },
+                }],
+            }),
+            metrics: (!rename_metrics.is_empty()).then(|| MetricsChanges {
+                changes: vec![MetricsChange {
+                    rename_metrics,
+                    ..Default::default()
+                }],
+            }),
+            ..Default::default()
+        }
+    }
 }
 
 #[cfg(test)]
@@ -82,4 +126,42 @@ mod tests {
         assert!(changes.registry.is_empty());
         assert!(changes.is_empty());
     }
+
+    #[test]
+    fn test_version_spec() {
+        assert!(
+            serde_yaml::to_string(&SchemaChanges::default().version_spec())
+                .unwrap()
+                .starts_with("{}")
+        );
+
+        let mut changes = SchemaChanges::default();
+        changes.registry.attribute_changes = vec![
+            SchemaItemChange::Renamed {
+                old_name: "db.name".to_owned(),
+                new_name: "db.namespace".to_owned(),
+                note: String::new(),
+            },
+            SchemaItemChange::Added {
+                name: "db.namespace".to_owned(),
+            },
+        ];
+        changes.registry.metric_changes = vec![SchemaItemChange::Renamed {
+            old_name: "db.client.connections.usage".to_owned(),
+            new_name: "db.client.connection.count".to_owned(),
+            note: String::new(),
+        }];
+        changes.registry.span_changes = vec![SchemaItemChange::Renamed {
+            old_name: "span.old".to_owned(),
+            new_name: "span.new".to_owned(),
+            note: String::new(),
+        }];
+
+        let spec = changes.version_spec();
+        assert_eq!(
+            serde_yaml::to_string(&spec).unwrap(),
+            "all:\n  changes:\n  - rename_attributes:\n      attribute_map:\n        db.name: db.namespace\n\
+             metrics:\n  changes:\n  - rename_metrics:\n      db.client.connections.usage: db.client.connection.count\n"
+        );
+    }
 }
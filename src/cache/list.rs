@@ -0,0 +1,71 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Lists the cached git repositories, archives and files.
+
+use crate::cache::{format_duration, format_size};
+use crate::{DiagnosticArgs, ExitDirectives};
+use clap::Args;
+use std::collections::HashSet;
+use weaver_common::cache::Cache;
+use weaver_common::diagnostic::DiagnosticMessages;
+
+/// Parameters for the `cache list` sub-command
+#[derive(Debug, Args)]
+pub struct CacheListArgs {
+    /// Parameters to specify the diagnostic format.
+    #[command(flatten)]
+    pub diagnostic: DiagnosticArgs,
+}
+
+/// Lists the cache entries with their status, age and size.
+pub(crate) fn command(
+    _args: &CacheListArgs,
+    cache: &Cache,
+) -> Result<ExitDirectives, DiagnosticMessages> {
+    let entries = cache.entries()?;
+    println!("Cache directory: {}", cache.root().display());
+    if entries.is_empty() {
+        println!("No cached entries");
+        return Ok(ExitDirectives {
+            exit_code: 0,
+            warnings: None,
+        });
+    }
+
+    let width = entries
+        .iter()
+        .map(|entry| entry.source.len())
+        .max()
+        .unwrap_or_default();
+    println!(
+        "{:<width$}  {:<7}  {:>5}  {:>10}",
+        "SOURCE", "STATUS", "AGE", "SIZE"
+    );
+    // The content shared by several entries is only counted once in the total.
+    let mut digests = HashSet::new();
+    let mut total = 0;
+    for entry in &entries {
+        let status = if entry.immutable {
+            "pinned"
+        } else if cache.is_expired(entry) {
+            "expired"
+        } else {
+            "fresh"
+        };
+        let size = cache.content_size(entry);
+        if digests.insert(&entry.digest) {
+            total += size;
+        }
+        println!(
+            "{:<width$}  {status:<7}  {:>5}  {:>10}",
+            entry.source,
+            format_duration(entry.age()),
+            format_size(size)
+        );
+    }
+    println!("{} entries, {} in total", entries.len(), format_size(total));
+    Ok(ExitDirectives {
+        exit_code: 0,
+        warnings: None,
+    })
+}
@@ -0,0 +1,283 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Command to manage the persistent cache of the remote registries and dependencies
+
+mod list;
+mod prune;
+
+use crate::CmdResult;
+use clap::{Args, Subcommand};
+use std::path::PathBuf;
+use std::time::Duration;
+use weaver_common::cache::{Cache, DEFAULT_TTL};
+use weaver_common::diagnostic::DiagnosticMessages;
+
+/// Parameters for the `cache` command
+#[derive(Debug, Args)]
+pub struct CacheCommand {
+    /// Define the sub-commands for the `cache` command
+    #[clap(subcommand)]
+    pub command: CacheSubCommand,
+}
+
+/// Sub-commands to manage the cache of the remote registries and dependencies.
+#[derive(Debug, Subcommand)]
+#[clap(verbatim_doc_comment)]
+pub enum CacheSubCommand {
+    /// Lists the cached git repositories, archives and files with their age and size.
+    List(list::CacheListArgs),
+    /// Removes cached entries, the expired ones by default.
+    Prune(prune::CachePruneArgs),
+}
+
+/// Global options of the persistent cache of the remote registries and dependencies
+/// (git repositories, archives and files).
+#[derive(Debug, Args)]
+pub struct CacheArgs {
+    /// Keep the remote registries and dependencies in a persistent cache and reuse them
+    /// until they expire (see `--cache-ttl`), instead of fetching them into temporary
+    /// directories deleted after use.
+    #[arg(long, global = true)]
+    pub cache: bool,
+
+    /// Directory of the persistent cache of the remote registries and dependencies.
+    /// Defaults to `~/.weaver/cache`. Implies `--cache`.
+    #[arg(long, global = true)]
+    pub cache_dir: Option<PathBuf>,
+
+    /// Time-to-live of the cached remote registries and dependencies, e.g. `30m`, `12h`
+    /// or `7d`. Git repositories pinned to a commit SHA never expire.
+    #[arg(long, global = true, default_value = "24h", value_parser = parse_duration)]
+    pub cache_ttl: Duration,
+
+    /// Only use the cached remote registries and dependencies, whatever their age,
+    /// without accessing the network. Implies `--cache`.
+    #[arg(long, global = true)]
+    pub offline: bool,
+}
+
+impl Default for CacheArgs {
+    fn default() -> Self {
+        Self {
+            cache: false,
+            cache_dir: None,
+            cache_ttl: DEFAULT_TTL,
+            offline: false,
+        }
+    }
+}
+
+impl CacheArgs {
+    /// Returns true if the remote registries and dependencies are fetched through the
+    /// persistent cache.
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.cache || self.cache_dir.is_some() || self.offline
+    }
+
+    /// Builds the persistent cache, fails if the cache directory can't be determined.
+    pub(crate) fn cache(&self) -> Result<Cache, weaver_common::Error> {
+        let root = match &self.cache_dir {
+            Some(dir) => dir.clone(),
+            None => Cache::default_dir()?,
+        };
+        Ok(Cache::new(root)
+            .with_ttl(self.cache_ttl)
+            .with_offline(self.offline))
+    }
+}
+
+/// Manage the cache of the remote registries and dependencies.
+pub fn cache(command: &CacheCommand, args: &CacheArgs) -> CmdResult {
+    let cache = args.cache().map_err(DiagnosticMessages::from);
+    match &command.command {
+        CacheSubCommand::List(args) => CmdResult::new(
+            cache.and_then(|cache| list::command(args, &cache)),
+            args.diagnostic.to_effective(None),
+        ),
+        CacheSubCommand::Prune(args) => CmdResult::new(
+            cache.and_then(|cache| prune::command(args, &cache)),
+            args.diagnostic.to_effective(None),
+        ),
+    }
+}
+
+/// Parses a duration made of a number and a unit: `s`, `m`, `h` or `d`, e.g. `12h`.
+pub(crate) fn parse_duration(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    let (number, seconds) = match value.char_indices().last() {
+        Some((i, 's')) => (&value[..i], 1),
+        Some((i, 'm')) => (&value[..i], 60),
+        Some((i, 'h')) => (&value[..i], 60 * 60),
+        Some((i, 'd')) => (&value[..i], 24 * 60 * 60),
+        _ => {
+            return Err(format!(
+                "invalid duration `{value}`, expected a number followed by s, m, h or d"
+            ))
+        }
+    };
+    number
+        .parse::<u64>()
+        .map(|number| Duration::from_secs(number * seconds))
+        .map_err(|e| format!("invalid duration `{value}`: {e}"))
+}
+
+/// Formats a duration with its largest unit, e.g. `3d`.
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    match seconds {
+        s if s >= 24 * 60 * 60 => format!("{}d", s / (24 * 60 * 60)),
+        s if s >= 60 * 60 => format!("{}h", s / (60 * 60)),
+        s if s >= 60 => format!("{}m", s / 60),
+        s => format!("{s}s"),
+    }
+}
+
+/// Formats a size in bytes with a binary unit, e.g. `1.5 MiB`.
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    #[allow(clippy::cast_precision_loss)]
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::time::Duration;
+
+    use clap::Parser;
+    use weaver_common::cache::Cache;
+
+    use crate::cache::{cache, format_duration, format_size, parse_duration};
+    use crate::cli::{Cli, Commands};
+
+    /// Runs a `cache` command line, returns true if it succeeds.
+    fn run(args: &[&str]) -> bool {
+        let cli = Cli::parse_from(args);
+        let Some(Commands::Cache(command)) = &cli.command else {
+            panic!("Not a cache command");
+        };
+        cache(command, &cli.cache)
+            .command_result
+            .is_ok_and(|exit_directives| exit_directives.exit_code == 0)
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("90s"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("30m"), Ok(Duration::from_secs(30 * 60)));
+        assert_eq!(parse_duration("12h"), Ok(Duration::from_secs(12 * 60 * 60)));
+        assert_eq!(
+            parse_duration("7d"),
+            Ok(Duration::from_secs(7 * 24 * 60 * 60))
+        );
+        assert!(parse_duration("7").is_err());
+        assert!(parse_duration("d").is_err());
+        assert!(parse_duration("1.5h").is_err());
+
+        assert_eq!(format_duration(Duration::from_secs(59)), "59s");
+        assert_eq!(
+            format_duration(Duration::from_secs(3 * 24 * 60 * 60 + 5)),
+            "3d"
+        );
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536 * 1024), "1.5 MiB");
+    }
+
+    #[test]
+    fn test_cache_cli_options() {
+        let cli = Cli::parse_from([
+            "weaver",
+            "cache",
+            "list",
+            "--cache-dir",
+            "/tmp/weaver-cache",
+            "--cache-ttl",
+            "2h",
+            "--offline",
+        ]);
+        assert!(matches!(cli.command, Some(Commands::Cache(_))));
+        assert!(cli.cache.is_enabled());
+        let cache = cli.cache.cache().expect("The cache directory is set");
+        assert_eq!(cache.root(), "/tmp/weaver-cache");
+        assert_eq!(cache.ttl(), Duration::from_secs(2 * 60 * 60));
+        assert!(cache.is_offline());
+
+        // The cache is opt-in.
+        let cli = Cli::parse_from(["weaver", "cache", "list"]);
+        assert!(!cli.cache.is_enabled());
+        let cli = Cli::parse_from(["weaver", "--cache", "cache", "list"]);
+        assert!(cli.cache.is_enabled());
+        assert!(Cli::parse_from(["weaver", "cache", "list", "--offline"])
+            .cache
+            .is_enabled());
+    }
+
+    #[test]
+    fn test_cache_list_and_prune() {
+        let cache_dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let cache = Cache::new(cache_dir.path());
+        for source in ["https://example.com/a.zip", "https://example.com/b.zip"] {
+            _ = cache
+                .get_or_fetch(source, false, |target| {
+                    fs::write(target.join("file.txt"), source).expect("Failed to write");
+                    Ok(source.trim_start_matches("https://example.com/").to_owned())
+                })
+                .expect("Fetch failed");
+        }
+        let cache_dir_arg = cache_dir.path().to_str().expect("Invalid temp dir");
+
+        assert!(run(&[
+            "weaver",
+            "--cache-dir",
+            cache_dir_arg,
+            "cache",
+            "list"
+        ]));
+
+        // Nothing is expired with the default time-to-live.
+        assert!(run(&[
+            "weaver",
+            "--cache-dir",
+            cache_dir_arg,
+            "cache",
+            "prune"
+        ]));
+        assert_eq!(cache.entries().expect("Failed to list").len(), 2);
+        assert!(run(&[
+            "weaver",
+            "--cache-dir",
+            cache_dir_arg,
+            "cache",
+            "prune",
+            "--older-than",
+            "1d"
+        ]));
+        assert_eq!(cache.entries().expect("Failed to list").len(), 2);
+
+        assert!(run(&[
+            "weaver",
+            "--cache-dir",
+            cache_dir_arg,
+            "--cache-ttl",
+            "0s",
+            "cache",
+            "prune",
+        ]));
+        assert!(cache.entries().expect("Failed to list").is_empty());
+        assert_eq!(
+            fs::read_dir(cache_dir.path().join("content"))
+                .expect("Failed to read content")
+                .count(),
+            0
+        );
+    }
+}
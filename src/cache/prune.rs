@@ -0,0 +1,49 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Removes entries from the cache.
+
+use crate::cache::{format_size, parse_duration};
+use crate::{DiagnosticArgs, ExitDirectives};
+use clap::Args;
+use std::time::Duration;
+use weaver_common::cache::Cache;
+use weaver_common::diagnostic::DiagnosticMessages;
+use weaver_common::log_success;
+
+/// Parameters for the `cache prune` sub-command
+#[derive(Debug, Args)]
+pub struct CachePruneArgs {
+    /// Remove the entries fetched longer ago than this duration, e.g. `7d`, including
+    /// the git repositories pinned to a commit SHA.
+    #[arg(long, value_parser = parse_duration, conflicts_with = "all")]
+    pub older_than: Option<Duration>,
+
+    /// Remove every entry.
+    #[arg(long)]
+    pub all: bool,
+
+    /// Parameters to specify the diagnostic format.
+    #[command(flatten)]
+    pub diagnostic: DiagnosticArgs,
+}
+
+/// Removes the selected entries and the content no longer used.
+pub(crate) fn command(
+    args: &CachePruneArgs,
+    cache: &Cache,
+) -> Result<ExitDirectives, DiagnosticMessages> {
+    let report = cache.prune(|entry| match args.older_than {
+        _ if args.all => true,
+        Some(older_than) => entry.age() >= older_than,
+        None => cache.is_expired(entry),
+    })?;
+    log_success(format!(
+        "{} cache entries removed, {} freed",
+        report.entries,
+        format_size(report.bytes)
+    ));
+    Ok(ExitDirectives {
+        exit_code: 0,
+        warnings: None,
+    })
+}
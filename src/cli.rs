@@ -2,6 +2,7 @@
 
 //! Manage command line arguments
 
+use crate::cache::{CacheArgs, CacheCommand};
 use crate::diagnostic::DiagnosticCommand;
 use crate::registry::RegistryCommand;
 use crate::serve::ServeCommand;
@@ -46,6 +47,10 @@ pub struct Cli {
     #[arg(long, global = true)]
     pub config: Option<PathBuf>,
 
+    /// Options of the persistent cache of the remote registries and dependencies.
+    #[command(flatten)]
+    pub cache: CacheArgs,
+
     /// List of supported commands
     #[command(subcommand)]
     pub command: Option<Commands>,
@@ -59,6 +64,8 @@ pub enum Commands {
     Registry(RegistryCommand),
     /// Manage Diagnostic Messages
     Diagnostic(DiagnosticCommand),
+    /// Manage the Cache of Remote Registries and Dependencies
+    Cache(CacheCommand),
     /// Generate shell completions
     Completion(CompletionCommand),
     /// Start the API server (Experimental)
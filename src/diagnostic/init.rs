@@ -95,6 +95,7 @@ mod tests {
             quiet: false,
             future: false,
             allow_git_credentials: false,
+            cache: Default::default(),
             config: None,
             command: Some(Commands::Diagnostic(DiagnosticCommand {
                 command: DiagnosticSubCommand::Init(DiagnosticInitArgs {
@@ -122,6 +123,7 @@ mod tests {
             quiet: false,
             future: false,
             allow_git_credentials: false,
+            cache: Default::default(),
             config: None,
             command: Some(Commands::Diagnostic(DiagnosticCommand {
                 command: DiagnosticSubCommand::Init(DiagnosticInitArgs {
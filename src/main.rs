@@ -19,6 +19,7 @@ use weaver_forge::{OutputProcessor, OutputTarget};
 use crate::cli::{Cli, Commands};
 use crate::diagnostic::DEFAULT_DIAGNOSTIC_TEMPLATES;
 
+mod cache;
 mod cli;
 mod diagnostic;
 mod registry;
@@ -162,6 +163,17 @@ fn run_command(cli: &Cli) -> ExitDirectives {
     if cli.allow_git_credentials {
         weaver_common::vdir::enable_git_credentials();
     }
+    if cli.cache.is_enabled() {
+        match cli.cache.cache() {
+            Ok(cache) => weaver_common::cache::init_global_cache(cache),
+            Err(e) => {
+                return process_diagnostics(CmdResult::new(
+                    Err(e.into()),
+                    DiagnosticArgs::default().to_effective(None),
+                ));
+            }
+        }
+    }
     // Load `.weaver.toml` (global `--config` overrides cwd discovery) and
     // build the HTTP auth resolver once for the whole invocation.
     let weaver_config = match resolve_weaver_config(cli.config.as_deref()) {
@@ -178,6 +190,7 @@ fn run_command(cli: &Cli) -> ExitDirectives {
     let cmd_result = match &cli.command {
         Some(Commands::Registry(params)) => semconv_registry(params, cfg, &auth),
         Some(Commands::Diagnostic(params)) => diagnostic::diagnostic(params),
+        Some(Commands::Cache(params)) => cache::cache(params, &cli.cache),
         Some(Commands::Serve(params)) => serve::command(params, cfg, &auth),
         Some(Commands::Completion(completions)) => {
             if let Err(e) = generate_completion(&completions.shell, &completions.completion_file) {
@@ -211,7 +224,7 @@ fn run_command(cli: &Cli) -> ExitDirectives {
     process_diagnostics(cmd_result)
 }
 
-fn print_diagnostics(
+pub(crate) fn print_diagnostics(
     diagnostics: &weaver_config::EffectiveDiagnosticConfig,
     diagnostic_messages: &DiagnosticMessages,
 ) -> Result<(), weaver_forge::error::Error> {
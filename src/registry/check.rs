@@ -108,6 +108,7 @@ mod tests {
             quiet: false,
             future: false,
             allow_git_credentials: false,
+            cache: Default::default(),
             config: None,
             command: Some(Commands::Registry(RegistryCommand {
                 command: RegistrySubCommand::Check(RegistryCheckArgs {
@@ -137,6 +138,7 @@ mod tests {
             quiet: false,
             future: false,
             allow_git_credentials: false,
+            cache: Default::default(),
             config: None,
             command: Some(Commands::Registry(RegistryCommand {
                 command: RegistrySubCommand::Check(RegistryCheckArgs {
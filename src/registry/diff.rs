@@ -2,8 +2,8 @@
 
 //! Generate a diff between two versions of a semantic convention registry.
 
-use crate::registry::{load_config, RegistryArgs};
-use crate::weaver::WeaverEngine;
+use crate::registry::{load_config, Error, RegistryArgs};
+use crate::weaver::{Resolved, WeaverEngine};
 use crate::{DiagnosticArgs, ExitDirectives};
 use clap::Args;
 use include_dir::{include_dir, Dir};
@@ -11,11 +11,13 @@ use log::info;
 use std::path::PathBuf;
 use weaver_common::diagnostic::DiagnosticMessages;
 use weaver_common::http_auth::HttpAuthResolver;
+use weaver_common::log_success;
 use weaver_common::vdir::VirtualDirectoryPath;
 use weaver_config::{WeaverCommand, WeaverConfig};
 use weaver_forge::{OutputProcessor, OutputTarget};
 use weaver_macros::weaver_command;
 use weaver_semconv::registry_repo::RegistryRepo;
+use weaver_version::v2::compatibility::VersionBump;
 
 /// Embedded default schema changes templates
 pub(crate) static DEFAULT_DIFF_TEMPLATES: Dir<'_> = include_dir!("defaults/diff_templates");
@@ -50,6 +52,15 @@ pub struct RegistryDiffArgs {
     #[config]
     output: Option<PathBuf>,
 
+    /// Fail when the version bump from the baseline registry to the registry is smaller
+    /// than its changes require: a major version for breaking changes, a minor version for
+    /// additive changes and a patch version otherwise. The versions are those of the
+    /// registry manifests. A bare `--check-version-bump` means `true`; when passing an
+    /// explicit value, the `=` form is required (e.g. `--check-version-bump=false`).
+    #[arg(long, num_args = 0..=1, default_missing_value = "true", require_equals = true)]
+    #[config(default = "false")]
+    check_version_bump: Option<bool>,
+
     /// Parameters to specify the diagnostic format.
     #[command(flatten)]
     #[shared(diagnostic)]
@@ -107,12 +118,61 @@ pub(crate) fn command(
     }
     .map_err(DiagnosticMessages::from)?;
 
+    if cmd_config.config.check_version_bump {
+        check_version_bump(main_resolved, baseline_resolved)?;
+    }
+
     Ok(ExitDirectives {
         exit_code: 0,
         warnings: None,
     })
 }
 
+/// Checks that the version bump from the baseline registry to the head registry is at
+/// least the one required by the compatibility of their changes.
+fn check_version_bump(head: Resolved, baseline: Resolved) -> Result<(), DiagnosticMessages> {
+    let head = head.into_v2()?;
+    let baseline = baseline.into_v2()?;
+    let head_schema = head.resolved_schema();
+    let baseline_schema = baseline.resolved_schema();
+
+    let report = head_schema
+        .diff(baseline_schema)
+        .classify(|item_type, name| baseline_schema.stability(item_type, name));
+    let required = report.required_bump();
+    let baseline_version = baseline_schema.schema_url.version();
+    let head_version = head_schema.schema_url.version();
+    let actual = VersionBump::between(baseline_version, head_version).map_err(|e| {
+        Error::VersionBumpCheck {
+            error: e.to_string(),
+        }
+    })?;
+
+    match report.compatibility() {
+        Some(compatibility) if actual < required => {
+            let changes = report
+                .changes_with(compatibility)
+                .map(|change| format!("  - {change}"))
+                .collect::<Vec<_>>()
+                .join("\n");
+            Err(Error::InsufficientVersionBump {
+                baseline: baseline_version.to_owned(),
+                head: head_version.to_owned(),
+                actual: actual.to_string(),
+                required: required.to_string(),
+                changes,
+            }
+            .into())
+        }
+        _ => {
+            log_success(format!(
+                "The `{actual}` version bump from `{baseline_version}` to `{head_version}` allows the changes (`{required}` required)"
+            ));
+            Ok(())
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::cli::{Cli, Commands};
@@ -138,6 +198,7 @@ mod tests {
             quiet: false,
             future: false,
             allow_git_credentials: false,
+            cache: Default::default(),
             config: None,
             command: Some(Commands::Registry(RegistryCommand {
                 command: RegistrySubCommand::Diff(RegistryDiffArgs {
@@ -153,6 +214,7 @@ mod tests {
                     format: Some("json".to_owned()),
                     templates: None,
                     output: None,
+                    check_version_bump: None,
                     diagnostic: Default::default(),
                 }),
             })),
@@ -184,6 +246,7 @@ mod tests {
                 format: Some("json".to_owned()),
                 templates: None,
                 output: Some(temp_dir.path().to_path_buf()),
+                check_version_bump: None,
                 diagnostic: Default::default(),
             }),
         };
@@ -224,4 +287,67 @@ mod tests {
         assert_eq!(schema_changes.count_event_changes(), 5);
         assert_eq!(schema_changes.count_resource_changes(), 5);
     }
+
+    #[test]
+    fn test_registry_diff_check_version_bump() {
+        let registry_cmd = |head: &str| RegistryCommand {
+            command: RegistrySubCommand::Diff(RegistryDiffArgs {
+                registry: RegistryArgs {
+                    registry: Some(VirtualDirectoryPath::LocalFolder {
+                        path: head.to_owned(),
+                    }),
+                    ..Default::default()
+                },
+                baseline_registry: VirtualDirectoryPath::LocalFolder {
+                    path: "tests/diff/registry_baseline/".to_owned(),
+                },
+                format: Some("json".to_owned()),
+                templates: None,
+                output: None,
+                check_version_bump: Some(true),
+                diagnostic: Default::default(),
+            }),
+        };
+        // The head registry deprecates and adds attributes in a minor version.
+        let cmd_result = semconv_registry(
+            &registry_cmd("tests/diff/registry_head/"),
+            None,
+            &HttpAuthResolver::empty(),
+        );
+        assert_eq!(
+            cmd_result
+                .command_result
+                .expect("A minor version allows additive changes")
+                .exit_code,
+            0
+        );
+
+        // The same changes in a patch version.
+        let head = tempfile::Builder::new()
+            .prefix("head")
+            .tempdir()
+            .expect("Failed to create temp dir");
+        for file in ["registry.yaml", "registry_manifest.yaml"] {
+            let content = std::fs::read_to_string(format!("tests/diff/registry_head/{file}"))
+                .expect("Failed to read the head registry");
+            std::fs::write(
+                head.path().join(file),
+                content.replace("semconv_version: 1.1", "semconv_version: 1.0.1"),
+            )
+            .expect("Failed to write the head registry");
+        }
+        let cmd_result = semconv_registry(
+            &registry_cmd(&head.path().to_string_lossy()),
+            None,
+            &HttpAuthResolver::empty(),
+        );
+        let error = format!(
+            "{:?}",
+            cmd_result
+                .command_result
+                .expect_err("A patch version doesn't allow additive changes")
+        );
+        assert!(error.contains("InsufficientVersionBump"), "{error}");
+        assert!(error.contains("attr4"), "{error}");
+    }
 }
@@ -128,6 +128,7 @@ mod tests {
             quiet: false,
             future: false,
             allow_git_credentials: false,
+            cache: Default::default(),
             config: None,
             command: Some(Commands::Registry(RegistryCommand {
                 command: RegistrySubCommand::Emit(RegistryEmitArgs {
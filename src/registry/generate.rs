@@ -2,17 +2,25 @@
 
 //! Generate artifacts for a semantic convention registry.
 
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 
-use clap::Args;
+use clap::{Args, ValueEnum};
 use log::info;
+use miette::Diagnostic;
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
 use serde_yaml::Value;
 
-use weaver_common::diagnostic::DiagnosticMessages;
-use weaver_common::log_success;
+use weaver_common::diagnostic::{DiagnosticMessage, DiagnosticMessages};
+use weaver_common::{log_error, log_info, log_success, log_warn};
 use weaver_forge::config::{Params, WeaverConfig};
-use weaver_forge::file_loader::{FileLoader, FileSystemFileLoader};
-use weaver_forge::{OutputProcessor, OutputTarget};
+use weaver_forge::file_loader::LayeredFileLoader;
+use weaver_forge::manifest::{GenerationManifest, MANIFEST_FILE_NAME};
+use weaver_forge::package::{LockMode, RegistrySchema, TemplatePackages};
+use weaver_forge::plan::GenerationPlan;
+use weaver_forge::{IncrementalState, OutputProcessor, OutputTarget};
 
 use crate::registry::{load_config, Error, PolicyArgs, RegistryArgs};
 use crate::weaver::WeaverEngine;
@@ -21,6 +29,7 @@ use weaver_common::http_auth::HttpAuthResolver;
 use weaver_common::vdir::VirtualDirectory;
 use weaver_common::vdir::VirtualDirectoryPath;
 use weaver_config::{WeaverCommand, WeaverConfig as ProjectWeaverConfig};
+use weaver_diff::{diff_dir_with_files, FileDiff};
 use weaver_macros::weaver_command;
 
 /// Generate artifacts from a semantic convention registry using Jinja templates.
@@ -56,6 +65,14 @@ pub struct RegistryGenerateArgs {
     #[arg(long)]
     pub params: Option<PathBuf>,
 
+    /// Additional targets to generate from the same resolved registry, as
+    /// `<target>=<output>` or `<target>=<output>,<params-file>`. The parameters file of
+    /// a target is loaded after `--params` and before the `--param` values. The targets
+    /// are rendered in parallel, after the positional target if any.
+    #[arg(long = "targets", value_parser = parse_generate_target)]
+    #[config]
+    pub targets: Option<Vec<GenerateTarget>>,
+
     /// Parameters to specify the semantic convention registry
     #[command(flatten)]
     #[shared(registry)]
@@ -71,12 +88,126 @@ pub struct RegistryGenerateArgs {
     #[arg(long, default_value = "false")]
     pub future: bool,
 
+    /// Keep running and regenerate the artifacts when the registry, the templates or the
+    /// `weaver.yaml` files change. Only the templates whose filter output changed, or
+    /// whose file changed, are rendered again. Requires a local registry to watch it.
+    #[arg(long, default_value = "false", conflicts_with = "check")]
+    pub watch: bool,
+
+    /// Delete the files generated by a previous run but not by this one, as recorded in the
+    /// `.weaver-manifest.json` of the output directory. Files edited since their generation
+    /// are kept. A bare `--prune` means `true`; when passing an explicit value, the `=` form
+    /// is required (e.g. `--prune=false`).
+    #[arg(long, num_args = 0..=1, default_missing_value = "true", require_equals = true)]
+    #[config(default = "false")]
+    pub prune: Option<bool>,
+
+    /// Record the generated files, with their SHA-256 and template, in a
+    /// `.weaver-manifest.json` file of the output directory. Implied by `--prune`. A bare
+    /// `--manifest` means `true`; when passing an explicit value, the `=` form is required
+    /// (e.g. `--manifest=false`).
+    #[arg(long, num_args = 0..=1, default_missing_value = "true", require_equals = true)]
+    #[config(default = "false")]
+    pub manifest: Option<bool>,
+
+    /// Check that the output directory is up to date instead of writing to it. The artifacts
+    /// are rendered in memory and compared to the output directory: missing and changed
+    /// files are reported with a unified diff, and the command fails on any drift. Files
+    /// recorded in the manifest of the output directory (see `--manifest`) but no longer
+    /// rendered are reported as orphaned; hand-written files are never reported.
+    #[arg(long, default_value = "false")]
+    pub check: bool,
+
+    /// Print the plan of the generation instead of writing anything: for each template, the
+    /// number of items produced by its filter, the files it would generate and why it is
+    /// skipped. The files are not post-processed, so no post-processing command is run. A
+    /// bare `--dry-run` prints the plan as text; use `--dry-run=json` for JSON.
+    #[arg(
+        long,
+        value_enum,
+        num_args = 0..=1,
+        default_missing_value = "text",
+        require_equals = true,
+        conflicts_with_all = ["check", "watch", "prune"]
+    )]
+    pub dry_run: Option<PlanFormat>,
+
+    /// Accept the template packages resolved from the `weaver-package.yaml` manifest of the
+    /// templates and rewrite the `weaver-package.lock` file, instead of failing when they
+    /// don't match it.
+    #[arg(long, default_value = "false")]
+    pub update_lock: bool,
+
+    /// Allow the `command` post-processors of the `weaver.yaml` files of the templates
+    /// directory to run external programs, e.g. code formatters. Without it, a generation
+    /// using one fails. The post-processors of the template packages it depends on are
+    /// always ignored.
+    #[arg(long, num_args = 0..=1, default_missing_value = "true", require_equals = true)]
+    #[config(default = "false")]
+    pub allow_post_process_commands: Option<bool>,
+
     /// Parameters to specify the diagnostic format.
     #[command(flatten)]
     #[shared(diagnostic)]
     pub diagnostic: DiagnosticArgs,
 }
 
+/// The format of the plan printed by `--dry-run`.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum PlanFormat {
+    /// Human-readable text.
+    Text,
+    /// JSON.
+    Json,
+}
+
+/// A target to generate and where to write its artifacts.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, schemars::JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GenerateTarget {
+    /// Target to generate the artifacts for.
+    pub target: String,
+    /// Path to the directory where the generated artifacts will be saved.
+    pub output: PathBuf,
+    /// Parameters, defined in a YAML file, to pass to the templates of this target.
+    #[serde(default)]
+    pub params: Option<PathBuf>,
+}
+
+/// Parse a `--targets <target>=<output>[,<params-file>]` value.
+fn parse_generate_target(s: &str) -> Result<GenerateTarget, Error> {
+    let (target, rest) = s.split_once('=').ok_or_else(|| Error::InvalidTargets {
+        error: format!(
+            "`{s}` is not a valid target, the expected format is `<target>=<output>[,<params-file>]`"
+        ),
+    })?;
+    let (output, params) = match rest.split_once(',') {
+        Some((output, params)) => (output, Some(PathBuf::from(params))),
+        None => (rest, None),
+    };
+    Ok(GenerateTarget {
+        target: target.to_owned(),
+        output: PathBuf::from(output),
+        params,
+    })
+}
+
+/// A difference between the artifacts rendered by `--check` and the output directory.
+#[derive(thiserror::Error, Debug, serde::Serialize, Diagnostic)]
+pub(crate) enum GenerateCheckError {
+    /// A generated file is missing from the output directory.
+    #[error("The generated file `{path}` is missing from the output directory.\n{diff}")]
+    Added { path: PathBuf, diff: String },
+
+    /// A file of the output directory differs from the generated one.
+    #[error("The file `{path}` is not up to date.\n{diff}")]
+    Changed { path: PathBuf, diff: String },
+
+    /// A file of the output directory is not generated anymore.
+    #[error("The file `{path}` is not generated from the registry anymore.")]
+    Orphaned { path: PathBuf },
+}
+
 /// Utility function to parse key-value pairs from the command line.
 pub(crate) fn parse_key_val(s: &str) -> Result<(String, Value), Error> {
     let pos = s.find('=').ok_or_else(|| Error::InvalidParam {
@@ -110,45 +241,78 @@ pub(crate) fn command(
         auth,
     );
     let config = cmd_config.config;
+    let targets = generate_targets(&config)?;
+    if args.watch {
+        let [target] = targets.as_slice() else {
+            return Err(Error::InvalidTargets {
+                error: "`--watch` only supports a single target".to_owned(),
+            }
+            .into());
+        };
+        watch(
+            args,
+            cfg,
+            auth,
+            &weaver,
+            &cmd_config.registry.registry,
+            cmd_config.registry.v2,
+            &config,
+            target,
+        );
+    }
     let resolved = weaver.load_and_resolve_main(&mut diag_msgs)?;
-    let params = generate_params(args)?;
-    let templates: VirtualDirectoryPath = config
-        .templates
-        .parse::<VirtualDirectoryPath>()
-        .map_err(|e| {
-            DiagnosticMessages::from(Error::InvalidParams {
-                params_file: PathBuf::from(&config.templates),
-                error: e.to_string(),
-            })
-        })?;
-    let target = config.target;
-    let output_path = config.output;
-    let templates_dir = VirtualDirectory::try_new_with_auth(&templates, auth).map_err(|e| {
-        Error::InvalidParams {
-            params_file: PathBuf::from(templates.to_string()),
-            error: e.to_string(),
-        }
-    })?;
-    let loader = FileSystemFileLoader::try_new(resolve_templates_root(&templates_dir), &target)?;
-    let mut config = if let Some(paths) = &args.config {
-        WeaverConfig::try_from_config_files(paths)
-    } else {
-        WeaverConfig::try_from_path(loader.root())
-    }?;
-    crate::registry::apply_template_config(&mut config, cfg);
-    let mut output = OutputProcessor::from_template_config(
-        config,
-        loader,
-        params,
-        OutputTarget::Directory(output_path),
-    )?;
+    // The templates directory and its packages are shared by all the targets.
+    let packages = Arc::new(open_generate_packages(
+        args,
+        auth,
+        cmd_config.registry.v2,
+        &config,
+    )?);
+    let generators = targets
+        .iter()
+        .map(|target| Generator::try_new(args, cfg, &packages, &config, target))
+        .collect::<Result<Vec<_>, _>>()?;
     resolved.check_after_resolution_policy(&mut diag_msgs)?;
-    match &resolved {
-        crate::weaver::Resolved::V2(v) => {
-            output.generate(v.template_schema())?;
-        }
-        crate::weaver::Resolved::V1(v) => {
-            output.generate(v.template_schema())?;
+
+    if let Some(format) = args.dry_run {
+        let plans = generators
+            .iter()
+            .map(|generator| match &resolved {
+                crate::weaver::Resolved::V2(v) => {
+                    generator.output.generate_plan(v.template_schema())
+                }
+                crate::weaver::Resolved::V1(v) => {
+                    generator.output.generate_plan(v.template_schema())
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        print_plans(&targets, &plans, format)?;
+        return Ok(ExitDirectives {
+            exit_code: 0,
+            warnings: None,
+        });
+    }
+
+    // The registry is resolved once and the targets are rendered in parallel.
+    let several = targets.len() > 1;
+    let results: Vec<Result<(), DiagnosticMessages>> = targets
+        .par_iter()
+        .zip(&generators)
+        .map(|(target, generator)| {
+            generate_target(
+                args,
+                config.prune,
+                config.manifest,
+                &resolved,
+                target,
+                generator,
+                several,
+            )
+        })
+        .collect();
+    for result in results {
+        if let Err(e) = result {
+            diag_msgs.extend(e);
         }
     }
 
@@ -156,13 +320,540 @@ pub(crate) fn command(
         return Err(diag_msgs);
     }
 
-    log_success("Artifacts generated successfully");
+    if !args.check {
+        log_success("Artifacts generated successfully");
+    }
     Ok(ExitDirectives {
         exit_code: 0,
         warnings: None,
     })
 }
 
+/// Print the plan of each target. The plans are named after their target when there
+/// are several.
+fn print_plans(
+    targets: &[GenerateTarget],
+    plans: &[GenerationPlan],
+    format: PlanFormat,
+) -> Result<(), DiagnosticMessages> {
+    let several = targets.len() > 1;
+    match format {
+        PlanFormat::Text => {
+            for (target, plan) in targets.iter().zip(plans) {
+                if several {
+                    println!("Target `{}` -> {:?}", target.target, target.output);
+                }
+                println!("{plan}");
+            }
+        }
+        PlanFormat::Json => {
+            let json = if several {
+                serde_json::Value::Array(
+                    targets
+                        .iter()
+                        .zip(plans)
+                        .map(|(target, plan)| {
+                            serde_json::json!({
+                                "target": target.target,
+                                "output": target.output,
+                                "plan": plan,
+                            })
+                        })
+                        .collect(),
+                )
+            } else {
+                serde_json::json!(plans.first())
+            };
+            let json = serde_json::to_string_pretty(&json).map_err(|e| {
+                weaver_forge::error::Error::SerializationError {
+                    error: e.to_string(),
+                }
+            })?;
+            println!("{json}");
+        }
+    }
+    Ok(())
+}
+
+/// The targets to generate: the positional target, then the `targets` of the
+/// command line or of the configuration. Fails if two targets share an output
+/// directory, or if the output directory of a target is nested in the output directory
+/// of another one: each target would report the files of the other as orphaned.
+fn generate_targets(config: &GenerateConfig) -> Result<Vec<GenerateTarget>, Error> {
+    let others = config.targets.clone().unwrap_or_default();
+    let mut targets = Vec::new();
+    if !config.target.is_empty() || others.is_empty() {
+        targets.push(GenerateTarget {
+            target: config.target.clone(),
+            output: config.output.clone(),
+            params: None,
+        });
+    }
+    targets.extend(others);
+    let outputs: Vec<PathBuf> = targets
+        .iter()
+        .map(|target| lexical_path(&target.output))
+        .collect();
+    for (i, output) in outputs.iter().enumerate() {
+        for (other, other_output) in targets.iter().zip(&outputs).skip(i + 1) {
+            let error = if output == other_output {
+                format!(
+                    "several targets are generated in the output directory {:?}",
+                    other.output
+                )
+            } else if output.starts_with(other_output) || other_output.starts_with(output) {
+                format!(
+                    "the output directories {:?} and {:?} of two targets are nested",
+                    targets[i].output, other.output
+                )
+            } else {
+                continue;
+            };
+            return Err(Error::InvalidTargets { error });
+        }
+    }
+    Ok(targets)
+}
+
+/// The path without its `.` components, to compare output directories.
+fn lexical_path(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|component| !matches!(component, Component::CurDir))
+        .collect()
+}
+
+/// Generate the artifacts of a target, or check its output directory with `--check`.
+/// The generated files are recorded in the manifest of the output directory with
+/// `--prune` or `--manifest`. The messages name the target when several are generated.
+fn generate_target(
+    args: &RegistryGenerateArgs,
+    prune: bool,
+    manifest: bool,
+    resolved: &crate::weaver::Resolved,
+    target: &GenerateTarget,
+    generator: &Generator,
+    several: bool,
+) -> Result<(), DiagnosticMessages> {
+    let of_target = if several {
+        format!(" of `{}`", target.target)
+    } else {
+        String::new()
+    };
+    let output = &target.output;
+    if args.check {
+        let files = match resolved {
+            crate::weaver::Resolved::V2(v) => {
+                generator.output.generate_in_memory(v.template_schema())?
+            }
+            crate::weaver::Resolved::V1(v) => {
+                generator.output.generate_in_memory(v.template_schema())?
+            }
+        };
+        // Only the files of a previous generation are orphans, not the hand-written ones.
+        let previous = GenerationManifest::load(output)?;
+        let drift = check_output_dir(output, &files, |path| {
+            previous
+                .as_ref()
+                .is_some_and(|previous| previous.contains(path))
+        })?;
+        if drift.is_empty() {
+            log_success(format!(
+                "The {} generated artifacts{of_target} are up to date",
+                files.len()
+            ));
+            return Ok(());
+        }
+        return Err(DiagnosticMessages::new(
+            drift.into_iter().map(DiagnosticMessage::new).collect(),
+        ));
+    }
+    let write_manifest = prune || manifest;
+    let previous = if write_manifest {
+        GenerationManifest::load(output)?
+    } else {
+        None
+    };
+    let mut generated = match resolved {
+        crate::weaver::Resolved::V2(v) => generator
+            .output
+            .generate_with_manifest(v.template_schema())?,
+        crate::weaver::Resolved::V1(v) => generator
+            .output
+            .generate_with_manifest(v.template_schema())?,
+    };
+    if let Some(previous) = &previous {
+        if prune {
+            let report = generated.prune(previous, output)?;
+            for path in &report.deleted {
+                log_info(format!("Pruned file {path:?}{of_target}"));
+            }
+            for path in &report.modified {
+                log_warn(format!(
+                    "File {path:?}{of_target} is not generated anymore but was edited since its generation, it is kept"
+                ));
+            }
+        } else {
+            generated.carry_over(previous);
+        }
+    }
+    if write_manifest {
+        generated.save(output)?;
+    }
+    if several {
+        log_success(format!("Artifacts{of_target} generated"));
+    }
+    Ok(())
+}
+
+/// Compare the files rendered in memory with the content of the output directory. The
+/// files of the output directory that are not rendered are only reported as orphaned
+/// when `is_tracked` returns true for them.
+pub(crate) fn check_output_dir(
+    output_dir: &Path,
+    files: &BTreeMap<PathBuf, String>,
+    is_tracked: impl Fn(&Path) -> bool,
+) -> Result<Vec<GenerateCheckError>, Error> {
+    let diffs = diff_dir_with_files(output_dir, files, |path| {
+        path != Path::new(MANIFEST_FILE_NAME) && is_tracked(path)
+    })
+    .map_err(|e| Error::OutputRead {
+        path: output_dir.to_path_buf(),
+        error: e.to_string(),
+    })?;
+    Ok(diffs
+        .into_iter()
+        .map(|diff| match diff {
+            FileDiff::Added { path, diff } => GenerateCheckError::Added { path, diff },
+            FileDiff::Changed { path, diff } => GenerateCheckError::Changed { path, diff },
+            FileDiff::Removed { path } => GenerateCheckError::Orphaned { path },
+        })
+        .collect())
+}
+
+/// The templates of a target and where their output goes.
+struct Generator {
+    output: OutputProcessor,
+    /// Root directories of the templates of the target, the templates directory first,
+    /// then the template packages it depends on.
+    roots: Vec<PathBuf>,
+    /// Parameters file of the target.
+    params_file: Option<PathBuf>,
+    /// Keeps remote templates on disk as long as the generator is used.
+    _packages: Arc<TemplatePackages>,
+}
+
+impl Generator {
+    fn try_new(
+        args: &RegistryGenerateArgs,
+        cfg: Option<&ProjectWeaverConfig>,
+        packages: &Arc<TemplatePackages>,
+        config: &GenerateConfig,
+        target: &GenerateTarget,
+    ) -> Result<Self, DiagnosticMessages> {
+        let params = generate_params(args, target)?;
+        let loader = template_loader(packages, &target.target)?;
+        let roots = loader.roots();
+        let mut template_config = if let Some(paths) = &args.config {
+            WeaverConfig::try_from_config_files(paths)
+        } else {
+            WeaverConfig::try_from_layers(&roots)
+        }?;
+        crate::registry::apply_template_config(&mut template_config, cfg);
+        template_config.allow_post_process_commands(config.allow_post_process_commands);
+        let output = OutputProcessor::from_template_config(
+            template_config,
+            loader,
+            params,
+            OutputTarget::Directory(target.output.clone()),
+        )?;
+        Ok(Self {
+            output,
+            roots,
+            params_file: target.params.clone(),
+            _packages: Arc::clone(packages),
+        })
+    }
+
+    /// The configuration files of the generation: the explicit `--config` files, or
+    /// the `weaver.yaml` files of the templates root and its parent directories, and
+    /// of the template packages.
+    fn config_files(&self, args: &RegistryGenerateArgs) -> Vec<PathBuf> {
+        let mut files = if let Some(paths) = &args.config {
+            paths.clone()
+        } else {
+            let (root, packages) = self
+                .roots
+                .split_first()
+                .expect("A generator has at least one root");
+            root.ancestors()
+                .chain(packages.iter().map(PathBuf::as_path))
+                .map(|dir| dir.join("weaver.yaml"))
+                .collect()
+        };
+        files.extend(args.params.clone());
+        files.extend(self.params_file.clone());
+        files
+    }
+}
+
+/// Interval between two scans of the watched files.
+const WATCH_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Regenerate the artifacts each time the registry, the templates or the
+/// configuration files change. Errors are reported inline and the watch goes on,
+/// it only stops with the process.
+#[allow(clippy::too_many_arguments)]
+fn watch(
+    args: &RegistryGenerateArgs,
+    cfg: Option<&ProjectWeaverConfig>,
+    auth: &HttpAuthResolver,
+    weaver: &WeaverEngine<'_>,
+    registry: &VirtualDirectoryPath,
+    v2: bool,
+    config: &GenerateConfig,
+    target: &GenerateTarget,
+) -> ! {
+    let diagnostics = args.diagnostic.to_effective(cfg);
+    let report = |diag_msgs: DiagnosticMessages| {
+        if let Err(e) = crate::print_diagnostics(&diagnostics, &diag_msgs) {
+            log_error(format!(
+                "Failed to render the diagnostic messages. Error: {e}"
+            ));
+        }
+    };
+    let registry_dir = if let VirtualDirectoryPath::LocalFolder { path } = registry {
+        Some(PathBuf::from(path))
+    } else {
+        log_warn(format!(
+            "The registry `{registry}` is not a local folder, only the templates are watched"
+        ));
+        None
+    };
+
+    let mut state = IncrementalState::default();
+    let mut generator: Option<Generator> = None;
+    let mut resolved = None;
+    let mut reload_config = true;
+    let mut resolve = true;
+    let mut changed_templates = Vec::new();
+    let mut snapshot: Option<WatchSnapshot> = None;
+    loop {
+        if reload_config {
+            state.clear();
+            generator = open_generate_packages(args, auth, v2, config)
+                .and_then(|packages| {
+                    Generator::try_new(args, cfg, &Arc::new(packages), config, target)
+                })
+                .map_err(report)
+                .ok();
+        }
+        if resolve {
+            let mut diag_msgs = DiagnosticMessages::empty();
+            resolved = match weaver.load_and_resolve_main(&mut diag_msgs) {
+                Ok(resolved) => match resolved.check_after_resolution_policy(&mut diag_msgs) {
+                    Ok(()) => Some(resolved),
+                    Err(e) => {
+                        diag_msgs.extend(e.into());
+                        None
+                    }
+                },
+                Err(e) => {
+                    diag_msgs.extend(e.into());
+                    None
+                }
+            };
+            if !diag_msgs.is_empty() {
+                report(diag_msgs);
+            }
+        }
+        if let (Some(generator), Some(resolved)) = (&generator, &resolved) {
+            let result = match resolved {
+                crate::weaver::Resolved::V2(v) => generator.output.generate_incremental(
+                    v.template_schema(),
+                    &mut state,
+                    &changed_templates,
+                ),
+                crate::weaver::Resolved::V1(v) => generator.output.generate_incremental(
+                    v.template_schema(),
+                    &mut state,
+                    &changed_templates,
+                ),
+            };
+            match result {
+                Ok(count) => log_success(format!("{count} template(s) rendered")),
+                Err(e) => report(DiagnosticMessages::from(e)),
+            }
+        }
+
+        // Wait for the next change.
+        // The local templates directory is watched even when the templates failed to
+        // load, so that fixing them triggers a new generation.
+        let mut watched: Vec<PathBuf> = registry_dir.iter().cloned().collect();
+        watched.push(PathBuf::from(&config.templates));
+        if let Some(generator) = &generator {
+            watched.extend(generator.roots.iter().cloned());
+            watched.extend(generator.config_files(args));
+        }
+        log_info("Watching for changes...");
+        let changes = loop {
+            let current = WatchSnapshot::take(&watched);
+            let changes = snapshot
+                .as_ref()
+                .map(|previous| previous.changes(&current))
+                .unwrap_or_default();
+            snapshot = Some(current);
+            if !changes.is_empty() {
+                break changes;
+            }
+            std::thread::sleep(WATCH_POLL_INTERVAL);
+        };
+
+        // Classify the changes.
+        reload_config = generator.is_none();
+        resolve = resolved.is_none();
+        changed_templates.clear();
+        for path in changes {
+            if registry_dir
+                .as_ref()
+                .is_some_and(|dir| path.starts_with(dir))
+            {
+                resolve = true;
+            } else if let Some(generator) = &generator {
+                if generator.config_files(args).contains(&path) {
+                    reload_config = true;
+                } else if let Some(template) = generator
+                    .roots
+                    .iter()
+                    .find_map(|root| path.strip_prefix(root).ok())
+                {
+                    changed_templates.push(template.to_path_buf());
+                }
+            }
+        }
+    }
+}
+
+/// Last modification time and size of the watched files.
+struct WatchSnapshot(HashMap<PathBuf, (Option<SystemTime>, u64)>);
+
+impl WatchSnapshot {
+    /// Scan the given files and directories (recursively). Missing paths are ignored.
+    fn take(paths: &[PathBuf]) -> Self {
+        let files = paths
+            .iter()
+            .flat_map(|path| {
+                walkdir::WalkDir::new(path)
+                    .into_iter()
+                    .filter_map(Result::ok)
+            })
+            .filter(|entry| entry.file_type().is_file())
+            .filter_map(|entry| {
+                let metadata = entry.metadata().ok()?;
+                Some((
+                    entry.into_path(),
+                    (metadata.modified().ok(), metadata.len()),
+                ))
+            })
+            .collect();
+        Self(files)
+    }
+
+    /// The files created, modified or deleted between this snapshot and the current one.
+    fn changes(&self, current: &Self) -> Vec<PathBuf> {
+        let mut changes: Vec<PathBuf> = current
+            .0
+            .iter()
+            .filter(|(path, stamp)| self.0.get(*path) != Some(stamp))
+            .map(|(path, _)| path.clone())
+            .chain(
+                self.0
+                    .keys()
+                    .filter(|path| !current.0.contains_key(*path))
+                    .cloned(),
+            )
+            .collect();
+        changes.sort();
+        changes
+    }
+}
+
+/// Open the templates directory, a local folder or a remote archive or repository.
+pub(crate) fn open_templates_dir(
+    templates: &str,
+    auth: &HttpAuthResolver,
+) -> Result<VirtualDirectory, DiagnosticMessages> {
+    let templates: VirtualDirectoryPath =
+        templates.parse::<VirtualDirectoryPath>().map_err(|e| {
+            DiagnosticMessages::from(Error::InvalidParams {
+                params_file: PathBuf::from(templates),
+                error: e.to_string(),
+            })
+        })?;
+    VirtualDirectory::try_new_with_auth(&templates, auth).map_err(|e| {
+        DiagnosticMessages::from(Error::InvalidParams {
+            params_file: PathBuf::from(templates.to_string()),
+            error: e.to_string(),
+        })
+    })
+}
+
+/// Open the templates directory of the generation and resolve its template packages,
+/// with the lock mode of the command line.
+fn open_generate_packages(
+    args: &RegistryGenerateArgs,
+    auth: &HttpAuthResolver,
+    v2: bool,
+    config: &GenerateConfig,
+) -> Result<TemplatePackages, DiagnosticMessages> {
+    let lock_mode = if args.update_lock {
+        LockMode::Update
+    } else if args.check || args.dry_run.is_some() {
+        LockMode::Check
+    } else {
+        LockMode::WriteMissing
+    };
+    open_template_packages(&config.templates, v2, lock_mode, auth)
+}
+
+/// Open the templates directory and resolve the template packages it depends on. The
+/// resolved packages are checked against the lock file of the templates directory, or
+/// recorded in it as allowed by `lock_mode`. A missing lock file that is not written is
+/// reported as a warning.
+pub(crate) fn open_template_packages(
+    templates: &str,
+    v2: bool,
+    lock_mode: LockMode,
+    auth: &HttpAuthResolver,
+) -> Result<TemplatePackages, DiagnosticMessages> {
+    let templates_dir = open_templates_dir(templates, auth)?;
+    let registry_schema = if v2 {
+        RegistrySchema::V2
+    } else {
+        RegistrySchema::V1
+    };
+    let packages = TemplatePackages::resolve(templates_dir, registry_schema, auth)?;
+    match packages.check_lock(lock_mode) {
+        Err(e @ weaver_forge::error::Error::TemplatePackageLockMissing { .. }) => {
+            log_warn(format!(
+                "{e} {}",
+                e.help().map(|h| h.to_string()).unwrap_or_default()
+            ));
+        }
+        result => result?,
+    }
+    Ok(packages)
+}
+
+/// The loader of the templates of a target, layering the templates directory over
+/// the template packages it depends on.
+pub(crate) fn template_loader(
+    packages: &TemplatePackages,
+    target: &str,
+) -> Result<LayeredFileLoader, DiagnosticMessages> {
+    let roots: Vec<PathBuf> = packages.dirs().iter().map(resolve_templates_root).collect();
+    Ok(LayeredFileLoader::try_new(&roots, target)?)
+}
+
 /// Compute the effective templates root.
 /// If a `registry` subdirectory exists under the provided templates directory,
 /// that subdirectory is returned, otherwise the original directory path is returned.
@@ -176,12 +867,20 @@ pub(crate) fn resolve_templates_root(templates_dir: &VirtualDirectory) -> PathBu
     }
 }
 
-/// Generate the parameters to pass to the templates.
+/// Generate the parameters to pass to the templates of a target.
 /// The `--params` argument (if provided) is used to load the parameters from a YAML file.
-/// Then the key-value pairs from the `--param` arguments are added to the parameters.
-/// So `--param key=value` will override the value of `key` if it exists in the YAML file.
-fn generate_params(args: &RegistryGenerateArgs) -> Result<Params, Error> {
-    generate_params_shared(&args.param, &args.params)
+/// Then the parameters file of the target (if provided) and the key-value pairs from the
+/// `--param` arguments are added to the parameters, in this order.
+/// So `--param key=value` will override the value of `key` if it exists in the YAML files.
+fn generate_params(args: &RegistryGenerateArgs, target: &GenerateTarget) -> Result<Params, Error> {
+    let mut params = generate_params_shared(&None, &args.params)?;
+    params
+        .params
+        .extend(generate_params_shared(&None, &target.params)?.params);
+    params
+        .params
+        .extend(generate_params_shared(&args.param, &None)?.params);
+    Ok(params)
 }
 
 pub(crate) fn generate_params_shared(
@@ -242,6 +941,7 @@ mod tests {
             quiet: false,
             future: false,
             allow_git_credentials: false,
+            cache: Default::default(),
             config: None,
             command: Some(Commands::Registry(RegistryCommand {
                 command: RegistrySubCommand::Generate(RegistryGenerateArgs {
@@ -262,6 +962,14 @@ mod tests {
                         ..Default::default()
                     },
                     future: false,
+                    watch: false,
+                    check: false,
+                    update_lock: false,
+                    dry_run: None,
+                    targets: None,
+                    prune: None,
+                    manifest: None,
+                    allow_post_process_commands: None,
                     diagnostic: Default::default(),
                 }),
             })),
@@ -318,6 +1026,7 @@ mod tests {
             quiet: false,
             future: false,
             allow_git_credentials: false,
+            cache: Default::default(),
             config: None,
             command: Some(Commands::Registry(RegistryCommand {
                 command: RegistrySubCommand::Generate(RegistryGenerateArgs {
@@ -337,6 +1046,14 @@ mod tests {
                         ..Default::default()
                     },
                     future: false,
+                    watch: false,
+                    check: false,
+                    update_lock: false,
+                    dry_run: None,
+                    targets: None,
+                    prune: None,
+                    manifest: None,
+                    allow_post_process_commands: None,
                     diagnostic: Default::default(),
                 }),
             })),
@@ -357,6 +1074,7 @@ mod tests {
             quiet: false,
             future: false,
             allow_git_credentials: false,
+            cache: Default::default(),
             config: None,
             command: Some(Commands::Registry(RegistryCommand {
                 command: RegistrySubCommand::Generate(RegistryGenerateArgs {
@@ -384,6 +1102,14 @@ mod tests {
                         ..Default::default()
                     },
                     future: false,
+                    watch: false,
+                    check: false,
+                    update_lock: false,
+                    dry_run: None,
+                    targets: None,
+                    prune: None,
+                    manifest: None,
+                    allow_post_process_commands: None,
                     diagnostic: Default::default(),
                 }),
             })),
@@ -470,6 +1196,7 @@ mod tests {
                 quiet: false,
                 future: false,
                 allow_git_credentials: false,
+                cache: Default::default(),
                 config: None,
                 command: Some(Commands::Registry(RegistryCommand {
                     command: RegistrySubCommand::Generate(RegistryGenerateArgs {
@@ -491,6 +1218,14 @@ mod tests {
                             ..Default::default()
                         },
                         future: false,
+                        watch: false,
+                        check: false,
+                        update_lock: false,
+                        dry_run: None,
+                        targets: None,
+                        prune: None,
+                        manifest: None,
+                        allow_post_process_commands: None,
                         diagnostic: Default::default(),
                     }),
                 })),
@@ -545,6 +1280,7 @@ mod tests {
             quiet: false,
             future: false,
             allow_git_credentials: false,
+            cache: Default::default(),
             config: None,
             command: Some(Commands::Registry(RegistryCommand {
                 command: RegistrySubCommand::Generate(RegistryGenerateArgs {
@@ -566,6 +1302,14 @@ mod tests {
                         ..Default::default()
                     },
                     future: false,
+                    watch: false,
+                    check: false,
+                    update_lock: false,
+                    dry_run: None,
+                    targets: None,
+                    prune: None,
+                    manifest: None,
+                    allow_post_process_commands: None,
                     diagnostic: Default::default(),
                 }),
             })),
@@ -52,6 +52,21 @@ pub struct RegistryInferArgs {
     #[config(default = "4317")]
     grpc_port: Option<u16>,
 
+    /// Enable the OTLP/HTTP listener (protobuf and JSON).
+    #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+    #[config(default = "false")]
+    otlp_http: Option<bool>,
+
+    /// Address used by the OTLP/HTTP listener. Defaults to the gRPC address.
+    #[arg(long)]
+    #[config]
+    http_address: Option<String>,
+
+    /// Port used by the OTLP/HTTP listener.
+    #[arg(long)]
+    #[config(default = "4318")]
+    http_port: Option<u16>,
+
     /// Port used by the HTTP admin server (endpoints: /stop).
     #[arg(long)]
     #[config(default = "8080")]
@@ -190,22 +205,33 @@ pub(crate) fn command(
     let output = config.output;
     let grpc_address = config.grpc_address;
     let grpc_port = config.grpc_port;
+    let http_address = config.http_address.unwrap_or_else(|| grpc_address.clone());
+    let http_port = config.http_port;
     let admin_port = config.admin_port;
     let inactivity_timeout = config.inactivity_timeout;
 
     info!("Weaver Registry Infer");
     info!("Starting OTLP gRPC server on {grpc_address}:{grpc_port}");
+    let http_endpoint = if config.otlp_http {
+        info!("Starting OTLP/HTTP server on {http_address}:{http_port}");
+        Some((http_address.as_str(), http_port))
+    } else {
+        None
+    };
 
-    // Start the OTLP gRPC server and get an iterator of requests
+    // Start the OTLP gRPC and HTTP servers and get an iterator of requests
     let (requests, _report_sender) = listen_otlp_requests(
         &grpc_address,
         grpc_port,
+        http_endpoint,
+        None,
+        None,
         admin_port,
         Duration::from_secs(inactivity_timeout),
     )
     .map_err(DiagnosticMessages::from)?;
 
-    info!("OTLP gRPC server started. Waiting for telemetry...");
+    info!("OTLP servers started. Waiting for telemetry...");
     info!("To stop: press CTRL+C, send SIGHUP, or POST to http://localhost:{admin_port}/stop");
 
     // Accumulate samples
@@ -41,6 +41,9 @@ struct WeaverConfigSchema {
     pub package: super::package::PackageConfig,
     pub serve: crate::serve::ServeConfig,
     pub stats: super::stats::StatsConfig,
+    #[serde(rename = "telemetry-schema")]
+    pub telemetry_schema: super::telemetry_schema::TelemetrySchemaConfig,
+    pub translate: super::translate::TranslateConfig,
     #[serde(rename = "update-markdown")]
     pub update_markdown: super::update_markdown::UpdateMarkdownConfig,
 }
@@ -157,6 +160,7 @@ mod tests {
                 quiet: false,
                 future: false,
                 allow_git_credentials: false,
+                cache: Default::default(),
                 config: None,
                 command: Some(Commands::Registry(RegistryCommand {
                     command: RegistrySubCommand::JsonSchema(RegistryJsonSchemaArgs {
@@ -6,6 +6,7 @@
 
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use clap::Args;
 use include_dir::{include_dir, Dir};
@@ -17,12 +18,15 @@ use weaver_common::{log_success, log_warn};
 use weaver_config::{FailOnLevel, WeaverConfig};
 use weaver_forge::{OutputProcessor, OutputTarget};
 use weaver_live_check::advice::{
-    Advisor, DeprecatedAdvisor, EnumAdvisor, RegoAdvisor, StabilityAdvisor, TypeAdvisor,
+    Advisor, DataPointAdvisor, DeprecatedAdvisor, EnumAdvisor, RegoAdvisor, StabilityAdvisor,
+    TypeAdvisor,
 };
+use weaver_live_check::aggregation::{AggregatedLiveCheckReport, FindingAggregator};
 use weaver_live_check::finding_modifier::FindingModifier;
 use weaver_live_check::json_file_ingester::JsonFileIngester;
 use weaver_live_check::json_stdin_ingester::JsonStdinIngester;
 use weaver_live_check::live_checker::LiveChecker;
+use weaver_live_check::sampler::Sampler;
 use weaver_live_check::text_file_ingester::TextFileIngester;
 use weaver_live_check::text_stdin_ingester::TextStdinIngester;
 use weaver_live_check::{
@@ -36,13 +40,18 @@ use crate::weaver::WeaverEngine;
 use crate::{DiagnosticArgs, ExitDirectives};
 use weaver_config::WeaverCommand;
 
+use super::otlp::otlp_file_ingester::{OtlpFileFormat, OtlpFileIngester};
 use super::otlp::otlp_ingester::OtlpIngester;
+use super::otlp::recorder::{RecordConfig, RecordFormat};
 use super::otlp::ShutdownCoordinator;
 
 /// Embedded default live check templates
 pub(crate) static DEFAULT_LIVE_CHECK_TEMPLATES: Dir<'_> =
     include_dir!("defaults/live_check_templates");
 
+/// How often the statistics served by the admin `/metrics` endpoint are refreshed.
+const METRICS_PUBLISH_INTERVAL: Duration = Duration::from_secs(1);
+
 /// The input source
 #[derive(Debug, Clone)]
 enum InputSource {
@@ -66,13 +75,15 @@ impl From<String> for InputSource {
 enum InputFormat {
     Text,
     Json,
+    /// Recorded OTLP export requests (e.g. from the collector `file` exporter)
+    Otlp(OtlpFileFormat),
 }
 
 impl From<String> for InputFormat {
     fn from(s: String) -> Self {
         match s.to_lowercase().as_str() {
             "json" | "js" => InputFormat::Json,
-            _ => InputFormat::Text,
+            format => format.parse().map_or(InputFormat::Text, InputFormat::Otlp),
         }
     }
 }
@@ -105,7 +116,8 @@ pub struct RegistryLiveCheckArgs {
     #[config(default = "otlp")]
     input_source: Option<String>,
 
-    /// The format of the input telemetry. text | json (not required for OTLP)
+    /// The format of the input telemetry. text | json | otlp-json |
+    /// otlp-proto-{traces,metrics,logs,profiles} (not required for OTLP)
     #[arg(long)]
     #[config(default = "json")]
     input_format: Option<String>,
@@ -131,6 +143,17 @@ pub struct RegistryLiveCheckArgs {
     #[config(default = "false")]
     no_stats: Option<bool>,
 
+    /// Report the findings aggregated by (id, signal type, signal name, attribute)
+    /// instead of attached to every sample. Forces report mode.
+    #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+    #[config(default = "false")]
+    aggregate: Option<bool>,
+
+    /// Number of first and last example contexts kept for each aggregated finding.
+    #[arg(long)]
+    #[config(default = "3")]
+    aggregate_examples: Option<usize>,
+
     /// Findings at this level or higher cause a non-zero exit code.
     /// Levels (highest→lowest): violation, improvement, information.
     /// Use `none` to never fail.
@@ -154,6 +177,28 @@ pub struct RegistryLiveCheckArgs {
     #[config(path = "otlp.grpc_port")]
     otlp_grpc_port: Option<u16>,
 
+    /// Enable the OTLP/HTTP listener (protobuf and JSON).
+    #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+    #[config(path = "otlp.http")]
+    otlp_http: Option<bool>,
+
+    /// Address used by the OTLP/HTTP listener. Defaults to the gRPC address.
+    #[clap(long)]
+    #[config(path = "otlp.http_address", optional)]
+    otlp_http_address: Option<String>,
+
+    /// Port used by the OTLP/HTTP listener.
+    #[clap(long)]
+    #[config(path = "otlp.http_port")]
+    otlp_http_port: Option<u16>,
+
+    /// Proxy mode: forward every received OTLP request, unchanged and with its
+    /// headers (e.g. `authorization`), to this upstream endpoint
+    /// (e.g. http://collector:4317). The upstream must speak OTLP/gRPC.
+    #[arg(long)]
+    #[config(path = "otlp.forward_endpoint", optional)]
+    otlp_forward_endpoint: Option<String>,
+
     /// Enable OTLP log emission for live check policy findings.
     #[arg(long, num_args = 0..=1, default_missing_value = "true")]
     #[config(path = "emit.otlp_logs")]
@@ -169,7 +214,7 @@ pub struct RegistryLiveCheckArgs {
     #[config(path = "emit.otlp_logs_stdout")]
     otlp_logs_stdout: Option<bool>,
 
-    /// Port used by the HTTP admin port (endpoints: /stop).
+    /// Port used by the HTTP admin port (endpoints: /stop, /health, /metrics).
     #[clap(long)]
     #[config(path = "otlp.admin_port")]
     admin_port: Option<u16>,
@@ -179,6 +224,45 @@ pub struct RegistryLiveCheckArgs {
     #[config(path = "otlp.inactivity_timeout")]
     inactivity_timeout: Option<u64>,
 
+    /// Directory where the received OTLP requests are recorded (OTLP input only).
+    /// The recorded files can be replayed with --input-format otlp-json or
+    /// otlp-proto-{signal}.
+    #[arg(long)]
+    #[config(path = "record.dir", optional)]
+    record_dir: Option<PathBuf>,
+
+    /// Encoding of the recorded files. json (OTLP/JSON lines) | proto
+    /// (length-prefixed protobuf, one file per signal)
+    #[arg(long)]
+    #[config(path = "record.format")]
+    record_format: Option<String>,
+
+    /// Size in MiB after which a recorded file is rotated.
+    #[arg(long)]
+    #[config(path = "record.max_file_size")]
+    record_max_file_size: Option<u64>,
+
+    /// Maximum number of recorded files kept per stream (0 = unlimited).
+    #[arg(long)]
+    #[config(path = "record.max_files")]
+    record_max_files: Option<usize>,
+
+    /// Check only this random fraction of the samples (0.0 to 1.0).
+    #[arg(long)]
+    #[config(path = "sampling.ratio", optional)]
+    sampling_ratio: Option<f64>,
+
+    /// Check only the first N samples of each signal name (e.g. span or metric name,
+    /// parent span name for the span links).
+    #[arg(long)]
+    #[config(path = "sampling.first_n", optional)]
+    sampling_first_n: Option<usize>,
+
+    /// Check at most this many samples per second for each signal type.
+    #[arg(long)]
+    #[config(path = "sampling.rate_limit", optional)]
+    sampling_rate_limit: Option<f64>,
+
     /// Advice policies directory. Set this to override the default policies.
     #[arg(long)]
     #[config]
@@ -195,36 +279,119 @@ pub struct RegistryLiveCheckArgs {
     advice_preprocessor: Option<PathBuf>,
 }
 
-fn default_advisors() -> Vec<Box<dyn Advisor>> {
+pub(crate) fn default_advisors() -> Vec<Box<dyn Advisor>> {
     vec![
         Box::new(DeprecatedAdvisor),
         Box::new(StabilityAdvisor),
         Box::new(TypeAdvisor),
         Box::new(EnumAdvisor),
+        Box::new(DataPointAdvisor::default()),
     ]
 }
 
+/// Tracks the most-recently-seen resource and attaches it to signals that carry one.
+/// This mirrors what the OTLP ingester does when building metrics/logs/spans from
+/// OTLP protocol buffers, and allows JSON inputs to associate a resource.
+pub(crate) fn attach_resource(
+    sample: &mut Sample,
+    current_resource: &mut Option<std::rc::Rc<SampleResource>>,
+) {
+    match sample {
+        Sample::Resource(r) => *current_resource = Some(std::rc::Rc::new(r.clone())),
+        Sample::Metric(m) => m.resource = current_resource.clone(),
+        Sample::Log(l) => l.resource = current_resource.clone(),
+        Sample::Span(s) => s.resource = current_resource.clone(),
+        _ => {}
+    }
+}
+
+/// The report built in report mode.
+enum Report {
+    /// Every checked sample, with its findings
+    Samples(Vec<Sample>),
+    /// The findings aggregated over all the checked samples
+    Aggregated(FindingAggregator),
+}
+
 /// Generate output for a complete report - handles line-oriented special case
 fn generate_report(
     output: &mut OutputProcessor,
-    samples: Vec<Sample>,
+    report: Report,
     stats: LiveCheckStatistics,
 ) -> Result<(), weaver_forge::error::Error> {
-    // Special handling: one line per sample, stats at end
+    // Special handling: one line per sample (or aggregated finding), stats at end
     if output.is_line_oriented() {
-        for sample in &samples {
-            output.generate(sample)?;
+        match report {
+            Report::Samples(samples) => {
+                for sample in &samples {
+                    output.generate(sample)?;
+                }
+            }
+            Report::Aggregated(aggregator) => {
+                for finding in &aggregator.into_findings() {
+                    output.generate(finding)?;
+                }
+            }
         }
         match stats {
             LiveCheckStatistics::Cumulative(_) => output.generate(&stats),
             LiveCheckStatistics::Disabled(_) => Ok(()),
         }
     } else {
-        let report = LiveCheckReport {
-            statistics: stats,
-            samples,
-        };
-        output.generate(&report)
+        match report {
+            Report::Samples(samples) => output.generate(&LiveCheckReport {
+                statistics: stats,
+                samples,
+            }),
+            Report::Aggregated(aggregator) => output.generate(&AggregatedLiveCheckReport {
+                findings: aggregator.into_findings(),
+                statistics: stats,
+            }),
+        }
+    }
+}
+
+/// Render a complete report to a string - handles line-oriented special case
+fn generate_report_to_string(
+    output: &OutputProcessor,
+    report: Report,
+    stats: LiveCheckStatistics,
+) -> Result<String, weaver_forge::error::Error> {
+    if output.is_line_oriented() {
+        // For line-oriented formats (jsonl), build the body line by line
+        let mut lines = Vec::new();
+        match report {
+            Report::Samples(samples) => {
+                for sample in &samples {
+                    lines.push(output.generate_to_string(sample)?);
+                }
+            }
+            Report::Aggregated(aggregator) => {
+                for finding in &aggregator.into_findings() {
+                    lines.push(output.generate_to_string(finding)?);
+                }
+            }
+        }
+        match &stats {
+            LiveCheckStatistics::Cumulative(_) => {
+                lines.push(output.generate_to_string(&stats)?);
+            }
+            LiveCheckStatistics::Disabled(_) => {}
+        }
+        Ok(lines.join("\n"))
+    } else {
+        match report {
+            Report::Samples(samples) => output.generate_to_string(&LiveCheckReport {
+                statistics: stats,
+                samples,
+            }),
+            Report::Aggregated(aggregator) => {
+                output.generate_to_string(&AggregatedLiveCheckReport {
+                    findings: aggregator.into_findings(),
+                    statistics: stats,
+                })
+            }
+        }
     }
 }
 
@@ -267,6 +434,15 @@ pub(crate) fn command(
         }));
     }
 
+    if config.record.dir.is_some() && !matches!(&input_source, InputSource::Otlp) {
+        log_warn("--record-dir is only used with --input-source otlp and will be ignored.");
+    }
+    let record_format = config
+        .record
+        .format
+        .parse::<RecordFormat>()
+        .map_err(|error| DiagnosticMessages::from(Error::ConfigError { error }))?;
+
     // For http output, create the processor in stdout mode (used for format/template config)
     let target = if is_http_output {
         OutputTarget::Stdout
@@ -304,6 +480,7 @@ pub(crate) fn command(
 
     live_checker.finding_modifier =
         FindingModifier::from_rules(&config.finding_filters, &config.finding_level_overrides)?;
+    let mut sampler = Sampler::from_config(&config.sampling)?;
 
     let rego_advisor = RegoAdvisor::new(
         &live_checker,
@@ -324,12 +501,35 @@ pub(crate) fn command(
 
         (InputSource::Stdin, InputFormat::Json) => JsonStdinIngester::new().ingest()?,
 
+        (InputSource::File(path), InputFormat::Otlp(format)) => {
+            OtlpFileIngester::new(path, *format).ingest()?
+        }
+
+        (InputSource::Stdin, InputFormat::Otlp(_)) => {
+            return Err(DiagnosticMessages::from(Error::IngestError {
+                error: "OTLP input formats are only supported for file input sources".to_owned(),
+            }));
+        }
+
         (InputSource::Otlp, _) => {
             let otlp = OtlpIngester {
                 otlp_grpc_address: config.otlp.grpc_address.clone(),
                 otlp_grpc_port: config.otlp.grpc_port,
+                otlp_http_address: config
+                    .otlp
+                    .http_address
+                    .clone()
+                    .unwrap_or_else(|| config.otlp.grpc_address.clone()),
+                otlp_http_port: config.otlp.http.then_some(config.otlp.http_port),
+                otlp_forward_endpoint: config.otlp.forward_endpoint.clone(),
                 admin_port: config.otlp.admin_port,
                 inactivity_timeout: config.otlp.inactivity_timeout,
+                record: config.record.dir.as_ref().map(|dir| RecordConfig {
+                    dir: dir.clone(),
+                    format: record_format,
+                    max_file_size: config.record.max_file_size.saturating_mul(1024 * 1024),
+                    max_files: config.record.max_files,
+                }),
             };
             let (iter, coordinator) = otlp.ingest_otlp()?;
             if is_http_output {
@@ -372,8 +572,8 @@ pub(crate) fn command(
         live_checker.otlp_emitter = Some(std::rc::Rc::new(emitter));
     }
 
-    let report_mode = if is_http_output || output.is_file_output() {
-        // HTTP output and file output force report mode
+    let report_mode = if is_http_output || output.is_file_output() || config.aggregate {
+        // HTTP output, file output and aggregation force report mode
         true
     } else {
         // This flag is not set by default. The user can set it to disable streaming output
@@ -387,23 +587,37 @@ pub(crate) fn command(
         LiveCheckStatistics::Cumulative(CumulativeStatistics::new(&live_checker.registry))
     };
 
-    let mut samples = Vec::new();
+    let mut report = if config.aggregate {
+        Report::Aggregated(FindingAggregator::new(config.aggregate_examples))
+    } else {
+        Report::Samples(Vec::new())
+    };
     let mut current_resource: Option<std::rc::Rc<SampleResource>> = None;
+    let mut last_metrics_publish = Instant::now();
+    if let Some(coordinator) = &shutdown_coordinator {
+        coordinator.publish_metrics(stats.to_prometheus());
+    }
     for mut sample in ingester {
-        // Track the most-recently-seen resource and attach it to signals that carry one.
-        // This mirrors what the OTLP ingester does when building metrics/logs/spans from
-        // OTLP protocol buffers, and allows JSON file inputs to associate a resource.
-        match &mut sample {
-            Sample::Resource(r) => current_resource = Some(std::rc::Rc::new(r.clone())),
-            Sample::Metric(m) => m.resource = current_resource.clone(),
-            Sample::Log(l) => l.resource = current_resource.clone(),
-            Sample::Span(s) => s.resource = current_resource.clone(),
-            _ => {}
+        attach_resource(&mut sample, &mut current_resource);
+        if let Some(sampler) = sampler.as_mut() {
+            if !sampler.keep(&sample, &mut stats) {
+                continue;
+            }
         }
         sample.run_live_check(&mut live_checker, &mut stats, None, &sample.clone())?;
+        // Refresh the statistics served by the admin `/metrics` endpoint
+        if let Some(coordinator) = &shutdown_coordinator {
+            if last_metrics_publish.elapsed() >= METRICS_PUBLISH_INTERVAL {
+                coordinator.publish_metrics(stats.to_prometheus());
+                last_metrics_publish = Instant::now();
+            }
+        }
         //TODO: Check for violations and set exit_code here for no-stats mode
         if report_mode {
-            samples.push(sample);
+            match &mut report {
+                Report::Samples(samples) => samples.push(sample),
+                Report::Aggregated(aggregator) => aggregator.add_sample(&sample),
+            }
         } else {
             // Output this sample immediately (streaming mode)
             output.generate(&sample).map_err(DiagnosticMessages::from)?;
@@ -428,36 +642,8 @@ pub(crate) fn command(
         if admin_waiting {
             // Format report and send through admin channel
             let content_type = output.content_type().to_owned();
-            let body = if output.is_line_oriented() {
-                // For line-oriented formats (jsonl), build the body line by line
-                let mut lines = Vec::new();
-                for sample in &samples {
-                    lines.push(
-                        output
-                            .generate_to_string(sample)
-                            .map_err(DiagnosticMessages::from)?,
-                    );
-                }
-                match &stats {
-                    LiveCheckStatistics::Cumulative(_) => {
-                        lines.push(
-                            output
-                                .generate_to_string(&stats)
-                                .map_err(DiagnosticMessages::from)?,
-                        );
-                    }
-                    LiveCheckStatistics::Disabled(_) => {}
-                }
-                lines.join("\n")
-            } else {
-                let report = LiveCheckReport {
-                    statistics: stats,
-                    samples,
-                };
-                output
-                    .generate_to_string(&report)
-                    .map_err(DiagnosticMessages::from)?
-            };
+            let body = generate_report_to_string(&output, report, stats)
+                .map_err(DiagnosticMessages::from)?;
             if let Some(coordinator) = shutdown_coordinator.take() {
                 coordinator.deliver_report(content_type, body);
                 // Don't let the process exit until the admin server has
@@ -466,10 +652,10 @@ pub(crate) fn command(
             }
         } else {
             // No HTTP client waiting (SIGINT/inactivity stop), fall back to stdout
-            generate_report(&mut output, samples, stats).map_err(DiagnosticMessages::from)?;
+            generate_report(&mut output, report, stats).map_err(DiagnosticMessages::from)?;
         }
     } else if report_mode {
-        generate_report(&mut output, samples, stats).map_err(DiagnosticMessages::from)?;
+        generate_report(&mut output, report, stats).map_err(DiagnosticMessages::from)?;
     } else {
         // Stats only (streaming mode finished)
         output.generate(&stats).map_err(DiagnosticMessages::from)?;
@@ -498,10 +684,13 @@ pub(crate) fn command(
 #[cfg(test)]
 mod tests {
     use serde_json::json;
+    use weaver_checker::{FindingLevel, PolicyFinding};
     use weaver_forge::{OutputProcessor, OutputTarget};
+    use weaver_live_check::aggregation::{AggregatedLiveCheckReport, FindingAggregator};
     use weaver_live_check::{
         sample_attribute::SampleAttribute,
-        sample_instrumentation_scope::SampleInstrumentationScope, LiveCheckResult, Sample,
+        sample_instrumentation_scope::SampleInstrumentationScope, DisabledStatistics,
+        LiveCheckResult, LiveCheckStatistics, Sample,
     };
 
     use super::{RegistryLiveCheckArgs, DEFAULT_LIVE_CHECK_TEMPLATES};
@@ -557,4 +746,54 @@ mod tests {
         );
         assert!(rendered.contains("scope.environment"), "{rendered}");
     }
+
+    #[test]
+    fn ansi_output_displays_aggregated_findings() {
+        let output = OutputProcessor::new(
+            "ansi",
+            "live_check",
+            Some(&DEFAULT_LIVE_CHECK_TEMPLATES),
+            None,
+            OutputTarget::Stdout,
+        )
+        .expect("ANSI output processor should load");
+
+        let mut aggregator = FindingAggregator::new(1);
+        for value in ["a", "b", "c"] {
+            let mut result = LiveCheckResult::new();
+            result.all_advice.push(PolicyFinding {
+                id: "missing_attribute".to_owned(),
+                context: Some(json!({ "attribute_key": "foo", "attribute_value": value })),
+                message: "Attribute `foo` does not exist in the registry.".to_owned(),
+                level: FindingLevel::Violation,
+                signal_type: Some("span".to_owned()),
+                signal_name: Some("GET /".to_owned()),
+            });
+            aggregator.add_sample(&Sample::Attribute(SampleAttribute {
+                name: "foo".to_owned(),
+                value: Some(json!(value)),
+                r#type: None,
+                live_check_result: Some(result),
+            }));
+        }
+        let report = AggregatedLiveCheckReport {
+            findings: aggregator.into_findings(),
+            statistics: LiveCheckStatistics::Disabled(DisabledStatistics),
+        };
+
+        let rendered = output
+            .generate_to_string(&report)
+            .expect("ANSI aggregated report should render");
+
+        assert_eq!(
+            rendered.matches("does not exist in the registry").count(),
+            1,
+            "{rendered}"
+        );
+        assert!(rendered.contains("occurrences: 3"), "{rendered}");
+        assert!(rendered.contains("attribute `foo`"), "{rendered}");
+        assert!(rendered.contains(r#""attribute_value":"a""#), "{rendered}");
+        assert!(!rendered.contains(r#""attribute_value":"b""#), "{rendered}");
+        assert!(rendered.contains(r#""attribute_value":"c""#), "{rendered}");
+    }
 }
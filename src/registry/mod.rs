@@ -19,6 +19,9 @@ use crate::registry::package::RegistryPackageArgs;
 use crate::registry::resolve::RegistryResolveArgs;
 use crate::registry::search::RegistrySearchArgs;
 use crate::registry::stats::RegistryStatsArgs;
+use crate::registry::telemetry_schema::RegistryTelemetrySchemaArgs;
+use crate::registry::test_templates::RegistryTestTemplatesArgs;
+use crate::registry::translate::RegistryTranslateArgs;
 use crate::registry::update_markdown::RegistryUpdateMarkdownArgs;
 use crate::CmdResult;
 use check::RegistryCheckArgs;
@@ -37,13 +40,16 @@ mod emit;
 mod generate;
 mod infer;
 mod json_schema;
-mod live_check;
+pub(crate) mod live_check;
 mod mcp;
-mod otlp;
+pub(crate) mod otlp;
 mod package;
 mod resolve;
 mod search;
 mod stats;
+mod telemetry_schema;
+mod test_templates;
+mod translate;
 mod update_markdown;
 
 /// Errors emitted by the `registry` sub-commands
@@ -73,6 +79,28 @@ pub enum Error {
     #[error("Failed to write output file `{path}`: {error}")]
     OutputWrite { path: PathBuf, error: String },
 
+    /// Failed to read a file of the output directory
+    #[error("Failed to read output file `{path}`: {error}")]
+    OutputRead { path: PathBuf, error: String },
+
+    /// Invalid generation targets
+    #[error("Invalid generation targets: {error}")]
+    InvalidTargets { error: String },
+
+    /// The version bump of the registry can't be checked
+    #[error("Cannot check the version bump of the registry: {error}")]
+    VersionBumpCheck { error: String },
+
+    /// The version bump of the registry is smaller than its changes require
+    #[error("The version bump from `{baseline}` to `{head}` is `{actual}` but the changes require a `{required}` version bump:\n{changes}")]
+    InsufficientVersionBump {
+        baseline: String,
+        head: String,
+        actual: String,
+        required: String,
+        changes: String,
+    },
+
     /// Configuration error (loading or parsing `.weaver.toml`)
     #[error("{error}")]
     Config { error: String },
@@ -129,6 +157,14 @@ pub enum RegistrySubCommand {
     Stats(RegistryStatsArgs),
     /// Update markdown files that contain markers indicating the templates used to update the specified sections.
     UpdateMarkdown(RegistryUpdateMarkdownArgs),
+    /// Runs the golden-file tests of a template target.
+    ///
+    /// Each sub-directory of the `tests` directory of the target is a test case with a `registry` directory, an optional `params.yaml` file and an `expected` directory.
+    /// The artifacts generated from the registry of each test case are compared to its expected output, and the differences are reported as unified diffs.
+    ///
+    /// The process exits with a code of 0 if all the test cases pass.
+    #[clap(verbatim_doc_comment)]
+    TestTemplates(RegistryTestTemplatesArgs),
     /// Generate the JSON Schema of the resolved registry documents consumed by the template generator and the policy engine.
     ///
     /// The produced JSON Schema can be used to generate documentation of the resolved registry format or to generate code in your language of choice if you need to interact with the resolved registry format for any reason.
@@ -142,6 +178,19 @@ pub enum RegistrySubCommand {
     /// - ...
     #[clap(verbatim_doc_comment)]
     Diff(RegistryDiffArgs),
+    /// Add the version of a semantic convention registry to an OpenTelemetry telemetry schema file (OTEP 0152).
+    ///
+    /// The attributes and the metrics renamed by a deprecation since the baseline registry are recorded in a new version section.
+    /// The file is created if it doesn't exist, and a section already defined for the version is replaced.
+    #[clap(verbatim_doc_comment)]
+    TelemetrySchema(RegistryTelemetrySchemaArgs),
+    /// Translate OTLP telemetry to a version of an OpenTelemetry telemetry schema (OTEP 0152).
+    ///
+    /// The attribute and metric names are rewritten with the renames of the telemetry schema file, forward or backward,
+    /// from the version of the schema URL of the telemetry to the target version.
+    /// Either rewrites a recorded OTLP file (`--input`), or runs an OTLP proxy forwarding the translated requests (`--forward-endpoint`).
+    #[clap(verbatim_doc_comment)]
+    Translate(RegistryTranslateArgs),
 
     /// Emits a semantic convention registry as example signals to your OTLP receiver.
     ///
@@ -152,7 +201,7 @@ pub enum RegistrySubCommand {
     ///
     /// Includes: Flexible input ingestion, configurable assessment, and template-based output.
     #[clap(verbatim_doc_comment)]
-    LiveCheck(RegistryLiveCheckArgs),
+    LiveCheck(Box<RegistryLiveCheckArgs>),
 
     /// Run an MCP (Model Context Protocol) server for the semantic convention registry.
     ///
@@ -407,6 +456,10 @@ pub fn semconv_registry(
             generate::command(args, cfg, auth),
             args.diagnostic.to_effective(cfg),
         ),
+        RegistrySubCommand::TestTemplates(args) => CmdResult::new(
+            test_templates::command(args, cfg, auth),
+            args.diagnostic.to_effective(cfg),
+        ),
         RegistrySubCommand::Stats(args) => CmdResult::new(
             stats::command(args, cfg, auth),
             args.diagnostic.to_effective(cfg),
@@ -431,6 +484,14 @@ pub fn semconv_registry(
             diff::command(args, cfg, auth),
             args.diagnostic.to_effective(cfg),
         ),
+        RegistrySubCommand::TelemetrySchema(args) => CmdResult::new(
+            telemetry_schema::command(args, cfg, auth),
+            args.diagnostic.to_effective(cfg),
+        ),
+        RegistrySubCommand::Translate(args) => CmdResult::new(
+            translate::command(args, cfg, auth),
+            args.diagnostic.to_effective(cfg),
+        ),
         RegistrySubCommand::LiveCheck(args) => CmdResult::new(
             live_check::command(args, cfg, auth),
             args.diagnostic.to_effective(cfg),
@@ -0,0 +1,326 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Encoding and decoding of OTLP export requests.
+//!
+//! The vendored gRPC stubs (see `grpc_stubs`) only support the binary protobuf
+//! encoding. OTLP/JSON is handled by the serde-enabled message types of the
+//! `opentelemetry-proto` crate: both sets of types are generated from the same
+//! proto files, so a message is transcoded from one to the other through its
+//! protobuf wire representation.
+
+use std::io::Read;
+
+use flate2::read::GzDecoder;
+use prost::Message;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use super::grpc_stubs::proto::collector::logs::v1::ExportLogsServiceRequest;
+use super::grpc_stubs::proto::collector::metrics::v1::ExportMetricsServiceRequest;
+use super::grpc_stubs::proto::collector::profiles::v1development::ExportProfilesServiceRequest;
+use super::grpc_stubs::proto::collector::trace::v1::ExportTraceServiceRequest;
+use super::{Error, OtlpRequest};
+
+/// The OTLP signal carried by an export request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OtlpSignal {
+    /// `ExportTraceServiceRequest`
+    Traces,
+    /// `ExportMetricsServiceRequest`
+    Metrics,
+    /// `ExportLogsServiceRequest`
+    Logs,
+    /// `ExportProfilesServiceRequest`
+    Profiles,
+}
+
+impl OtlpSignal {
+    /// The OTLP/HTTP path of the export endpoint for this signal.
+    #[must_use]
+    pub fn http_path(self) -> &'static str {
+        match self {
+            OtlpSignal::Traces => "/v1/traces",
+            OtlpSignal::Metrics => "/v1/metrics",
+            OtlpSignal::Logs => "/v1/logs",
+            OtlpSignal::Profiles => "/v1/profiles",
+        }
+    }
+
+    /// The top-level field of an OTLP/JSON export request for this signal.
+    #[must_use]
+    pub fn json_field(self) -> &'static str {
+        match self {
+            OtlpSignal::Traces => "resourceSpans",
+            OtlpSignal::Metrics => "resourceMetrics",
+            OtlpSignal::Logs => "resourceLogs",
+            OtlpSignal::Profiles => "resourceProfiles",
+        }
+    }
+
+    /// All the OTLP signals.
+    pub const ALL: [OtlpSignal; 4] = [
+        OtlpSignal::Traces,
+        OtlpSignal::Metrics,
+        OtlpSignal::Logs,
+        OtlpSignal::Profiles,
+    ];
+}
+
+/// The wire encoding of an OTLP message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OtlpEncoding {
+    /// Binary protobuf (`application/x-protobuf`).
+    Protobuf,
+    /// OTLP/JSON (`application/json`).
+    Json,
+}
+
+impl OtlpEncoding {
+    /// Returns the encoding matching an HTTP `Content-Type` header value, or
+    /// `None` if the media type is not supported by OTLP/HTTP.
+    #[must_use]
+    pub fn from_content_type(content_type: &str) -> Option<Self> {
+        let media_type = content_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_lowercase();
+        match media_type.as_str() {
+            "application/x-protobuf" | "application/protobuf" => Some(OtlpEncoding::Protobuf),
+            "application/json" => Some(OtlpEncoding::Json),
+            _ => None,
+        }
+    }
+
+    /// The HTTP `Content-Type` header value for this encoding.
+    #[must_use]
+    pub fn content_type(self) -> &'static str {
+        match self {
+            OtlpEncoding::Protobuf => "application/x-protobuf",
+            OtlpEncoding::Json => "application/json",
+        }
+    }
+}
+
+/// An OTLP message of the vendored gRPC stubs that has an OTLP/JSON
+/// counterpart in `opentelemetry-proto`.
+pub trait OtlpMessage: Message + Default + Sized {
+    /// The serde-enabled equivalent of this message.
+    type Json: Message + Default + Serialize + DeserializeOwned;
+
+    /// Decodes a binary protobuf message.
+    fn from_protobuf(bytes: &[u8]) -> Result<Self, Error> {
+        Self::decode(bytes).map_err(|e| Error::OtlpError {
+            error: format!("Invalid OTLP protobuf message: {e}"),
+        })
+    }
+
+    /// Decodes an OTLP/JSON message.
+    fn from_json(bytes: &[u8]) -> Result<Self, Error> {
+        let json: Self::Json = serde_json::from_slice(bytes).map_err(|e| Error::OtlpError {
+            error: format!("Invalid OTLP/JSON message: {e}"),
+        })?;
+        Self::from_protobuf(&json.encode_to_vec())
+    }
+
+    /// Decodes an already parsed OTLP/JSON message.
+    fn from_json_value(value: serde_json::Value) -> Result<Self, Error> {
+        let json: Self::Json = serde_json::from_value(value).map_err(|e| Error::OtlpError {
+            error: format!("Invalid OTLP/JSON message: {e}"),
+        })?;
+        Self::from_protobuf(&json.encode_to_vec())
+    }
+
+    /// Decodes a message in the given encoding.
+    fn from_bytes(bytes: &[u8], encoding: OtlpEncoding) -> Result<Self, Error> {
+        match encoding {
+            OtlpEncoding::Protobuf => Self::from_protobuf(bytes),
+            OtlpEncoding::Json => Self::from_json(bytes),
+        }
+    }
+
+    /// Encodes this message as OTLP/JSON.
+    fn to_json(&self) -> Result<String, Error> {
+        let json =
+            Self::Json::decode(self.encode_to_vec().as_slice()).map_err(|e| Error::OtlpError {
+                error: format!("Failed to transcode OTLP message: {e}"),
+            })?;
+        serde_json::to_string(&json).map_err(|e| Error::OtlpError {
+            error: format!("Failed to serialize OTLP/JSON message: {e}"),
+        })
+    }
+}
+
+impl OtlpMessage for ExportTraceServiceRequest {
+    type Json = opentelemetry_proto::tonic::collector::trace::v1::ExportTraceServiceRequest;
+}
+
+impl OtlpMessage for ExportMetricsServiceRequest {
+    type Json = opentelemetry_proto::tonic::collector::metrics::v1::ExportMetricsServiceRequest;
+}
+
+impl OtlpMessage for ExportLogsServiceRequest {
+    type Json = opentelemetry_proto::tonic::collector::logs::v1::ExportLogsServiceRequest;
+}
+
+impl OtlpMessage for ExportProfilesServiceRequest {
+    type Json =
+        opentelemetry_proto::tonic::collector::profiles::v1development::ExportProfilesServiceRequest;
+}
+
+/// Decodes an export request of the given signal into an `OtlpRequest`.
+pub fn decode_request(
+    signal: OtlpSignal,
+    bytes: &[u8],
+    encoding: OtlpEncoding,
+) -> Result<OtlpRequest, Error> {
+    Ok(match signal {
+        OtlpSignal::Traces => {
+            OtlpRequest::Traces(ExportTraceServiceRequest::from_bytes(bytes, encoding)?)
+        }
+        OtlpSignal::Metrics => {
+            OtlpRequest::Metrics(ExportMetricsServiceRequest::from_bytes(bytes, encoding)?)
+        }
+        OtlpSignal::Logs => {
+            OtlpRequest::Logs(ExportLogsServiceRequest::from_bytes(bytes, encoding)?)
+        }
+        OtlpSignal::Profiles => {
+            OtlpRequest::Profiles(ExportProfilesServiceRequest::from_bytes(bytes, encoding)?)
+        }
+    })
+}
+
+/// Decodes an OTLP/JSON export request, inferring its signal from the
+/// top-level field (`resourceSpans`, `resourceMetrics`, ...).
+pub fn decode_json_request(bytes: &[u8]) -> Result<OtlpRequest, Error> {
+    let value: serde_json::Value = serde_json::from_slice(bytes).map_err(|e| Error::OtlpError {
+        error: format!("Invalid OTLP/JSON message: {e}"),
+    })?;
+    let signal = OtlpSignal::ALL
+        .into_iter()
+        .find(|signal| value.get(signal.json_field()).is_some())
+        .ok_or_else(|| Error::OtlpError {
+            error: "Unable to determine the OTLP signal of the JSON message, expected one of \
+                    `resourceSpans`, `resourceMetrics`, `resourceLogs` or `resourceProfiles`"
+                .to_owned(),
+        })?;
+    Ok(match signal {
+        OtlpSignal::Traces => {
+            OtlpRequest::Traces(ExportTraceServiceRequest::from_json_value(value)?)
+        }
+        OtlpSignal::Metrics => {
+            OtlpRequest::Metrics(ExportMetricsServiceRequest::from_json_value(value)?)
+        }
+        OtlpSignal::Logs => OtlpRequest::Logs(ExportLogsServiceRequest::from_json_value(value)?),
+        OtlpSignal::Profiles => {
+            OtlpRequest::Profiles(ExportProfilesServiceRequest::from_json_value(value)?)
+        }
+    })
+}
+
+/// Decompresses a gzip-encoded payload.
+pub fn gunzip(bytes: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut decompressed = Vec::new();
+    let _ = GzDecoder::new(bytes)
+        .read_to_end(&mut decompressed)
+        .map_err(|e| Error::OtlpError {
+            error: format!("Invalid gzip payload: {e}"),
+        })?;
+    Ok(decompressed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::otlp::grpc_stubs::proto::common::v1::any_value::Value;
+    use crate::registry::otlp::grpc_stubs::proto::common::v1::AnyValue;
+
+    const TRACES_JSON: &str = r#"{
+      "resourceSpans": [{
+        "resource": {"attributes": [{"key": "service.name", "value": {"stringValue": "checkout"}}]},
+        "scopeSpans": [{
+          "scope": {"name": "my.library", "version": "1.0.0"},
+          "spans": [{
+            "traceId": "5b8efff798038103d269b633813fc60c",
+            "spanId": "eee19b7ec3c1b174",
+            "name": "GET /cart",
+            "kind": 2,
+            "startTimeUnixNano": "1544712660000000000",
+            "endTimeUnixNano": "1544712661000000000",
+            "attributes": [{"key": "http.response.status_code", "value": {"intValue": "200"}}]
+          }]
+        }]
+      }]
+    }"#;
+
+    #[test]
+    fn test_decode_json_traces() {
+        let request = ExportTraceServiceRequest::from_json(TRACES_JSON.as_bytes()).unwrap();
+        let span = &request.resource_spans[0].scope_spans[0].spans[0];
+        assert_eq!(span.name, "GET /cart");
+        assert_eq!(
+            span.span_id,
+            vec![0xee, 0xe1, 0x9b, 0x7e, 0xc3, 0xc1, 0xb1, 0x74]
+        );
+        assert_eq!(span.start_time_unix_nano, 1_544_712_660_000_000_000);
+        assert_eq!(
+            span.attributes[0].value,
+            Some(AnyValue {
+                value: Some(Value::IntValue(200))
+            })
+        );
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let request = ExportTraceServiceRequest::from_json(TRACES_JSON.as_bytes()).unwrap();
+        let json = request.to_json().unwrap();
+        assert!(json.contains(r#""traceId":"5b8efff798038103d269b633813fc60c""#));
+        assert_eq!(
+            ExportTraceServiceRequest::from_json(json.as_bytes()).unwrap(),
+            request
+        );
+    }
+
+    #[test]
+    fn test_decode_json_request_detects_signal() {
+        assert!(matches!(
+            decode_json_request(TRACES_JSON.as_bytes()),
+            Ok(OtlpRequest::Traces(_))
+        ));
+        assert!(matches!(
+            decode_json_request(br#"{"resourceMetrics": []}"#),
+            Ok(OtlpRequest::Metrics(_))
+        ));
+        assert!(matches!(
+            decode_json_request(br#"{"resourceLogs": []}"#),
+            Ok(OtlpRequest::Logs(_))
+        ));
+        assert!(matches!(
+            decode_json_request(br#"{"resourceProfiles": []}"#),
+            Ok(OtlpRequest::Profiles(_))
+        ));
+        assert!(decode_json_request(b"{}").is_err());
+    }
+
+    #[test]
+    fn test_invalid_payloads() {
+        assert!(ExportLogsServiceRequest::from_json(b"not json").is_err());
+        assert!(ExportLogsServiceRequest::from_protobuf(&[0xff, 0xff, 0xff]).is_err());
+        assert!(gunzip(b"not gzip").is_err());
+    }
+
+    #[test]
+    fn test_encoding_from_content_type() {
+        assert_eq!(
+            OtlpEncoding::from_content_type("application/x-protobuf"),
+            Some(OtlpEncoding::Protobuf)
+        );
+        assert_eq!(
+            OtlpEncoding::from_content_type("application/json; charset=utf-8"),
+            Some(OtlpEncoding::Json)
+        );
+        assert_eq!(OtlpEncoding::from_content_type("text/plain"), None);
+    }
+}
@@ -8,7 +8,7 @@ use weaver_live_check::{
     sample_attribute::SampleAttribute,
     sample_instrumentation_scope::SampleInstrumentationScope,
     sample_log::SampleLog,
-    sample_metric::{DataPoints, SampleInstrument, SampleMetric},
+    sample_metric::{DataPoints, SampleAggregationTemporality, SampleInstrument, SampleMetric},
     sample_profile::SampleProfile,
     sample_span::{Status, StatusCode},
 };
@@ -21,7 +21,9 @@ use super::grpc_stubs::proto::trace::v1::status::StatusCode as OtlpStatusCode;
 use super::grpc_stubs::proto::{
     common::v1::{AnyValue, InstrumentationScope, KeyValue},
     logs::v1::LogRecord,
-    metrics::v1::{metric::Data, HistogramDataPoint, Metric, NumberDataPoint},
+    metrics::v1::{
+        metric::Data, AggregationTemporality, HistogramDataPoint, Metric, NumberDataPoint,
+    },
     trace::v1::span::SpanKind,
 };
 
@@ -133,6 +135,7 @@ pub fn otlp_metric_to_sample(otlp_metric: Metric) -> SampleMetric {
         instrument: otlp_data_to_instrument(&otlp_metric.data),
         unit: otlp_metric.unit,
         data_points: otlp_data_to_data_points(&otlp_metric.data),
+        aggregation_temporality: otlp_data_to_temporality(&otlp_metric.data),
         instrumentation_scope: None,
         live_check_result: None,
         resource: None,
@@ -166,6 +169,26 @@ fn otlp_data_to_instrument(data: &Option<Data>) -> SampleInstrument {
     }
 }
 
+/// Converts the aggregation temporality of OTLP sums and histograms.
+/// Gauges and summaries have none.
+fn otlp_data_to_temporality(data: &Option<Data>) -> Option<SampleAggregationTemporality> {
+    let temporality = match data {
+        Some(Data::Sum(sum)) => sum.aggregation_temporality,
+        Some(Data::Histogram(histogram)) => histogram.aggregation_temporality,
+        Some(Data::ExponentialHistogram(exponential_histogram)) => {
+            exponential_histogram.aggregation_temporality
+        }
+        _ => return None,
+    };
+    Some(match AggregationTemporality::try_from(temporality) {
+        Ok(AggregationTemporality::Delta) => SampleAggregationTemporality::Delta,
+        Ok(AggregationTemporality::Cumulative) => SampleAggregationTemporality::Cumulative,
+        Ok(AggregationTemporality::Unspecified) | Err(_) => {
+            SampleAggregationTemporality::Unspecified
+        }
+    })
+}
+
 /// Converts OTLP data to SampleMetric data points
 fn otlp_data_to_data_points(data: &Option<Data>) -> Option<DataPoints> {
     match data {
@@ -351,6 +374,9 @@ fn otlp_number_data_points(otlp: &Vec<NumberDataPoint>) -> DataPoints {
                 },
                 None => Value::Null,
             },
+            // Zero means the start time is unknown.
+            start_time_unix_nano: (point.start_time_unix_nano != 0)
+                .then_some(point.start_time_unix_nano),
             flags: point.flags,
             exemplars,
             live_check_result: None,
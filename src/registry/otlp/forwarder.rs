@@ -0,0 +1,217 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Proxy mode: forwards the export requests received by the OTLP listener,
+//! unchanged or translated to a version of a telemetry schema, to an upstream
+//! OTLP gRPC endpoint (e.g. the real collector). The upstream must speak
+//! OTLP/gRPC, including for the requests received over OTLP/HTTP.
+//!
+//! The headers of the incoming requests (e.g. `authorization` or a tenant id)
+//! are propagated to the upstream as gRPC metadata, except the ones describing
+//! the incoming connection or body, which the gRPC client sets itself.
+//!
+//! A request is forwarded before the client is answered, so the client gets
+//! the upstream response (or error) and nothing is lost when the listener is
+//! stopped. The live-check runs on a copy of every request, whether or not the
+//! upstream accepted it.
+
+use std::sync::Arc;
+
+use tonic::codegen::http::HeaderMap;
+use tonic::metadata::MetadataMap;
+use tonic::transport::{Channel, Endpoint};
+use tonic::{Request, Status};
+
+use super::grpc_stubs::proto::collector::logs::v1::logs_service_client::LogsServiceClient;
+use super::grpc_stubs::proto::collector::logs::v1::{
+    ExportLogsServiceRequest, ExportLogsServiceResponse,
+};
+use super::grpc_stubs::proto::collector::metrics::v1::metrics_service_client::MetricsServiceClient;
+use super::grpc_stubs::proto::collector::metrics::v1::{
+    ExportMetricsServiceRequest, ExportMetricsServiceResponse,
+};
+use super::grpc_stubs::proto::collector::profiles::v1development::profiles_service_client::ProfilesServiceClient;
+use super::grpc_stubs::proto::collector::profiles::v1development::{
+    ExportProfilesServiceRequest, ExportProfilesServiceResponse,
+};
+use super::grpc_stubs::proto::collector::trace::v1::trace_service_client::TraceServiceClient;
+use super::grpc_stubs::proto::collector::trace::v1::{
+    ExportTraceServiceRequest, ExportTraceServiceResponse,
+};
+use super::translator::OtlpTranslator;
+use super::{Error, OtlpRequest};
+
+/// Parse the upstream endpoint, e.g. `http://collector:4317`.
+pub(super) fn parse_endpoint(endpoint: &str) -> Result<Endpoint, Error> {
+    Endpoint::from_shared(endpoint.to_owned()).map_err(|e| Error::OtlpError {
+        error: format!("Invalid OTLP forward endpoint `{endpoint}`: {e}"),
+    })
+}
+
+/// Headers of the incoming connection or body, never propagated upstream.
+/// The `grpc-*` headers are not propagated either.
+const NON_PROPAGATED_HEADERS: [&str; 12] = [
+    "accept-encoding",
+    "connection",
+    "content-encoding",
+    "content-length",
+    "content-type",
+    "host",
+    "keep-alive",
+    "proxy-connection",
+    "te",
+    "transfer-encoding",
+    "upgrade",
+    "user-agent",
+];
+
+/// The gRPC metadata propagated to the upstream for an incoming request with
+/// the given headers (gRPC metadata or HTTP headers).
+pub(super) fn propagated_metadata(headers: &HeaderMap) -> MetadataMap {
+    let mut propagated = HeaderMap::new();
+    for (name, value) in headers {
+        let name_str = name.as_str();
+        if !NON_PROPAGATED_HEADERS.contains(&name_str) && !name_str.starts_with("grpc-") {
+            _ = propagated.append(name.clone(), value.clone());
+        }
+    }
+    MetadataMap::from_headers(propagated)
+}
+
+/// Build the upstream request with the propagated metadata.
+fn upstream_request<T>(message: T, metadata: MetadataMap) -> Request<T> {
+    let mut request = Request::new(message);
+    *request.metadata_mut() = metadata;
+    request
+}
+
+/// gRPC clients of the upstream OTLP endpoint.
+#[derive(Clone)]
+pub(super) struct OtlpForwarder {
+    logs: LogsServiceClient<Channel>,
+    metrics: MetricsServiceClient<Channel>,
+    traces: TraceServiceClient<Channel>,
+    profiles: ProfilesServiceClient<Channel>,
+    /// Translates the requests before they are forwarded.
+    translator: Option<Arc<OtlpTranslator>>,
+}
+
+impl OtlpForwarder {
+    /// Create the clients. The connection is established lazily, so the
+    /// upstream doesn't need to be reachable when the listener starts.
+    ///
+    /// Must be called from a Tokio runtime.
+    pub(super) fn new(endpoint: &Endpoint, translator: Option<Arc<OtlpTranslator>>) -> Self {
+        let channel = endpoint.connect_lazy();
+        Self {
+            logs: LogsServiceClient::new(channel.clone()),
+            metrics: MetricsServiceClient::new(channel.clone()),
+            traces: TraceServiceClient::new(channel.clone()),
+            profiles: ProfilesServiceClient::new(channel),
+            translator,
+        }
+    }
+
+    pub(super) async fn export_logs(
+        &self,
+        mut request: ExportLogsServiceRequest,
+        metadata: MetadataMap,
+    ) -> Result<ExportLogsServiceResponse, Status> {
+        if let Some(translator) = &self.translator {
+            translator.translate_logs(&mut request);
+        }
+        Ok(self
+            .logs
+            .clone()
+            .export(upstream_request(request, metadata))
+            .await?
+            .into_inner())
+    }
+
+    pub(super) async fn export_metrics(
+        &self,
+        mut request: ExportMetricsServiceRequest,
+        metadata: MetadataMap,
+    ) -> Result<ExportMetricsServiceResponse, Status> {
+        if let Some(translator) = &self.translator {
+            translator.translate_metrics(&mut request);
+        }
+        Ok(self
+            .metrics
+            .clone()
+            .export(upstream_request(request, metadata))
+            .await?
+            .into_inner())
+    }
+
+    pub(super) async fn export_traces(
+        &self,
+        mut request: ExportTraceServiceRequest,
+        metadata: MetadataMap,
+    ) -> Result<ExportTraceServiceResponse, Status> {
+        if let Some(translator) = &self.translator {
+            translator.translate_traces(&mut request);
+        }
+        Ok(self
+            .traces
+            .clone()
+            .export(upstream_request(request, metadata))
+            .await?
+            .into_inner())
+    }
+
+    pub(super) async fn export_profiles(
+        &self,
+        request: ExportProfilesServiceRequest,
+        metadata: MetadataMap,
+    ) -> Result<ExportProfilesServiceResponse, Status> {
+        Ok(self
+            .profiles
+            .clone()
+            .export(upstream_request(request, metadata))
+            .await?
+            .into_inner())
+    }
+
+    /// Forward a decoded request with the propagated metadata, ignoring the
+    /// upstream response body. Stop signals and errors are not forwarded.
+    pub(super) async fn forward(
+        &self,
+        request: &OtlpRequest,
+        metadata: MetadataMap,
+    ) -> Result<(), Status> {
+        match request {
+            OtlpRequest::Logs(r) => self.export_logs(r.clone(), metadata).await.map(drop),
+            OtlpRequest::Metrics(r) => self.export_metrics(r.clone(), metadata).await.map(drop),
+            OtlpRequest::Traces(r) => self.export_traces(r.clone(), metadata).await.map(drop),
+            OtlpRequest::Profiles(r) => self.export_profiles(r.clone(), metadata).await.map(drop),
+            OtlpRequest::Error(_) | OtlpRequest::Stop(_) => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_propagated_metadata() {
+        let mut headers = HeaderMap::new();
+        for (name, value) in [
+            ("authorization", "Bearer token"),
+            ("x-scope-orgid", "tenant-1"),
+            ("content-type", "application/grpc"),
+            ("content-length", "42"),
+            ("host", "localhost:4317"),
+            ("te", "trailers"),
+            ("grpc-encoding", "gzip"),
+            ("grpc-timeout", "10S"),
+        ] {
+            _ = headers.insert(name, value.parse().unwrap());
+        }
+
+        let metadata = propagated_metadata(&headers).into_headers();
+        assert_eq!(metadata.len(), 2);
+        assert_eq!(metadata["authorization"], "Bearer token");
+        assert_eq!(metadata["x-scope-orgid"], "tenant-1");
+    }
+}
@@ -0,0 +1,188 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! OTLP/HTTP receiver.
+//!
+//! Serves the `/v1/traces`, `/v1/metrics`, `/v1/logs` and `/v1/profiles`
+//! export endpoints, accepting binary protobuf and OTLP/JSON bodies
+//! (optionally gzip-compressed). Decoded requests are sent to the same
+//! channel as the gRPC services, and forwarded upstream in proxy mode.
+
+use std::time::Instant;
+
+use axum::body::Bytes;
+use axum::extract::{DefaultBodyLimit, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::Router;
+use log::warn;
+use tokio::net::TcpListener;
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinSet;
+
+use super::codec::{decode_request, gunzip, OtlpEncoding, OtlpSignal};
+use super::forwarder::{propagated_metadata, OtlpForwarder};
+use super::{AdminMetrics, OtlpRequest};
+
+/// Maximum accepted size of an (uncompressed) export request body.
+const MAX_BODY_SIZE: usize = 64 * 1024 * 1024;
+
+/// Shared state for the OTLP/HTTP handlers.
+#[derive(Clone)]
+struct ReceiverState {
+    tx: mpsc::Sender<OtlpRequest>,
+    activity_tx: watch::Sender<Instant>,
+    forwarder: Option<OtlpForwarder>,
+    metrics: AdminMetrics,
+}
+
+/// Spawn the OTLP/HTTP server on an already bound listener.
+///
+/// Note: All the tasks created in this function are recorded into a
+/// JoinSet. `JoinSet::spawn` returns a `AbortHandle` that we can
+/// ignore as we don't need to abort these tasks.
+pub(super) fn spawn_otlp_http_handler(
+    listener: TcpListener,
+    tx: mpsc::Sender<OtlpRequest>,
+    activity_tx: watch::Sender<Instant>,
+    forwarder: Option<OtlpForwarder>,
+    metrics: AdminMetrics,
+    tasks: &mut JoinSet<()>,
+) {
+    let state = ReceiverState {
+        tx,
+        activity_tx,
+        forwarder,
+        metrics,
+    };
+    let mut app = Router::new();
+    for signal in [
+        OtlpSignal::Traces,
+        OtlpSignal::Metrics,
+        OtlpSignal::Logs,
+        OtlpSignal::Profiles,
+    ] {
+        app = app.route(
+            signal.http_path(),
+            post(
+                move |state: State<ReceiverState>, headers: HeaderMap, body: Bytes| {
+                    export_handler(state, signal, headers, body)
+                },
+            ),
+        );
+    }
+    let app = app
+        .layer(DefaultBodyLimit::max(MAX_BODY_SIZE))
+        .with_state(state);
+
+    let _ = tasks.spawn(async move {
+        if let Err(e) = axum::serve(listener, app).await {
+            warn!("OTLP/HTTP server error: {e}");
+        }
+    });
+}
+
+/// POST /v1/{signal} — decodes an export request and forwards it to the
+/// OTLP request channel.
+async fn export_handler(
+    State(state): State<ReceiverState>,
+    signal: OtlpSignal,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+    let Some(encoding) = OtlpEncoding::from_content_type(content_type) else {
+        return (
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            format!(
+                "Unsupported content type `{content_type}`, expected \
+                 `application/x-protobuf` or `application/json`"
+            ),
+        )
+            .into_response();
+    };
+
+    let content_encoding = headers
+        .get(header::CONTENT_ENCODING)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("identity")
+        .trim()
+        .to_lowercase();
+    let decompressed;
+    let payload = match content_encoding.as_str() {
+        "identity" | "" => body.as_ref(),
+        "gzip" => match gunzip(&body) {
+            Ok(bytes) => {
+                decompressed = bytes;
+                decompressed.as_slice()
+            }
+            Err(e) => return (StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+        },
+        other => {
+            return (
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                format!("Unsupported content encoding `{other}`"),
+            )
+                .into_response();
+        }
+    };
+
+    let request = match decode_request(signal, payload, encoding) {
+        Ok(request) => request,
+        Err(e) => return (StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+    };
+
+    // Update last activity time
+    if state.activity_tx.send(Instant::now()).is_err() {
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to update activity timestamp",
+        )
+            .into_response();
+    }
+
+    // In proxy mode, an upstream failure is reported to the client.
+    let upstream = match &state.forwarder {
+        Some(forwarder) => {
+            forwarder
+                .forward(&request, propagated_metadata(&headers))
+                .await
+        }
+        None => Ok(()),
+    };
+
+    if let Err(e) = state.metrics.send_request(&state.tx, request).await {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            format!("Channel closed: {e}"),
+        )
+            .into_response();
+    }
+
+    if let Err(status) = upstream {
+        return (
+            StatusCode::BAD_GATEWAY,
+            format!(
+                "Failed to forward the request upstream: {}",
+                status.message()
+            ),
+        )
+            .into_response();
+    }
+
+    // All the Export*ServiceResponse messages are empty on full success, so
+    // their encoding doesn't depend on the signal.
+    let body: &'static [u8] = match encoding {
+        OtlpEncoding::Protobuf => b"",
+        OtlpEncoding::Json => b"{}",
+    };
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, encoding.content_type())],
+        body,
+    )
+        .into_response()
+}
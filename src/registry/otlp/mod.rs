@@ -2,8 +2,14 @@
 
 //! A basic OTLP receiver integrated into Weaver.
 
+pub mod codec;
 pub mod conversion;
+mod forwarder;
+mod http_receiver;
+pub mod otlp_file_ingester;
 pub mod otlp_ingester;
+pub mod recorder;
+pub mod translator;
 
 use axum::extract::State;
 use axum::http::{header, StatusCode};
@@ -33,6 +39,7 @@ use grpc_stubs::proto::collector::trace::v1::{
 use log::warn;
 use miette::Diagnostic;
 use serde::Serialize;
+use std::collections::VecDeque;
 use std::fmt::{Display, Formatter};
 use std::net::{AddrParseError, SocketAddr};
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -45,6 +52,9 @@ use tokio::time::sleep;
 use tonic::codegen::tokio_stream::wrappers::TcpListenerStream;
 use tonic::transport::Server;
 use tonic::{Request, Response, Status};
+
+use forwarder::{propagated_metadata, OtlpForwarder};
+use translator::OtlpTranslator;
 use weaver_common::diagnostic::{DiagnosticMessage, DiagnosticMessages};
 
 /// How long `/stop` waits for the report, and how long the admin server's
@@ -78,6 +88,8 @@ pub struct ShutdownCoordinator {
     /// `Option<(Sender, Receiver)>` because each half is consumed
     /// independently — by a different thread, at a different time.
     admin_done: Arc<Mutex<(Option<oneshot::Sender<()>>, Option<oneshot::Receiver<()>>)>>,
+    /// State served by the admin `/metrics` endpoint.
+    metrics: AdminMetrics,
 }
 
 impl ShutdownCoordinator {
@@ -91,6 +103,7 @@ impl ShutdownCoordinator {
             report_slot: Arc::new(Mutex::new(None)),
             admin_shutdown_trigger_slot: Arc::new(Mutex::new(Some(shutdown_tx))),
             admin_done: Arc::new(Mutex::new((Some(admin_done_tx), Some(admin_done_rx)))),
+            metrics: AdminMetrics::default(),
         };
         (coordinator, shutdown_rx)
     }
@@ -154,6 +167,74 @@ impl ShutdownCoordinator {
             let _ = rx.blocking_recv();
         }
     }
+
+    /// Replaces the statistics served by `/metrics` (Prometheus text format).
+    pub fn publish_metrics(&self, statistics: String) {
+        *self.metrics.statistics.lock().expect(LOCK_POISONED_MSG) = statistics;
+    }
+}
+
+/// State behind the admin `/metrics` endpoint: the latest statistics
+/// published by live-check, and the reception time of the export requests
+/// that are still waiting in the channel (ingestion lag).
+#[derive(Clone, Default)]
+struct AdminMetrics {
+    statistics: Arc<Mutex<String>>,
+    pending: Arc<Mutex<VecDeque<Instant>>>,
+}
+
+impl AdminMetrics {
+    /// Sends an export request to the channel and records its reception
+    /// time. The time is recorded before sending, so the request can't be
+    /// dequeued before, and is removed if the request couldn't be sent.
+    async fn send_request(
+        &self,
+        tx: &mpsc::Sender<OtlpRequest>,
+        request: OtlpRequest,
+    ) -> Result<(), mpsc::error::SendError<OtlpRequest>> {
+        let received = Instant::now();
+        self.pending
+            .lock()
+            .expect(LOCK_POISONED_MSG)
+            .push_back(received);
+        let result = tx.send(request).await;
+        if result.is_err() {
+            let mut pending = self.pending.lock().expect(LOCK_POISONED_MSG);
+            // A dequeued request may have popped this time in place of its own.
+            match pending.iter().rposition(|time| *time == received) {
+                Some(index) => _ = pending.remove(index),
+                None => _ = pending.pop_front(),
+            }
+        }
+        result
+    }
+
+    /// Called when an export request is taken from the channel.
+    fn request_dequeued(&self) {
+        let _ = self.pending.lock().expect(LOCK_POISONED_MSG).pop_front();
+    }
+
+    /// How long the oldest request still in the channel has been waiting.
+    fn ingestion_lag(&self) -> Duration {
+        self.pending
+            .lock()
+            .expect(LOCK_POISONED_MSG)
+            .front()
+            .map_or(Duration::ZERO, Instant::elapsed)
+    }
+
+    /// The statistics followed by the ingestion lag, in Prometheus text format.
+    fn render(&self) -> String {
+        let mut text = self.statistics.lock().expect(LOCK_POISONED_MSG).clone();
+        text.push_str(&format!(
+            "# HELP weaver_live_check_ingestion_lag_seconds How long the oldest received \
+             request not yet checked has been waiting.\n\
+             # TYPE weaver_live_check_ingestion_lag_seconds gauge\n\
+             weaver_live_check_ingestion_lag_seconds {}\n",
+            self.ingestion_lag().as_secs_f64()
+        ));
+        text
+    }
 }
 
 /// Expose the OTLP gRPC services.
@@ -307,58 +388,43 @@ impl Display for StopSignal {
     }
 }
 
-/// Start an OTLP receiver listening to a specific port on all IPv4 interfaces
-/// and return an iterator of received OTLP requests and a shutdown coordinator.
+/// Start an OTLP receiver (gRPC and, unless `http_endpoint` is `None`, HTTP)
+/// listening to specific addresses and ports and return an iterator of
+/// received OTLP requests and a shutdown coordinator.
 ///
 /// The `ShutdownCoordinator` sends the report back through `/stop`, and lets
 /// the caller wait for the admin server to finish delivering it before exiting.
 ///
+/// When `forward_endpoint` is set, every export request is also forwarded to
+/// this upstream OTLP gRPC endpoint (proxy mode), unchanged unless a
+/// `translator` is set. The requests of the returned iterator are never
+/// translated.
+///
 /// This function guarantees that the OTLP server is started and ready when the
 /// result is Ok(iterator).
 pub fn listen_otlp_requests(
     grpc_addr: &str,
     grpc_port: u16,
+    http_endpoint: Option<(&str, u16)>,
+    forward_endpoint: Option<&str>,
+    translator: Option<Arc<OtlpTranslator>>,
     admin_port: u16,
     inactivity_timeout: Duration,
 ) -> Result<(impl Iterator<Item = OtlpRequest>, ShutdownCoordinator), Error> {
-    let addr: SocketAddr =
-        format!("{grpc_addr}:{grpc_port}")
-            .parse()
-            .map_err(|e: AddrParseError| Error::OtlpError {
-                error: e.to_string(),
-            })?;
-
-    let listener = std::net::TcpListener::bind(addr).map_err(|e| Error::OtlpError {
-        error: e.to_string(),
-    })?;
-    listener
-        .set_nonblocking(true)
-        .map_err(|e| Error::OtlpError {
-            error: e.to_string(),
-        })?;
+    let forward_endpoint = forward_endpoint
+        .map(forwarder::parse_endpoint)
+        .transpose()?;
+    let listener = bind_listener(grpc_addr, grpc_port)?;
+    let http_listener = http_endpoint
+        .map(|(http_addr, http_port)| bind_listener(http_addr, http_port))
+        .transpose()?;
 
     let (tx, rx) = mpsc::channel(100);
     let stop_tx = tx.clone();
     // Create a watch channel for the last activity timestamp
     let (activity_tx, activity_rx) = watch::channel(Instant::now());
     let (coordinator, admin_shutdown_rx) = ShutdownCoordinator::new();
-    let logs_service = LogsServiceImpl {
-        tx: tx.clone(),
-        activity_tx: activity_tx.clone(),
-    };
-    let metrics_service = MetricsServiceImpl {
-        tx: tx.clone(),
-        activity_tx: activity_tx.clone(),
-    };
-    let trace_service = TraceServiceImpl {
-        tx: tx.clone(),
-        activity_tx: activity_tx.clone(),
-    };
-    let profiles_service = ProfilesServiceImpl {
-        tx: tx.clone(),
-        activity_tx: activity_tx.clone(),
-    };
-
+    let metrics = coordinator.metrics.clone();
     let (ready_tx, ready_rx) = oneshot::channel();
 
     // Start an OS thread and run a single threaded Tokio runtime inside.
@@ -373,6 +439,35 @@ pub fn listen_otlp_requests(
             .block_on(async {
                 let mut tasks = JoinSet::new();
 
+                // The upstream clients need a runtime, so they are created here.
+                let forwarder = forward_endpoint
+                    .as_ref()
+                    .map(|endpoint| OtlpForwarder::new(endpoint, translator));
+                let logs_service = LogsServiceImpl {
+                    tx: tx.clone(),
+                    activity_tx: activity_tx.clone(),
+                    forwarder: forwarder.clone(),
+                    metrics: metrics.clone(),
+                };
+                let metrics_service = MetricsServiceImpl {
+                    tx: tx.clone(),
+                    activity_tx: activity_tx.clone(),
+                    forwarder: forwarder.clone(),
+                    metrics: metrics.clone(),
+                };
+                let trace_service = TraceServiceImpl {
+                    tx: tx.clone(),
+                    activity_tx: activity_tx.clone(),
+                    forwarder: forwarder.clone(),
+                    metrics: metrics.clone(),
+                };
+                let profiles_service = ProfilesServiceImpl {
+                    tx: tx.clone(),
+                    activity_tx: activity_tx.clone(),
+                    forwarder: forwarder.clone(),
+                    metrics: metrics.clone(),
+                };
+
                 // Spawn tasks to handle different stop signals
                 spawn_stop_signal_handlers(stop_tx.clone(), &mut tasks);
                 spawn_http_admin_handler(
@@ -393,6 +488,19 @@ pub fn listen_otlp_requests(
                     );
                 }
 
+                if let Some(http_listener) = http_listener {
+                    let tokio_http_listener = TcpListener::from_std(http_listener)
+                        .expect("Failed to convert std listener to tokio listener");
+                    http_receiver::spawn_otlp_http_handler(
+                        tokio_http_listener,
+                        tx.clone(),
+                        activity_tx.clone(),
+                        forwarder.clone(),
+                        metrics.clone(),
+                        &mut tasks,
+                    );
+                }
+
                 let tokio_listener = TcpListener::from_std(listener)
                     .expect("Failed to convert std listener to tokio listener");
                 let inbound = TcpListenerStream::new(tokio_listener);
@@ -427,7 +535,32 @@ pub fn listen_otlp_requests(
         error: format!("OTLP server dropped before signaling readiness (error: {e})"),
     })?;
 
-    Ok((SyncReceiver { receiver: rx }, coordinator))
+    let metrics = coordinator.metrics.clone();
+    let requests = SyncReceiver { receiver: rx }.inspect(move |request| {
+        if !matches!(request, OtlpRequest::Stop(_) | OtlpRequest::Error(_)) {
+            metrics.request_dequeued();
+        }
+    });
+    Ok((requests, coordinator))
+}
+
+/// Bind a non-blocking TCP listener to the given address and port.
+fn bind_listener(addr: &str, port: u16) -> Result<std::net::TcpListener, Error> {
+    let addr: SocketAddr = format!("{addr}:{port}")
+        .parse()
+        .map_err(|e: AddrParseError| Error::OtlpError {
+            error: e.to_string(),
+        })?;
+
+    let listener = std::net::TcpListener::bind(addr).map_err(|e| Error::OtlpError {
+        error: e.to_string(),
+    })?;
+    listener
+        .set_nonblocking(true)
+        .map_err(|e| Error::OtlpError {
+            error: e.to_string(),
+        })?;
+    Ok(listener)
 }
 
 /// Spawn tasks to handle CTRL+C and SIGHUP signals.
@@ -477,6 +610,18 @@ async fn health_handler() -> impl IntoResponse {
     Json(serde_json::json!({"status": "ready"}))
 }
 
+/// GET /metrics — the live-check statistics and the ingestion lag, in the
+/// Prometheus text exposition format.
+async fn metrics_handler(State(state): State<AdminState>) -> impl IntoResponse {
+    (
+        [(
+            header::CONTENT_TYPE,
+            "text/plain; version=0.0.4; charset=utf-8",
+        )],
+        state.coordinator.metrics.render(),
+    )
+}
+
 /// POST /stop — sends a stop signal. If `--output=http` was set, waits for
 /// the report and returns it as the response body; otherwise returns 200
 /// immediately.
@@ -526,7 +671,7 @@ async fn stop_handler(State(state): State<AdminState>) -> impl IntoResponse {
     }
 }
 
-/// Spawn a minimal HTTP server that handles admin endpoints (/health, /stop).
+/// Spawn a minimal HTTP server that handles admin endpoints (/health, /metrics, /stop).
 ///
 /// Note: All the tasks created in this function are recorded into a
 /// JoinSet. `JoinSet::spawn` returns a `AbortHandle` that we can
@@ -551,6 +696,7 @@ async fn spawn_http_admin_handler(
 
             let app = Router::new()
                 .route("/health", get(health_handler))
+                .route("/metrics", get(metrics_handler))
                 .route("/stop", post(stop_handler))
                 .with_state(state);
 
@@ -650,11 +796,12 @@ impl<T> Iterator for SyncReceiver<T> {
 
 async fn forward_to_channel<T>(
     sender: &mpsc::Sender<OtlpRequest>,
+    metrics: &AdminMetrics,
     otlp_request: T,
     wrapper: fn(T) -> OtlpRequest,
 ) -> Result<(), Status> {
-    sender
-        .send(wrapper(otlp_request))
+    metrics
+        .send_request(sender, wrapper(otlp_request))
         .await
         .map_err(|e| Status::resource_exhausted(format!("Channel full: {e}")))
 }
@@ -662,18 +809,26 @@ async fn forward_to_channel<T>(
 pub struct LogsServiceImpl {
     tx: mpsc::Sender<OtlpRequest>,
     activity_tx: watch::Sender<Instant>,
+    forwarder: Option<OtlpForwarder>,
+    metrics: AdminMetrics,
 }
 pub struct MetricsServiceImpl {
     tx: mpsc::Sender<OtlpRequest>,
     activity_tx: watch::Sender<Instant>,
+    forwarder: Option<OtlpForwarder>,
+    metrics: AdminMetrics,
 }
 pub struct TraceServiceImpl {
     tx: mpsc::Sender<OtlpRequest>,
     activity_tx: watch::Sender<Instant>,
+    forwarder: Option<OtlpForwarder>,
+    metrics: AdminMetrics,
 }
 pub struct ProfilesServiceImpl {
     tx: mpsc::Sender<OtlpRequest>,
     activity_tx: watch::Sender<Instant>,
+    forwarder: Option<OtlpForwarder>,
+    metrics: AdminMetrics,
 }
 
 #[tonic::async_trait]
@@ -687,10 +842,25 @@ impl LogsService for LogsServiceImpl {
             .send(Instant::now())
             .map_err(|_| Status::internal("Failed to update activity timestamp"))?;
 
-        forward_to_channel(&self.tx, request.into_inner(), OtlpRequest::Logs).await?;
-        Ok(Response::new(ExportLogsServiceResponse {
-            partial_success: None,
-        }))
+        let (metadata, _, request) = request.into_parts();
+        // In proxy mode, the client gets the upstream response.
+        let upstream = match &self.forwarder {
+            Some(forwarder) => Some(
+                forwarder
+                    .export_logs(
+                        request.clone(),
+                        propagated_metadata(&metadata.into_headers()),
+                    )
+                    .await,
+            ),
+            None => None,
+        };
+        forward_to_channel(&self.tx, &self.metrics, request, OtlpRequest::Logs).await?;
+        Ok(Response::new(upstream.transpose()?.unwrap_or(
+            ExportLogsServiceResponse {
+                partial_success: None,
+            },
+        )))
     }
 }
 
@@ -705,10 +875,25 @@ impl MetricsService for MetricsServiceImpl {
             .send(Instant::now())
             .map_err(|_| Status::internal("Failed to update activity timestamp"))?;
 
-        forward_to_channel(&self.tx, request.into_inner(), OtlpRequest::Metrics).await?;
-        Ok(Response::new(ExportMetricsServiceResponse {
-            partial_success: None,
-        }))
+        let (metadata, _, request) = request.into_parts();
+        // In proxy mode, the client gets the upstream response.
+        let upstream = match &self.forwarder {
+            Some(forwarder) => Some(
+                forwarder
+                    .export_metrics(
+                        request.clone(),
+                        propagated_metadata(&metadata.into_headers()),
+                    )
+                    .await,
+            ),
+            None => None,
+        };
+        forward_to_channel(&self.tx, &self.metrics, request, OtlpRequest::Metrics).await?;
+        Ok(Response::new(upstream.transpose()?.unwrap_or(
+            ExportMetricsServiceResponse {
+                partial_success: None,
+            },
+        )))
     }
 }
 
@@ -723,10 +908,25 @@ impl TraceService for TraceServiceImpl {
             .send(Instant::now())
             .map_err(|_| Status::internal("Failed to update activity timestamp"))?;
 
-        forward_to_channel(&self.tx, request.into_inner(), OtlpRequest::Traces).await?;
-        Ok(Response::new(ExportTraceServiceResponse {
-            partial_success: None,
-        }))
+        let (metadata, _, request) = request.into_parts();
+        // In proxy mode, the client gets the upstream response.
+        let upstream = match &self.forwarder {
+            Some(forwarder) => Some(
+                forwarder
+                    .export_traces(
+                        request.clone(),
+                        propagated_metadata(&metadata.into_headers()),
+                    )
+                    .await,
+            ),
+            None => None,
+        };
+        forward_to_channel(&self.tx, &self.metrics, request, OtlpRequest::Traces).await?;
+        Ok(Response::new(upstream.transpose()?.unwrap_or(
+            ExportTraceServiceResponse {
+                partial_success: None,
+            },
+        )))
     }
 }
 
@@ -740,10 +940,25 @@ impl ProfilesService for ProfilesServiceImpl {
             .send(Instant::now())
             .map_err(|_| Status::internal("Failed to update activity timestamp"))?;
 
-        forward_to_channel(&self.tx, request.into_inner(), OtlpRequest::Profiles).await?;
-        Ok(Response::new(ExportProfilesServiceResponse {
-            partial_success: None,
-        }))
+        let (metadata, _, request) = request.into_parts();
+        // In proxy mode, the client gets the upstream response.
+        let upstream = match &self.forwarder {
+            Some(forwarder) => Some(
+                forwarder
+                    .export_profiles(
+                        request.clone(),
+                        propagated_metadata(&metadata.into_headers()),
+                    )
+                    .await,
+            ),
+            None => None,
+        };
+        forward_to_channel(&self.tx, &self.metrics, request, OtlpRequest::Profiles).await?;
+        Ok(Response::new(upstream.transpose()?.unwrap_or(
+            ExportProfilesServiceResponse {
+                partial_success: None,
+            },
+        )))
     }
 }
 
@@ -762,8 +977,16 @@ mod tests {
         let admin_port = reserve_test_port();
         let inactivity_timeout = Duration::from_secs(1);
 
-        let (mut receiver, _report_sender) =
-            listen_otlp_requests("127.0.0.1", grpc_port, admin_port, inactivity_timeout).unwrap();
+        let (mut receiver, _report_sender) = listen_otlp_requests(
+            "127.0.0.1",
+            grpc_port,
+            None,
+            None,
+            None,
+            admin_port,
+            inactivity_timeout,
+        )
+        .unwrap();
         let grpc_endpoint = format!("http://127.0.0.1:{grpc_port}");
         let expected_metrics_count = 3;
         let expected_logs_count = 4;
@@ -855,8 +1078,16 @@ mod tests {
         let admin_port = reserve_test_port();
         let inactivity_timeout = Duration::from_secs(5);
 
-        let (mut receiver, report_sender) =
-            listen_otlp_requests("127.0.0.1", grpc_port, admin_port, inactivity_timeout).unwrap();
+        let (mut receiver, report_sender) = listen_otlp_requests(
+            "127.0.0.1",
+            grpc_port,
+            None,
+            None,
+            None,
+            admin_port,
+            inactivity_timeout,
+        )
+        .unwrap();
 
         // Enable report-via-HTTP mode (simulates --output http)
         report_sender.set_expect_report(true);
@@ -899,8 +1130,16 @@ mod tests {
         let admin_port = reserve_test_port();
         let inactivity_timeout = Duration::from_secs(5);
 
-        let (mut receiver, _report_sender) =
-            listen_otlp_requests("127.0.0.1", grpc_port, admin_port, inactivity_timeout).unwrap();
+        let (mut receiver, _report_sender) = listen_otlp_requests(
+            "127.0.0.1",
+            grpc_port,
+            None,
+            None,
+            None,
+            admin_port,
+            inactivity_timeout,
+        )
+        .unwrap();
 
         // expect_report defaults to false — /stop should return 200 immediately
 
@@ -934,8 +1173,16 @@ mod tests {
         let admin_port = reserve_test_port();
         let inactivity_timeout = Duration::from_secs(5);
 
-        let (_receiver, _report_sender) =
-            listen_otlp_requests("127.0.0.1", grpc_port, admin_port, inactivity_timeout).unwrap();
+        let (_receiver, _report_sender) = listen_otlp_requests(
+            "127.0.0.1",
+            grpc_port,
+            None,
+            None,
+            None,
+            admin_port,
+            inactivity_timeout,
+        )
+        .unwrap();
 
         // Give the server a little time to finish binding the port.
         thread::sleep(Duration::from_millis(200));
@@ -952,6 +1199,375 @@ mod tests {
         assert_eq!(response2.status(), 200);
     }
 
+    #[test]
+    fn test_metrics_endpoint() {
+        use prost::Message;
+
+        let grpc_port = reserve_test_port();
+        let http_port = reserve_test_port();
+        let admin_port = reserve_test_port();
+        let inactivity_timeout = Duration::from_secs(5);
+
+        let (mut receiver, coordinator) = listen_otlp_requests(
+            "127.0.0.1",
+            grpc_port,
+            Some(("127.0.0.1", http_port)),
+            None,
+            None,
+            admin_port,
+            inactivity_timeout,
+        )
+        .unwrap();
+        thread::sleep(Duration::from_millis(200));
+
+        coordinator
+            .publish_metrics("weaver_live_check_samples_total{type=\"span\"} 3\n".to_owned());
+
+        // A request waiting in the channel shows up as ingestion lag.
+        let _ = ureq::post(&format!("http://127.0.0.1:{http_port}/v1/metrics"))
+            .header("Content-Type", "application/x-protobuf")
+            .send(ExportMetricsServiceRequest::default().encode_to_vec())
+            .expect("HTTP POST to /v1/metrics failed");
+        thread::sleep(Duration::from_millis(50));
+
+        let url = format!("http://127.0.0.1:{admin_port}/metrics");
+        let response = ureq::get(&url).call().expect("GET /metrics failed");
+        assert_eq!(response.status(), 200);
+        assert!(response
+            .headers()
+            .get("content-type")
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.starts_with("text/plain; version=0.0.4")));
+        let body = response.into_body().read_to_string().unwrap();
+        assert!(body.starts_with("weaver_live_check_samples_total{type=\"span\"} 3\n"));
+        let lag = |body: &str| -> f64 {
+            body.lines()
+                .find_map(|l| l.strip_prefix("weaver_live_check_ingestion_lag_seconds "))
+                .expect("missing ingestion lag")
+                .parse()
+                .unwrap()
+        };
+        assert!(lag(&body) > 0.0);
+
+        // Once the request is consumed, the lag drops back to zero.
+        assert!(matches!(receiver.next(), Some(OtlpRequest::Metrics(_))));
+        let body = ureq::get(&url)
+            .call()
+            .unwrap()
+            .into_body()
+            .read_to_string()
+            .unwrap();
+        assert_eq!(lag(&body), 0.0);
+    }
+
+    #[test]
+    fn test_otlp_http_receiver() {
+        use flate2::write::GzEncoder;
+        use prost::Message;
+        use std::io::Write;
+
+        let grpc_port = reserve_test_port();
+        let http_port = reserve_test_port();
+        let admin_port = reserve_test_port();
+        let inactivity_timeout = Duration::from_secs(5);
+
+        let (mut receiver, _report_sender) = listen_otlp_requests(
+            "127.0.0.1",
+            grpc_port,
+            Some(("127.0.0.1", http_port)),
+            None,
+            None,
+            admin_port,
+            inactivity_timeout,
+        )
+        .unwrap();
+
+        // Binary protobuf
+        let url = format!("http://127.0.0.1:{http_port}/v1/metrics");
+        let response = ureq::post(&url)
+            .header("Content-Type", "application/x-protobuf")
+            .send(ExportMetricsServiceRequest::default().encode_to_vec())
+            .expect("HTTP POST to /v1/metrics failed");
+        assert_eq!(response.status(), 200);
+        assert!(matches!(receiver.next(), Some(OtlpRequest::Metrics(_))));
+
+        // OTLP/JSON
+        let url = format!("http://127.0.0.1:{http_port}/v1/logs");
+        let response = ureq::post(&url)
+            .header("Content-Type", "application/json")
+            .send(r#"{"resourceLogs":[{"scopeLogs":[{"logRecords":[{"eventName":"my.event"}]}]}]}"#)
+            .expect("HTTP POST to /v1/logs failed");
+        assert_eq!(response.status(), 200);
+        assert_eq!(
+            response.into_body().read_to_string().unwrap(),
+            "{}",
+            "JSON requests expect a JSON response"
+        );
+        match receiver.next() {
+            Some(OtlpRequest::Logs(logs)) => assert_eq!(
+                logs.resource_logs[0].scope_logs[0].log_records[0].event_name,
+                "my.event"
+            ),
+            other => panic!("Expected OtlpRequest::Logs, got {other:?}"),
+        }
+
+        // Gzip-compressed OTLP/JSON
+        let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder
+            .write_all(br#"{"resourceSpans":[{"scopeSpans":[{"spans":[{"name":"span"}]}]}]}"#)
+            .unwrap();
+        let url = format!("http://127.0.0.1:{http_port}/v1/traces");
+        let response = ureq::post(&url)
+            .header("Content-Type", "application/json")
+            .header("Content-Encoding", "gzip")
+            .send(encoder.finish().unwrap())
+            .expect("HTTP POST to /v1/traces failed");
+        assert_eq!(response.status(), 200);
+        match receiver.next() {
+            Some(OtlpRequest::Traces(traces)) => assert_eq!(
+                traces.resource_spans[0].scope_spans[0].spans[0].name,
+                "span"
+            ),
+            other => panic!("Expected OtlpRequest::Traces, got {other:?}"),
+        }
+
+        // Unsupported content type and invalid payload
+        let response = ureq::post(&url)
+            .config()
+            .http_status_as_error(false)
+            .build()
+            .header("Content-Type", "text/plain")
+            .send("hello")
+            .unwrap();
+        assert_eq!(response.status(), 415);
+        let response = ureq::post(&url)
+            .config()
+            .http_status_as_error(false)
+            .build()
+            .header("Content-Type", "application/json")
+            .send("not json")
+            .unwrap();
+        assert_eq!(response.status(), 400);
+    }
+
+    #[test]
+    fn test_otlp_forwarding_translated() {
+        let inactivity_timeout = Duration::from_secs(5);
+        let schema: weaver_otel_schema::TelemetrySchema = serde_yaml::from_str(
+            r#"file_format: 1.1.0
+schema_url: https://example.com/schemas/1.1.0
+versions:
+  1.1.0:
+    spans:
+      changes:
+        - rename_attributes:
+            attribute_map:
+              http.method: http.request.method
+  1.0.0: {}
+"#,
+        )
+        .unwrap();
+        let translator = Arc::new(OtlpTranslator::new(schema, None).unwrap());
+        let request = |key: &str, schema_url: &str| ExportTraceServiceRequest {
+            resource_spans: vec![grpc_stubs::proto::trace::v1::ResourceSpans {
+                scope_spans: vec![grpc_stubs::proto::trace::v1::ScopeSpans {
+                    spans: vec![grpc_stubs::proto::trace::v1::Span {
+                        attributes: vec![grpc_stubs::proto::common::v1::KeyValue {
+                            key: key.to_owned(),
+                            value: None,
+                        }],
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                }],
+                schema_url: schema_url.to_owned(),
+                ..Default::default()
+            }],
+        };
+
+        let upstream_port = reserve_test_port();
+        let (mut upstream, _upstream_report_sender) = listen_otlp_requests(
+            "127.0.0.1",
+            upstream_port,
+            None,
+            None,
+            None,
+            reserve_test_port(),
+            inactivity_timeout,
+        )
+        .unwrap();
+        let grpc_port = reserve_test_port();
+        let forward_endpoint = format!("http://127.0.0.1:{upstream_port}");
+        let (mut receiver, _report_sender) = listen_otlp_requests(
+            "127.0.0.1",
+            grpc_port,
+            None,
+            Some(&forward_endpoint),
+            Some(translator),
+            reserve_test_port(),
+            inactivity_timeout,
+        )
+        .unwrap();
+
+        // Only the forwarded request is translated
+        let sent = request("http.method", "https://example.com/schemas/1.0.0");
+        let original = sent.clone();
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(async move {
+                let mut client =
+                    TraceServiceClient::connect(format!("http://127.0.0.1:{grpc_port}"))
+                        .await
+                        .unwrap();
+                let _ = client.export(sent).await.unwrap();
+            });
+        match (receiver.next(), upstream.next()) {
+            (Some(OtlpRequest::Traces(local)), Some(OtlpRequest::Traces(forwarded))) => {
+                assert_eq!(local, original);
+                assert_eq!(
+                    forwarded,
+                    request("http.request.method", "https://example.com/schemas/1.1.0")
+                );
+            }
+            other => panic!("Expected the traces to be received twice, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_otlp_forwarding() {
+        let inactivity_timeout = Duration::from_secs(5);
+        let span = |name: &str| ExportTraceServiceRequest {
+            resource_spans: vec![grpc_stubs::proto::trace::v1::ResourceSpans {
+                scope_spans: vec![grpc_stubs::proto::trace::v1::ScopeSpans {
+                    spans: vec![grpc_stubs::proto::trace::v1::Span {
+                        name: name.to_owned(),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+        };
+
+        // A second receiver plays the upstream collector
+        let upstream_port = reserve_test_port();
+        let (mut upstream, _upstream_report_sender) = listen_otlp_requests(
+            "127.0.0.1",
+            upstream_port,
+            None,
+            None,
+            None,
+            reserve_test_port(),
+            inactivity_timeout,
+        )
+        .unwrap();
+
+        let grpc_port = reserve_test_port();
+        let http_port = reserve_test_port();
+        let forward_endpoint = format!("http://127.0.0.1:{upstream_port}");
+        let (mut receiver, _report_sender) = listen_otlp_requests(
+            "127.0.0.1",
+            grpc_port,
+            Some(("127.0.0.1", http_port)),
+            Some(&forward_endpoint),
+            None,
+            reserve_test_port(),
+            inactivity_timeout,
+        )
+        .unwrap();
+
+        // gRPC requests are forwarded unchanged, and still checked locally
+        let request = span("grpc.span");
+        let sent = request.clone();
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(async move {
+                let mut client =
+                    TraceServiceClient::connect(format!("http://127.0.0.1:{grpc_port}"))
+                        .await
+                        .unwrap();
+                let _ = client.export(sent).await.unwrap();
+            });
+        match (receiver.next(), upstream.next()) {
+            (Some(OtlpRequest::Traces(local)), Some(OtlpRequest::Traces(forwarded))) => {
+                assert_eq!(local, request);
+                assert_eq!(forwarded, request);
+            }
+            other => panic!("Expected the traces to be received twice, got {other:?}"),
+        }
+
+        // OTLP/HTTP requests are forwarded over gRPC
+        let response = ureq::post(&format!("http://127.0.0.1:{http_port}/v1/traces"))
+            .header("Content-Type", "application/json")
+            .send(r#"{"resourceSpans":[{"scopeSpans":[{"spans":[{"name":"http.span"}]}]}]}"#)
+            .expect("HTTP POST to /v1/traces failed");
+        assert_eq!(response.status(), 200);
+        assert!(matches!(receiver.next(), Some(OtlpRequest::Traces(_))));
+        match upstream.next() {
+            Some(OtlpRequest::Traces(forwarded)) => assert_eq!(forwarded, span("http.span")),
+            other => panic!("Expected OtlpRequest::Traces, got {other:?}"),
+        }
+
+        // An unreachable upstream is reported to the client, but the request
+        // is still checked
+        let http_port = reserve_test_port();
+        let unreachable_endpoint = format!("http://127.0.0.1:{}", reserve_test_port());
+        let (mut receiver, _report_sender) = listen_otlp_requests(
+            "127.0.0.1",
+            reserve_test_port(),
+            Some(("127.0.0.1", http_port)),
+            Some(&unreachable_endpoint),
+            None,
+            reserve_test_port(),
+            inactivity_timeout,
+        )
+        .unwrap();
+        let response = ureq::post(&format!("http://127.0.0.1:{http_port}/v1/traces"))
+            .config()
+            .http_status_as_error(false)
+            .build()
+            .header("Content-Type", "application/json")
+            .send(r#"{"resourceSpans":[]}"#)
+            .unwrap();
+        assert_eq!(response.status(), 502);
+        assert!(matches!(receiver.next(), Some(OtlpRequest::Traces(_))));
+
+        // An invalid endpoint is rejected upfront
+        assert!(listen_otlp_requests(
+            "127.0.0.1",
+            reserve_test_port(),
+            None,
+            Some("not a uri"),
+            None,
+            reserve_test_port(),
+            inactivity_timeout,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_ingestion_lag_ignores_unsent_requests() {
+        let metrics = AdminMetrics::default();
+        let (tx, rx) = mpsc::channel(1);
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        runtime
+            .block_on(metrics.send_request(&tx, OtlpRequest::Stop(StopSignal::Inactivity)))
+            .unwrap();
+        assert_eq!(metrics.pending.lock().unwrap().len(), 1);
+
+        drop(rx);
+        assert!(runtime
+            .block_on(metrics.send_request(&tx, OtlpRequest::Stop(StopSignal::Inactivity)))
+            .is_err());
+        assert_eq!(metrics.pending.lock().unwrap().len(), 1);
+    }
+
     #[test]
     fn test_deliver_report_noop_without_pending_request() {
         let (coordinator, _admin_shutdown_rx) = ShutdownCoordinator::new();
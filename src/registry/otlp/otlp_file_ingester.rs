@@ -0,0 +1,267 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! An ingester that replays OTLP export requests recorded in a file, e.g. by
+//! the OpenTelemetry Collector `file` exporter.
+//!
+//! Two layouts are supported:
+//! - OTLP/JSON lines: one export request per line. The signal of each line is
+//!   inferred from its top-level field, so a file may mix signals.
+//! - Binary protobuf: a sequence of export requests of a single signal, each
+//!   prefixed with its length as a 4-byte big-endian integer.
+//!
+//! The requests are converted into samples by the same code as the OTLP
+//! receiver (see [`OtlpIterator`]).
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use weaver_live_check::{Error, Ingester, Sample};
+
+use super::codec::{decode_json_request, decode_request, OtlpEncoding, OtlpSignal};
+use super::otlp_ingester::OtlpIterator;
+use super::OtlpRequest;
+
+/// The layout of a recorded OTLP file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OtlpFileFormat {
+    /// One OTLP/JSON export request per line.
+    JsonLines,
+    /// Length-prefixed binary protobuf export requests of the given signal.
+    Protobuf(OtlpSignal),
+}
+
+impl FromStr for OtlpFileFormat {
+    type Err = String;
+
+    /// Parses `otlp-json` or `otlp-proto-{traces,metrics,logs,profiles}`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "otlp-json" => Ok(OtlpFileFormat::JsonLines),
+            "otlp-proto-traces" => Ok(OtlpFileFormat::Protobuf(OtlpSignal::Traces)),
+            "otlp-proto-metrics" => Ok(OtlpFileFormat::Protobuf(OtlpSignal::Metrics)),
+            "otlp-proto-logs" => Ok(OtlpFileFormat::Protobuf(OtlpSignal::Logs)),
+            "otlp-proto-profiles" => Ok(OtlpFileFormat::Protobuf(OtlpSignal::Profiles)),
+            _ => Err(format!(
+                "Unknown OTLP file format `{s}`, expected otlp-json or \
+                 otlp-proto-{{traces,metrics,logs,profiles}}"
+            )),
+        }
+    }
+}
+
+/// An ingester that reads recorded OTLP export requests from a file.
+pub struct OtlpFileIngester {
+    path: PathBuf,
+    format: OtlpFileFormat,
+}
+
+impl OtlpFileIngester {
+    /// Create a new OtlpFileIngester
+    #[must_use]
+    pub fn new(path: &Path, format: OtlpFileFormat) -> Self {
+        OtlpFileIngester {
+            path: path.to_path_buf(),
+            format,
+        }
+    }
+
+    /// Read and decode all the export requests of the file.
+    pub(crate) fn read_requests(&self) -> Result<Vec<OtlpRequest>, Error> {
+        let content = fs::read(&self.path).map_err(|e| Error::IngestError {
+            error: format!("Failed to open file {}: {}", self.path.display(), e),
+        })?;
+
+        match self.format {
+            OtlpFileFormat::JsonLines => content
+                .split(|b| *b == b'\n')
+                .enumerate()
+                .filter(|(_, line)| !line.trim_ascii().is_empty())
+                .map(|(line_no, line)| {
+                    decode_json_request(line).map_err(|e| Error::IngestError {
+                        error: format!(
+                            "Failed to parse OTLP/JSON from file {} at line {}: {}",
+                            self.path.display(),
+                            line_no + 1,
+                            e
+                        ),
+                    })
+                })
+                .collect(),
+            OtlpFileFormat::Protobuf(signal) => {
+                let mut requests = Vec::new();
+                let mut remaining = content.as_slice();
+                while !remaining.is_empty() {
+                    let (len, rest) = remaining
+                        .split_first_chunk::<4>()
+                        .map(|(len, rest)| (u32::from_be_bytes(*len) as usize, rest))
+                        .ok_or_else(|| self.truncated_error(content.len() - remaining.len()))?;
+                    if rest.len() < len {
+                        return Err(self.truncated_error(content.len() - remaining.len()));
+                    }
+                    let (message, rest) = rest.split_at(len);
+                    requests.push(
+                        decode_request(signal, message, OtlpEncoding::Protobuf).map_err(|e| {
+                            Error::IngestError {
+                                error: format!(
+                                    "Failed to parse OTLP protobuf from file {} at offset {}: {}",
+                                    self.path.display(),
+                                    content.len() - remaining.len(),
+                                    e
+                                ),
+                            }
+                        })?,
+                    );
+                    remaining = rest;
+                }
+                Ok(requests)
+            }
+        }
+    }
+
+    fn truncated_error(&self, offset: usize) -> Error {
+        Error::IngestError {
+            error: format!(
+                "Truncated OTLP protobuf message in file {} at offset {}",
+                self.path.display(),
+                offset
+            ),
+        }
+    }
+}
+
+impl Ingester for OtlpFileIngester {
+    fn ingest(&self) -> Result<Box<dyn Iterator<Item = Sample>>, Error> {
+        let requests = self.read_requests()?;
+        Ok(Box::new(OtlpIterator::new(Box::new(requests.into_iter()))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::otlp::grpc_stubs::proto::collector::metrics::v1::ExportMetricsServiceRequest;
+    use crate::registry::otlp::grpc_stubs::proto::metrics::v1::{
+        Metric, ResourceMetrics, ScopeMetrics,
+    };
+    use prost::Message;
+    use std::io::Write;
+    use tempfile::tempdir;
+
+    fn write_file(dir: &Path, name: &str, content: &[u8]) -> PathBuf {
+        let path = dir.join(name);
+        fs::File::create(&path).unwrap().write_all(content).unwrap();
+        path
+    }
+
+    fn sample_names(samples: &[Sample]) -> Vec<String> {
+        samples
+            .iter()
+            .filter_map(|sample| match sample {
+                Sample::Span(span) => Some(span.name.clone()),
+                Sample::Metric(metric) => Some(metric.name.clone()),
+                Sample::Log(log) => Some(log.event_name.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_json_lines_ingestion() {
+        let dir = tempdir().unwrap();
+        let path = write_file(
+            dir.path(),
+            "recorded.jsonl",
+            br#"{"resourceSpans":[{"resource":{"attributes":[{"key":"service.name","value":{"stringValue":"checkout"}}]},"scopeSpans":[{"spans":[{"name":"GET /cart","attributes":[{"key":"http.request.method","value":{"stringValue":"GET"}}]}]}]}]}
+
+{"resourceMetrics":[{"scopeMetrics":[{"metrics":[{"name":"http.server.request.duration","histogram":{"dataPoints":[{"count":"1","sum":0.5}],"aggregationTemporality":2}}]}]}]}
+{"resourceLogs":[{"scopeLogs":[{"logRecords":[{"eventName":"session.start"}]}]}]}
+"#,
+        );
+
+        let samples = OtlpFileIngester::new(&path, OtlpFileFormat::JsonLines)
+            .ingest()
+            .unwrap()
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            sample_names(&samples),
+            vec!["GET /cart", "http.server.request.duration", "session.start"]
+        );
+        let Some(Sample::Span(span)) = samples.iter().find(|s| matches!(s, Sample::Span(_))) else {
+            panic!("Expected a span sample");
+        };
+        assert_eq!(span.attributes[0].name, "http.request.method");
+        assert_eq!(
+            span.resource.as_ref().unwrap().attributes[0].name,
+            "service.name"
+        );
+    }
+
+    #[test]
+    fn test_json_lines_invalid_line() {
+        let dir = tempdir().unwrap();
+        let path = write_file(
+            dir.path(),
+            "invalid.jsonl",
+            b"{\"resourceLogs\":[]}\n{\"unknown\":[]}\n",
+        );
+
+        match OtlpFileIngester::new(&path, OtlpFileFormat::JsonLines).ingest() {
+            Err(Error::IngestError { error }) => assert!(error.contains("at line 2"), "{error}"),
+            _ => panic!("Expected IngestError"),
+        }
+    }
+
+    #[test]
+    fn test_protobuf_ingestion() {
+        let request = |name: &str| ExportMetricsServiceRequest {
+            resource_metrics: vec![ResourceMetrics {
+                scope_metrics: vec![ScopeMetrics {
+                    metrics: vec![Metric {
+                        name: name.to_owned(),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+        };
+        let mut content = Vec::new();
+        for name in ["first.metric", "second.metric"] {
+            let message = request(name).encode_to_vec();
+            content.extend_from_slice(&(message.len() as u32).to_be_bytes());
+            content.extend_from_slice(&message);
+        }
+        let dir = tempdir().unwrap();
+        let path = write_file(dir.path(), "recorded.pb", &content);
+
+        let samples = OtlpFileIngester::new(&path, OtlpFileFormat::Protobuf(OtlpSignal::Metrics))
+            .ingest()
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            sample_names(&samples),
+            vec!["first.metric", "second.metric"]
+        );
+
+        // A truncated file is reported
+        let path = write_file(dir.path(), "truncated.pb", &content[..content.len() - 1]);
+        match OtlpFileIngester::new(&path, OtlpFileFormat::Protobuf(OtlpSignal::Metrics)).ingest() {
+            Err(Error::IngestError { error }) => assert!(error.contains("Truncated"), "{error}"),
+            _ => panic!("Expected IngestError"),
+        }
+    }
+
+    #[test]
+    fn test_file_not_found() {
+        let ingester = OtlpFileIngester::new(
+            Path::new("/path/to/nonexistent/file.jsonl"),
+            OtlpFileFormat::JsonLines,
+        );
+        match ingester.ingest() {
+            Err(Error::IngestError { error }) => assert!(error.contains("Failed to open file")),
+            _ => panic!("Expected IngestError"),
+        }
+    }
+}
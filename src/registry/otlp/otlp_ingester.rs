@@ -13,12 +13,15 @@ use weaver_live_check::{
 };
 
 use super::{
+    codec::decode_json_request,
     conversion::{
         otlp_instrumentation_scope_to_sample, otlp_log_record_to_sample_log, otlp_metric_to_sample,
         otlp_profile_to_sample, sample_attribute_from_key_value, span_kind_from_otlp_kind,
         status_from_otlp_status,
     },
-    listen_otlp_requests, OtlpRequest, ShutdownCoordinator,
+    listen_otlp_requests,
+    recorder::{OtlpRecorder, RecordConfig, RecordingIterator},
+    OtlpRequest, ShutdownCoordinator,
 };
 
 /// An ingester for OTLP data
@@ -27,20 +30,29 @@ pub struct OtlpIngester {
     pub otlp_grpc_address: String,
     /// The port of the OTLP gRPC server
     pub otlp_grpc_port: u16,
+    /// The address of the OTLP/HTTP server
+    pub otlp_http_address: String,
+    /// The port of the OTLP/HTTP server (`None` disables the OTLP/HTTP server)
+    pub otlp_http_port: Option<u16>,
+    /// The upstream OTLP gRPC endpoint every received request is forwarded to
+    /// (proxy mode)
+    pub otlp_forward_endpoint: Option<String>,
     /// The port of the admin server
     pub admin_port: u16,
     /// The inactivity timeout
     pub inactivity_timeout: u64,
+    /// When set, every received OTLP request is also written to this archive
+    pub record: Option<RecordConfig>,
 }
 
 /// Iterator for OTLP samples
-struct OtlpIterator {
+pub(super) struct OtlpIterator {
     otlp_requests: Box<dyn Iterator<Item = OtlpRequest>>,
     buffer: Vec<Sample>,
 }
 
 impl OtlpIterator {
-    fn new(otlp_requests: Box<dyn Iterator<Item = OtlpRequest>>) -> Self {
+    pub(super) fn new(otlp_requests: Box<dyn Iterator<Item = OtlpRequest>>) -> Self {
         Self {
             otlp_requests,
             buffer: Vec::new(),
@@ -273,6 +285,13 @@ impl Iterator for OtlpIterator {
     }
 }
 
+/// Converts an OTLP/JSON export request into samples, the same way the
+/// requests received by the OTLP listener are.
+pub(crate) fn samples_from_otlp_json(bytes: &[u8]) -> Result<Vec<Sample>, super::Error> {
+    let request = decode_json_request(bytes)?;
+    Ok(OtlpIterator::new(Box::new(std::iter::once(request))).collect())
+}
+
 impl OtlpIngester {
     /// Ingest OTLP data and return both the sample iterator and the shutdown coordinator.
     ///
@@ -282,9 +301,22 @@ impl OtlpIngester {
     pub fn ingest_otlp(
         &self,
     ) -> Result<(Box<dyn Iterator<Item = Sample>>, ShutdownCoordinator), Error> {
+        let recorder = self
+            .record
+            .as_ref()
+            .map(|record| OtlpRecorder::new(record.clone()))
+            .transpose()
+            .map_err(|e| Error::IngestError {
+                error: e.to_string(),
+            })?;
+
         let (otlp_requests, coordinator) = listen_otlp_requests(
             self.otlp_grpc_address.as_str(),
             self.otlp_grpc_port,
+            self.otlp_http_port
+                .map(|port| (self.otlp_http_address.as_str(), port)),
+            self.otlp_forward_endpoint.as_deref(),
+            None,
             self.admin_port,
             Duration::from_secs(self.inactivity_timeout),
         )
@@ -292,6 +324,10 @@ impl OtlpIngester {
             error: format!("Failed to listen to OTLP requests: {e}"),
         })?;
 
+        if let Some(endpoint) = &self.otlp_forward_endpoint {
+            info!("The received OTLP requests are forwarded to {endpoint}.");
+        }
+
         log_info("To stop the OTLP receiver:");
         info!("  - press CTRL+C,");
         info!(
@@ -311,10 +347,18 @@ impl OtlpIngester {
             );
         };
 
-        Ok((
-            Box::new(OtlpIterator::new(Box::new(otlp_requests))),
-            coordinator,
-        ))
+        let otlp_requests: Box<dyn Iterator<Item = OtlpRequest>> = match recorder {
+            Some(recorder) => {
+                info!(
+                    "The received OTLP requests are recorded to {}.",
+                    recorder.dir().display()
+                );
+                Box::new(RecordingIterator::new(otlp_requests, recorder))
+            }
+            None => Box::new(otlp_requests),
+        };
+
+        Ok((Box::new(OtlpIterator::new(otlp_requests)), coordinator))
     }
 }
 
@@ -0,0 +1,428 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Record mode: tees the OTLP requests received by the listener to a rotating
+//! on-disk archive.
+//!
+//! The archive files can be replayed with the `otlp-json` and
+//! `otlp-proto-{signal}` live-check input formats:
+//! - `json`: every request is appended to `otlp-NNNNNN.jsonl` as one OTLP/JSON
+//!   line, whatever its signal.
+//! - `proto`: every request is appended to `{signal}-NNNNNN.pb` as a
+//!   length-prefixed binary protobuf message (the layout of the collector
+//!   `file` exporter).
+//!
+//! A file is rotated once it reaches `max_file_size` bytes. When `max_files`
+//! is not zero, the oldest files of a stream are deleted so that at most
+//! `max_files` are kept.
+//!
+//! Recording into a directory that already holds an archive never overwrites
+//! it: the numbering continues after the highest existing index, and only the
+//! files written by the current session are subject to `max_files`.
+
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use log::warn;
+
+use super::codec::{OtlpMessage, OtlpSignal};
+use super::{Error, OtlpRequest};
+
+/// The encoding of the recorded archive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordFormat {
+    /// OTLP/JSON lines.
+    Json,
+    /// Length-prefixed binary protobuf, one stream per signal.
+    Proto,
+}
+
+impl FromStr for RecordFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(RecordFormat::Json),
+            "proto" | "protobuf" => Ok(RecordFormat::Proto),
+            _ => Err(format!(
+                "invalid record format '{s}' (expected one of: json, proto)"
+            )),
+        }
+    }
+}
+
+/// Settings of the recorded archive.
+#[derive(Debug, Clone)]
+pub struct RecordConfig {
+    /// Directory where the archive files are written.
+    pub dir: PathBuf,
+    /// Encoding of the archive files.
+    pub format: RecordFormat,
+    /// Size in bytes after which a file is rotated.
+    pub max_file_size: u64,
+    /// Maximum number of files kept per stream (0 = unlimited).
+    pub max_files: usize,
+}
+
+/// Writes OTLP requests to a rotating on-disk archive.
+pub struct OtlpRecorder {
+    config: RecordConfig,
+    streams: HashMap<&'static str, ArchiveStream>,
+}
+
+/// A sequence of rotated archive files sharing the same prefix.
+struct ArchiveStream {
+    prefix: &'static str,
+    extension: &'static str,
+    index: u64,
+    file: Option<File>,
+    written: u64,
+    files: VecDeque<PathBuf>,
+}
+
+impl OtlpRecorder {
+    /// Create a recorder, creating the archive directory if needed.
+    pub fn new(config: RecordConfig) -> Result<Self, Error> {
+        fs::create_dir_all(&config.dir).map_err(|e| Error::OtlpError {
+            error: format!(
+                "Failed to create the record directory {}: {e}",
+                config.dir.display()
+            ),
+        })?;
+        Ok(Self {
+            config,
+            streams: HashMap::new(),
+        })
+    }
+
+    /// The directory where the archive files are written.
+    #[must_use]
+    pub fn dir(&self) -> &Path {
+        &self.config.dir
+    }
+
+    /// Append a request to the archive. Stop signals and errors are ignored.
+    pub fn record(&mut self, request: &OtlpRequest) -> Result<(), Error> {
+        let (signal, bytes) = match request {
+            OtlpRequest::Traces(r) => (OtlpSignal::Traces, self.encode(r)?),
+            OtlpRequest::Metrics(r) => (OtlpSignal::Metrics, self.encode(r)?),
+            OtlpRequest::Logs(r) => (OtlpSignal::Logs, self.encode(r)?),
+            OtlpRequest::Profiles(r) => (OtlpSignal::Profiles, self.encode(r)?),
+            OtlpRequest::Error(_) | OtlpRequest::Stop(_) => return Ok(()),
+        };
+        let (prefix, extension) = match self.config.format {
+            RecordFormat::Json => ("otlp", "jsonl"),
+            RecordFormat::Proto => (signal_name(signal), "pb"),
+        };
+        let stream = match self.streams.entry(prefix) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(ArchiveStream {
+                prefix,
+                extension,
+                index: last_index(&self.config.dir, prefix, extension)?,
+                file: None,
+                written: 0,
+                files: VecDeque::new(),
+            }),
+        };
+        stream.write(&self.config, &bytes)
+    }
+
+    fn encode<M: OtlpMessage>(&self, message: &M) -> Result<Vec<u8>, Error> {
+        encode(message, self.config.format)
+    }
+}
+
+/// Encode a message as an OTLP/JSON line or a length-prefixed protobuf message.
+pub(crate) fn encode<M: OtlpMessage>(message: &M, format: RecordFormat) -> Result<Vec<u8>, Error> {
+    match format {
+        RecordFormat::Json => {
+            let mut line = message.to_json()?.into_bytes();
+            line.push(b'\n');
+            Ok(line)
+        }
+        RecordFormat::Proto => {
+            let message = message.encode_to_vec();
+            let len = u32::try_from(message.len()).map_err(|_| Error::OtlpError {
+                error: "OTLP message too large to be recorded".to_owned(),
+            })?;
+            let mut bytes = Vec::with_capacity(message.len() + 4);
+            bytes.extend_from_slice(&len.to_be_bytes());
+            bytes.extend_from_slice(&message);
+            Ok(bytes)
+        }
+    }
+}
+
+impl ArchiveStream {
+    fn write(&mut self, config: &RecordConfig, bytes: &[u8]) -> Result<(), Error> {
+        let must_rotate =
+            self.written > 0 && self.written + bytes.len() as u64 > config.max_file_size;
+        if self.file.is_none() || must_rotate {
+            self.rotate(config)?;
+        }
+        let file = self
+            .file
+            .as_mut()
+            .expect("the archive file is opened by rotate");
+        file.write_all(bytes).map_err(|e| Error::OtlpError {
+            error: format!("Failed to write the record file: {e}"),
+        })?;
+        self.written += bytes.len() as u64;
+        Ok(())
+    }
+
+    /// Open the next file of the stream, deleting the oldest files beyond
+    /// `max_files`.
+    fn rotate(&mut self, config: &RecordConfig) -> Result<(), Error> {
+        self.index += 1;
+        let path = config.dir.join(format!(
+            "{}-{:06}.{}",
+            self.prefix, self.index, self.extension
+        ));
+        let file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .map_err(|e| Error::OtlpError {
+                error: format!("Failed to create the record file {}: {e}", path.display()),
+            })?;
+        self.file = Some(file);
+        self.written = 0;
+        self.files.push_back(path);
+
+        while config.max_files > 0 && self.files.len() > config.max_files {
+            if let Some(oldest) = self.files.pop_front() {
+                remove_file(&oldest);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The highest index of the `{prefix}-NNNNNN.{extension}` files already present
+/// in the directory, or 0 if there are none.
+fn last_index(dir: &Path, prefix: &str, extension: &str) -> Result<u64, Error> {
+    let entries = fs::read_dir(dir).map_err(|e| Error::OtlpError {
+        error: format!("Failed to read the record directory {}: {e}", dir.display()),
+    })?;
+    Ok(entries
+        .filter_map(|entry| {
+            let name = entry.ok()?.file_name();
+            name.to_str()?
+                .strip_prefix(prefix)?
+                .strip_prefix('-')?
+                .strip_suffix(extension)?
+                .strip_suffix('.')?
+                .parse::<u64>()
+                .ok()
+        })
+        .max()
+        .unwrap_or(0))
+}
+
+fn remove_file(path: &Path) {
+    if let Err(e) = fs::remove_file(path) {
+        warn!("Failed to delete the record file {}: {e}", path.display());
+    }
+}
+
+fn signal_name(signal: OtlpSignal) -> &'static str {
+    match signal {
+        OtlpSignal::Traces => "traces",
+        OtlpSignal::Metrics => "metrics",
+        OtlpSignal::Logs => "logs",
+        OtlpSignal::Profiles => "profiles",
+    }
+}
+
+/// An iterator that records every OTLP request it yields.
+///
+/// A recording failure doesn't interrupt the live-check session: it is
+/// reported once and recording stops.
+pub struct RecordingIterator<I> {
+    inner: I,
+    recorder: Option<OtlpRecorder>,
+}
+
+impl<I> RecordingIterator<I> {
+    /// Wrap an iterator of OTLP requests.
+    pub fn new(inner: I, recorder: OtlpRecorder) -> Self {
+        Self {
+            inner,
+            recorder: Some(recorder),
+        }
+    }
+}
+
+impl<I: Iterator<Item = OtlpRequest>> Iterator for RecordingIterator<I> {
+    type Item = OtlpRequest;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let request = self.inner.next()?;
+        if let Some(recorder) = self.recorder.as_mut() {
+            if let Err(e) = recorder.record(&request) {
+                warn!("{e}. Recording is disabled for the rest of the session.");
+                self.recorder = None;
+            }
+        }
+        Some(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::otlp::grpc_stubs::proto::collector::logs::v1::ExportLogsServiceRequest;
+    use crate::registry::otlp::grpc_stubs::proto::collector::trace::v1::ExportTraceServiceRequest;
+    use crate::registry::otlp::grpc_stubs::proto::trace::v1::{ResourceSpans, ScopeSpans, Span};
+    use crate::registry::otlp::otlp_file_ingester::{OtlpFileFormat, OtlpFileIngester};
+    use crate::registry::otlp::StopSignal;
+    use tempfile::tempdir;
+    use weaver_live_check::{Ingester, Sample};
+
+    fn traces(name: &str) -> OtlpRequest {
+        OtlpRequest::Traces(ExportTraceServiceRequest {
+            resource_spans: vec![ResourceSpans {
+                scope_spans: vec![ScopeSpans {
+                    spans: vec![Span {
+                        name: name.to_owned(),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+        })
+    }
+
+    fn span_names(path: &Path, format: OtlpFileFormat) -> Vec<String> {
+        OtlpFileIngester::new(path, format)
+            .ingest()
+            .unwrap()
+            .filter_map(|sample| match sample {
+                Sample::Span(span) => Some(span.name),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn file_names(dir: &Path) -> Vec<String> {
+        let mut names: Vec<_> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_parse_record_format() {
+        assert_eq!("json".parse(), Ok(RecordFormat::Json));
+        assert_eq!("Protobuf".parse(), Ok(RecordFormat::Proto));
+        assert!("yaml".parse::<RecordFormat>().is_err());
+    }
+
+    #[test]
+    fn test_json_recording_round_trips() {
+        let dir = tempdir().unwrap();
+        let recorder = OtlpRecorder::new(RecordConfig {
+            dir: dir.path().to_path_buf(),
+            format: RecordFormat::Json,
+            max_file_size: u64::MAX,
+            max_files: 0,
+        })
+        .unwrap();
+        let requests = vec![
+            traces("first"),
+            OtlpRequest::Logs(ExportLogsServiceRequest::default()),
+            traces("second"),
+            OtlpRequest::Stop(StopSignal::Inactivity),
+        ];
+
+        let forwarded = RecordingIterator::new(requests.into_iter(), recorder).count();
+        assert_eq!(forwarded, 4, "all the requests are forwarded");
+
+        assert_eq!(file_names(dir.path()), vec!["otlp-000001.jsonl"]);
+        assert_eq!(
+            span_names(
+                &dir.path().join("otlp-000001.jsonl"),
+                OtlpFileFormat::JsonLines
+            ),
+            vec!["first", "second"]
+        );
+    }
+
+    #[test]
+    fn test_recording_preserves_existing_archive() {
+        let dir = tempdir().unwrap();
+        let config = RecordConfig {
+            dir: dir.path().to_path_buf(),
+            format: RecordFormat::Json,
+            max_file_size: u64::MAX,
+            max_files: 1,
+        };
+        for name in ["first session", "second session"] {
+            OtlpRecorder::new(config.clone())
+                .unwrap()
+                .record(&traces(name))
+                .unwrap();
+        }
+
+        // The second session continues the numbering, and `max_files` doesn't
+        // delete the files of the first one.
+        assert_eq!(
+            file_names(dir.path()),
+            vec!["otlp-000001.jsonl", "otlp-000002.jsonl"]
+        );
+        assert_eq!(
+            span_names(
+                &dir.path().join("otlp-000001.jsonl"),
+                OtlpFileFormat::JsonLines
+            ),
+            vec!["first session"]
+        );
+        assert_eq!(
+            span_names(
+                &dir.path().join("otlp-000002.jsonl"),
+                OtlpFileFormat::JsonLines
+            ),
+            vec!["second session"]
+        );
+    }
+
+    #[test]
+    fn test_proto_recording_rotates_files() {
+        let dir = tempdir().unwrap();
+        let mut recorder = OtlpRecorder::new(RecordConfig {
+            dir: dir.path().to_path_buf(),
+            format: RecordFormat::Proto,
+            max_file_size: 1,
+            max_files: 2,
+        })
+        .unwrap();
+        for name in ["first", "second", "third"] {
+            recorder.record(&traces(name)).unwrap();
+        }
+        recorder
+            .record(&OtlpRequest::Logs(ExportLogsServiceRequest::default()))
+            .unwrap();
+
+        // Every message exceeds the max file size, so each one gets its own
+        // file, and only the last two trace files are kept.
+        assert_eq!(
+            file_names(dir.path()),
+            vec!["logs-000001.pb", "traces-000002.pb", "traces-000003.pb"]
+        );
+        assert_eq!(
+            span_names(
+                &dir.path().join("traces-000003.pb"),
+                OtlpFileFormat::Protobuf(OtlpSignal::Traces)
+            ),
+            vec!["third"]
+        );
+    }
+}
@@ -0,0 +1,518 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Translation of OTLP export requests to a version of a telemetry schema.
+//!
+//! The telemetry of a resource or a scope is tagged with the schema URL of the
+//! version it conforms to. When this URL belongs to the schema family of the
+//! telemetry schema file, i.e. it only differs by its version, the attribute and
+//! metric names are rewritten with the renames defined by the file between this
+//! version and the target version, forward or backward, and the schema URL is
+//! replaced by the target one. The telemetry of other families, or without a
+//! schema URL, is left unchanged.
+//!
+//! The schema URL of a scope applies to its telemetry, the one of its resource
+//! otherwise. Profiles are left unchanged. When an attribute is renamed to the key of
+//! another attribute of the same telemetry, the renamed attribute is dropped: the
+//! attribute already using the key of the target version is kept.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use log::debug;
+use weaver_otel_schema::TelemetrySchema;
+use weaver_version::translator::Translator;
+use weaver_version::Versions;
+
+use super::grpc_stubs::proto::collector::logs::v1::ExportLogsServiceRequest;
+use super::grpc_stubs::proto::collector::metrics::v1::ExportMetricsServiceRequest;
+use super::grpc_stubs::proto::collector::trace::v1::ExportTraceServiceRequest;
+use super::grpc_stubs::proto::common::v1::KeyValue;
+use super::grpc_stubs::proto::metrics::v1::metric::Data;
+use super::grpc_stubs::proto::metrics::v1::Metric;
+use super::{Error, OtlpRequest};
+
+/// The maximum number of source schema URLs whose translator is cached, the translator
+/// of the next ones is built for each request.
+const MAX_CACHED_TRANSLATORS: usize = 64;
+
+/// Translates OTLP export requests to a version of a telemetry schema.
+pub struct OtlpTranslator {
+    /// The schema URL without its version, e.g. `https://opentelemetry.io/schemas`.
+    family: String,
+    target_version: String,
+    target_schema_url: String,
+    versions: Versions,
+    /// The translators by source schema URL of the family, `None` for the URLs that can't
+    /// be translated, at most `MAX_CACHED_TRANSLATORS`.
+    translators: Mutex<HashMap<String, Option<Arc<Translator>>>>,
+}
+
+impl OtlpTranslator {
+    /// Create a translator to the given version of the telemetry schema, or to the
+    /// version of its schema URL if `None`.
+    pub fn new(schema: TelemetrySchema, target_version: Option<&str>) -> Result<Self, Error> {
+        let (family, schema_version) =
+            schema
+                .schema_url
+                .rsplit_once('/')
+                .ok_or_else(|| Error::OtlpError {
+                    error: format!(
+                        "The schema URL `{}` of the telemetry schema has no version",
+                        schema.schema_url
+                    ),
+                })?;
+        let target_version = target_version.unwrap_or(schema_version).to_owned();
+        let versions = schema.versions.unwrap_or_default();
+        // Fails early on an invalid target version.
+        _ = versions
+            .translator(&target_version, &target_version)
+            .map_err(|e| Error::OtlpError {
+                error: e.to_string(),
+            })?;
+        Ok(Self {
+            target_schema_url: format!("{family}/{target_version}"),
+            family: family.to_owned(),
+            target_version,
+            versions,
+            translators: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// The schema URL of the translated telemetry.
+    #[must_use]
+    pub fn target_schema_url(&self) -> &str {
+        &self.target_schema_url
+    }
+
+    /// Translate a request in place. Stop signals and errors are left unchanged.
+    pub fn translate(&self, request: &mut OtlpRequest) {
+        match request {
+            OtlpRequest::Traces(r) => self.translate_traces(r),
+            OtlpRequest::Metrics(r) => self.translate_metrics(r),
+            OtlpRequest::Logs(r) => self.translate_logs(r),
+            OtlpRequest::Profiles(_) | OtlpRequest::Error(_) | OtlpRequest::Stop(_) => {}
+        }
+    }
+
+    /// Translate the resource attributes, span attributes and span event and link
+    /// attributes of a trace export request.
+    pub fn translate_traces(&self, request: &mut ExportTraceServiceRequest) {
+        for resource_spans in &mut request.resource_spans {
+            let resource_translator = self.translate_resource(
+                &mut resource_spans.schema_url,
+                resource_spans.resource.as_mut().map(|r| &mut r.attributes),
+            );
+            for scope_spans in &mut resource_spans.scope_spans {
+                let Some(translator) =
+                    self.scope_translator(&mut scope_spans.schema_url, &resource_translator)
+                else {
+                    continue;
+                };
+                for span in &mut scope_spans.spans {
+                    rename(&mut span.attributes, |name| {
+                        translator.span_attribute_name(name)
+                    });
+                    for event in &mut span.events {
+                        rename(&mut event.attributes, |name| {
+                            translator.event_attribute_name(name)
+                        });
+                    }
+                    for link in &mut span.links {
+                        rename(&mut link.attributes, |name| {
+                            translator.event_attribute_name(name)
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    /// Translate the resource attributes, metric names and data point attributes
+    /// of a metric export request.
+    pub fn translate_metrics(&self, request: &mut ExportMetricsServiceRequest) {
+        for resource_metrics in &mut request.resource_metrics {
+            let resource_translator = self.translate_resource(
+                &mut resource_metrics.schema_url,
+                resource_metrics
+                    .resource
+                    .as_mut()
+                    .map(|r| &mut r.attributes),
+            );
+            for scope_metrics in &mut resource_metrics.scope_metrics {
+                let Some(translator) =
+                    self.scope_translator(&mut scope_metrics.schema_url, &resource_translator)
+                else {
+                    continue;
+                };
+                for metric in &mut scope_metrics.metrics {
+                    translate_metric(&translator, metric);
+                }
+            }
+        }
+    }
+
+    /// Translate the resource attributes and log record attributes of a log export
+    /// request.
+    pub fn translate_logs(&self, request: &mut ExportLogsServiceRequest) {
+        for resource_logs in &mut request.resource_logs {
+            let resource_translator = self.translate_resource(
+                &mut resource_logs.schema_url,
+                resource_logs.resource.as_mut().map(|r| &mut r.attributes),
+            );
+            for scope_logs in &mut resource_logs.scope_logs {
+                let Some(translator) =
+                    self.scope_translator(&mut scope_logs.schema_url, &resource_translator)
+                else {
+                    continue;
+                };
+                for log_record in &mut scope_logs.log_records {
+                    rename(&mut log_record.attributes, |name| {
+                        translator.log_attribute_name(name)
+                    });
+                }
+            }
+        }
+    }
+
+    /// Translate the resource attributes and return the translator of the resource
+    /// schema URL, if any, after replacing it with the target schema URL.
+    fn translate_resource(
+        &self,
+        schema_url: &mut String,
+        attributes: Option<&mut Vec<KeyValue>>,
+    ) -> Option<Arc<Translator>> {
+        let translator = self.translator_for(schema_url)?;
+        if let Some(attributes) = attributes {
+            rename(attributes, |name| translator.resource_attribute_name(name));
+        }
+        schema_url.clone_from(&self.target_schema_url);
+        Some(translator)
+    }
+
+    /// Returns the translator of the telemetry of a scope, replacing its schema URL,
+    /// if any, with the target schema URL.
+    fn scope_translator(
+        &self,
+        schema_url: &mut String,
+        resource_translator: &Option<Arc<Translator>>,
+    ) -> Option<Arc<Translator>> {
+        if schema_url.is_empty() {
+            return resource_translator.clone();
+        }
+        let translator = self.translator_for(schema_url)?;
+        schema_url.clone_from(&self.target_schema_url);
+        Some(translator)
+    }
+
+    /// Returns the translator from the version of a schema URL of the family to the
+    /// target version, or `None` if the schema URL is not one of the family.
+    fn translator_for(&self, schema_url: &str) -> Option<Arc<Translator>> {
+        let (family, version) = schema_url.rsplit_once('/')?;
+        if family != self.family {
+            return None;
+        }
+        let mut translators = self
+            .translators
+            .lock()
+            .expect("the translator cache lock is never held during a panic");
+        if let Some(translator) = translators.get(schema_url) {
+            return translator.clone();
+        }
+        let translator = match self.versions.translator(version, &self.target_version) {
+            Ok(translator) => Some(Arc::new(translator)),
+            Err(e) => {
+                debug!("The telemetry of the schema URL `{schema_url}` is not translated: {e}");
+                None
+            }
+        };
+        if translators.len() < MAX_CACHED_TRANSLATORS {
+            _ = translators.insert(schema_url.to_owned(), translator.clone());
+        }
+        translator
+    }
+}
+
+/// Translate the data point attributes then the name of a metric.
+fn translate_metric(translator: &Translator, metric: &mut Metric) {
+    let data_point_attributes: Vec<&mut Vec<KeyValue>> = match &mut metric.data {
+        Some(Data::Gauge(gauge)) => gauge
+            .data_points
+            .iter_mut()
+            .map(|p| &mut p.attributes)
+            .collect(),
+        Some(Data::Sum(sum)) => sum
+            .data_points
+            .iter_mut()
+            .map(|p| &mut p.attributes)
+            .collect(),
+        Some(Data::Histogram(histogram)) => histogram
+            .data_points
+            .iter_mut()
+            .map(|p| &mut p.attributes)
+            .collect(),
+        Some(Data::ExponentialHistogram(histogram)) => histogram
+            .data_points
+            .iter_mut()
+            .map(|p| &mut p.attributes)
+            .collect(),
+        Some(Data::Summary(summary)) => summary
+            .data_points
+            .iter_mut()
+            .map(|p| &mut p.attributes)
+            .collect(),
+        None => Vec::new(),
+    };
+    for attributes in data_point_attributes {
+        rename(attributes, |name| {
+            translator.metric_attribute_name(&metric.name, name)
+        });
+    }
+    metric.name = translator.metric_name(&metric.name);
+}
+
+/// Rename the keys of the attributes. A renamed attribute is dropped when its new key is
+/// already used, by an attribute that is not renamed or by a previous renamed one.
+fn rename(attributes: &mut Vec<KeyValue>, name: impl Fn(&str) -> String) {
+    let new_keys: Vec<String> = attributes
+        .iter()
+        .map(|attribute| name(&attribute.key))
+        .collect();
+    let mut keys: HashSet<String> = attributes
+        .iter()
+        .zip(&new_keys)
+        .filter(|(attribute, new_key)| attribute.key == **new_key)
+        .map(|(attribute, _)| attribute.key.clone())
+        .collect();
+    let mut new_keys = new_keys.into_iter();
+    attributes.retain_mut(|attribute| {
+        let new_key = new_keys
+            .next()
+            .expect("there is a new key for each attribute");
+        if attribute.key == new_key {
+            return true;
+        }
+        if !keys.insert(new_key.clone()) {
+            return false;
+        }
+        attribute.key = new_key;
+        true
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::otlp::grpc_stubs::proto::common::v1::InstrumentationScope;
+    use crate::registry::otlp::grpc_stubs::proto::metrics::v1::{
+        NumberDataPoint, ResourceMetrics, ScopeMetrics, Sum,
+    };
+    use crate::registry::otlp::grpc_stubs::proto::resource::v1::Resource;
+    use crate::registry::otlp::grpc_stubs::proto::trace::v1::{ResourceSpans, ScopeSpans, Span};
+
+    const SCHEMA: &str = r#"
+file_format: 1.1.0
+schema_url: https://example.com/schemas/1.2.0
+versions:
+  1.2.0:
+    spans:
+      changes:
+        - rename_attributes:
+            attribute_map:
+              http.method: http.request.method
+    resources:
+      changes:
+        - rename_attributes:
+            attribute_map:
+              telemetry.auto.version: telemetry.distro.version
+    metrics:
+      changes:
+        - rename_metrics:
+            system.cpu.time: system.cpu.duration
+        - rename_attributes:
+            attribute_map:
+              state: cpu.state
+  1.1.0: {}
+  1.0.0: {}
+"#;
+
+    fn translator(target_version: Option<&str>) -> OtlpTranslator {
+        let schema: TelemetrySchema = serde_yaml::from_str(SCHEMA).unwrap();
+        OtlpTranslator::new(schema, target_version).unwrap()
+    }
+
+    fn attribute(key: &str) -> KeyValue {
+        KeyValue {
+            key: key.to_owned(),
+            value: None,
+        }
+    }
+
+    fn traces(resource_schema_url: &str, scope_schema_url: &str) -> ExportTraceServiceRequest {
+        ExportTraceServiceRequest {
+            resource_spans: vec![ResourceSpans {
+                resource: Some(Resource {
+                    attributes: vec![attribute("telemetry.auto.version")],
+                    ..Default::default()
+                }),
+                scope_spans: vec![ScopeSpans {
+                    scope: Some(InstrumentationScope::default()),
+                    spans: vec![Span {
+                        attributes: vec![attribute("http.method")],
+                        ..Default::default()
+                    }],
+                    schema_url: scope_schema_url.to_owned(),
+                }],
+                schema_url: resource_schema_url.to_owned(),
+            }],
+        }
+    }
+
+    fn keys(request: &ExportTraceServiceRequest) -> (String, String, String, String) {
+        let resource_spans = &request.resource_spans[0];
+        let scope_spans = &resource_spans.scope_spans[0];
+        (
+            resource_spans.schema_url.clone(),
+            resource_spans.resource.as_ref().unwrap().attributes[0]
+                .key
+                .clone(),
+            scope_spans.schema_url.clone(),
+            scope_spans.spans[0].attributes[0].key.clone(),
+        )
+    }
+
+    #[test]
+    fn test_translate_traces() {
+        let translator = translator(None);
+        assert_eq!(
+            translator.target_schema_url(),
+            "https://example.com/schemas/1.2.0"
+        );
+
+        // The scope inherits the schema URL of the resource.
+        let mut request = traces("https://example.com/schemas/1.0.0", "");
+        translator.translate_traces(&mut request);
+        assert_eq!(
+            keys(&request),
+            (
+                "https://example.com/schemas/1.2.0".to_owned(),
+                "telemetry.distro.version".to_owned(),
+                String::new(),
+                "http.request.method".to_owned()
+            )
+        );
+
+        // The schema URL of the scope takes precedence, and other families are
+        // left unchanged.
+        let mut request = traces(
+            "https://other.com/schemas/1.0.0",
+            "https://example.com/schemas/1.1.0",
+        );
+        translator.translate_traces(&mut request);
+        assert_eq!(
+            keys(&request),
+            (
+                "https://other.com/schemas/1.0.0".to_owned(),
+                "telemetry.auto.version".to_owned(),
+                "https://example.com/schemas/1.2.0".to_owned(),
+                "http.request.method".to_owned()
+            )
+        );
+
+        // Telemetry without a schema URL is left unchanged.
+        let mut request = traces("", "");
+        let expected = request.clone();
+        translator.translate_traces(&mut request);
+        assert_eq!(request, expected);
+    }
+
+    #[test]
+    fn test_translate_backward() {
+        let translator = translator(Some("1.0.0"));
+        let mut request = traces("https://example.com/schemas/1.2.0", "");
+        translator.translate_traces(&mut request);
+        assert_eq!(
+            keys(&request),
+            (
+                "https://example.com/schemas/1.0.0".to_owned(),
+                "telemetry.auto.version".to_owned(),
+                String::new(),
+                "http.method".to_owned()
+            )
+        );
+    }
+
+    #[test]
+    fn test_translate_metrics() {
+        let translator = translator(None);
+        let mut request = ExportMetricsServiceRequest {
+            resource_metrics: vec![ResourceMetrics {
+                scope_metrics: vec![ScopeMetrics {
+                    metrics: vec![Metric {
+                        name: "system.cpu.time".to_owned(),
+                        data: Some(Data::Sum(Sum {
+                            data_points: vec![NumberDataPoint {
+                                attributes: vec![attribute("state")],
+                                ..Default::default()
+                            }],
+                            ..Default::default()
+                        })),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                }],
+                schema_url: "https://example.com/schemas/1.1.0".to_owned(),
+                ..Default::default()
+            }],
+        };
+        translator.translate_metrics(&mut request);
+        let metric = &request.resource_metrics[0].scope_metrics[0].metrics[0];
+        assert_eq!(metric.name, "system.cpu.duration");
+        let Some(Data::Sum(sum)) = &metric.data else {
+            panic!("The metric data must be a sum");
+        };
+        assert_eq!(sum.data_points[0].attributes[0].key, "cpu.state");
+    }
+
+    #[test]
+    fn test_rename_collision() {
+        let translator = translator(None);
+        let mut request = traces("https://example.com/schemas/1.1.0", "");
+        let attributes = &mut request.resource_spans[0].scope_spans[0].spans[0].attributes;
+        attributes.push(attribute("http.request.method"));
+        attributes.push(attribute("url.full"));
+        translator.translate_traces(&mut request);
+        let keys: Vec<&str> = request.resource_spans[0].scope_spans[0].spans[0]
+            .attributes
+            .iter()
+            .map(|attribute| attribute.key.as_str())
+            .collect();
+        assert_eq!(keys, vec!["http.request.method", "url.full"]);
+    }
+
+    #[test]
+    fn test_translator_cache() {
+        let translator = translator(None);
+        // Only the schema URLs of the family are cached, up to a limit.
+        for version in 0..(MAX_CACHED_TRANSLATORS + 10) {
+            let mut request = traces(&format!("https://other.com/schemas/1.{version}.0"), "");
+            translator.translate_traces(&mut request);
+            let mut request = traces(&format!("https://example.com/schemas/1.{version}.0"), "");
+            translator.translate_traces(&mut request);
+            assert_eq!(
+                request.resource_spans[0].schema_url,
+                "https://example.com/schemas/1.2.0"
+            );
+        }
+        let translators = translator.translators.lock().unwrap();
+        assert_eq!(translators.len(), MAX_CACHED_TRANSLATORS);
+        assert!(translators
+            .keys()
+            .all(|url| url.starts_with("https://example.com/schemas/")));
+    }
+
+    #[test]
+    fn test_invalid_target_version() {
+        let schema: TelemetrySchema = serde_yaml::from_str(SCHEMA).unwrap();
+        assert!(OtlpTranslator::new(schema, Some("latest")).is_err());
+    }
+}
@@ -0,0 +1,189 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Generate an OpenTelemetry telemetry schema file from the differences between two
+//! versions of a semantic convention registry.
+
+use crate::registry::{load_config, RegistryArgs};
+use crate::weaver::WeaverEngine;
+use crate::{DiagnosticArgs, ExitDirectives};
+use clap::Args;
+use log::info;
+use std::path::PathBuf;
+use weaver_common::diagnostic::DiagnosticMessages;
+use weaver_common::http_auth::HttpAuthResolver;
+use weaver_common::log_success;
+use weaver_common::vdir::VirtualDirectoryPath;
+use weaver_config::{WeaverCommand, WeaverConfig};
+use weaver_macros::weaver_command;
+use weaver_otel_schema::TelemetrySchema;
+use weaver_semconv::registry_repo::RegistryRepo;
+use weaver_version::VersionSpec;
+
+/// Add the version of a semantic convention registry to an OpenTelemetry telemetry schema file.
+#[weaver_command(section = "telemetry-schema", no_policy)]
+#[derive(Debug, Args, WeaverCommand)]
+pub struct RegistryTelemetrySchemaArgs {
+    /// Parameters to specify the semantic convention registry
+    #[command(flatten)]
+    #[shared(registry)]
+    registry: RegistryArgs,
+
+    /// Parameters to specify the baseline semantic convention registry
+    #[arg(long)]
+    baseline_registry: VirtualDirectoryPath,
+
+    /// Path to the telemetry schema file to update. The file is created, with a section
+    /// for the version of the baseline registry, if it doesn't exist.
+    #[arg(long)]
+    schema_file: PathBuf,
+
+    /// Parameters to specify the diagnostic format.
+    #[command(flatten)]
+    #[shared(diagnostic)]
+    pub(crate) diagnostic: DiagnosticArgs,
+}
+
+/// Add the version of the registry to a telemetry schema file, with the renames found
+/// in the diff against the baseline registry.
+pub(crate) fn command(
+    args: &RegistryTelemetrySchemaArgs,
+    cfg: Option<&WeaverConfig>,
+    auth: &HttpAuthResolver,
+) -> Result<ExitDirectives, DiagnosticMessages> {
+    let cmd_config = load_config(args, cfg);
+    let mut diag_msgs = DiagnosticMessages::empty();
+    let weaver = WeaverEngine::new(
+        &cmd_config.registry,
+        &cmd_config.policy,
+        &cmd_config.resolve,
+        auth,
+    );
+
+    info!("Weaver Registry Telemetry Schema");
+    info!("Checking registry `{}`", cmd_config.registry.registry);
+
+    let registry_path = cmd_config.registry.registry.clone();
+    let main_registry_repo =
+        RegistryRepo::try_new_with_auth(None, &registry_path, &mut vec![], auth)?;
+    let baseline_registry_repo =
+        RegistryRepo::try_new_with_auth(None, &args.baseline_registry, &mut vec![], auth)?;
+
+    let main_resolved = weaver
+        .load_and_resolve_repo(main_registry_repo, &mut diag_msgs)?
+        .into_v2()?;
+    let baseline_resolved = weaver
+        .load_and_resolve_repo(baseline_registry_repo, &mut diag_msgs)?
+        .into_v2()?;
+    if diag_msgs.has_error() {
+        return Err(diag_msgs);
+    }
+
+    let head_schema = main_resolved.resolved_schema();
+    let baseline_schema = baseline_resolved.resolved_schema();
+    let spec = head_schema.diff(baseline_schema).version_spec();
+    let head_version = head_schema.schema_url.version();
+
+    if !args.schema_file.exists() {
+        let mut schema = TelemetrySchema::new(baseline_schema.schema_url.as_str());
+        schema.add_version(baseline_schema.schema_url.version(), VersionSpec::default())?;
+        schema.write_to_file(&args.schema_file)?;
+    }
+    // The file is edited in place to keep its comments. Its schema URL is the one of its
+    // latest version.
+    TelemetrySchema::add_version_to_file(
+        &args.schema_file,
+        head_schema.schema_url.as_str(),
+        head_version,
+        spec,
+    )?;
+
+    log_success(format!(
+        "Version `{head_version}` added to the telemetry schema `{}`",
+        args.schema_file.display()
+    ));
+    Ok(ExitDirectives {
+        exit_code: 0,
+        warnings: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::registry::telemetry_schema::RegistryTelemetrySchemaArgs;
+    use crate::registry::{
+        semconv_registry, RegistryArgs, RegistryCommand, RegistrySubCommand, VirtualDirectoryPath,
+    };
+    use std::path::Path;
+    use weaver_common::http_auth::HttpAuthResolver;
+    use weaver_otel_schema::TelemetrySchema;
+
+    #[test]
+    fn test_config_cli_consistency() {
+        use crate::registry::tests::assert_config_cli_consistency;
+        assert_config_cli_consistency::<RegistryTelemetrySchemaArgs>();
+    }
+
+    fn add_version(schema_file: &Path) {
+        let registry_cmd = RegistryCommand {
+            command: RegistrySubCommand::TelemetrySchema(RegistryTelemetrySchemaArgs {
+                registry: RegistryArgs {
+                    registry: Some(VirtualDirectoryPath::LocalFolder {
+                        path: "tests/diff/registry_head/".to_owned(),
+                    }),
+                    ..Default::default()
+                },
+                baseline_registry: VirtualDirectoryPath::LocalFolder {
+                    path: "tests/diff/registry_baseline/".to_owned(),
+                },
+                schema_file: schema_file.to_path_buf(),
+                diagnostic: Default::default(),
+            }),
+        };
+        let cmd_result = semconv_registry(&registry_cmd, None, &HttpAuthResolver::empty());
+        assert_eq!(
+            cmd_result
+                .command_result
+                .expect("Command should complete successfully")
+                .exit_code,
+            0
+        );
+    }
+
+    #[test]
+    fn test_registry_telemetry_schema_cmd() {
+        let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let schema_file = temp_dir.path().join("schema.yaml");
+
+        // A new schema file has a section for the baseline and the head versions.
+        add_version(&schema_file);
+        let schema =
+            TelemetrySchema::try_from_file(&schema_file).expect("Invalid telemetry schema");
+        assert_eq!(schema.schema_url, "https://opentelemetry.io/schemas/1.1");
+        let content = std::fs::read_to_string(&schema_file).expect("Failed to read the schema");
+        assert_eq!(
+            content,
+            r#"file_format: 1.1.0
+schema_url: https://opentelemetry.io/schemas/1.1
+versions:
+  1.0.0: {}
+  1.1.0:
+    all:
+      changes:
+      - rename_attributes:
+          attribute_map:
+            attr1: attr1_bis
+    metrics:
+      changes:
+      - rename_metrics:
+          metric4: metric4_bis
+"#
+        );
+
+        // Adding the same version again replaces its section.
+        add_version(&schema_file);
+        assert_eq!(
+            std::fs::read_to_string(&schema_file).expect("Failed to read the schema"),
+            content
+        );
+    }
+}
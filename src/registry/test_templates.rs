@@ -0,0 +1,268 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Run the golden-file tests of a template target.
+//!
+//! Each sub-directory of the `tests` directory of a target is a test case made of:
+//! - a `registry` directory, the semantic convention registry to generate from,
+//! - an optional `params.yaml` file, the parameters passed to the templates,
+//! - an `expected` directory, the artifacts the templates must generate.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use clap::Args;
+use miette::Diagnostic;
+use serde::Serialize;
+
+use weaver_common::diagnostic::{DiagnosticMessage, DiagnosticMessages};
+use weaver_common::http_auth::HttpAuthResolver;
+use weaver_common::vdir::VirtualDirectoryPath;
+use weaver_common::{log_error, log_info, log_success};
+use weaver_config::{
+    EffectivePolicyConfig, EffectiveRegistryConfig, EffectiveResolveConfig,
+    WeaverConfig as ProjectWeaverConfig,
+};
+use weaver_forge::config::WeaverConfig;
+use weaver_forge::package::{LockMode, TemplatePackages};
+use weaver_forge::{OutputProcessor, OutputTarget};
+
+use crate::registry::apply_template_config;
+use crate::registry::generate::{
+    check_output_dir, generate_params_shared, open_template_packages, resolve_templates_root,
+    template_loader, GenerateCheckError,
+};
+use crate::weaver::{Resolved, WeaverEngine};
+use crate::{DiagnosticArgs, ExitDirectives};
+
+/// Directory of the test cases in the templates of a target.
+const TESTS_DIR: &str = "tests";
+/// Registry of a test case.
+const REGISTRY_DIR: &str = "registry";
+/// Optional parameters of a test case.
+const PARAMS_FILE: &str = "params.yaml";
+/// Expected output of a test case.
+const EXPECTED_DIR: &str = "expected";
+
+/// Parameters for the `registry test-templates` sub-command
+#[derive(Debug, Args)]
+pub struct RegistryTestTemplatesArgs {
+    /// Target whose templates are tested.
+    pub target: String,
+
+    /// Path to the directory where the templates are located.
+    /// Default is the `templates` directory.
+    #[arg(short = 't', long, default_value = "templates")]
+    pub templates: String,
+
+    /// Only run the test cases whose name contains this string.
+    #[arg(long)]
+    pub case: Option<String>,
+
+    /// Resolve the registries of the test cases with version 2 of the schema.
+    #[arg(long, default_value = "false")]
+    pub v2: bool,
+
+    /// Write the generated artifacts to the `expected` directory of each test case
+    /// instead of comparing them, e.g. to bless an intended change of the templates.
+    /// Files that are not generated anymore are deleted.
+    #[arg(long, default_value = "false")]
+    pub update: bool,
+
+    /// Allow the `command` post-processors of the templates directory to run external
+    /// programs, e.g. code formatters.
+    #[arg(long, default_value = "false")]
+    pub allow_post_process_commands: bool,
+
+    /// Parameters to specify the diagnostic format.
+    #[command(flatten)]
+    pub diagnostic: DiagnosticArgs,
+}
+
+/// A failure of the golden-file tests.
+#[derive(thiserror::Error, Debug, Serialize, Diagnostic)]
+enum TemplateTestError {
+    /// The target has no test case.
+    #[error("No test case found in `{tests_dir}`. Each test case is a directory with a `registry` directory, an optional `params.yaml` file and an `expected` directory.")]
+    NoTestCases { tests_dir: PathBuf },
+
+    /// The output of a test case differs from the expected one.
+    #[error("Test case `{case}`: {drift}")]
+    Drift {
+        case: String,
+        drift: GenerateCheckError,
+    },
+
+    /// The expected output of a test case could not be updated.
+    #[error("Test case `{case}`: failed to update `{path}`: {error}")]
+    Update {
+        case: String,
+        path: PathBuf,
+        error: String,
+    },
+}
+
+impl From<TemplateTestError> for DiagnosticMessages {
+    fn from(error: TemplateTestError) -> Self {
+        DiagnosticMessages::new(vec![DiagnosticMessage::new(error)])
+    }
+}
+
+/// Run the golden-file tests of a template target.
+pub(crate) fn command(
+    args: &RegistryTestTemplatesArgs,
+    cfg: Option<&ProjectWeaverConfig>,
+    auth: &HttpAuthResolver,
+) -> Result<ExitDirectives, DiagnosticMessages> {
+    let packages = open_template_packages(&args.templates, args.v2, LockMode::Check, auth)?;
+    let root = resolve_templates_root(&packages.dirs()[0]);
+    let tests_dir = root.join(&args.target).join(TESTS_DIR);
+    let mut cases: Vec<PathBuf> = std::fs::read_dir(&tests_dir)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .map(|entry| entry.path())
+                .filter(|path| path.join(REGISTRY_DIR).is_dir())
+                .filter(|path| {
+                    args.case
+                        .as_ref()
+                        .is_none_or(|filter| case_name(path).contains(filter.as_str()))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    if cases.is_empty() {
+        return Err(TemplateTestError::NoTestCases { tests_dir }.into());
+    }
+    cases.sort();
+
+    let mut diag_msgs = DiagnosticMessages::empty();
+    let mut failed = 0;
+    for case_dir in &cases {
+        let case = case_name(case_dir);
+        let expected_dir = case_dir.join(EXPECTED_DIR);
+        let result = render_case(args, cfg, auth, &packages, case_dir).and_then(|files| {
+            if args.update {
+                update_expected(&case, &expected_dir, &files)?;
+                Ok(Vec::new())
+            } else {
+                Ok(check_output_dir(&expected_dir, &files, |_| true)?)
+            }
+        });
+        match result {
+            Ok(drift) if drift.is_empty() => {
+                if args.update {
+                    log_info(format!("Test case `{case}` updated"));
+                } else {
+                    log_success(format!("Test case `{case}` passed"));
+                }
+            }
+            Ok(drift) => {
+                failed += 1;
+                log_error(format!("Test case `{case}` failed"));
+                diag_msgs.extend_from_vec(
+                    drift
+                        .into_iter()
+                        .map(|drift| {
+                            DiagnosticMessage::new(TemplateTestError::Drift {
+                                case: case.clone(),
+                                drift,
+                            })
+                        })
+                        .collect(),
+                );
+            }
+            Err(e) => {
+                failed += 1;
+                log_error(format!("Test case `{case}` failed"));
+                diag_msgs.extend(e);
+            }
+        }
+    }
+
+    if !diag_msgs.is_empty() {
+        log_error(format!("{failed} of {} test case(s) failed", cases.len()));
+        return Err(diag_msgs);
+    }
+    if !args.update {
+        log_success(format!("{} test case(s) passed", cases.len()));
+    }
+    Ok(ExitDirectives {
+        exit_code: 0,
+        warnings: None,
+    })
+}
+
+/// Name of a test case, its directory name.
+fn case_name(case_dir: &Path) -> String {
+    case_dir
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Resolve the registry of a test case and render the templates of the target in memory.
+fn render_case(
+    args: &RegistryTestTemplatesArgs,
+    cfg: Option<&ProjectWeaverConfig>,
+    auth: &HttpAuthResolver,
+    packages: &TemplatePackages,
+    case_dir: &Path,
+) -> Result<BTreeMap<PathBuf, String>, DiagnosticMessages> {
+    let registry = EffectiveRegistryConfig {
+        registry: VirtualDirectoryPath::LocalFolder {
+            path: case_dir.join(REGISTRY_DIR).display().to_string(),
+        },
+        v2: args.v2,
+        ..Default::default()
+    };
+    let policy = EffectivePolicyConfig::skip_all();
+    let resolve = EffectiveResolveConfig::default();
+    let weaver = WeaverEngine::new(&registry, &policy, &resolve, auth);
+    let mut diag_msgs = DiagnosticMessages::empty();
+    let resolved = weaver.load_and_resolve_main(&mut diag_msgs)?;
+    if !diag_msgs.is_empty() {
+        return Err(diag_msgs);
+    }
+
+    let params_file = case_dir.join(PARAMS_FILE);
+    let params = generate_params_shared(&None, &params_file.is_file().then_some(params_file))?;
+    let loader = template_loader(packages, &args.target)?;
+    let mut config = WeaverConfig::try_from_layers(&loader.roots())?;
+    apply_template_config(&mut config, cfg);
+    config.allow_post_process_commands(args.allow_post_process_commands);
+    let output = OutputProcessor::from_template_config(
+        config,
+        loader,
+        params,
+        OutputTarget::Directory(case_dir.join(EXPECTED_DIR)),
+    )?;
+    let files = match &resolved {
+        Resolved::V2(v) => output.generate_in_memory(v.template_schema())?,
+        Resolved::V1(v) => output.generate_in_memory(v.template_schema())?,
+    };
+    Ok(files)
+}
+
+/// Replace the expected output of a test case with the generated files.
+fn update_expected(
+    case: &str,
+    expected_dir: &Path,
+    files: &BTreeMap<PathBuf, String>,
+) -> Result<(), TemplateTestError> {
+    let update_error = |path: &Path, e: std::io::Error| TemplateTestError::Update {
+        case: case.to_owned(),
+        path: path.to_path_buf(),
+        error: e.to_string(),
+    };
+    if expected_dir.exists() {
+        std::fs::remove_dir_all(expected_dir).map_err(|e| update_error(expected_dir, e))?;
+    }
+    for (path, content) in files {
+        let path = expected_dir.join(path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| update_error(parent, e))?;
+        }
+        std::fs::write(&path, content).map_err(|e| update_error(&path, e))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,296 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Translate OTLP telemetry to a version of a telemetry schema, either by rewriting
+//! a recorded OTLP file or as an OTLP proxy.
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use clap::Args;
+use log::{info, warn};
+use weaver_common::diagnostic::DiagnosticMessages;
+use weaver_common::http_auth::HttpAuthResolver;
+use weaver_common::log_success;
+use weaver_config::{WeaverCommand, WeaverConfig};
+use weaver_macros::weaver_command;
+use weaver_otel_schema::TelemetrySchema;
+
+use super::otlp::otlp_file_ingester::{OtlpFileFormat, OtlpFileIngester};
+use super::otlp::recorder::{encode, RecordFormat};
+use super::otlp::translator::OtlpTranslator;
+use super::otlp::{listen_otlp_requests, OtlpRequest};
+use crate::registry::{load_config, Error};
+use crate::{DiagnosticArgs, ExitDirectives};
+
+/// Translate OTLP telemetry to a version of an OpenTelemetry telemetry schema.
+#[weaver_command(section = "translate", no_policy)]
+#[derive(Debug, Args, WeaverCommand)]
+pub struct RegistryTranslateArgs {
+    /// Parameters to specify the diagnostic format.
+    #[command(flatten)]
+    #[shared(diagnostic)]
+    pub diagnostic: DiagnosticArgs,
+
+    /// Path to the telemetry schema file (OTEP 0152) defining the renames of each version.
+    #[arg(long)]
+    schema_file: PathBuf,
+
+    /// Version to translate the telemetry to, forward or backward. Defaults to the
+    /// version of the schema URL of the telemetry schema file.
+    #[arg(long)]
+    target_version: Option<String>,
+
+    /// Recorded OTLP file to translate. When not set, the OTLP requests received by the
+    /// listeners are translated and forwarded to `--forward-endpoint`.
+    #[arg(long, requires = "output")]
+    input: Option<PathBuf>,
+
+    /// The format of the input file, also used for the output file. otlp-json |
+    /// otlp-proto-{traces,metrics,logs,profiles}
+    #[arg(long)]
+    #[config(default = "otlp-json")]
+    input_format: Option<String>,
+
+    /// Path of the translated file.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Upstream endpoint receiving the translated requests with their headers
+    /// (e.g. `authorization`), e.g. `http://collector:4317`. The upstream must
+    /// speak OTLP/gRPC.
+    #[arg(long)]
+    #[config]
+    forward_endpoint: Option<String>,
+
+    /// Address used by the gRPC OTLP listener.
+    #[arg(long)]
+    #[config(default = "0.0.0.0")]
+    grpc_address: Option<String>,
+
+    /// Port used by the gRPC OTLP listener.
+    #[arg(long)]
+    #[config(default = "4317")]
+    grpc_port: Option<u16>,
+
+    /// Enable the OTLP/HTTP listener (protobuf and JSON).
+    #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+    #[config(default = "false")]
+    otlp_http: Option<bool>,
+
+    /// Address used by the OTLP/HTTP listener. Defaults to the gRPC address.
+    #[arg(long)]
+    #[config]
+    http_address: Option<String>,
+
+    /// Port used by the OTLP/HTTP listener.
+    #[arg(long)]
+    #[config(default = "4318")]
+    http_port: Option<u16>,
+
+    /// Port used by the HTTP admin server (endpoints: /stop).
+    #[arg(long)]
+    #[config(default = "8080")]
+    admin_port: Option<u16>,
+
+    /// Seconds of inactivity before auto-stop (0 = never).
+    #[arg(long)]
+    #[config(default = "0")]
+    inactivity_timeout: Option<u64>,
+}
+
+/// Translate a recorded OTLP file or run an OTLP proxy translating the requests.
+pub(crate) fn command(
+    args: &RegistryTranslateArgs,
+    cfg: Option<&WeaverConfig>,
+    _auth: &HttpAuthResolver,
+) -> Result<ExitDirectives, DiagnosticMessages> {
+    let cmd_config = load_config(args, cfg);
+    let config = cmd_config.config;
+
+    info!("Weaver Registry Translate");
+    let schema = TelemetrySchema::try_from_file(&args.schema_file)?;
+    let translator = OtlpTranslator::new(schema, args.target_version.as_deref())?;
+    info!(
+        "Translating the telemetry to `{}`",
+        translator.target_schema_url()
+    );
+
+    if let Some(input) = &args.input {
+        let output = args.output.as_ref().ok_or_else(|| Error::Config {
+            error: "--output is required with --input".to_owned(),
+        })?;
+        let format: OtlpFileFormat = config
+            .input_format
+            .parse()
+            .map_err(|error| Error::Config { error })?;
+        let record_format = match format {
+            OtlpFileFormat::JsonLines => RecordFormat::Json,
+            OtlpFileFormat::Protobuf(_) => RecordFormat::Proto,
+        };
+
+        let mut content = Vec::new();
+        let requests = OtlpFileIngester::new(input, format).read_requests()?;
+        let count = requests.len();
+        for mut request in requests {
+            translator.translate(&mut request);
+            let bytes = match &request {
+                OtlpRequest::Traces(r) => encode(r, record_format),
+                OtlpRequest::Metrics(r) => encode(r, record_format),
+                OtlpRequest::Logs(r) => encode(r, record_format),
+                OtlpRequest::Profiles(r) => encode(r, record_format),
+                OtlpRequest::Error(_) | OtlpRequest::Stop(_) => continue,
+            }?;
+            content.extend_from_slice(&bytes);
+        }
+        fs::write(output, content).map_err(|e| Error::OutputWrite {
+            path: output.clone(),
+            error: e.to_string(),
+        })?;
+        log_success(format!(
+            "{count} OTLP requests translated to `{}`",
+            output.display()
+        ));
+        return Ok(ExitDirectives {
+            exit_code: 0,
+            warnings: None,
+        });
+    }
+
+    let forward_endpoint = config.forward_endpoint.ok_or_else(|| Error::Config {
+        error: "forward_endpoint is required without --input (set via --forward-endpoint or \
+                [translate] config)"
+            .to_owned(),
+    })?;
+    info!(
+        "Starting OTLP gRPC server on {}:{}",
+        config.grpc_address, config.grpc_port
+    );
+    let http_address = config
+        .http_address
+        .unwrap_or_else(|| config.grpc_address.clone());
+    let http_endpoint = if config.otlp_http {
+        info!(
+            "Starting OTLP/HTTP server on {http_address}:{}",
+            config.http_port
+        );
+        Some((http_address.as_str(), config.http_port))
+    } else {
+        None
+    };
+    let (requests, _coordinator) = listen_otlp_requests(
+        &config.grpc_address,
+        config.grpc_port,
+        http_endpoint,
+        Some(&forward_endpoint),
+        Some(Arc::new(translator)),
+        config.admin_port,
+        Duration::from_secs(config.inactivity_timeout),
+    )?;
+    info!("Forwarding the translated requests to {forward_endpoint}");
+    info!(
+        "To stop: press CTRL+C, send SIGHUP, or POST to http://localhost:{}/stop",
+        config.admin_port
+    );
+
+    let mut count = 0;
+    for request in requests {
+        match request {
+            OtlpRequest::Stop(signal) => {
+                info!("Received stop signal: {signal}");
+                break;
+            }
+            OtlpRequest::Error(e) => warn!("{e}"),
+            _ => count += 1,
+        }
+    }
+    log_success(format!("{count} OTLP requests translated and forwarded"));
+    Ok(ExitDirectives {
+        exit_code: 0,
+        warnings: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use weaver_common::http_auth::HttpAuthResolver;
+
+    use crate::registry::translate::RegistryTranslateArgs;
+    use crate::registry::{semconv_registry, RegistryCommand, RegistrySubCommand};
+
+    #[test]
+    fn test_config_cli_consistency() {
+        use crate::registry::tests::assert_config_cli_consistency;
+        assert_config_cli_consistency::<RegistryTranslateArgs>();
+    }
+
+    #[test]
+    fn test_translate_file() {
+        let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let schema_file = temp_dir.path().join("schema.yaml");
+        fs::write(
+            &schema_file,
+            r#"file_format: 1.1.0
+schema_url: https://example.com/schemas/1.1.0
+versions:
+  1.1.0:
+    spans:
+      changes:
+        - rename_attributes:
+            attribute_map:
+              http.method: http.request.method
+  1.0.0: {}
+"#,
+        )
+        .expect("Failed to write the schema file");
+        let input = temp_dir.path().join("traces.jsonl");
+        fs::write(
+            &input,
+            r#"{"resourceSpans":[{"scopeSpans":[{"spans":[{"name":"GET","attributes":[{"key":"http.method","value":{"stringValue":"GET"}}]}]}],"schemaUrl":"https://example.com/schemas/1.0.0"}]}
+"#,
+        )
+        .expect("Failed to write the input file");
+        let output = temp_dir.path().join("translated.jsonl");
+
+        let registry_cmd = RegistryCommand {
+            command: RegistrySubCommand::Translate(RegistryTranslateArgs {
+                diagnostic: Default::default(),
+                schema_file,
+                target_version: None,
+                input: Some(input),
+                input_format: None,
+                output: Some(output.clone()),
+                forward_endpoint: None,
+                grpc_address: None,
+                grpc_port: None,
+                otlp_http: None,
+                http_address: None,
+                http_port: None,
+                admin_port: None,
+                inactivity_timeout: None,
+            }),
+        };
+        let cmd_result = semconv_registry(&registry_cmd, None, &HttpAuthResolver::empty());
+        assert_eq!(
+            cmd_result
+                .command_result
+                .expect("Command should complete successfully")
+                .exit_code,
+            0
+        );
+
+        let translated = fs::read_to_string(&output).expect("Failed to read the output file");
+        assert_eq!(translated.lines().count(), 1);
+        assert!(
+            translated.contains(r#""key":"http.request.method""#),
+            "{translated}"
+        );
+        assert!(
+            translated.contains(r#""schemaUrl":"https://example.com/schemas/1.1.0""#),
+            "{translated}"
+        );
+    }
+}
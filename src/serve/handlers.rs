@@ -5,6 +5,7 @@
 use std::sync::Arc;
 
 use axum::{
+    body::Bytes,
     extract::{Path, Query, State},
     http::StatusCode,
     response::IntoResponse,
@@ -14,6 +15,7 @@ use schemars::schema_for;
 use serde_json::json;
 use weaver_forge::run_filter_raw;
 
+use crate::serve::live_check::LiveCheckError;
 use crate::serve::types::FilterParams;
 
 use super::server::AppState;
@@ -343,3 +345,51 @@ pub async fn filter_registry(
         }
     }
 }
+
+/// Live check a batch of telemetry samples against the registry.
+///
+/// Accepts a JSON array of live check samples (see the `LiveCheckSample`
+/// schema) or an OTLP/JSON export request (`resourceSpans`, `resourceMetrics`,
+/// `resourceLogs` or `resourceProfiles`), and returns the checked samples with
+/// their `live_check_result` findings plus the statistics of the batch.
+#[utoipa::path(
+    post,
+    path = "/api/v1/live-check",
+    request_body(
+        content = serde_json::Value,
+        description = "An array of live check samples, or an OTLP/JSON export request",
+        content_type = "application/json"
+    ),
+    responses(
+        (status = 200, description = "Live check report: `samples` with their findings and `statistics`", body = serde_json::Value),
+        (status = 400, description = "The body is not a batch of samples or an OTLP/JSON request"),
+        (status = 500, description = "The live check failed")
+    ),
+    tag = "live-check"
+)]
+pub async fn live_check(State(state): State<Arc<AppState>>, body: Bytes) -> impl IntoResponse {
+    // Samples and the live checker are not `Send`, the whole check runs on a
+    // blocking thread and only the serialized report comes back.
+    let result = tokio::task::spawn_blocking(move || state.live_check.check(&body)).await;
+    match result {
+        Ok(Ok(report)) => (
+            StatusCode::OK,
+            [(axum::http::header::CONTENT_TYPE, "application/json")],
+            report,
+        )
+            .into_response(),
+        Ok(Err(LiveCheckError::InvalidInput(error))) => {
+            (StatusCode::BAD_REQUEST, Json(json!({"error": error}))).into_response()
+        }
+        Ok(Err(LiveCheckError::Check(error))) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": error})),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": format!("Live check task failed: {e}")})),
+        )
+            .into_response(),
+    }
+}
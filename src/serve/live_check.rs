@@ -0,0 +1,130 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! On-demand live check of telemetry samples for the serve command.
+
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+
+use weaver_checker::PolicyFinding;
+use weaver_live_check::advice::{Advisor, RegoAdvisor};
+use weaver_live_check::live_checker::LiveChecker;
+use weaver_live_check::otlp_logger::OtlpEmitter;
+use weaver_live_check::{
+    CumulativeStatistics, Error, LiveCheckReport, LiveCheckRunner, LiveCheckStatistics, Sample,
+    SampleRef, VersionedAttribute, VersionedRegistry, VersionedSignal,
+};
+
+use crate::registry::live_check::{attach_resource, default_advisors};
+use crate::registry::otlp::otlp_ingester::samples_from_otlp_json;
+
+/// Everything needed to run a live check against the served registry.
+pub struct LiveCheckSettings {
+    /// The served registry, in the form expected by the live checker.
+    registry: Arc<VersionedRegistry>,
+    /// The Rego advisor, compiled once and shared by the requests.
+    rego_advisor: Arc<Mutex<RegoAdvisor>>,
+}
+
+/// Why a live check request could not be answered.
+#[derive(Debug)]
+pub enum LiveCheckError {
+    /// The request body is neither an array of samples nor an OTLP/JSON export request.
+    InvalidInput(String),
+    /// The live checker could not be created or failed while checking.
+    Check(String),
+}
+
+impl LiveCheckSettings {
+    /// Compiles the Rego advice policies (the default ones if `advice_policies` is
+    /// not set) against the served registry.
+    pub fn try_new(
+        registry: Arc<VersionedRegistry>,
+        advice_policies: &Option<PathBuf>,
+        advice_preprocessor: &Option<PathBuf>,
+        advice_data: &Option<String>,
+    ) -> Result<Self, Error> {
+        let live_checker = LiveChecker::new(Arc::clone(&registry), vec![]);
+        let rego_advisor = RegoAdvisor::new(
+            &live_checker,
+            advice_policies,
+            advice_preprocessor,
+            advice_data,
+        )?;
+        Ok(Self {
+            registry,
+            rego_advisor: Arc::new(Mutex::new(rego_advisor)),
+        })
+    }
+
+    /// Checks a batch of samples and returns the JSON live check report
+    /// (`{"samples": [...], "statistics": {...}}`).
+    ///
+    /// The body is either a JSON array of samples (the `weaver` JSON input
+    /// format) or an OTLP/JSON export request. The LiveChecker contains Rc
+    /// internally, so a fresh one is created for each request, sharing the
+    /// compiled Rego advisor.
+    pub fn check(&self, body: &[u8]) -> Result<String, LiveCheckError> {
+        let mut samples = parse_samples(body)?;
+
+        let mut live_checker = LiveChecker::new(Arc::clone(&self.registry), default_advisors());
+        live_checker.add_advisor(Box::new(SharedRegoAdvisor(Arc::clone(&self.rego_advisor))));
+
+        let mut stats =
+            LiveCheckStatistics::Cumulative(CumulativeStatistics::new(&live_checker.registry));
+        let mut current_resource = None;
+        for sample in &mut samples {
+            attach_resource(sample, &mut current_resource);
+            sample
+                .run_live_check(&mut live_checker, &mut stats, None, &sample.clone())
+                .map_err(|e| LiveCheckError::Check(e.to_string()))?;
+        }
+        stats.finalize();
+
+        serde_json::to_string(&LiveCheckReport {
+            samples,
+            statistics: stats,
+        })
+        .map_err(|e| LiveCheckError::Check(e.to_string()))
+    }
+}
+
+/// The Rego advisor shared by the requests, which evaluate its policies in turn.
+struct SharedRegoAdvisor(Arc<Mutex<RegoAdvisor>>);
+
+impl Advisor for SharedRegoAdvisor {
+    fn advise(
+        &mut self,
+        sample: SampleRef<'_>,
+        signal: &Sample,
+        registry_attribute: Option<Rc<VersionedAttribute>>,
+        registry_group: Option<Rc<VersionedSignal>>,
+        otlp_emitter: Option<Rc<OtlpEmitter>>,
+    ) -> Result<Vec<PolicyFinding>, Error> {
+        self.0
+            .lock()
+            .map_err(|e| Error::AdviceError {
+                error: e.to_string(),
+            })?
+            .advise(
+                sample,
+                signal,
+                registry_attribute,
+                registry_group,
+                otlp_emitter,
+            )
+    }
+}
+
+/// Reads the samples of a request body: a JSON array is a batch of samples,
+/// anything else is expected to be an OTLP/JSON export request.
+fn parse_samples(body: &[u8]) -> Result<Vec<Sample>, LiveCheckError> {
+    let value: serde_json::Value = serde_json::from_slice(body)
+        .map_err(|e| LiveCheckError::InvalidInput(format!("Invalid JSON: {e}")))?;
+    if value.is_array() {
+        serde_json::from_value(value)
+            .map_err(|e| LiveCheckError::InvalidInput(format!("Invalid samples: {e}")))
+    } else {
+        samples_from_otlp_json(body).map_err(|e| LiveCheckError::InvalidInput(e.to_string()))
+    }
+}
@@ -3,6 +3,8 @@
 //! Web API server for registry search, schema browsing, and more.
 
 use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
 
 use clap::Args;
 use log::info;
@@ -12,9 +14,11 @@ use crate::registry::{load_config, PolicyArgs, RegistryArgs};
 use crate::{CmdResult, DiagnosticArgs, ExitDirectives};
 use weaver_common::http_auth::HttpAuthResolver;
 use weaver_config::{WeaverCommand, WeaverConfig};
+use weaver_live_check::VersionedRegistry;
 use weaver_macros::weaver_command;
 
 mod handlers;
+mod live_check;
 mod server;
 mod types;
 mod ui;
@@ -46,6 +50,22 @@ pub struct ServeCommand {
     #[config]
     pub cors_origins: Option<String>,
 
+    /// Advice policies directory used by the live check endpoint. Set this to override the default policies.
+    #[arg(long)]
+    #[config]
+    pub advice_policies: Option<PathBuf>,
+
+    /// Advice preprocessor used by the live check endpoint. A jq script to preprocess the registry data before passing to rego.
+    #[arg(long)]
+    #[config]
+    pub advice_preprocessor: Option<PathBuf>,
+
+    /// Glob pattern pointing to additional JSON/YAML files to load into OPA rego data for the live check endpoint.
+    /// Files are nested in OPA data using their relative path inside the glob base directory (e.g. schemas/user.json is loaded at data.user).
+    #[arg(long)]
+    #[config]
+    pub advice_data: Option<String>,
+
     /// Parameters to specify the diagnostic format.
     #[command(flatten)]
     #[shared(diagnostic)]
@@ -121,6 +141,13 @@ fn run_serve(
         forge_registry.registry.events.len(),
         forge_registry.registry.entities.len(),
     );
+    let live_check = live_check::LiveCheckSettings::try_new(
+        Arc::new(VersionedRegistry::V2(Box::new(forge_registry.clone()))),
+        &cmd_config.config.advice_policies,
+        &cmd_config.config.advice_preprocessor,
+        &cmd_config.config.advice_data,
+    )
+    .map_err(DiagnosticMessages::from_error)?;
     let bind = cmd_config.config.bind;
     let cors_origins = cmd_config.config.cors_origins.as_deref();
 
@@ -129,7 +156,9 @@ fn run_serve(
     // Run the async server using tokio runtime
     tokio::runtime::Runtime::new()
         .expect("Failed to create tokio runtime")
-        .block_on(async { run_server(bind, forge_registry, stats_json, cors_origins).await })
+        .block_on(async {
+            run_server(bind, forge_registry, stats_json, live_check, cors_origins).await
+        })
         .map_err(DiagnosticMessages::from_error)?;
 
     Ok(ExitDirectives {
@@ -10,7 +10,7 @@ use std::sync::Arc;
 use axum::{
     http::{header, StatusCode},
     response::{Html, IntoResponse},
-    routing::get,
+    routing::{get, post},
     Json, Router,
 };
 use miette::Diagnostic;
@@ -24,6 +24,7 @@ use weaver_forge::v2::{
 use weaver_semconv::stability::Stability;
 
 use super::handlers;
+use super::live_check::LiveCheckSettings;
 use super::types::SearchResponse;
 use super::ui::UI_DIST;
 use weaver_search::{ScoredResult, SearchContext, SearchResult, SearchType};
@@ -36,6 +37,8 @@ pub struct AppState {
     pub search_ctx: SearchContext,
     /// Full registry statistics, serialized once at startup and served verbatim.
     pub stats_json: String,
+    /// Registry and advisor settings used by the live check endpoint.
+    pub live_check: LiveCheckSettings,
 }
 
 /// Error type for server operations.
@@ -98,6 +101,7 @@ impl From<serde_json::Error> for Error {
         handlers::get_registry_entity,
         handlers::search_registry,
         handlers::filter_registry,
+        handlers::live_check,
     ),
     components(
         schemas(
@@ -121,6 +125,7 @@ impl From<serde_json::Error> for Error {
         (name = "health", description = "Health check endpoints"),
         (name = "schemas", description = "JSON schema endpoints"),
         (name = "registry", description = "Registry stats, search, and resource lookup"),
+        (name = "live-check", description = "Validation of telemetry samples against the registry"),
     )
 )]
 pub struct ApiDoc;
@@ -137,11 +142,13 @@ async fn openapi_spec() -> Json<utoipa::openapi::OpenApi> {
 /// * `bind_addr` - The address to bind the server to.
 /// * `registry` - The resolved V2 registry to serve.
 /// * `stats_json` - Full registry statistics, pre-serialized to JSON at startup.
+/// * `live_check` - Registry and advisor settings used by the live check endpoint.
 /// * `cors_origins` - Optional CORS origins. Use "*" for any origin, comma-separated for specific origins, or None for no CORS.
 pub async fn run_server(
     bind_addr: SocketAddr,
     registry: ForgeResolvedRegistry,
     stats_json: String,
+    live_check: LiveCheckSettings,
     cors_origins: Option<&str>,
 ) -> Result<(), Error> {
     // Build search context once at startup
@@ -151,6 +158,7 @@ pub async fn run_server(
         registry,
         search_ctx,
         stats_json,
+        live_check,
     });
 
     let mut app = Router::new()
@@ -185,6 +193,8 @@ pub async fn run_server(
         .route("/api/v1/registry/search", get(handlers::search_registry))
         // Filter
         .route("/api/v1/registry/filter", get(handlers::filter_registry))
+        // Live check
+        .route("/api/v1/live-check", post(handlers::live_check))
         // OpenAPI specification
         .route("/api/v1/openapi.json", get(openapi_spec))
         // UI fallback - serves embedded static files
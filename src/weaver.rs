@@ -241,6 +241,14 @@ impl Resolved {
         }
     }
 
+    /// Converts into a V2 resolved repository, converting a V1 one if needed.
+    pub fn into_v2(self) -> Result<ResolvedV2, Error> {
+        match self {
+            Resolved::V1(v) => v.try_into(),
+            Resolved::V2(v) => Ok(v),
+        }
+    }
+
     pub fn check_comparison_after_resolution(
         &self,
         baseline: &Resolved,
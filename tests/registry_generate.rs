@@ -293,3 +293,711 @@ templates:
         "readme.md should be generated when the `when` clause is true"
     );
 }
+
+/// A registry with a single attribute group `registry.{id}`.
+fn registry_group(id: &str) -> String {
+    format!(
+        "groups:\n\
+         \x20 - id: registry.{id}\n\
+         \x20   type: attribute_group\n\
+         \x20   brief: Test.\n\
+         \x20   attributes:\n\
+         \x20     - id: {id}.attr\n\
+         \x20       type: string\n\
+         \x20       brief: An attribute.\n\
+         \x20       stability: stable\n\
+         \x20       examples: [\"a\"]\n"
+    )
+}
+
+/// Write a project with a `registry` and the `tgt` target with two templates:
+/// `ids.md` lists the group ids and `static.md` does not depend on the registry.
+/// Returns the registry and the target directories.
+fn write_project(proj: &Path, group_id: &str) -> (std::path::PathBuf, std::path::PathBuf) {
+    let registry = proj.join("registry");
+    fs::create_dir_all(&registry).expect("Failed to create registry dir");
+    fs::write(registry.join("test.yaml"), registry_group(group_id))
+        .expect("Failed to write registry");
+
+    let tdir = proj.join("templates").join("registry").join("tgt");
+    fs::create_dir_all(&tdir).expect("Failed to create template dir");
+    fs::write(
+        tdir.join("weaver.yaml"),
+        r#"templates:
+  - template: "ids.md"
+    filter: "[.groups[].id]"
+    application_mode: single
+  - template: "static.md"
+    filter: '{"name": "static"}'
+    application_mode: single
+"#,
+    )
+    .expect("Failed to write weaver.yaml");
+    fs::write(tdir.join("ids.md"), "{{ ctx | join(\",\") }}").expect("Failed to write template");
+    fs::write(tdir.join("static.md"), "{{ ctx.name }}").expect("Failed to write template");
+    (registry, tdir)
+}
+
+/// Run `weaver registry generate` on the project of `write_project`.
+fn generate(proj: &Path, extra_args: &[&str]) -> std::process::Output {
+    let mut cmd = Command::cargo_bin("weaver").expect("weaver binary not found");
+    cmd.current_dir(proj)
+        .args([
+            "--quiet",
+            "registry",
+            "generate",
+            "-r",
+            "registry",
+            "-t",
+            "templates",
+            "--skip-policies",
+        ])
+        .args(extra_args)
+        .args(["tgt", "out"])
+        .timeout(std::time::Duration::from_secs(60))
+        .output()
+        .expect("failed to execute process")
+}
+
+/// `--check` reports the drift of the output directory without touching it.
+#[test]
+fn test_generate_check() {
+    let project = tempfile::tempdir().expect("Failed to create temp dir");
+    let proj = project.path();
+    let _ = write_project(proj, "first");
+    let out = proj.join("out");
+
+    assert!(generate(proj, &["--manifest"]).status.success());
+    let output = generate(proj, &["--check"]);
+    assert!(
+        output.status.success(),
+        "check failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    // Change a generated file, remove another, add a hand-written one and a file recorded
+    // in the manifest by a previous generation.
+    fs::write(out.join("ids.md"), "registry.old").expect("Failed to write ids.md");
+    fs::remove_file(out.join("static.md")).expect("Failed to remove static.md");
+    fs::write(out.join("extra.md"), "extra").expect("Failed to write extra.md");
+    fs::write(out.join("old.md"), "old").expect("Failed to write old.md");
+    let manifest_path = out.join(".weaver-manifest.json");
+    let mut manifest: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&manifest_path).expect("Missing manifest"))
+            .expect("Invalid manifest");
+    manifest["files"]["old.md"] = manifest["files"]["ids.md"].clone();
+    fs::write(&manifest_path, manifest.to_string()).expect("Failed to write the manifest");
+
+    let output = generate(
+        proj,
+        &[
+            "--check",
+            "--diagnostic-format",
+            "json",
+            "--diagnostic-stdout",
+            "true",
+        ],
+    );
+    assert!(!output.status.success());
+    let stdout = String::from_utf8(output.stdout).expect("Invalid UTF-8");
+    let diagnostics: Vec<serde_json::Value> = serde_json::from_str(&stdout).expect("Invalid JSON");
+    // Errors are serialized as `{"<kind>": {"path": ..., "diff": ...}}`.
+    let errors: Vec<(&String, &serde_json::Value)> = diagnostics
+        .iter()
+        .filter_map(|d| d["error"].as_object()?.iter().next())
+        .collect();
+    let kinds: Vec<(&str, &str)> = errors
+        .iter()
+        .map(|(kind, error)| (kind.as_str(), error["path"].as_str().unwrap_or_default()))
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            ("Changed", "ids.md"),
+            ("Added", "static.md"),
+            ("Orphaned", "old.md")
+        ]
+    );
+    let diff = errors[0].1["diff"].as_str().expect("missing diff");
+    assert!(diff.contains("-registry.old") && diff.contains("+registry.first"));
+
+    // The output directory is left untouched.
+    assert_eq!(
+        fs::read_to_string(out.join("ids.md")).unwrap(),
+        "registry.old"
+    );
+    assert!(!out.join("static.md").exists());
+    assert!(out.join("extra.md").exists());
+}
+
+/// Wait until `condition` holds, panics after 60 seconds.
+fn wait_for(what: &str, condition: impl Fn() -> bool) {
+    let start = std::time::Instant::now();
+    while !condition() {
+        assert!(
+            start.elapsed() < std::time::Duration::from_secs(60),
+            "timed out waiting for {what}"
+        );
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+}
+
+/// Kills the watching process even when an assertion fails.
+struct Watcher(std::process::Child);
+
+impl Drop for Watcher {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+/// `--watch` regenerates only the templates affected by a change and keeps
+/// running after an invalid registry.
+#[test]
+fn test_generate_watch() {
+    let project = tempfile::tempdir().expect("Failed to create temp dir");
+    let proj = project.path();
+    let (registry, tdir) = write_project(proj, "first");
+
+    let mut watcher = Watcher(
+        std::process::Command::new(env!("CARGO_BIN_EXE_weaver"))
+            .current_dir(proj)
+            .args([
+                "--quiet",
+                "registry",
+                "generate",
+                "-r",
+                "registry",
+                "-t",
+                "templates",
+                "--skip-policies",
+                "--watch",
+                "tgt",
+                "out",
+            ])
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .spawn()
+            .expect("Failed to start weaver"),
+    );
+    let out = proj.join("out");
+    let read = |file: &str| fs::read_to_string(out.join(file)).unwrap_or_default();
+
+    wait_for("the initial generation", || {
+        read("ids.md") == "registry.first" && read("static.md") == "static"
+    });
+
+    // A registry change only renders the templates whose filter output changed.
+    fs::remove_file(out.join("static.md")).expect("Failed to remove static.md");
+    fs::write(registry.join("test.yaml"), registry_group("second"))
+        .expect("Failed to write registry");
+    wait_for("the registry change", || {
+        read("ids.md") == "registry.second"
+    });
+    assert!(!out.join("static.md").exists());
+
+    // A template change renders that template.
+    fs::write(tdir.join("static.md"), "{{ ctx.name }}!").expect("Failed to write template");
+    wait_for("the template change", || read("static.md") == "static!");
+
+    // An invalid registry is reported and the watch goes on.
+    fs::write(registry.join("test.yaml"), "groups: [").expect("Failed to write registry");
+    std::thread::sleep(std::time::Duration::from_secs(2));
+    assert!(watcher.0.try_wait().expect("try_wait failed").is_none());
+    fs::write(registry.join("test.yaml"), registry_group("third"))
+        .expect("Failed to write registry");
+    wait_for("the registry fix", || read("ids.md") == "registry.third");
+}
+
+/// The files that are not generated anymore are pruned on demand, except when
+/// they were edited since their generation.
+#[test]
+fn test_generate_prune() {
+    let project = tempfile::tempdir().expect("Failed to create temp dir");
+    let proj = project.path();
+    let (_, tdir) = write_project(proj, "first");
+    let out = proj.join("out");
+    let weaver_yaml = fs::read_to_string(tdir.join("weaver.yaml")).expect("Failed to read");
+    // Drop the `static.md` template from the configuration.
+    let without_static = || {
+        fs::write(
+            tdir.join("weaver.yaml"),
+            r#"templates:
+  - template: "ids.md"
+    filter: "[.groups[].id]"
+    application_mode: single
+"#,
+        )
+        .expect("Failed to write weaver.yaml");
+    };
+
+    // The manifest is only written on demand.
+    assert!(generate(proj, &[]).status.success());
+    assert!(!out.join(".weaver-manifest.json").exists());
+    assert!(generate(proj, &["--manifest"]).status.success());
+    let manifest = fs::read_to_string(out.join(".weaver-manifest.json")).expect("No manifest");
+    assert!(manifest.contains("\"static.md\""));
+
+    // Without `--prune`, the file is kept and stays in the manifest.
+    without_static();
+    assert!(generate(proj, &["--manifest"]).status.success());
+    assert!(out.join("static.md").exists());
+    let manifest = fs::read_to_string(out.join(".weaver-manifest.json")).expect("No manifest");
+    assert!(manifest.contains("\"static.md\""));
+    // With `--prune`, it is deleted.
+    assert!(generate(proj, &["--prune"]).status.success());
+    assert!(!out.join("static.md").exists());
+    assert!(out.join("ids.md").exists());
+
+    // A file edited since its generation is kept.
+    fs::write(tdir.join("weaver.yaml"), &weaver_yaml).expect("Failed to write weaver.yaml");
+    assert!(generate(proj, &["--prune"]).status.success());
+    fs::write(out.join("static.md"), "edited").expect("Failed to edit static.md");
+    without_static();
+    assert!(generate(proj, &["--prune"]).status.success());
+    assert_eq!(
+        fs::read_to_string(out.join("static.md")).expect("static.md was pruned"),
+        "edited"
+    );
+}
+
+/// Run `weaver registry test-templates` on the `tgt` target of `write_project`.
+fn test_templates(proj: &Path, extra_args: &[&str]) -> std::process::Output {
+    let mut cmd = Command::cargo_bin("weaver").expect("weaver binary not found");
+    cmd.current_dir(proj)
+        .args(["--quiet", "registry", "test-templates", "-t", "templates"])
+        .args(extra_args)
+        .arg("tgt")
+        .timeout(std::time::Duration::from_secs(60))
+        .output()
+        .expect("failed to execute process")
+}
+
+/// The golden-file test cases of a target are generated from their own registry,
+/// compared to their expected output and blessed with `--update`.
+#[test]
+fn test_registry_test_templates() {
+    let project = tempfile::tempdir().expect("Failed to create temp dir");
+    let proj = project.path();
+    let (_, tdir) = write_project(proj, "first");
+
+    // No test case.
+    assert!(!test_templates(proj, &[]).status.success());
+
+    let case = tdir.join("tests").join("basic");
+    fs::create_dir_all(case.join("registry")).expect("Failed to create case registry");
+    fs::write(case.join("registry/test.yaml"), registry_group("case"))
+        .expect("Failed to write case registry");
+
+    // Without expected output the case fails, `--update` blesses the output.
+    assert!(!test_templates(proj, &[]).status.success());
+    let output = test_templates(proj, &["--update"]);
+    assert!(
+        output.status.success(),
+        "update failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let expected = case.join("expected");
+    assert_eq!(
+        fs::read_to_string(expected.join("ids.md")).expect("ids.md not updated"),
+        "registry.case"
+    );
+    assert!(expected.join("static.md").exists());
+    assert!(test_templates(proj, &[]).status.success());
+
+    // A change of the templates is reported with a diff.
+    fs::write(tdir.join("ids.md"), "{{ ctx | join(\";\") }} ids").expect("Failed to write");
+    let output = test_templates(
+        proj,
+        &["--diagnostic-format", "json", "--diagnostic-stdout", "true"],
+    );
+    assert!(!output.status.success());
+    let stdout = String::from_utf8(output.stdout).expect("Invalid UTF-8");
+    let diagnostics: Vec<serde_json::Value> = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert_eq!(diagnostics.len(), 1);
+    let drift = &diagnostics[0]["error"]["Drift"];
+    assert_eq!(drift["case"], "basic");
+    let diff = drift["drift"]["Changed"]["diff"]
+        .as_str()
+        .expect("missing diff");
+    assert!(diff.contains("-registry.case") && diff.contains("+registry.case ids"));
+
+    // An unknown case filter selects no test case.
+    assert!(!test_templates(proj, &["--case", "unknown"])
+        .status
+        .success());
+    assert!(test_templates(proj, &["--case", "bas", "--update"])
+        .status
+        .success());
+}
+
+/// The templates of a package are layered over the template packages it depends
+/// on, which are recorded in a lock file.
+#[test]
+fn test_generate_template_packages() {
+    let project = tempfile::tempdir().expect("Failed to create temp dir");
+    let proj = project.path();
+    let (_, shared_tdir) = write_project(proj, "first");
+    // The templates of `write_project` become the `shared` package.
+    fs::rename(proj.join("templates"), proj.join("shared")).expect("Failed to move templates");
+    let shared = proj.join("shared");
+    let shared_tdir = shared.join(shared_tdir.strip_prefix(proj.join("templates")).unwrap());
+    fs::write(
+        shared.join("weaver-package.yaml"),
+        "name: shared\nversion: 1.2.0\nregistry_schemas: [v1]\n",
+    )
+    .expect("Failed to write the shared manifest");
+
+    // The consumer only overrides `static.md`.
+    let tdir = proj.join("templates").join("registry").join("tgt");
+    fs::create_dir_all(&tdir).expect("Failed to create template dir");
+    fs::write(tdir.join("static.md"), "{{ ctx.name }} overridden").expect("Failed to write");
+    fs::write(
+        proj.join("templates").join("weaver-package.yaml"),
+        "name: consumer\nversion: 0.1.0\ndependencies:\n  - name: shared\n    version: ^1.0\n    path: ../shared\n",
+    )
+    .expect("Failed to write the consumer manifest");
+
+    // A dry run doesn't write the lock file.
+    assert!(generate(proj, &["--dry-run"]).status.success());
+    assert!(!proj.join("templates").join("weaver-package.lock").exists());
+
+    let output = generate(proj, &[]);
+    assert!(
+        output.status.success(),
+        "generation failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let out = proj.join("out");
+    assert_eq!(
+        fs::read_to_string(out.join("ids.md")).unwrap(),
+        "registry.first"
+    );
+    assert_eq!(
+        fs::read_to_string(out.join("static.md")).unwrap(),
+        "static overridden"
+    );
+    let lock = fs::read_to_string(proj.join("templates").join("weaver-package.lock"))
+        .expect("No lock file");
+    assert!(lock.contains("shared:") && lock.contains("version: 1.2.0"));
+
+    // A change of the dependency fails until the lock file is updated.
+    fs::write(shared_tdir.join("ids.md"), "{{ ctx | join(\";\") }}").expect("Failed to write");
+    assert!(!generate(proj, &[]).status.success());
+    assert!(generate(proj, &["--update-lock"]).status.success());
+    assert!(generate(proj, &[]).status.success());
+
+    // A dependency that doesn't match the required version is rejected.
+    fs::write(
+        shared.join("weaver-package.yaml"),
+        "name: shared\nversion: 2.0.0\n",
+    )
+    .expect("Failed to write the shared manifest");
+    assert!(!generate(proj, &["--update-lock"]).status.success());
+}
+
+/// Several targets are generated from a single resolution, from the command line
+/// and from the `[[generate.targets]]` of `.weaver.toml`, each with its own output
+/// directory and parameters.
+#[test]
+fn test_generate_multiple_targets() {
+    let project = tempfile::tempdir().expect("Failed to create temp dir");
+    let proj = project.path();
+    _ = write_project(proj, "first");
+    let docs = proj.join("templates").join("registry").join("docs");
+    fs::create_dir_all(&docs).expect("Failed to create template dir");
+    fs::write(
+        docs.join("weaver.yaml"),
+        "templates:\n  - template: \"ids.md\"\n    filter: \"[.groups[].id]\"\n    application_mode: single\n",
+    )
+    .expect("Failed to write weaver.yaml");
+    fs::write(
+        docs.join("ids.md"),
+        "{{ params.prefix }}{{ ctx | join(\",\") }}",
+    )
+    .expect("Failed to write template");
+    fs::write(proj.join("docs.yaml"), "params:\n  prefix: \"docs:\"\n")
+        .expect("Failed to write params");
+
+    // The positional target and a `--targets` one.
+    let output = generate(proj, &["--targets", "docs=out-docs,docs.yaml"]);
+    assert!(
+        output.status.success(),
+        "generation failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        fs::read_to_string(proj.join("out").join("ids.md")).unwrap(),
+        "registry.first"
+    );
+    assert_eq!(
+        fs::read_to_string(proj.join("out-docs").join("ids.md")).unwrap(),
+        "docs:registry.first"
+    );
+    assert!(
+        generate(proj, &["--check", "--targets", "docs=out-docs,docs.yaml"])
+            .status
+            .success()
+    );
+    // Two targets can't share an output directory.
+    assert!(!generate(proj, &["--targets", "docs=out"]).status.success());
+    // Nor nested output directories.
+    assert!(!generate(proj, &["--targets", "docs=./out/docs"])
+        .status
+        .success());
+
+    // The targets of `.weaver.toml`.
+    fs::write(
+        proj.join(".weaver.toml"),
+        "[[generate.targets]]\ntarget = \"tgt\"\noutput = \"cfg-out\"\n\n\
+         [[generate.targets]]\ntarget = \"docs\"\noutput = \"cfg-docs\"\nparams = \"docs.yaml\"\n",
+    )
+    .expect("Failed to write .weaver.toml");
+    let mut cmd = Command::cargo_bin("weaver").expect("weaver binary not found");
+    let output = cmd
+        .current_dir(proj)
+        .args([
+            "--quiet",
+            "registry",
+            "generate",
+            "-r",
+            "registry",
+            "-t",
+            "templates",
+            "--skip-policies",
+        ])
+        .timeout(std::time::Duration::from_secs(60))
+        .output()
+        .expect("failed to execute process");
+    assert!(
+        output.status.success(),
+        "generation failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(proj.join("cfg-out").join("static.md").exists());
+    assert_eq!(
+        fs::read_to_string(proj.join("cfg-docs").join("ids.md")).unwrap(),
+        "docs:registry.first"
+    );
+}
+
+/// `--dry-run` prints the plan of the generation without writing anything.
+#[test]
+fn test_generate_dry_run() {
+    let project = tempfile::tempdir().expect("Failed to create temp dir");
+    let proj = project.path();
+    let (_, tdir) = write_project(proj, "first");
+    fs::write(
+        tdir.join("weaver.yaml"),
+        r#"templates:
+  - template: "ids.md"
+    filter: "[.groups[].id]"
+    application_mode: each
+    file_name: "{{ ctx }}.md"
+  - template: "static.md"
+    filter: '{"name": "static"}'
+    application_mode: single
+    when: "false"
+"#,
+    )
+    .expect("Failed to write weaver.yaml");
+
+    let output = generate(proj, &["--dry-run"]);
+    assert!(
+        output.status.success(),
+        "dry run failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let text = String::from_utf8_lossy(&output.stdout);
+    assert!(text.contains("  -> registry.first.md"), "{text}");
+    assert!(
+        text.contains("skipped: `when` clause `false` is not met"),
+        "{text}"
+    );
+    assert!(!proj.join("out").exists());
+
+    let output = generate(proj, &["--dry-run=json"]);
+    assert!(output.status.success());
+    let plan: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("The plan must be JSON");
+    assert_eq!(plan["templates"][0]["template"], "ids.md");
+    assert_eq!(plan["templates"][0]["items"], 1);
+    assert_eq!(plan["templates"][0]["files"][0], "registry.first.md");
+    assert_eq!(plan["templates"][1]["skipped"]["reason"], "when");
+    assert!(!proj.join("out").exists());
+
+    // A dry run doesn't check or prune an output directory.
+    assert!(!generate(proj, &["--dry-run", "--check"]).status.success());
+}
+
+/// The `post_process` steps of `weaver.yaml` transform the generated files, and
+/// `--check` compares the post-processed content.
+#[test]
+fn test_generate_post_process() {
+    let project = tempfile::tempdir().expect("Failed to create temp dir");
+    let proj = project.path();
+    let (_, tdir) = write_project(proj, "first");
+    fs::write(
+        tdir.join("weaver.yaml"),
+        r#"post_process:
+  - license_header: "<!-- Generated -->"
+templates:
+  - template: "ids.md"
+    filter: "[.groups[].id]"
+    application_mode: single
+    post_process:
+      - trim_trailing_whitespace
+"#,
+    )
+    .expect("Failed to write weaver.yaml");
+    fs::write(tdir.join("ids.md"), "{{ ctx | join(\",\") }}   ").expect("Failed to write template");
+
+    let output = generate(proj, &[]);
+    assert!(
+        output.status.success(),
+        "generation failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        fs::read_to_string(proj.join("out").join("ids.md")).unwrap(),
+        "<!-- Generated -->\nregistry.first"
+    );
+    assert!(generate(proj, &["--check"]).status.success());
+
+    // A command post-processor only runs when explicitly allowed.
+    #[cfg(unix)]
+    {
+        fs::write(
+            tdir.join("weaver.yaml"),
+            r#"templates:
+  - template: "ids.md"
+    filter: "[.groups[].id]"
+    application_mode: single
+    post_process:
+      - command: ["tr", "a-z", "A-Z"]
+"#,
+        )
+        .expect("Failed to write weaver.yaml");
+        let output = generate(
+            proj,
+            &["--diagnostic-format", "json", "--diagnostic-stdout", "true"],
+        );
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stdout).contains("PostProcessCommandNotAllowed"));
+        assert!(generate(proj, &["--allow-post-process-commands"])
+            .status
+            .success());
+        assert_eq!(
+            fs::read_to_string(proj.join("out").join("ids.md")).unwrap(),
+            "REGISTRY.FIRST   "
+        );
+    }
+}
+
+/// Returns the content of the files of a directory, keyed by their relative path.
+fn dir_content(dir: &Path) -> std::collections::BTreeMap<String, Vec<u8>> {
+    let mut content = std::collections::BTreeMap::new();
+    let mut dirs = vec![dir.to_path_buf()];
+    while let Some(current) = dirs.pop() {
+        for entry in fs::read_dir(&current).expect("Failed to read dir") {
+            let path = entry.expect("Failed to read entry").path();
+            if path.is_dir() {
+                dirs.push(path);
+            } else {
+                let relative = path.strip_prefix(dir).expect("Failed to strip prefix");
+                _ = content.insert(
+                    relative.display().to_string(),
+                    fs::read(&path).expect("Failed to read file"),
+                );
+            }
+        }
+    }
+    content
+}
+
+/// A generation with remote templates served from the persistent cache leaves the
+/// cached content unchanged.
+#[test]
+fn test_generate_cached_remote_templates() {
+    use std::io::Write;
+
+    let project = tempfile::tempdir().expect("Failed to create temp dir");
+    let proj = project.path();
+    _ = write_project(proj, "first");
+
+    let mut archive = Vec::new();
+    let mut zip = zip::ZipWriter::new(std::io::Cursor::new(&mut archive));
+    for (name, content) in dir_content(&proj.join("templates")) {
+        zip.start_file(name, zip::write::SimpleFileOptions::default())
+            .expect("Failed to add file to zip");
+        zip.write_all(&content).expect("Failed to write to zip");
+    }
+    _ = zip.finish().expect("Failed to finish zip");
+
+    // Serves the archive to every request.
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("Failed to bind");
+    let templates = format!(
+        "http://{}/templates.zip",
+        listener.local_addr().expect("No local address")
+    );
+    _ = std::thread::spawn(move || {
+        for mut stream in listener.incoming().map_while(Result::ok) {
+            let mut request = Vec::new();
+            let mut buf = [0; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                match std::io::Read::read(&mut stream, &mut buf) {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => request.extend_from_slice(&buf[..n]),
+                }
+            }
+            let header = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/zip\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                archive.len()
+            );
+            _ = stream
+                .write_all(header.as_bytes())
+                .and_then(|()| stream.write_all(&archive));
+        }
+    });
+
+    let run = || {
+        Command::cargo_bin("weaver")
+            .expect("weaver binary not found")
+            .current_dir(proj)
+            .args(["--quiet", "--cache-dir", "cache", "registry", "generate"])
+            .args([
+                "-r",
+                "registry",
+                "-t",
+                &templates,
+                "--skip-policies",
+                "tgt",
+                "out",
+            ])
+            .timeout(std::time::Duration::from_secs(60))
+            .output()
+            .expect("failed to execute process")
+    };
+    let output = run();
+    assert!(
+        output.status.success(),
+        "generate failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let cached = dir_content(&proj.join("cache").join("content"));
+    assert!(!cached.is_empty());
+
+    fs::remove_dir_all(proj.join("out")).expect("Failed to remove output");
+    let output = run();
+    assert!(
+        output.status.success(),
+        "generate failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        fs::read_to_string(proj.join("out").join("ids.md")).expect("Failed to read output"),
+        "registry.first"
+    );
+    assert_eq!(dir_content(&proj.join("cache").join("content")), cached);
+}
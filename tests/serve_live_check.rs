@@ -0,0 +1,108 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Test the live check endpoint of the weaver serve command.
+
+use std::process::{Child, Command as StdCommand};
+use std::thread::sleep;
+use std::time::Duration;
+use weaver_test_support::reserve_test_port;
+
+/// Kills the serve process even when an assertion fails.
+struct Server(Child);
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+fn post(url: &str, body: &str) -> (u16, serde_json::Value) {
+    let response = ureq::post(url)
+        .config()
+        .http_status_as_error(false)
+        .build()
+        .header("Content-Type", "application/json")
+        .send(body)
+        .expect("POST /api/v1/live-check failed");
+    let status = response.status().as_u16();
+    let body = response
+        .into_body()
+        .read_to_string()
+        .expect("failed to read the response");
+    (
+        status,
+        serde_json::from_str(&body).expect("response is not JSON"),
+    )
+}
+
+/// Polls the registry stats endpoint until the server answers.
+fn wait_until_ready(port: u16) {
+    let url = format!("http://127.0.0.1:{port}/api/v1/registry/stats");
+    for _ in 0..300 {
+        if ureq::get(&url).call().is_ok() {
+            return;
+        }
+        sleep(Duration::from_millis(100));
+    }
+    panic!("weaver serve is not ready after 30s");
+}
+
+fn finding_ids(result: &serde_json::Value) -> Vec<&str> {
+    result["live_check_result"]["all_advice"]
+        .as_array()
+        .expect("missing findings")
+        .iter()
+        .filter_map(|advice| advice["id"].as_str())
+        .collect()
+}
+
+/// Test that samples and OTLP/JSON requests are checked against the served registry.
+#[test]
+fn test_live_check_endpoint() {
+    let port = reserve_test_port();
+    let _server = Server(
+        StdCommand::new(env!("CARGO_BIN_EXE_weaver"))
+            .args([
+                "serve",
+                "-r",
+                "crates/weaver_emit/data",
+                "--bind",
+                &format!("127.0.0.1:{port}"),
+            ])
+            .spawn()
+            .expect("Failed to start weaver serve process"),
+    );
+
+    wait_until_ready(port);
+    let url = format!("http://127.0.0.1:{port}/api/v1/live-check");
+
+    // A batch of samples
+    let (status, report) = post(
+        &url,
+        r#"[{"attribute": {"name": "task.id", "value": "12345"}}]"#,
+    );
+    assert_eq!(status, 200);
+    assert!(finding_ids(&report["samples"][0]["attribute"]).contains(&"missing_attribute"));
+    assert_eq!(report["statistics"]["total_entities"], 1);
+
+    // An OTLP/JSON export request
+    let (status, report) = post(
+        &url,
+        r#"{"resourceSpans": [{"scopeSpans": [{"spans": [{"name": "span",
+            "attributes": [{"key": "task.id", "value": {"stringValue": "12345"}}]}]}]}]}"#,
+    );
+    assert_eq!(status, 200);
+    let span = report["samples"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find_map(|sample| sample.get("span"))
+        .expect("missing span sample");
+    assert!(finding_ids(&span["attributes"][0]).contains(&"missing_attribute"));
+
+    // Invalid input
+    let (status, error) = post(&url, r#"{"foo": 1}"#);
+    assert_eq!(status, 400);
+    assert!(error["error"].is_string());
+}